@@ -0,0 +1,57 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use a_sabr::{
+    contact::{Contact, ContactInfo},
+    contact_manager::legacy::evl::EVLManager,
+    node_manager::none::NoManagement,
+    route_stage::{RouteStage, SharedRouteStage},
+    routing::{FirstHops, RcKey},
+};
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+
+type SharedContact = Rc<RefCell<Contact<NoManagement, EVLManager>>>;
+type Stage = SharedRouteStage<NoManagement, EVLManager>;
+
+pub fn benchmark(c: &mut Criterion) {
+    let contact: SharedContact = Rc::new(RefCell::new(
+        Contact::try_new(
+            ContactInfo::new(0, 1, 0.0, 100.0),
+            EVLManager::new(10.0, 1.0),
+        )
+        .unwrap(),
+    ));
+    let stage: Stage = Rc::new(RefCell::new(RouteStage::new(
+        1.0,
+        1,
+        None,
+        #[cfg(feature = "node_proc")]
+        a_sabr::bundle::Bundle {
+            source: 0,
+            destinations: vec![1],
+            priority: 0,
+            size: 1.0,
+            expiration: 100.0,
+        },
+    )));
+
+    // the first hops of a unicast output, as a map keyed by contact identity and as `FirstHops`
+    let mut group = c.benchmark_group("UnicastFirstHops");
+    group.bench_function("Map", |b| {
+        b.iter(|| {
+            let mut first_hops: BTreeMap<RcKey, (SharedContact, Vec<Stage>)> = BTreeMap::new();
+            first_hops
+                .entry(RcKey::of(&contact))
+                .or_insert_with(|| (contact.clone(), Vec::new()))
+                .1
+                .push(stage.clone());
+            black_box(first_hops)
+        });
+    });
+    group.bench_function("FirstHops", |b| {
+        b.iter(|| black_box(FirstHops::single(contact.clone(), stage.clone())));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
@@ -1,9 +1,17 @@
 use std::fs::File;
 
+use std::{cell::RefCell, rc::Rc};
+
 use a_sabr::{
-    bundle::Bundle, contact_manager::segmentation::seg::SegmentationManager,
-    contact_plan::from_tvgutil_file::TVGUtilContactPlan, node_manager::none::NoManagement,
-    routing::aliases::*, types::NodeID,
+    bundle::Bundle,
+    contact_manager::segmentation::seg::SegmentationManager,
+    contact_plan::from_tvgutil_file::TVGUtilContactPlan,
+    distance::sabr::SABR,
+    multigraph::Multigraph,
+    node_manager::none::NoManagement,
+    pathfinding::{Pathfinding, node_parenting::NodeParentingTreeExcl},
+    routing::aliases::*,
+    types::NodeID,
 };
 use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
 
@@ -102,9 +110,70 @@ pub fn benchmark(c: &mut Criterion) {
     }
 }
 
+pub fn first_contact_benchmark(c: &mut Criterion) {
+    let file = File::open("benches/ptvg_files/sample1.json").unwrap();
+    let json = serde_json::from_reader(file).unwrap();
+    let contact_plan =
+        TVGUtilContactPlan::parse::<NoManagement, SegmentationManager>(json).unwrap();
+    let multigraph = Multigraph::new(contact_plan).unwrap();
+
+    // the lookups of a pathfinding run at increasing times, from the start of the plan
+    c.bench_function("FirstValidContact", |b| {
+        b.iter(|| {
+            for sender in &multigraph.senders {
+                for receiver in &sender.receivers {
+                    *receiver.next.borrow_mut() = 0;
+                    for time in [60.0, 6000.0, 24060.0] {
+                        black_box(receiver.lazy_prune_and_get_first_idx(black_box(time)));
+                    }
+                }
+            }
+        });
+    });
+}
+
+pub fn stage_recycling_benchmark(c: &mut Criterion) {
+    let bundle = Bundle {
+        source: 178,
+        destinations: vec![159],
+        priority: 0,
+        size: 47419533.0,
+        expiration: 24060.0,
+    };
+
+    // consecutive node parenting searches, their outputs dropped in between
+    let mut group = c.benchmark_group("StageRecycling");
+    for recycle_stages in [false, true] {
+        let file = File::open("benches/ptvg_files/sample1.json").unwrap();
+        let json = serde_json::from_reader(file).unwrap();
+        let contact_plan =
+            TVGUtilContactPlan::parse::<NoManagement, SegmentationManager>(json).unwrap();
+        let mut multigraph = Multigraph::new(contact_plan).unwrap();
+        multigraph.recycle_stages = recycle_stages;
+        let mut pathfinding =
+            NodeParentingTreeExcl::<_, _, SABR>::new(Rc::new(RefCell::new(multigraph)));
+
+        let name = if recycle_stages {
+            "Recycled"
+        } else {
+            "Allocated"
+        };
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(
+                    pathfinding
+                        .get_next(black_box(60.0), 178, black_box(&bundle), &[])
+                        .unwrap(),
+                );
+            });
+        });
+    }
+    group.finish();
+}
+
 criterion_group! {
     name=benches;
     config=Criterion::default().sample_size(50);
-    targets=benchmark
+    targets=benchmark, first_contact_benchmark, stage_recycling_benchmark
 }
 criterion_main!(benches);
@@ -5,7 +5,11 @@ use alloc::vec::Vec;
 ///
 /// The `Bundle` struct encapsulates the routing details required for determining optimal paths
 /// in a network, including source and destination nodes, priority, size, and expiration time.
-#[derive(Clone, Debug)]
+///
+/// With the `serde` feature, a bundle is (de)serialized as a map of its fields, e.g.
+/// `{"source": 0, "destinations": [2], "priority": 1, "size": 10.0, "expiration": 100.0}`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bundle {
     /// The starting node identifier for the routing operation.
     pub source: NodeID,
@@ -20,21 +24,30 @@ pub struct Bundle {
 }
 
 impl Bundle {
-    /// Determines if the current bundle "shadows" existing routes based on size and priority checks.
+    /// Checks whether a tree computed for `self` must not be reused for `other`, see `TreeCache`.
+    ///
+    /// `self` is the bundle the tree was computed for. The search skipped the contacts that could
+    /// not carry `self`, a tree is only reliable for bundles seeing at most the volume `self` saw.
+    /// `self` shadows `other`, i.e. the tree may miss better routes for `other`:
+    /// - with `check_by_size`, if `self` is larger than `other` (lower volume paths might have
+    ///   been skipped),
+    /// - with `check_by_priority`, if `self` has a higher priority than `other` (a higher
+    ///   priority can claim the volume booked by the lower ones, its routes may be out of reach
+    ///   for `other`).
     ///
-    /// This method is used to enhance volume-aware pathfinding by tracking possible paths that
-    /// might have been skipped if a bundle of lower size or lower priority needs to be scheduled for a tree originally
-    /// computed for a larger or higher priority bundle. `self` is the bundle attached to the tree.
+    /// A bundle never shadows an identical one, nor a larger or higher priority one. The
+    /// destinations and the expiration are not compared: the routes of a tree that is not shadowed
+    /// still need a dry run for `other`, which `TreeCache::select` performs before any reuse.
     ///
     /// # Parameters
     ///
-    /// * `other` - The other bundle to compare against.
-    /// * `check_by_size` - A boolean indicating whether to consider size in the comparison.
-    /// * `check_by_priority` - A boolean indicating whether to consider priority in the comparison.
+    /// * `other` - The bundle to route with the tree computed for `self`.
+    /// * `check_by_size` - Whether a larger `self` shadows `other`.
+    /// * `check_by_priority` - Whether a higher priority `self` shadows `other`.
     ///
     /// # Returns
     ///
-    /// * `bool` - Returns `true` if the current bundle shadows the other bundle, otherwise `false`.
+    /// * `bool` - Returns `true` if the tree of `self` must not be reused for `other`.
     pub fn shadows(&self, other: &Bundle, check_by_size: bool, check_by_priority: bool) -> bool {
         // lower volume paths might have been skipped
         if check_by_size && self.size > other.size {
@@ -48,3 +61,110 @@ impl Bundle {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn bundle(priority: Priority, size: Volume) -> Bundle {
+        Bundle {
+            source: 0,
+            destinations: vec![1],
+            priority,
+            size,
+            expiration: 100.0,
+        }
+    }
+
+    #[test]
+    fn identical_bundles_do_not_shadow() {
+        for (check_size, check_priority) in
+            [(false, false), (true, false), (false, true), (true, true)]
+        {
+            assert!(
+                !bundle(1, 10.0).shadows(&bundle(1, 10.0), check_size, check_priority),
+                "TEST FAILED: A tree should serve an identical bundle."
+            );
+        }
+    }
+
+    #[test]
+    fn larger_bundles_shadow_smaller_ones_when_checking_size() {
+        assert!(
+            bundle(0, 20.0).shadows(&bundle(0, 10.0), true, false),
+            "TEST FAILED: The tree of a larger bundle may miss the routes of a smaller one."
+        );
+        assert!(
+            !bundle(0, 10.0).shadows(&bundle(0, 20.0), true, false),
+            "TEST FAILED: A smaller bundle does not shadow a larger one, the dry run decides."
+        );
+        assert!(
+            !bundle(0, 20.0).shadows(&bundle(0, 10.0), false, true),
+            "TEST FAILED: The size should be ignored without check_by_size."
+        );
+        // the priority check does not hide a size mismatch, and the other way around
+        assert!(
+            bundle(0, 20.0).shadows(&bundle(2, 10.0), true, true),
+            "TEST FAILED: A larger bundle shadows a smaller one whatever its priority."
+        );
+    }
+
+    #[test]
+    fn higher_priorities_shadow_lower_ones_when_checking_priority() {
+        assert!(
+            bundle(2, 10.0).shadows(&bundle(0, 10.0), false, true),
+            "TEST FAILED: The tree of a higher priority may use volume a lower one cannot claim."
+        );
+        assert!(
+            !bundle(0, 10.0).shadows(&bundle(2, 10.0), false, true),
+            "TEST FAILED: A lower priority does not shadow a higher one."
+        );
+        assert!(
+            !bundle(2, 10.0).shadows(&bundle(0, 10.0), true, false),
+            "TEST FAILED: The priority should be ignored without check_by_priority."
+        );
+        assert!(
+            bundle(2, 10.0).shadows(&bundle(0, 20.0), true, true),
+            "TEST FAILED: A higher priority shadows a lower one whatever its size."
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bundle_json_round_trip() {
+        let bundle = Bundle {
+            source: 0,
+            destinations: vec![2, 3],
+            priority: 1,
+            size: 10.5,
+            expiration: 100.0,
+        };
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Bundle>(&json).unwrap(),
+            bundle,
+            "TEST FAILED: The bundle should survive the JSON round trip."
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bundle_from_hand_written_json() {
+        let bundle: Bundle = serde_json::from_str(
+            r#"{"source": 1, "destinations": [4], "priority": 2, "size": 3.0, "expiration": 50.0}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            bundle,
+            Bundle {
+                source: 1,
+                destinations: vec![4],
+                priority: 2,
+                size: 3.0,
+                expiration: 50.0,
+            },
+            "TEST FAILED: Unexpected fields parsed from the JSON bundle."
+        );
+    }
+}
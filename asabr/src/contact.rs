@@ -9,10 +9,14 @@ use core::cell::RefCell;
 use core::cmp::Ordering;
 use core::marker::PhantomData;
 extern crate alloc;
-use alloc::rc::Rc;
+use alloc::{format, rc::Rc, string::String};
 
 /// Represents basic information about a contact between two nodes.
+///
+/// With the `serde` feature, the information is (de)serialized as a map of its fields:
+/// `tx_node_id`, `rx_node_id`, `start` and `end`.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContactInfo {
     ///The ID of the transmitting node.
     pub tx_node_id: NodeID,
@@ -64,7 +68,7 @@ impl ContactInfo {
     /// # Returns
     ///
     /// * `bool` - Returns `true` if the start time is before the end time; otherwise, returns `false`.
-    fn try_init(&self) -> bool {
+    pub(crate) fn try_init(&self) -> bool {
         self.start < self.end
     }
 }
@@ -88,6 +92,11 @@ pub struct Contact<NM: NodeManager, CM: ContactManager> {
     #[cfg(feature = "contact_suppression")]
     /// Suppression option for path construction (compilation option).
     pub suppressed: bool,
+    /// Whether the contact is excluded from the current routing operation.
+    pub excluded: bool,
+    /// Bumped each time the routing books, releases or restores the manager's resources, so
+    /// that a dry run output is only reused while the manager is left as it was.
+    pub(crate) generation: u64,
 
     // for compilation
     #[doc(hidden)]
@@ -116,6 +125,8 @@ impl<NM: NodeManager, CM: ContactManager> Contact<NM, CM> {
                 work_area: None,
                 #[cfg(feature = "contact_suppression")]
                 suppressed: false,
+                excluded: false,
+                generation: 0,
                 // for compilation
                 _phantom_nm: PhantomData,
             });
@@ -143,6 +154,31 @@ impl<NM: NodeManager, CM: ContactManager> Contact<NM, CM> {
         self.info.rx_node_id
     }
 
+    /// Retrieves the number of times the routing changed the manager's resources.
+    ///
+    /// Bookings made directly through `manager` are not counted, run a new dry run before
+    /// scheduling a stage after such a booking.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The generation of the manager's resources.
+    #[inline(always)]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Describes the contact on one line, its endpoints and window, e.g. for logging.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The description, as `contact 0 -> 1 [10, 20]`.
+    pub fn summary(&self) -> String {
+        format!(
+            "contact {} -> {} [{}, {}]",
+            self.info.tx_node_id, self.info.rx_node_id, self.info.start, self.info.end
+        )
+    }
+
     /// Compare two contacts by start time.
     pub fn cmp_by_start(&self, other: &Self) -> Ordering {
         self.info
@@ -190,3 +226,50 @@ impl<NM: NodeManager, CM: ContactManager> PartialEq for Contact<NM, CM> {
     }
 }
 impl<NM: NodeManager, CM: ContactManager> Eq for Contact<NM, CM> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_contact;
+    use core::cmp::Ordering;
+
+    #[test]
+    fn identical_contacts_are_equal() {
+        let a = make_contact::<NoManagement>(0, 1, 10.0, 20.0, 1.0, 0.0);
+        let b = make_contact::<NoManagement>(0, 1, 10.0, 20.0, 1.0, 0.0);
+        assert!(
+            a == b,
+            "TEST FAILED: Contacts with identical info should be equal."
+        );
+        assert_eq!(
+            a == b,
+            b == a,
+            "TEST FAILED: Contact equality should be symmetric."
+        );
+        assert_eq!(
+            a.cmp(&b),
+            Ordering::Equal,
+            "TEST FAILED: eq should be consistent with cmp."
+        );
+    }
+
+    #[test]
+    fn contacts_with_different_starts_are_not_equal() {
+        let a = make_contact::<NoManagement>(0, 1, 10.0, 20.0, 1.0, 0.0);
+        let b = make_contact::<NoManagement>(0, 1, 15.0, 20.0, 1.0, 0.0);
+        assert!(
+            a != b,
+            "TEST FAILED: Contacts with different starts should not be equal."
+        );
+    }
+
+    #[test]
+    fn summary_shows_the_endpoints_and_window() {
+        let contact = make_contact::<NoManagement>(3, 7, 10.0, 20.5, 1.0, 0.0);
+        assert_eq!(
+            contact.summary(),
+            "contact 3 -> 7 [10, 20.5]",
+            "TEST FAILED: Unexpected contact summary."
+        );
+    }
+}
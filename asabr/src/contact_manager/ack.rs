@@ -0,0 +1,412 @@
+extern crate alloc;
+use alloc::{boxed::Box, vec::Vec};
+use core::any::Any;
+
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    contact_manager::{ContactManager, ContactManagerTxData, ManagerState, TxReject},
+    parse_transparent,
+    parsing::Unparse,
+    types::{DataRate, Date, Duration, Priority, Volume},
+};
+
+/// A queue delay manager for reliable links, where each bundle is acknowledged by the receiver.
+///
+/// The bundle is considered delivered once acknowledged: the arrival is delayed by the
+/// acknowledgement overhead (e.g. the round trip of the ACK), and the link waits for the
+/// acknowledgement before the next transmission. Each transmission also reserves the size of an
+/// ACK on the reverse contact, see `reverse_volume`. Only the last booked bundle can be
+/// unscheduled, as the later bookings wait for the earlier ones.
+#[derive(Debug)]
+pub struct AckManager {
+    /// The data transmission rate.
+    rate: DataRate,
+    /// The transmission delay.
+    delay: Duration,
+    /// The time needed to get the acknowledgement of a transmission.
+    ack_overhead: Duration,
+    /// The volume of an acknowledgement on the reverse contact.
+    ack_size: Volume,
+    /// The transmission and acknowledgement time already booked on the contact.
+    busy: Duration,
+    /// The date the link is released by the last booked acknowledgement.
+    next_free: Date,
+    /// The hold time of each booking, with the release date of the link before it, in booking
+    /// order.
+    bookings: Vec<(Duration, Date)>,
+    /// The volume of the acknowledgements booked on the reverse contact.
+    reverse_volume: Volume,
+    /// The duration of the contact, known after initialization.
+    window: Duration,
+}
+
+impl AckManager {
+    /// Creates a new `AckManager` with nothing booked and empty acknowledgements.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The average data rate for this contact.
+    /// * `delay` - The link delay for this contact.
+    /// * `ack_overhead` - The time needed to get the acknowledgement of a transmission.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `AckManager`.
+    pub fn new(rate: DataRate, delay: Duration, ack_overhead: Duration) -> Self {
+        Self {
+            rate,
+            delay,
+            ack_overhead,
+            ack_size: 0.0,
+            busy: 0.0,
+            next_free: Date::NEG_INFINITY,
+            bookings: Vec::new(),
+            reverse_volume: 0.0,
+            window: 0.0,
+        }
+    }
+
+    /// Sets the volume of an acknowledgement on the reverse contact.
+    ///
+    /// # Arguments
+    ///
+    /// * `ack_size` - The volume reserved on the reverse contact for each transmission.
+    ///
+    /// # Returns
+    ///
+    /// The updated `AckManager`.
+    pub fn with_ack_size(mut self, ack_size: Volume) -> Self {
+        self.ack_size = ack_size;
+        self
+    }
+
+    /// Returns the volume of the acknowledgements of the scheduled bundles, to be accounted
+    /// for on the reverse contact.
+    pub fn reverse_volume(&self) -> Volume {
+        self.reverse_volume
+    }
+
+    /// Returns the time the transmission of `bundle` and its acknowledgement hold the link.
+    fn hold_time(&self, bundle: &Bundle) -> Duration {
+        bundle.size / self.rate + self.ack_overhead
+    }
+}
+
+impl ContactManager for AckManager {
+    /// Simulates the transmission of a bundle after the booked ones, the arrival including the
+    /// acknowledgement overhead.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None`
+    /// if the bundle cannot be transmitted before the contact ends.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        self.dry_run_tx_detailed(contact_data, at_time, bundle).ok()
+    }
+
+    /// Simulates the transmission of a bundle like `dry_run_tx`, telling why it is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ContactManagerTxData` with transmission start and end times, or the `TxReject` reason.
+    fn dry_run_tx_detailed(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Result<ContactManagerTxData, TxReject> {
+        let tx_start = self.next_free.max(contact_data.start).max(at_time);
+        let tx_end = tx_start + bundle.size / self.rate;
+        if tx_end > contact_data.end {
+            return Err(TxReject::DeadlineExceeded);
+        }
+        let rx_end = self.delay + tx_end + self.ack_overhead;
+        if rx_end > bundle.expiration {
+            return Err(TxReject::Expired);
+        }
+        Ok(ContactManagerTxData {
+            tx_start,
+            tx_end,
+            expiration: contact_data.end,
+            rx_start: self.delay + tx_start,
+            rx_end,
+        })
+    }
+
+    /// Schedules the transmission of a bundle by booking its transmission and acknowledgement.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let data = self.dry_run_tx(contact_data, at_time, bundle)?;
+        self.schedule_tx_from_dry_run(contact_data, at_time, bundle, data)
+    }
+
+    /// Books the transmission and acknowledgement of a bundle whose dry run output is `data`.
+    fn schedule_tx_from_dry_run(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _at_time: Date,
+        bundle: &Bundle,
+        data: ContactManagerTxData,
+    ) -> Option<ContactManagerTxData> {
+        let hold_time = self.hold_time(bundle);
+        self.busy += hold_time;
+        self.bookings.push((hold_time, self.next_free));
+        self.next_free = data.tx_start + hold_time;
+        self.reverse_volume += self.ack_size;
+        Some(data)
+    }
+
+    /// Releases the transmission and acknowledgement booked for the bundle, the link being
+    /// released as before the booking. Only the last booking can be released, `false` is
+    /// returned if `bundle` does not match it.
+    fn unschedule_tx(&mut self, bundle: &Bundle) -> bool {
+        let time = self.hold_time(bundle);
+        match self.bookings.last() {
+            Some(&(hold_time, previous_free)) if hold_time == time => {
+                self.bookings.pop();
+                self.busy -= time;
+                self.next_free = previous_free;
+                self.reverse_volume = Volume::max(0.0, self.reverse_volume - self.ack_size);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the volume the remaining time can carry, the acknowledgements aside.
+    fn remaining_volume(&self, _priority: Priority) -> Volume {
+        Volume::max(0.0, (self.window - self.busy) * self.rate)
+    }
+
+    /// Checks that the rate is positive and the delay, the overhead and the ACK size are not negative.
+    fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+        self.window = contact_data.end - contact_data.start;
+        self.rate > 0.0 && self.delay >= 0.0 && self.ack_overhead >= 0.0 && self.ack_size >= 0.0
+    }
+
+    /// Releases the booked transmissions and acknowledgements.
+    fn reset(&mut self, contact_data: &ContactInfo) -> bool {
+        self.busy = 0.0;
+        self.next_free = Date::NEG_INFINITY;
+        self.bookings.clear();
+        self.reverse_volume = 0.0;
+        self.try_init(contact_data)
+    }
+
+    /// Captures the booked time, the release of the link, the bookings and the reverse volume.
+    fn snapshot(&self) -> ManagerState {
+        Box::new((
+            self.busy,
+            self.next_free,
+            self.bookings.clone(),
+            self.reverse_volume,
+        ))
+    }
+
+    /// Restores the booked time, the release of the link, the bookings and the reverse volume
+    /// captured by `snapshot`.
+    fn restore(&mut self, state: &dyn Any) -> bool {
+        match state.downcast_ref::<(Duration, Date, Vec<(Duration, Date)>, Volume)>() {
+            Some((busy, next_free, bookings, reverse_volume)) => {
+                self.busy = *busy;
+                self.next_free = *next_free;
+                self.bookings.clone_from(bookings);
+                self.reverse_volume = *reverse_volume;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the volume of the contact without acknowledgements.
+    fn get_original_volume(&self) -> Volume {
+        self.rate * self.window
+    }
+
+    /// Returns the link delay, the acknowledgement overhead aside.
+    fn get_delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+parse_transparent!(AckManager, (DataRate, Duration, Duration));
+
+impl From<(DataRate, Duration, Duration)> for AckManager {
+    fn from((rate, delay, ack_overhead): (DataRate, Duration, Duration)) -> Self {
+        AckManager::new(rate, delay, ack_overhead)
+    }
+}
+
+impl Unparse for AckManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        if self.ack_size != 0.0 {
+            return Err(core::fmt::Error);
+        }
+        write!(out, "{} {} {}", self.rate, self.delay, self.ack_overhead)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_bundle;
+
+    #[test]
+    fn acknowledgements_delay_the_arrivals() {
+        // rate 10, delay 1, each ACK takes 2 more
+        let plan = parse_from_str::<NoManagement, AckManager>(
+            "node 0 a
+            node 1 b
+            contact 0 1 0 100 10 1 2",
+        )
+        .unwrap();
+        let info = plan.contacts[0].info;
+        let mut manager = AckManager::new(10.0, 1.0, 2.0).with_ack_size(0.5);
+        assert!(
+            manager.try_init(&info),
+            "TEST FAILED: The manager should accept the contact."
+        );
+        assert_eq!(
+            plan.contacts[0].manager.get_original_volume(),
+            manager.get_original_volume(),
+            "TEST FAILED: The parsed manager should carry the same volume."
+        );
+
+        let first = manager
+            .schedule_tx(&info, 0.0, &make_bundle(1, 0, 10.0, 1000.0))
+            .expect("TEST FAILED: The first bundle fits.");
+        assert_eq!(
+            (first.tx_end, first.rx_end),
+            (1.0, 4.0),
+            "TEST FAILED: The arrival should include the ACK overhead."
+        );
+        // the second transmission waits for the first acknowledgement
+        let second = manager
+            .schedule_tx(&info, 0.0, &make_bundle(1, 0, 10.0, 1000.0))
+            .expect("TEST FAILED: The second bundle fits.");
+        assert_eq!(
+            (second.tx_start, second.tx_end, second.rx_end),
+            (3.0, 4.0, 7.0),
+            "TEST FAILED: The overheads of back-to-back bundles should accumulate."
+        );
+        assert_eq!(
+            manager.reverse_volume(),
+            1.0,
+            "TEST FAILED: Each bundle should reserve an ACK on the reverse contact."
+        );
+
+        assert!(
+            manager.unschedule_tx(&make_bundle(1, 0, 10.0, 1000.0)),
+            "TEST FAILED: The second bundle should be unscheduled."
+        );
+        assert_eq!(
+            (manager.reverse_volume(), manager.remaining_volume(0)),
+            (0.5, 970.0),
+            "TEST FAILED: Unscheduling should release the bundle and its ACK."
+        );
+    }
+
+    #[test]
+    fn only_the_last_booking_can_be_unscheduled() {
+        let info = ContactInfo::new(0, 1, 0.0, 100.0);
+        let mut manager = AckManager::new(10.0, 1.0, 2.0);
+        assert!(
+            manager.try_init(&info),
+            "TEST FAILED: The manager should accept the contact."
+        );
+
+        let small = make_bundle(1, 0, 10.0, 1000.0);
+        let large = make_bundle(1, 0, 20.0, 1000.0);
+        assert!(
+            manager.schedule_tx(&info, 0.0, &small).is_some()
+                && manager.schedule_tx(&info, 0.0, &large).is_some(),
+            "TEST FAILED: Both bundles fit."
+        );
+        assert!(
+            !manager.unschedule_tx(&small),
+            "TEST FAILED: The first bundle is not the last booking."
+        );
+        let next = manager
+            .dry_run_tx(&info, 0.0, &small)
+            .expect("TEST FAILED: The bundle fits.");
+        assert_eq!(
+            next.tx_start, 7.0,
+            "TEST FAILED: The refused unscheduling should keep the link booked."
+        );
+
+        // the bookings are released in reverse order
+        assert!(
+            manager.unschedule_tx(&large) && manager.unschedule_tx(&small),
+            "TEST FAILED: The last bookings should be unscheduled."
+        );
+        let next = manager
+            .dry_run_tx(&info, 0.0, &small)
+            .expect("TEST FAILED: The bundle fits.");
+        assert_eq!(
+            (next.tx_start, manager.remaining_volume(0)),
+            (0.0, 1000.0),
+            "TEST FAILED: The link should be free again."
+        );
+    }
+
+    #[test]
+    fn late_bookings_wait_for_each_other() {
+        let info = ContactInfo::new(0, 1, 0.0, 100.0);
+        let mut manager = AckManager::new(10.0, 1.0, 2.0);
+        assert!(
+            manager.try_init(&info),
+            "TEST FAILED: The manager should accept the contact."
+        );
+
+        let first = manager
+            .schedule_tx(&info, 50.0, &make_bundle(1, 0, 10.0, 1000.0))
+            .expect("TEST FAILED: The first bundle fits.");
+        let second = manager
+            .schedule_tx(&info, 50.0, &make_bundle(1, 0, 10.0, 1000.0))
+            .expect("TEST FAILED: The second bundle fits.");
+        assert_eq!(
+            (first.tx_start, first.tx_end, first.rx_end),
+            (50.0, 51.0, 54.0),
+            "TEST FAILED: The first bundle should be sent at once."
+        );
+        assert_eq!(
+            (second.tx_start, second.tx_end, second.rx_end),
+            (53.0, 54.0, 57.0),
+            "TEST FAILED: The second bundle should wait for the first acknowledgement."
+        );
+    }
+}
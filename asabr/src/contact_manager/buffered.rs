@@ -0,0 +1,396 @@
+extern crate alloc;
+use alloc::boxed::Box;
+use core::any::Any;
+
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    contact_manager::{ContactManager, ContactManagerTxData, ManagerState, TxReject},
+    parse_transparent,
+    parsing::Unparse,
+    types::{DataRate, Date, Duration, Priority, Volume},
+};
+
+/// A queue delay manager with a finite transmission buffer.
+///
+/// The bundles waiting for the contact are held in a buffer of fixed capacity, a transmission
+/// starts once the buffer ahead of it is drained. Scheduling a bundle enqueues it, a bundle not
+/// fitting in the buffer is dropped and its volume accounted for in `dropped_volume`, instead of
+/// overbooking the contact. Drops are counted by `schedule_tx` and `enqueue`, the dry runs of
+/// the pathfinding reject the bundle without counting it.
+#[derive(Debug)]
+pub struct BufferedManager {
+    /// The data transmission rate.
+    rate: DataRate,
+    /// The transmission delay.
+    delay: Duration,
+    /// The volume the buffer can hold.
+    capacity: Volume,
+    /// The volume held in the buffer.
+    occupancy: Volume,
+    /// The volume of the bundles dropped for lack of buffer space.
+    dropped_volume: Volume,
+    /// The number of bundles dropped for lack of buffer space.
+    dropped_count: usize,
+}
+
+impl BufferedManager {
+    /// Creates a new `BufferedManager` with an empty buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The average data rate for this contact.
+    /// * `delay` - The link delay for this contact.
+    /// * `capacity` - The volume the buffer can hold.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `BufferedManager`.
+    pub fn new(rate: DataRate, delay: Duration, capacity: Volume) -> Self {
+        Self {
+            rate,
+            delay,
+            capacity,
+            occupancy: 0.0,
+            dropped_volume: 0.0,
+            dropped_count: 0,
+        }
+    }
+
+    /// Adds a bundle to the buffer, or drops it if the buffer cannot hold it.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The bundle to enqueue.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the bundle was enqueued, `false` if it was dropped.
+    pub fn enqueue(&mut self, bundle: &Bundle) -> bool {
+        if self.occupancy + bundle.size > self.capacity {
+            self.dropped_volume += bundle.size;
+            self.dropped_count += 1;
+            return false;
+        }
+        self.occupancy += bundle.size;
+        true
+    }
+
+    /// Removes a bundle from the buffer, e.g. once transmitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The bundle to dequeue.
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the buffer holds less than the bundle size.
+    pub fn dequeue(&mut self, bundle: &Bundle) -> bool {
+        if self.occupancy < bundle.size {
+            return false;
+        }
+        self.occupancy -= bundle.size;
+        true
+    }
+
+    /// Returns the volume held in the buffer.
+    pub fn occupancy(&self) -> Volume {
+        self.occupancy
+    }
+
+    /// Returns the volume of the bundles dropped for lack of buffer space.
+    pub fn dropped_volume(&self) -> Volume {
+        self.dropped_volume
+    }
+
+    /// Returns the number of bundles dropped for lack of buffer space.
+    pub fn dropped_count(&self) -> usize {
+        self.dropped_count
+    }
+}
+
+impl ContactManager for BufferedManager {
+    /// Simulates the transmission of a bundle, which starts once the buffer is drained.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None`
+    /// if the bundle does not fit in the buffer or cannot be transmitted before the contact ends.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        self.dry_run_tx_detailed(contact_data, at_time, bundle).ok()
+    }
+
+    /// Simulates the transmission of a bundle like `dry_run_tx`, telling why it is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ContactManagerTxData` with transmission start and end times, or the `TxReject` reason.
+    fn dry_run_tx_detailed(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Result<ContactManagerTxData, TxReject> {
+        if self.occupancy + bundle.size > self.capacity {
+            return Err(TxReject::VolumeExceeded);
+        }
+        let tx_start = contact_data.start.max(at_time) + self.occupancy / self.rate;
+        let tx_end = tx_start + bundle.size / self.rate;
+        if tx_end > contact_data.end {
+            return Err(TxReject::DeadlineExceeded);
+        }
+        if self.delay + tx_end > bundle.expiration {
+            return Err(TxReject::Expired);
+        }
+        Ok(ContactManagerTxData {
+            tx_start,
+            tx_end,
+            expiration: contact_data.end,
+            rx_start: self.delay + tx_start,
+            rx_end: self.delay + tx_end,
+        })
+    }
+
+    /// Schedules the transmission of a bundle by enqueueing it.
+    ///
+    /// A bundle rejected because the buffer cannot hold it is accounted for as dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        match self.dry_run_tx_detailed(contact_data, at_time, bundle) {
+            Ok(data) => self.schedule_tx_from_dry_run(contact_data, at_time, bundle, data),
+            Err(TxReject::VolumeExceeded) => {
+                self.dropped_volume += bundle.size;
+                self.dropped_count += 1;
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Enqueues a bundle whose dry run output is `data`, without running it again.
+    fn schedule_tx_from_dry_run(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _at_time: Date,
+        bundle: &Bundle,
+        data: ContactManagerTxData,
+    ) -> Option<ContactManagerTxData> {
+        if !self.enqueue(bundle) {
+            return None;
+        }
+        Some(data)
+    }
+
+    /// Removes the bundle from the buffer.
+    fn unschedule_tx(&mut self, bundle: &Bundle) -> bool {
+        self.dequeue(bundle)
+    }
+
+    /// Returns the free space of the buffer.
+    fn remaining_volume(&self, _priority: Priority) -> Volume {
+        Volume::max(0.0, self.capacity - self.occupancy)
+    }
+
+    /// Enqueues a bundle, see `enqueue`.
+    #[cfg(feature = "manual_queueing")]
+    fn manual_enqueue(&mut self, bundle: &Bundle) -> bool {
+        self.enqueue(bundle)
+    }
+
+    /// Dequeues a bundle, see `dequeue`.
+    #[cfg(feature = "manual_queueing")]
+    fn manual_dequeue(&mut self, bundle: &Bundle) -> bool {
+        self.dequeue(bundle)
+    }
+
+    /// Checks that the rate is positive and the delay and the capacity are not negative.
+    fn try_init(&mut self, _contact_data: &ContactInfo) -> bool {
+        self.rate > 0.0 && self.delay >= 0.0 && self.capacity >= 0.0
+    }
+
+    /// Empties the buffer and forgets the drops.
+    fn reset(&mut self, contact_data: &ContactInfo) -> bool {
+        self.occupancy = 0.0;
+        self.dropped_volume = 0.0;
+        self.dropped_count = 0;
+        self.try_init(contact_data)
+    }
+
+    /// Captures the buffer occupancy and the drops.
+    fn snapshot(&self) -> ManagerState {
+        Box::new((self.occupancy, self.dropped_volume, self.dropped_count))
+    }
+
+    /// Restores the buffer occupancy and the drops captured by `snapshot`.
+    fn restore(&mut self, state: &dyn Any) -> bool {
+        match state.downcast_ref() {
+            Some(&(occupancy, dropped_volume, dropped_count)) => {
+                self.occupancy = occupancy;
+                self.dropped_volume = dropped_volume;
+                self.dropped_count = dropped_count;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the buffer capacity.
+    fn get_original_volume(&self) -> Volume {
+        self.capacity
+    }
+
+    /// Returns the buffer occupancy.
+    fn get_queue_size(&self) -> &[Volume] {
+        core::slice::from_ref(&self.occupancy)
+    }
+
+    /// Returns the buffer capacity.
+    fn get_mav(&self) -> &[Volume] {
+        core::slice::from_ref(&self.capacity)
+    }
+
+    /// Returns the link delay.
+    fn get_delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+parse_transparent!(BufferedManager, (DataRate, Duration, Volume));
+
+impl From<(DataRate, Duration, Volume)> for BufferedManager {
+    fn from((rate, delay, capacity): (DataRate, Duration, Volume)) -> Self {
+        BufferedManager::new(rate, delay, capacity)
+    }
+}
+
+impl Unparse for BufferedManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        write!(out, "{} {} {}", self.rate, self.delay, self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathfinding::test_helpers::make_bundle;
+
+    #[test]
+    fn overbooked_buffer_drops_bundles() {
+        let contact = ContactInfo::new(0, 1, 0.0, 100.0);
+        let mut manager = BufferedManager::new(10.0, 1.0, 50.0);
+        assert!(
+            manager.try_init(&contact),
+            "TEST FAILED: The manager should accept the contact."
+        );
+
+        assert!(
+            manager.enqueue(&make_bundle(1, 0, 30.0, 1000.0)),
+            "TEST FAILED: The bundle fits in the empty buffer."
+        );
+        // the 30 units ahead are drained in 3.0
+        let data = manager
+            .dry_run_tx(&contact, 0.0, &make_bundle(1, 0, 10.0, 1000.0))
+            .expect("TEST FAILED: The bundle fits in the buffer.");
+        assert_eq!(
+            (data.tx_start, data.tx_end),
+            (3.0, 4.0),
+            "TEST FAILED: The transmission should wait for the buffer to drain."
+        );
+
+        assert!(
+            !manager.enqueue(&make_bundle(1, 0, 25.0, 1000.0)),
+            "TEST FAILED: The bundle exceeds the free buffer space."
+        );
+        assert!(
+            manager
+                .schedule_tx(&contact, 0.0, &make_bundle(1, 0, 25.0, 1000.0))
+                .is_none(),
+            "TEST FAILED: The bundle exceeds the free buffer space."
+        );
+        assert!(
+            !manager.enqueue(&make_bundle(1, 0, 40.0, 1000.0)),
+            "TEST FAILED: The bundle exceeds the free buffer space."
+        );
+        assert_eq!(
+            (manager.dropped_volume(), manager.dropped_count()),
+            (90.0, 3),
+            "TEST FAILED: The dropped bundles should be accounted for."
+        );
+        assert_eq!(
+            manager.occupancy(),
+            30.0,
+            "TEST FAILED: The dropped bundles should not be buffered."
+        );
+    }
+
+    #[test]
+    fn scheduling_an_oversized_bundle_counts_the_drop() {
+        let contact = ContactInfo::new(0, 1, 0.0, 100.0);
+        let mut manager = BufferedManager::new(10.0, 1.0, 50.0);
+        assert!(
+            manager.try_init(&contact),
+            "TEST FAILED: The manager should accept the contact."
+        );
+
+        assert!(
+            manager
+                .schedule_tx(&contact, 0.0, &make_bundle(1, 0, 60.0, 1000.0))
+                .is_none(),
+            "TEST FAILED: The bundle exceeds the buffer capacity."
+        );
+        assert_eq!(
+            (manager.dropped_volume(), manager.dropped_count()),
+            (60.0, 1),
+            "TEST FAILED: The rejected bundle should be accounted for as dropped."
+        );
+
+        // the bundle fits in the buffer but expires before reaching the next node
+        assert!(
+            manager
+                .schedule_tx(&contact, 0.0, &make_bundle(1, 0, 10.0, 1.5))
+                .is_none(),
+            "TEST FAILED: The bundle expires before its reception."
+        );
+        assert_eq!(
+            (manager.dropped_volume(), manager.dropped_count()),
+            (60.0, 1),
+            "TEST FAILED: Only the buffer overflows should be accounted for as drops."
+        );
+        assert_eq!(
+            manager.occupancy(),
+            0.0,
+            "TEST FAILED: The rejected bundles should not be buffered."
+        );
+    }
+}
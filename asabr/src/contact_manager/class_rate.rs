@@ -0,0 +1,280 @@
+extern crate alloc;
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use core::any::Any;
+
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    contact_manager::{ContactManager, ContactManagerTxData, ManagerState, TxReject},
+    parse_transparent,
+    parsing::Unparse,
+    types::{DataRate, Date, Duration, Priority, Volume},
+};
+
+/// A queue delay manager whose rate and delay depend on the class (priority) of the bundle,
+/// e.g. for links applying a coding rate per traffic class.
+///
+/// A bundle uses the profile of its priority, or of the closest lower class that has one (the
+/// lowest class if none is lower). The transmissions are queued one after the other, scheduling a
+/// bundle books the time needed to transmit it at the rate of its class.
+#[derive(Debug)]
+pub struct ClassRateManager {
+    /// The rate and delay of each class.
+    profiles: BTreeMap<Priority, (DataRate, Duration)>,
+    /// The transmission time already booked on the contact.
+    busy: Duration,
+    /// The duration of the contact, known after initialization.
+    window: Duration,
+}
+
+impl ClassRateManager {
+    /// Creates a new `ClassRateManager` with nothing booked.
+    ///
+    /// # Arguments
+    ///
+    /// * `profiles` - The class, rate and delay of each profile. For a class given twice, the
+    ///   last profile is kept.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `ClassRateManager`.
+    pub fn new(profiles: Vec<(Priority, DataRate, Duration)>) -> Self {
+        Self {
+            profiles: profiles
+                .into_iter()
+                .map(|(class, rate, delay)| (class, (rate, delay)))
+                .collect(),
+            busy: 0.0,
+            window: 0.0,
+        }
+    }
+
+    /// Returns the rate and delay applying to the bundles of `priority`.
+    fn profile(&self, priority: Priority) -> Option<(DataRate, Duration)> {
+        self.profiles
+            .range(..=priority)
+            .next_back()
+            .or_else(|| self.profiles.iter().next())
+            .map(|(_, profile)| *profile)
+    }
+}
+
+impl ContactManager for ClassRateManager {
+    /// Simulates the transmission of a bundle at the rate of its class, after the booked ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None`
+    /// if the bundle cannot be transmitted before the contact ends.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        self.dry_run_tx_detailed(contact_data, at_time, bundle).ok()
+    }
+
+    /// Simulates the transmission of a bundle like `dry_run_tx`, telling why it is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ContactManagerTxData` with transmission start and end times, or the `TxReject` reason.
+    fn dry_run_tx_detailed(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Result<ContactManagerTxData, TxReject> {
+        let (rate, delay) = self
+            .profile(bundle.priority)
+            .ok_or(TxReject::VolumeExceeded)?;
+        let tx_start = (contact_data.start + self.busy).max(at_time);
+        let tx_end = tx_start + bundle.size / rate;
+        if tx_end > contact_data.end {
+            return Err(TxReject::DeadlineExceeded);
+        }
+        if delay + tx_end > bundle.expiration {
+            return Err(TxReject::Expired);
+        }
+        Ok(ContactManagerTxData {
+            tx_start,
+            tx_end,
+            expiration: contact_data.end,
+            rx_start: delay + tx_start,
+            rx_end: delay + tx_end,
+        })
+    }
+
+    /// Schedules the transmission of a bundle by booking its transmission time.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let data = self.dry_run_tx(contact_data, at_time, bundle)?;
+        self.schedule_tx_from_dry_run(contact_data, at_time, bundle, data)
+    }
+
+    /// Books the transmission time of a bundle whose dry run output is `data`.
+    fn schedule_tx_from_dry_run(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _at_time: Date,
+        bundle: &Bundle,
+        data: ContactManagerTxData,
+    ) -> Option<ContactManagerTxData> {
+        let (rate, _) = self.profile(bundle.priority)?;
+        self.busy += bundle.size / rate;
+        Some(data)
+    }
+
+    /// Releases the transmission time booked for the bundle.
+    fn unschedule_tx(&mut self, bundle: &Bundle) -> bool {
+        let Some((rate, _)) = self.profile(bundle.priority) else {
+            return false;
+        };
+        let time = bundle.size / rate;
+        if time > self.busy {
+            return false;
+        }
+        self.busy -= time;
+        true
+    }
+
+    /// Returns the volume the remaining time can carry at the rate of `priority`.
+    fn remaining_volume(&self, priority: Priority) -> Volume {
+        self.profile(priority).map_or(0.0, |(rate, _)| {
+            Volume::max(0.0, (self.window - self.busy) * rate)
+        })
+    }
+
+    /// Checks that a profile is given, that the rates are positive and the delays not negative.
+    fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+        self.window = contact_data.end - contact_data.start;
+        !self.profiles.is_empty()
+            && self
+                .profiles
+                .values()
+                .all(|&(rate, delay)| rate > 0.0 && delay >= 0.0)
+    }
+
+    /// Releases the booked transmission time.
+    fn reset(&mut self, contact_data: &ContactInfo) -> bool {
+        self.busy = 0.0;
+        self.try_init(contact_data)
+    }
+
+    /// Captures the booked transmission time.
+    fn snapshot(&self) -> ManagerState {
+        Box::new(self.busy)
+    }
+
+    /// Restores the booked transmission time captured by `snapshot`.
+    fn restore(&mut self, state: &dyn Any) -> bool {
+        match state.downcast_ref() {
+            Some(&busy) => {
+                self.busy = busy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the volume of the contact at the fastest rate.
+    fn get_original_volume(&self) -> Volume {
+        self.profiles
+            .values()
+            .map(|&(rate, _)| rate * self.window)
+            .fold(0.0, Volume::max)
+    }
+}
+
+parse_transparent!(ClassRateManager, Vec<(Priority, DataRate, Duration)>);
+
+impl From<Vec<(Priority, DataRate, Duration)>> for ClassRateManager {
+    fn from(profiles: Vec<(Priority, DataRate, Duration)>) -> Self {
+        ClassRateManager::new(profiles)
+    }
+}
+
+impl Unparse for ClassRateManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        out.write_char('[')?;
+        for (idx, (class, (rate, delay))) in self.profiles.iter().enumerate() {
+            if idx > 0 {
+                out.write_str(", ")?;
+            }
+            write!(out, "{} {} {}", class, rate, delay)?;
+        }
+        out.write_char(']')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_bundle;
+
+    #[test]
+    fn high_priority_gets_a_better_rate() {
+        // class 2 is coded for 100, class 0 for 10
+        let plan = parse_from_str::<NoManagement, ClassRateManager>(
+            "node 0 a
+            node 1 b
+            contact 0 1 0 100 [0 10 2, 2 100 1]",
+        )
+        .unwrap();
+        let contact = &plan.contacts[0];
+
+        let low = contact
+            .manager
+            .dry_run_tx(&contact.info, 0.0, &make_bundle(1, 0, 100.0, 1000.0))
+            .expect("TEST FAILED: The low priority bundle fits.");
+        let high = contact
+            .manager
+            .dry_run_tx(&contact.info, 0.0, &make_bundle(1, 2, 100.0, 1000.0))
+            .expect("TEST FAILED: The high priority bundle fits.");
+        assert_eq!(
+            (low.tx_end, low.rx_end),
+            (10.0, 12.0),
+            "TEST FAILED: The low priority bundle should use the class 0 profile."
+        );
+        assert_eq!(
+            (high.tx_end, high.rx_end),
+            (1.0, 2.0),
+            "TEST FAILED: The high priority bundle should use the class 2 profile."
+        );
+        // class 1 has no profile of its own
+        assert_eq!(
+            contact.manager.remaining_volume(1),
+            1000.0,
+            "TEST FAILED: Priority 1 should fall back to the class 0 profile."
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{
+    contact_manager::legacy::evl::EVLManager,
+    parse_transparent,
+    parsing::Unparse,
+    transparent_CM,
+    types::{DataRate, Duration},
+};
+
+/// An `EVLManager` for opportunistic links, carrying the confidence of the contact.
+///
+/// The confidence does not affect scheduling, it is accumulated along the routes (see
+/// `RouteStage::confidence`) for the distances, e.g. `MaxConfidence`.
+#[derive(Debug)]
+pub struct ConfidenceManager(EVLManager, f64);
+
+transparent_CM!(
+    ConfidenceManager,
+    fn confidence(&self) -> f64 {
+        self.1
+    }
+);
+
+impl ConfidenceManager {
+    /// Creates a new `ConfidenceManager` with specified average rate, delay and confidence.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The average data rate for this contact.
+    /// * `delay` - The link delay for this contact.
+    /// * `confidence` - The probability that a transmission over this contact succeeds.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `ConfidenceManager`, or `None` if `confidence` is not in `[0, 1]`.
+    pub fn new(rate: DataRate, delay: Duration, confidence: f64) -> Option<Self> {
+        if !(0.0..=1.0).contains(&confidence) {
+            return None;
+        }
+        Some(Self(EVLManager::new(rate, delay), confidence))
+    }
+}
+
+parse_transparent!(ConfidenceManager, (DataRate, Duration, f64));
+
+impl TryFrom<(DataRate, Duration, f64)> for ConfidenceManager {
+    type Error = ();
+
+    fn try_from((rate, delay, confidence): (DataRate, Duration, f64)) -> Result<Self, ()> {
+        ConfidenceManager::new(rate, delay, confidence).ok_or(())
+    }
+}
+
+impl Unparse for ConfidenceManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        self.0.unparse(out)?;
+        write!(out, " {}", self.1)
+    }
+}
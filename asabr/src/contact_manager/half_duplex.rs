@@ -0,0 +1,455 @@
+extern crate alloc;
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap as HashMap, BTreeSet},
+    rc::Rc,
+};
+use core::{any::Any, cell::RefCell};
+
+use crate::{
+    bundle::Bundle,
+    contact::{Contact, ContactInfo},
+    contact_manager::{ContactManager, ContactManagerTxData, ManagerState, TxReject},
+    node_manager::NodeManager,
+    parse_transparent,
+    parsing::Unparse,
+    routing::RcKey,
+    types::{DataRate, Date, Duration, Priority, Volume},
+};
+
+/// The identifier of a channel shared by several contacts.
+pub type ChannelID = u16;
+
+/// The volume budget of a channel shared by the contacts of both directions of a link.
+#[derive(Debug, Default)]
+pub struct ChannelState {
+    /// The volume the channel can carry, all directions included.
+    volume: Volume,
+    /// The volume booked on the channel, all directions included.
+    booked: Volume,
+}
+
+impl ChannelState {
+    /// Creates a new `ChannelState` with nothing booked.
+    ///
+    /// # Arguments
+    ///
+    /// * `volume` - The volume the channel can carry.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `ChannelState`.
+    pub fn new(volume: Volume) -> Self {
+        Self {
+            volume,
+            booked: 0.0,
+        }
+    }
+
+    /// Returns the volume that can still be booked on the channel.
+    pub fn remaining(&self) -> Volume {
+        self.volume - self.booked
+    }
+}
+
+/// A channel shared between contacts.
+pub type SharedChannel = Rc<RefCell<ChannelState>>;
+
+/// An EVL-like manager for half-duplex links: the contacts of both directions of a link share a
+/// single channel, the volume booked on one direction is not available to the other.
+///
+/// Each manager also checks its own contact window, as `EVLManager` does. The volume of the
+/// channel is the largest volume of the contacts using it, see `share_channels`.
+#[derive(Debug)]
+pub struct HalfDuplexManager {
+    /// The identifier of the channel, used to share it when parsing.
+    channel_id: ChannelID,
+    /// The data transmission rate.
+    rate: DataRate,
+    /// The transmission delay.
+    delay: Duration,
+    /// The volume booked on this contact.
+    queue_size: Volume,
+    /// The total volume of this contact at initialization.
+    original_volume: Volume,
+    /// The channel shared with the contacts of the other direction.
+    channel: SharedChannel,
+}
+
+impl HalfDuplexManager {
+    /// Creates a new `HalfDuplexManager` using its own channel, until `with_channel` or
+    /// `share_channels` is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_id` - The identifier of the channel.
+    /// * `rate` - The average data rate for this contact.
+    /// * `delay` - The link delay for this contact.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `HalfDuplexManager`.
+    pub fn new(channel_id: ChannelID, rate: DataRate, delay: Duration) -> Self {
+        Self {
+            channel_id,
+            rate,
+            delay,
+            queue_size: 0.0,
+            original_volume: 0.0,
+            channel: Rc::new(RefCell::new(ChannelState::default())),
+        }
+    }
+
+    /// Sets the channel shared with other managers. The volume of the channel is raised to the
+    /// volume of the contact at initialization if lower.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The shared channel.
+    ///
+    /// # Returns
+    ///
+    /// The updated `HalfDuplexManager`.
+    pub fn with_channel(mut self, channel: SharedChannel) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Returns the identifier of the channel.
+    pub fn channel_id(&self) -> ChannelID {
+        self.channel_id
+    }
+
+    /// Returns the channel used by this manager.
+    pub fn channel(&self) -> SharedChannel {
+        self.channel.clone()
+    }
+}
+
+impl ContactManager for HalfDuplexManager {
+    /// Simulates the transmission of a bundle, the volume must be available on both the contact
+    /// and the channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn dry_run_tx(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        self.dry_run_tx_detailed(contact_data, at_time, bundle).ok()
+    }
+
+    /// Simulates the transmission of a bundle like `dry_run_tx`, telling why it is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ContactManagerTxData` with transmission start and end times, or the `TxReject` reason.
+    fn dry_run_tx_detailed(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Result<ContactManagerTxData, TxReject> {
+        if bundle.size > self.remaining_volume(bundle.priority) {
+            return Err(TxReject::VolumeExceeded);
+        }
+        let tx_start = contact_data.start.max(at_time);
+        let tx_end = tx_start + bundle.size / self.rate;
+        if tx_end > contact_data.end {
+            return Err(TxReject::DeadlineExceeded);
+        }
+        if self.delay + tx_end > bundle.expiration {
+            return Err(TxReject::Expired);
+        }
+        Ok(ContactManagerTxData {
+            tx_start,
+            tx_end,
+            expiration: contact_data.end,
+            rx_start: self.delay + tx_start,
+            rx_end: self.delay + tx_end,
+        })
+    }
+
+    /// Schedules the transmission of a bundle, its volume is booked on the contact and deducted
+    /// from the channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` with transmission start and end times, or `None` if the bundle can't be transmitted.
+    fn schedule_tx(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<ContactManagerTxData> {
+        let data = self.dry_run_tx(contact_data, at_time, bundle)?;
+        self.schedule_tx_from_dry_run(contact_data, at_time, bundle, data)
+    }
+
+    /// Books the volume of a bundle whose dry run output is `data`, without running it again.
+    fn schedule_tx_from_dry_run(
+        &mut self,
+        _contact_data: &ContactInfo,
+        _at_time: Date,
+        bundle: &Bundle,
+        data: ContactManagerTxData,
+    ) -> Option<ContactManagerTxData> {
+        self.queue_size += bundle.size;
+        self.channel.borrow_mut().booked += bundle.size;
+        Some(data)
+    }
+
+    /// Releases the volume booked by a previous `schedule_tx` call for this bundle, on the
+    /// contact and on the channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The bundle whose transmission was scheduled.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the volume was released, `false` if less volume than the bundle size is booked.
+    fn unschedule_tx(&mut self, bundle: &Bundle) -> bool {
+        if self.queue_size < bundle.size {
+            return false;
+        }
+        self.queue_size -= bundle.size;
+        self.channel.borrow_mut().booked -= bundle.size;
+        true
+    }
+
+    /// Returns the volume that can still be booked, i.e. the lowest of the volumes left on the
+    /// contact and on the channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `_priority` - Unused, the manager has no priority levels.
+    ///
+    /// # Returns
+    ///
+    /// The remaining volume (zero if the contact or the channel is overbooked).
+    fn remaining_volume(&self, _priority: Priority) -> Volume {
+        let contact_remaining = self.original_volume - self.queue_size;
+        Volume::max(
+            0.0,
+            contact_remaining.min(self.channel.borrow().remaining()),
+        )
+    }
+
+    /// Computes the volume of the contact and raises the volume of the channel to it if lower.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the rate is positive and the delay is not negative.
+    fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+        if self.rate <= 0.0 || self.delay < 0.0 {
+            return false;
+        }
+        self.original_volume = self.rate * (contact_data.end - contact_data.start);
+        let mut channel = self.channel.borrow_mut();
+        channel.volume = channel.volume.max(self.original_volume);
+        true
+    }
+
+    /// Releases the volume booked on the contact, on the contact and on the channel, and
+    /// initializes the manager again.
+    fn reset(&mut self, contact_data: &ContactInfo) -> bool {
+        self.channel.borrow_mut().booked -= self.queue_size;
+        self.queue_size = 0.0;
+        self.try_init(contact_data)
+    }
+
+    /// Captures the volume booked on the contact.
+    fn snapshot(&self) -> ManagerState {
+        Box::new(self.queue_size)
+    }
+
+    /// Restores the volume booked on the contact captured by `snapshot`, the channel is updated
+    /// by the difference so that restoring every contact of a channel restores the channel.
+    fn restore(&mut self, state: &dyn Any) -> bool {
+        match state.downcast_ref::<Volume>() {
+            Some(&queue_size) => {
+                self.channel.borrow_mut().booked += queue_size - self.queue_size;
+                self.queue_size = queue_size;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the original volume of the contact.
+    ///
+    /// # Returns
+    ///
+    /// A `Volume` representing the original volume.
+    fn get_original_volume(&self) -> Volume {
+        self.original_volume
+    }
+
+    /// Returns the volume booked on the contact (not on the whole channel).
+    fn get_queue_size(&self) -> &[Volume] {
+        core::slice::from_ref(&self.queue_size)
+    }
+
+    /// Returns the original volume of the contact, the channel can limit it further.
+    fn get_mav(&self) -> &[Volume] {
+        core::slice::from_ref(&self.original_volume)
+    }
+
+    /// Returns the link delay.
+    fn get_delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+/// Makes the contacts declaring the same channel id share a single channel, e.g. after parsing
+/// a contact plan.
+///
+/// The volume of each channel is the largest volume of its contacts, the volume already booked
+/// through the previous channels of the contacts is carried over, once per previous channel.
+/// Sharing the channels again leaves the volumes and the bookings as they are.
+///
+/// # Arguments
+///
+/// * `contacts` - The contacts to link, e.g. `ContactPlan::contacts`.
+pub fn share_channels<NM: NodeManager>(contacts: &mut [Contact<NM, HalfDuplexManager>]) {
+    let mut channels: HashMap<ChannelID, SharedChannel> = HashMap::new();
+    let mut merged: BTreeSet<(ChannelID, RcKey)> = BTreeSet::new();
+    for contact in contacts.iter_mut() {
+        let manager = &mut contact.manager;
+        let channel = channels
+            .entry(manager.channel_id)
+            .or_insert_with(|| Rc::new(RefCell::new(ChannelState::default())));
+        if !Rc::ptr_eq(channel, &manager.channel) {
+            let previous = manager.channel.borrow();
+            let mut shared = channel.borrow_mut();
+            shared.volume = shared.volume.max(previous.volume);
+            if merged.insert((manager.channel_id, RcKey::of(&manager.channel))) {
+                shared.booked += previous.booked;
+            }
+        }
+        manager.channel = channel.clone();
+    }
+}
+
+parse_transparent!(HalfDuplexManager, (ChannelID, DataRate, Duration));
+
+impl From<(ChannelID, DataRate, Duration)> for HalfDuplexManager {
+    fn from((channel_id, rate, delay): (ChannelID, DataRate, Duration)) -> Self {
+        HalfDuplexManager::new(channel_id, rate, delay)
+    }
+}
+
+impl Unparse for HalfDuplexManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        write!(out, "{} {} {}", self.channel_id, self.rate, self.delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_bundle;
+
+    #[test]
+    fn booking_one_direction_reduces_the_other() {
+        // both directions over channel 7, a third contact over its own channel
+        let mut plan = parse_from_str::<NoManagement, HalfDuplexManager>(
+            "node 0 a
+            node 1 b
+            contact 0 1 0 100 7 10 1
+            contact 1 0 0 100 7 10 1
+            contact 0 1 200 300 8 10 1",
+        )
+        .unwrap();
+        share_channels(&mut plan.contacts);
+
+        let info = plan.contacts[0].info;
+        plan.contacts[0]
+            .manager
+            .schedule_tx(&info, 0.0, &make_bundle(1, 0, 400.0, 1000.0))
+            .unwrap();
+
+        assert_eq!(
+            plan.contacts[1].manager.remaining_volume(0),
+            600.0,
+            "TEST FAILED: The B to A contact should lose the volume booked from A to B."
+        );
+        assert_eq!(
+            plan.contacts[2].manager.remaining_volume(0),
+            1000.0,
+            "TEST FAILED: Contacts over another channel should not be affected."
+        );
+
+        let reverse = plan.contacts[1].info;
+        assert!(
+            plan.contacts[1]
+                .manager
+                .dry_run_tx(&reverse, 0.0, &make_bundle(0, 0, 700.0, 1000.0))
+                .is_none(),
+            "TEST FAILED: The channel cannot carry more than its volume."
+        );
+    }
+
+    #[test]
+    fn sharing_the_channels_twice_keeps_the_bookings() {
+        let mut plan = parse_from_str::<NoManagement, HalfDuplexManager>(
+            "node 0 a
+            node 1 b
+            contact 0 1 0 100 7 10 1
+            contact 1 0 0 100 7 10 1",
+        )
+        .unwrap();
+        share_channels(&mut plan.contacts);
+
+        let info = plan.contacts[0].info;
+        assert!(
+            plan.contacts[0]
+                .manager
+                .schedule_tx(&info, 0.0, &make_bundle(1, 0, 400.0, 1000.0))
+                .is_some(),
+            "TEST FAILED: The bundle fits in the channel."
+        );
+        share_channels(&mut plan.contacts);
+
+        assert!(
+            Rc::ptr_eq(
+                &plan.contacts[0].manager.channel,
+                &plan.contacts[1].manager.channel
+            ),
+            "TEST FAILED: Both directions should still share a channel."
+        );
+        assert_eq!(
+            plan.contacts[1].manager.remaining_volume(0),
+            600.0,
+            "TEST FAILED: The booked volume should be carried over once."
+        );
+    }
+}
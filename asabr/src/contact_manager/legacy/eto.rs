@@ -49,6 +49,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn unschedule_tx_is_refused() {
+        let mut manager = eto();
+        let contact = make_contact_info(C_START, C_END);
+        manager
+            .schedule_tx(&contact, C_START, &bp0(1000.0))
+            .unwrap();
+        assert!(
+            !manager.unschedule_tx(&bp0(1000.0)),
+            "TEST FAILED: ETO does not book volume, there is nothing to release."
+        );
+    }
+
     #[test]
     fn schedule_tx_always_returns_same_result() {
         let mut manager = eto();
@@ -14,6 +14,7 @@ mod tests {
     use super::{EVLManager, PBEVLManager, PEVLManager};
     use crate::contact_manager::ContactManager;
     use crate::contact_manager::legacy::test_helpers::*;
+    use crate::types::Date;
 
     fn evl() -> EVLManager {
         let mut manager = EVLManager::new(RATE, DELAY);
@@ -58,4 +59,104 @@ mod tests {
             "TEST FAILED: EVL tx_start should not be affected by queue occupancy."
         );
     }
+
+    #[test]
+    fn ramp_delays_tx_end_at_contact_start() {
+        let contact = make_contact_info(C_START, C_END);
+        let mut ramped = EVLManager::new(RATE, DELAY).with_ramp(2.0);
+        assert!(
+            ramped.try_init(&contact),
+            "TEST FAILED: The manager should accept the contact."
+        );
+        let flat = evl().dry_run_tx(&contact, C_START, &bp0(100.0)).unwrap();
+        let slow = ramped.dry_run_tx(&contact, C_START, &bp0(100.0)).unwrap();
+        assert!(
+            slow.tx_end > flat.tx_end,
+            "TEST FAILED: The ramp should slow down transmissions at contact start."
+        );
+        // 100 = 1000 * t^2 / (2 * 2.0)
+        let tolerance = if cfg!(feature = "small_floats") {
+            1e-6
+        } else {
+            1e-9
+        };
+        assert!(
+            (slow.tx_end - Date::powf(0.4, 0.5)).abs() < tolerance,
+            "TEST FAILED: tx_end should integrate the linear ramp."
+        );
+    }
+
+    #[test]
+    fn rate_profile_integrates_across_segments() {
+        let contact = make_contact_info(C_START, C_END);
+        let mut manager = EVLManager::new_with_rate_profile(
+            rate_profile(&[(0.0, 5.0, RATE), (5.0, C_END, 500.0)]),
+            DELAY,
+        );
+        assert!(manager.try_init(&contact));
+        // 5000 sent at full rate, the remaining 1000 at half rate
+        let data = manager.dry_run_tx(&contact, C_START, &bp0(6000.0)).unwrap();
+        assert_eq!(
+            data.tx_end, 7.0,
+            "TEST FAILED: tx_end should integrate the volume across the rate segments."
+        );
+        assert!(
+            manager
+                .dry_run_tx(&contact, C_START, &bp0(7501.0))
+                .is_none(),
+            "TEST FAILED: The contact volume should follow the rate profile."
+        );
+    }
+
+    #[test]
+    fn ramp_has_no_effect_after_warm_up() {
+        let contact = make_contact_info(C_START, C_END);
+        let mut ramped = EVLManager::new(RATE, DELAY).with_ramp(2.0);
+        assert!(
+            ramped.try_init(&contact),
+            "TEST FAILED: The manager should accept the contact."
+        );
+        assert_eq!(
+            ramped.dry_run_tx(&contact, 5.0, &bp0(100.0)),
+            evl().dry_run_tx(&contact, 5.0, &bp0(100.0)),
+            "TEST FAILED: The ramp should not affect transmissions after the warm-up."
+        );
+        assert!(
+            ramped
+                .dry_run_tx(&contact, C_START, &bp0(TOTAL_VOL))
+                .is_none(),
+            "TEST FAILED: The ramp should reduce the contact volume."
+        );
+    }
+
+    #[test]
+    fn detailed_dry_run_tells_the_reject_reason() {
+        use crate::contact_manager::TxReject;
+
+        let manager = evl();
+        let contact = make_contact_info(C_START, C_END);
+        let mut short_lived = bp0(1000.0);
+        short_lived.expiration = 1.5;
+
+        for (at_time, bundle, expected) in [
+            (C_START, bp0(TOTAL_VOL + 1.0), TxReject::VolumeExceeded),
+            // 5000 would be sent from 8.0 to 13.0
+            (8.0, bp0(5000.0), TxReject::DeadlineExceeded),
+            // sent by 1.0, received by 2.0
+            (C_START, short_lived, TxReject::Expired),
+        ] {
+            assert_eq!(
+                manager.dry_run_tx_detailed(&contact, at_time, &bundle),
+                Err(expected),
+                "TEST FAILED: Expected a {expected:?} rejection."
+            );
+        }
+        assert_eq!(
+            manager
+                .dry_run_tx_detailed(&contact, C_START, &bp0(1000.0))
+                .ok(),
+            manager.dry_run_tx(&contact, C_START, &bp0(1000.0)),
+            "TEST FAILED: The detailed dry run should match dry_run_tx."
+        );
+    }
 }
@@ -5,6 +5,187 @@ pub mod qd;
 #[cfg(test)]
 pub(crate) mod test_helpers;
 
+extern crate alloc;
+
+use alloc::{boxed::Box, vec::Vec};
+use core::any::Any;
+
+use crate::contact::ContactInfo;
+use crate::contact_manager::ManagerState;
+use crate::contact_manager::segmentation::{self, Segment};
+use crate::parse_single_tok;
+use crate::parsing::Trailing;
+use crate::types::{DataRate, Date, Duration, Float, Priority, Volume};
+
+/// A piecewise-constant data rate, as a list of contiguous rate segments.
+pub type RateProfile = Vec<Segment<DataRate>>;
+
+/// The keyword announcing the optional warm-up ramp of a legacy manager in a contact plan,
+/// e.g. `contact 0 1 0 10 evl 8 1 ramp 2`.
+#[derive(Clone, Copy, Debug)]
+pub struct RampKeyword;
+
+parse_single_tok!(RampKeyword);
+
+impl TryFrom<&str> for RampKeyword {
+    type Error = ();
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "ramp" => Ok(RampKeyword),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The parameters of a legacy manager in a contact plan, followed by the optional ramp.
+pub type Ramped<T> = Trailing<T, (RampKeyword, Duration)>;
+
+/// Returns the ramp of parsed parameters, zero if omitted.
+#[doc(hidden)]
+#[inline(always)]
+pub fn ramp_of<T>(params: &Ramped<T>) -> Duration {
+    params.1.map_or(0.0, |(_, ramp)| ramp)
+}
+
+/// Newton's method square root, `f64::sqrt` is not available without std.
+#[inline(always)]
+fn sqrt(value: Float) -> Float {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    let mut root = if value > 1.0 { value } else { 1.0 };
+    loop {
+        let next = 0.5 * (root + value / root);
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// Computes the transmission end time of a volume starting at `tx_start`, for a rate growing
+/// linearly from zero at `ramp_start` to `rate` at `ramp_start + ramp`, and constant afterwards.
+///
+/// # Arguments
+///
+/// * `rate` - The nominal data rate.
+/// * `ramp` - The warm-up duration (no warm-up if lower or equal to zero).
+/// * `ramp_start` - The start of the warm-up, i.e. the contact start.
+/// * `tx_start` - The transmission start time.
+/// * `volume` - The volume to transmit.
+///
+/// # Returns
+///
+/// The transmission end time, never reached if the rate is not positive.
+#[doc(hidden)]
+#[inline(always)]
+pub fn get_tx_end(
+    rate: DataRate,
+    ramp: Duration,
+    ramp_start: Date,
+    tx_start: Date,
+    volume: Volume,
+) -> Date {
+    if rate <= 0.0 {
+        return Date::INFINITY;
+    }
+    let ramp_end = ramp_start + ramp;
+    if ramp <= 0.0 || tx_start >= ramp_end {
+        return tx_start + volume / rate;
+    }
+    // the rate at time t is rate * (t - ramp_start) / ramp
+    let offset = tx_start - ramp_start;
+    let ramp_volume = rate * (ramp * ramp - offset * offset) / (2.0 * ramp);
+    if volume > ramp_volume {
+        return ramp_end + (volume - ramp_volume) / rate;
+    }
+    ramp_start + sqrt(offset * offset + 2.0 * ramp * volume / rate)
+}
+
+/// Computes the volume that can be transmitted between `ramp_start` and `end`, with the
+/// warm-up described in `get_tx_end`.
+#[doc(hidden)]
+#[inline(always)]
+pub fn get_tx_volume(rate: DataRate, ramp: Duration, ramp_start: Date, end: Date) -> Volume {
+    let duration = end - ramp_start;
+    if ramp <= 0.0 {
+        return duration * rate;
+    }
+    if duration <= ramp {
+        return rate * duration * duration / (2.0 * ramp);
+    }
+    rate * (duration - ramp / 2.0)
+}
+
+/// Returns the index of a priority in the per-priority arrays of a manager with `prio_count` levels.
+///
+/// Negative priorities are clamped to 0 (the lowest level), and priorities above the highest level
+/// to `prio_count - 1`. `prio_count` must be at least 1, the managers reject `P == 0` at compile
+/// time.
+#[doc(hidden)]
+#[inline(always)]
+pub fn priority_index(priority: Priority, prio_count: usize) -> usize {
+    (priority.max(0) as usize).min(prio_count - 1)
+}
+
+/// Computes the transmission end time of a volume starting at `tx_start` with a rate profile.
+///
+/// # Returns
+///
+/// The transmission end time, or `None` if the profile ends before the volume is transmitted.
+#[doc(hidden)]
+#[inline(always)]
+pub fn get_profile_tx_end(profile: &RateProfile, tx_start: Date, volume: Volume) -> Option<Date> {
+    segmentation::get_tx_end(profile, tx_start, volume, Date::INFINITY)
+}
+
+/// Computes the volume of a rate profile, and checks that the profile covers exactly the
+/// contact without gaps.
+///
+/// # Returns
+///
+/// The volume of the contact, or `None` if the profile does not match the contact.
+#[doc(hidden)]
+pub fn get_profile_volume(profile: &RateProfile, contact_data: &ContactInfo) -> Option<Volume> {
+    let mut time = contact_data.start;
+    for seg in profile {
+        if seg.start != time || seg.val <= 0.0 {
+            return None;
+        }
+        time = seg.end;
+    }
+    if time != contact_data.end || profile.is_empty() {
+        return None;
+    }
+    Some(segmentation::get_volume(
+        profile,
+        contact_data.start,
+        contact_data.end,
+    ))
+}
+
+/// Captures the queue of a legacy manager, a volume or a volume per priority.
+#[doc(hidden)]
+pub fn snapshot_queue<Q: Any>(queue_size: Q) -> ManagerState {
+    Box::new(queue_size)
+}
+
+/// Restores the queue of a legacy manager captured by `snapshot_queue`.
+///
+/// # Returns
+///
+/// Returns `true` if the state is a queue of the same type, `false` otherwise.
+#[doc(hidden)]
+pub fn restore_queue<Q: Any + Copy>(queue_size: &mut Q, state: &dyn Any) -> bool {
+    match state.downcast_ref::<Q>() {
+        Some(snapshot) => {
+            *queue_size = *snapshot;
+            true
+        }
+        None => false,
+    }
+}
+
 /// Generates a legacy volume management structure and a part of its implementation based on the provided parameters. This
 /// macro is called by the generate_prio_volume_manager macro.
 ///
@@ -31,6 +212,12 @@ macro_rules! generate_struct_management {
             queue_size: $crate::types::Volume,
             /// The total volume at initialization.
             original_volume: $crate::types::Volume,
+            /// The warm-up duration during which the rate grows linearly from zero to `rate`.
+            ramp: $crate::types::Duration,
+            /// The piecewise-constant rate replacing `rate` and `ramp`, if any.
+            rate_profile: Option<$crate::contact_manager::legacy::RateProfile>,
+            /// Whether the schedules overbooking the contact are rejected.
+            strict: bool,
         }
         impl $manager_name {
             #[doc = concat!( "Creates a new `", stringify!($manager_name),"`  with specified average rate and delay.")]
@@ -49,10 +236,35 @@ macro_rules! generate_struct_management {
                     delay,
                     queue_size: 0.0,
                     original_volume: 0.0,
+                    ramp: 0.0,
+                    rate_profile: None,
+                    strict: false,
                 }
             }
+
+            #[doc = concat!( "Creates a new `", stringify!($manager_name),"` with a piecewise-constant rate and a delay.")]
+            ///
+            /// The rate segments must exactly cover the contact without gaps, `new` behaves like a single
+            /// segment covering the whole contact.
+            ///
+            /// # Arguments
+            ///
+            /// * `rate_profile` - The rate segments of this contact.
+            /// * `delay` - The link delay for this contact.
+            ///
+            /// # Returns
+            ///
+            #[doc = concat!( " A new instance of  `", stringify!($manager_name),"`.")]
+            pub fn new_with_rate_profile(
+                rate_profile: $crate::contact_manager::legacy::RateProfile,
+                delay: $crate::types::Duration,
+            ) -> Self {
+                let mut manager = Self::new(rate_profile.first().map_or(0.0, |seg| seg.val), delay);
+                manager.rate_profile = Some(rate_profile);
+                manager
+            }
             #[inline(always)]
-            fn get_queue_size(&self, _bundle: &$crate::bundle::Bundle) -> $crate::types::Volume {
+            fn queue_size_of(&self, _bundle: &$crate::bundle::Bundle) -> $crate::types::Volume {
                     self.queue_size
             }
            #[inline(always)]
@@ -65,11 +277,50 @@ macro_rules! generate_struct_management {
                 self.queue_size -= bundle.size;
             }
             #[inline(always)]
+            fn clear_queue(&mut self) {
+                self.queue_size = 0.0;
+            }
+            #[inline(always)]
+            fn queue_sizes(&self) -> &[$crate::types::Volume] {
+                core::slice::from_ref(&self.queue_size)
+            }
+            #[inline(always)]
+            fn volume_limits(&self) -> &[$crate::types::Volume] {
+                core::slice::from_ref(&self.original_volume)
+            }
+            #[inline(always)]
+            fn prio_count(&self) -> Option<usize> {
+                None
+            }
+            #[inline(always)]
+            fn booked_volume(&self) -> $crate::types::Volume {
+                self.queue_size
+            }
+            #[inline(always)]
             fn get_budget(&self, _bundle: &$crate::bundle::Bundle) -> $crate::types::Volume  {
                return self.original_volume;
             }
+            #[inline(always)]
+            fn get_remaining(&self, _priority: $crate::types::Priority) -> $crate::types::Volume {
+                self.original_volume - self.queue_size
+            }
+        }
+        $crate::parse_transparent!($manager_name,$crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration)>);
+        impl $crate::parsing::Unparse for $manager_name {
+            fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+                if self.rate_profile.is_some() {
+                    return Err(core::fmt::Error);
+                }
+                write!(out, "{} {}", self.rate, self.delay)?;
+                self.unparse_ramp(out)
+            }
+        }
+        impl From<$crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration)>> for $manager_name {
+            fn from(params: $crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration)>) -> Self {
+                let ramp = $crate::contact_manager::legacy::ramp_of(&params);
+                Self::from(params.0).with_ramp(ramp)
+            }
         }
-        $crate::parse_transparent!($manager_name,($crate::types::DataRate,$crate::types::Duration));
         impl From<($crate::types::DataRate,$crate::types::Duration)> for $manager_name {
             fn from((rate,delay): ($crate::types::DataRate,$crate::types::Duration)) -> Self {
                 $manager_name::new(rate, delay)
@@ -78,19 +329,26 @@ macro_rules! generate_struct_management {
     };
     ($manager_name:ident, $prio_count:tt, false) => {
 
+        /// Macro-generated, `P` is the number of priority levels.
         #[derive(Debug)]
-        pub struct $manager_name {
+        pub struct $manager_name<const P: usize = $prio_count> {
             /// The data transmission rate.
             rate: $crate::types::DataRate,
             /// The transmission delay.
             delay: $crate::types::Duration,
             /// The volume scheduled for this contact for each priority.
-            queue_size: [$crate::types::Volume; $prio_count],
+            queue_size: [$crate::types::Volume; P],
             /// The total volume at initialization.
             original_volume: $crate::types::Volume,
+            /// The warm-up duration during which the rate grows linearly from zero to `rate`.
+            ramp: $crate::types::Duration,
+            /// The piecewise-constant rate replacing `rate` and `ramp`, if any.
+            rate_profile: Option<$crate::contact_manager::legacy::RateProfile>,
+            /// Whether the schedules overbooking the contact are rejected.
+            strict: bool,
         }
 
-        impl $manager_name {
+        impl<const P: usize> $manager_name<P> {
             #[doc = concat!( "Creates a new `", stringify!($manager_name),"`  with specified average rate and delay.")]
             ///
             /// # Arguments
@@ -102,61 +360,134 @@ macro_rules! generate_struct_management {
             ///
              #[doc = concat!( " A new instance of  `", stringify!($manager_name),"`.")]
             pub fn new(rate: $crate::types::DataRate, delay: $crate::types::Duration) -> Self {
+                const { assert!(P > 0, "a manager needs at least one priority level") };
                 Self {
                     rate,
                     delay,
-                    queue_size: [0.0; $prio_count],
+                    queue_size: [0.0; P],
                     original_volume: 0.0,
+                    ramp: 0.0,
+                    rate_profile: None,
+                    strict: false,
                 }
             }
 
+            #[doc = concat!( "Creates a new `", stringify!($manager_name),"` with a piecewise-constant rate and a delay.")]
+            ///
+            /// The rate segments must exactly cover the contact without gaps, `new` behaves like a single
+            /// segment covering the whole contact.
+            ///
+            /// # Arguments
+            ///
+            /// * `rate_profile` - The rate segments of this contact.
+            /// * `delay` - The link delay for this contact.
+            ///
+            /// # Returns
+            ///
+            #[doc = concat!( " A new instance of  `", stringify!($manager_name),"`.")]
+            pub fn new_with_rate_profile(
+                rate_profile: $crate::contact_manager::legacy::RateProfile,
+                delay: $crate::types::Duration,
+            ) -> Self {
+                let mut manager = Self::new(rate_profile.first().map_or(0.0, |seg| seg.val), delay);
+                manager.rate_profile = Some(rate_profile);
+                manager
+            }
+
             #[inline(always)]
-            fn get_queue_size(&self, bundle: &$crate::bundle::Bundle) -> $crate::types::Volume {
-                    self.queue_size[(bundle.priority as usize).min($prio_count - 1)]
+            fn queue_size_of(&self, bundle: &$crate::bundle::Bundle) -> $crate::types::Volume {
+                    self.queue_size[$crate::contact_manager::legacy::priority_index(bundle.priority, P)]
             }
             #[inline(always)]
             fn enqueue(&mut self, bundle: &$crate::bundle::Bundle)  {
-                for prio in 0..(bundle.priority as usize + 1).min($prio_count) {
+                for prio in 0..=$crate::contact_manager::legacy::priority_index(bundle.priority, P) {
                     self.queue_size[prio] += bundle.size;
                 }
             }
             #[allow(dead_code)]
             #[inline(always)]
             fn dequeue(&mut self, bundle: &$crate::bundle::Bundle)  {
-                for prio in 0..(bundle.priority as usize + 1).min($prio_count) {
+                for prio in 0..=$crate::contact_manager::legacy::priority_index(bundle.priority, P) {
                     self.queue_size[prio] -= bundle.size;
                 }
             }
             #[inline(always)]
+            fn clear_queue(&mut self) {
+                self.queue_size = [0.0; P];
+            }
+            #[inline(always)]
+            fn queue_sizes(&self) -> &[$crate::types::Volume] {
+                &self.queue_size
+            }
+            #[inline(always)]
+            fn volume_limits(&self) -> &[$crate::types::Volume] {
+                core::slice::from_ref(&self.original_volume)
+            }
+            #[inline(always)]
+            fn prio_count(&self) -> Option<usize> {
+                Some(P)
+            }
+            #[inline(always)]
+            fn booked_volume(&self) -> $crate::types::Volume {
+                // the lowest priority queue accounts for the bundles of every priority
+                self.queue_size[0]
+            }
+            #[inline(always)]
             fn get_budget(&self, _bundle: &$crate::bundle::Bundle) -> $crate::types::Volume  {
                return self.original_volume;
             }
+            #[inline(always)]
+            fn get_remaining(&self, priority: $crate::types::Priority) -> $crate::types::Volume {
+                self.original_volume - self.queue_size[$crate::contact_manager::legacy::priority_index(priority, P)]
+            }
         }
-        $crate::parse_transparent!($manager_name,($crate::types::DataRate,$crate::types::Duration));
-        impl From<($crate::types::DataRate,$crate::types::Duration)> for $manager_name {
+        $crate::parse_transparent!($manager_name<P>,$crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration)>,const P: usize);
+        impl<const P: usize> $crate::parsing::Unparse for $manager_name<P> {
+            fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+                if self.rate_profile.is_some() {
+                    return Err(core::fmt::Error);
+                }
+                write!(out, "{} {}", self.rate, self.delay)?;
+                self.unparse_ramp(out)
+            }
+        }
+        impl<const P: usize> From<$crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration)>> for $manager_name<P> {
+            fn from(params: $crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration)>) -> Self {
+                let ramp = $crate::contact_manager::legacy::ramp_of(&params);
+                Self::from(params.0).with_ramp(ramp)
+            }
+        }
+        impl<const P: usize> From<($crate::types::DataRate,$crate::types::Duration)> for $manager_name<P> {
             fn from((rate,delay): ($crate::types::DataRate,$crate::types::Duration)) -> Self {
-                $manager_name::new(rate, delay)
+                Self::new(rate, delay)
             }
         }
     };
     // if the priority count is different than one, queue_size is an array
     ($manager_name:ident, $prio_count:tt, true) => {
 
+        /// Macro-generated, `P` is the number of priority levels.
         #[derive(Debug)]
-        pub struct $manager_name {
+        pub struct $manager_name<const P: usize = $prio_count> {
             /// The data transmission rate.
             rate: $crate::types::DataRate,
             /// The transmission delay.
             delay: $crate::types::Duration,
             /// The volume scheduled for this contact for each priority.
-            queue_size: [$crate::types::Volume; $prio_count],
+            queue_size: [$crate::types::Volume; P],
             /// The budget for each priority.
-            budgets: [$crate::types::Volume; $prio_count],
+            budgets: [$crate::types::Volume; P],
             /// The total volume at initialization.
             original_volume: $crate::types::Volume,
+            /// The warm-up duration during which the rate grows linearly from zero to `rate`.
+            ramp: $crate::types::Duration,
+            /// The piecewise-constant rate replacing `rate` and `ramp`, if any.
+            rate_profile: Option<$crate::contact_manager::legacy::RateProfile>,
+            /// Whether the schedules overbooking the contact are rejected.
+            strict: bool,
         }
 
-        impl $manager_name {
+        impl<const P: usize> $manager_name<P> {
             #[doc = concat!( "Creates a new `", stringify!($manager_name),"`  with specified average rate and delay.")]
             ///
             /// # Arguments
@@ -168,42 +499,115 @@ macro_rules! generate_struct_management {
             /// # Returns
             ///
              #[doc = concat!( " A new instance of  `", stringify!($manager_name),"`.")]
-            pub fn new(rate: $crate::types::DataRate, delay: $crate::types::Duration, budgets: [$crate::types::Volume; $prio_count] ) -> Self {
+            pub fn new(rate: $crate::types::DataRate, delay: $crate::types::Duration, budgets: [$crate::types::Volume; P] ) -> Self {
+                const { assert!(P > 0, "a manager needs at least one priority level") };
                 Self {
                     rate,
                     delay,
-                    queue_size: [0.0; $prio_count],
+                    queue_size: [0.0; P],
                     budgets,
                     original_volume: 0.0,
+                    ramp: 0.0,
+                    rate_profile: None,
+                    strict: false,
                 }
             }
 
+            #[doc = concat!( "Creates a new `", stringify!($manager_name),"` with a piecewise-constant rate and a delay.")]
+            ///
+            /// The rate segments must exactly cover the contact without gaps, `new` behaves like a single
+            /// segment covering the whole contact.
+            ///
+            /// # Arguments
+            ///
+            /// * `rate_profile` - The rate segments of this contact.
+            /// * `delay` - The link delay for this contact.
+            /// * `budgets` - The budget for each priority.
+            ///
+            /// # Returns
+            ///
+            #[doc = concat!( " A new instance of  `", stringify!($manager_name),"`.")]
+            pub fn new_with_rate_profile(
+                rate_profile: $crate::contact_manager::legacy::RateProfile,
+                delay: $crate::types::Duration,
+                budgets: [$crate::types::Volume; P],
+            ) -> Self {
+                let mut manager =
+                    Self::new(rate_profile.first().map_or(0.0, |seg| seg.val), delay, budgets);
+                manager.rate_profile = Some(rate_profile);
+                manager
+            }
+
             #[inline(always)]
-            fn get_queue_size(&self, bundle: &$crate::bundle::Bundle) -> $crate::types::Volume {
-                    self.queue_size[(bundle.priority as usize).min($prio_count - 1)]
+            fn queue_size_of(&self, bundle: &$crate::bundle::Bundle) -> $crate::types::Volume {
+                    self.queue_size[$crate::contact_manager::legacy::priority_index(bundle.priority, P)]
             }
             #[inline(always)]
             fn enqueue(&mut self, bundle: &$crate::bundle::Bundle)  {
-                for prio in 0..(bundle.priority as usize + 1).min($prio_count) {
+                for prio in 0..=$crate::contact_manager::legacy::priority_index(bundle.priority, P) {
                     self.queue_size[prio] += bundle.size;
                 }
             }
             #[allow(dead_code)]
             #[inline(always)]
             fn dequeue(&mut self, bundle: &$crate::bundle::Bundle)  {
-                for prio in 0..(bundle.priority as usize + 1).min($prio_count) {
+                for prio in 0..=$crate::contact_manager::legacy::priority_index(bundle.priority, P) {
                     self.queue_size[prio] -= bundle.size;
                 }
             }
             #[inline(always)]
+            fn clear_queue(&mut self) {
+                self.queue_size = [0.0; P];
+            }
+            #[inline(always)]
+            fn queue_sizes(&self) -> &[$crate::types::Volume] {
+                &self.queue_size
+            }
+            #[inline(always)]
+            fn volume_limits(&self) -> &[$crate::types::Volume] {
+                &self.budgets
+            }
+            #[inline(always)]
+            fn prio_count(&self) -> Option<usize> {
+                Some(P)
+            }
+            #[inline(always)]
+            fn booked_volume(&self) -> $crate::types::Volume {
+                // the lowest priority queue accounts for the bundles of every priority
+                self.queue_size[0]
+            }
+            #[inline(always)]
             fn get_budget(&self, bundle: &$crate::bundle::Bundle) -> $crate::types::Volume  {
-               return self.budgets[(bundle.priority as usize).min($prio_count - 1)];
+               return self.budgets[$crate::contact_manager::legacy::priority_index(bundle.priority, P)];
+            }
+            #[inline(always)]
+            fn get_remaining(&self, priority: $crate::types::Priority) -> $crate::types::Volume {
+                let prio = $crate::contact_manager::legacy::priority_index(priority, P);
+                self.budgets[prio] - self.queue_size[prio]
+            }
+        }
+        $crate::parse_transparent!($manager_name<P>,$crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration,[$crate::types::Volume;P])>,const P: usize);
+        impl<const P: usize> $crate::parsing::Unparse for $manager_name<P> {
+            fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+                if self.rate_profile.is_some() {
+                    return Err(core::fmt::Error);
+                }
+                write!(out, "{} {}", self.rate, self.delay)?;
+                for budget in &self.budgets {
+                    write!(out, " {budget}")?;
+                }
+                self.unparse_ramp(out)
+            }
+        }
+        impl<const P: usize> From<$crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration,[$crate::types::Volume;P])>> for $manager_name<P> {
+            fn from(params: $crate::contact_manager::legacy::Ramped<($crate::types::DataRate,$crate::types::Duration,[$crate::types::Volume;P])>) -> Self {
+                let ramp = $crate::contact_manager::legacy::ramp_of(&params);
+                Self::from(params.0).with_ramp(ramp)
             }
         }
-        $crate::parse_transparent!($manager_name,($crate::types::DataRate,$crate::types::Duration,[$crate::types::Volume;$prio_count]));
-        impl From<($crate::types::DataRate,$crate::types::Duration,[$crate::types::Volume;$prio_count])> for $manager_name {
-            fn from((rate,delay,budgets): ($crate::types::DataRate,$crate::types::Duration,[$crate::types::Volume;$prio_count])) -> Self {
-                $manager_name::new(rate, delay, budgets)
+        impl<const P: usize> From<($crate::types::DataRate,$crate::types::Duration,[$crate::types::Volume;P])> for $manager_name<P> {
+            fn from((rate,delay,budgets): ($crate::types::DataRate,$crate::types::Duration,[$crate::types::Volume;P])) -> Self {
+                Self::new(rate, delay, budgets)
             }
         }
     };
@@ -241,16 +645,114 @@ macro_rules! generate_manual_enqueue {
 /// - `$auto_update`: A flag (`true` or `false`) that specifies if the volume must be updated by the manager
 ///   or manually (like for ETO), this impact the $auto_update behavior, if set to fase, the booked volume is
 ///   considered as real time queue occupancy.
-/// - `$prio_count`: The number of priority levels. A value of `1` means no priority logic is applied.
+/// - `$prio_count`: The number of priority levels. A value of `1` means no priority logic is applied, otherwise
+///   the structure takes the number of levels as a const generic `P` with `$prio_count` as default value.
 /// - `$with_budget`: A flag (`true` or `false`) to conditionnally add budgets (for priorities only).
 #[macro_export]
 macro_rules! generate_prio_volume_manager {
 
+    ($manager_name:ident, $add_delay:tt, $auto_update:tt, 1, $with_budget:tt)  => {
+        $crate::generate_struct_management!($manager_name, 1, $with_budget);
+        $crate::generate_volume_manager_impl!([], $manager_name, $manager_name, $add_delay, $auto_update);
+    };
     ($manager_name:ident, $add_delay:tt, $auto_update:tt, $prio_count:tt, $with_budget:tt)  => {
-
         $crate::generate_struct_management!($manager_name, $prio_count, $with_budget);
+        $crate::generate_volume_manager_impl!([const P: usize], $manager_name, $manager_name<P>, $add_delay, $auto_update);
+    };
+}
+
+/// Generates the implementation shared by all the legacy volume managers. This macro is called by the
+/// generate_prio_volume_manager macro.
+///
+/// # Arguments
+///
+/// - `[$generics]`: The generic parameters of the implementation (e.g. `[const P: usize]`).
+/// - `$manager_name`: The name of the generated structure, for the documentation.
+/// - `$manager_type`: The generated structure with its generic arguments (e.g. `PQDManager<P>`).
+/// - `$add_delay` and `$auto_update`: See generate_prio_volume_manager.
+#[macro_export]
+macro_rules! generate_volume_manager_impl {
+    ([$($generics:tt)*], $manager_name:ident, $manager_type:ty, $add_delay:tt, $auto_update:tt) => {
+        impl<$($generics)*> $manager_type {
+            /// Sets a warm-up ramp: during `[start, start + ramp]` of the contact, the effective rate grows
+            /// linearly from zero to the nominal rate. The ramp has no effect with a rate profile.
+            ///
+            /// # Arguments
+            ///
+            /// * `ramp` - The duration of the warm-up.
+            ///
+            /// # Returns
+            ///
+            #[doc = concat!( " The updated `", stringify!($manager_name),"`.")]
+            pub fn with_ramp(mut self, ramp: $crate::types::Duration) -> Self {
+                self.ramp = ramp;
+                self
+            }
 
-        impl $crate::contact_manager::ContactManager for $manager_name {
+            /// Writes the optional ramp of the contact plan syntax, nothing without ramp.
+            fn unparse_ramp<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+                if self.ramp != 0.0 {
+                    write!(out, " ramp {}", self.ramp)?;
+                }
+                Ok(())
+            }
+
+            /// Sets the strict mode: a schedule booking more than the volume of the contact is
+            /// rejected, the state being left unchanged. Otherwise, the higher priorities can
+            /// overbook the contact, see `overbooked_volume`. Only the managers updating the queue
+            /// volume on schedule book volume.
+            ///
+            /// # Arguments
+            ///
+            /// * `strict` - Whether the overbooking schedules are rejected.
+            ///
+            /// # Returns
+            ///
+            #[doc = concat!( " The updated `", stringify!($manager_name),"`.")]
+            pub fn with_strict(mut self, strict: bool) -> Self {
+                self.strict = strict;
+                self
+            }
+
+            /// Returns the volume booked beyond the volume of the contact, by the priorities
+            /// allowed to preempt the lower ones (never in strict mode).
+            pub fn overbooked_volume(&self) -> $crate::types::Volume {
+                $crate::types::Volume::max(0.0, self.booked_volume() - self.original_volume)
+            }
+
+            /// Returns `true` if booking `bundle` is rejected by the strict mode.
+            #[inline(always)]
+            fn rejects_overbooking(&self, bundle: &$crate::bundle::Bundle) -> bool {
+                $auto_update
+                    && self.strict
+                    && self.booked_volume() + bundle.size > self.original_volume
+            }
+
+            /// Computes when `volume` is drained if the transmission starts at `start`, the ramp
+            /// starting at `ramp_start` (the contact start).
+            #[inline(always)]
+            fn get_drain_end(
+                &self,
+                ramp_start: $crate::types::Date,
+                start: $crate::types::Date,
+                volume: $crate::types::Volume,
+            ) -> Option<$crate::types::Date> {
+                match &self.rate_profile {
+                    None => Some($crate::contact_manager::legacy::get_tx_end(
+                        self.rate,
+                        self.ramp,
+                        ramp_start,
+                        start,
+                        volume,
+                    )),
+                    Some(profile) => {
+                        $crate::contact_manager::legacy::get_profile_tx_end(profile, start, volume)
+                    }
+                }
+            }
+        }
+
+        impl<$($generics)*> $crate::contact_manager::ContactManager for $manager_type {
             #[cfg(feature = "manual_queueing")]
             $crate::generate_manual_enqueue!($auto_update);
 
@@ -273,18 +775,46 @@ macro_rules! generate_prio_volume_manager {
                 at_time: $crate::types::Date,
                 bundle: &$crate::bundle::Bundle,
             ) -> Option<$crate::contact_manager::ContactManagerTxData> {
+                self.dry_run_tx_detailed(contact_data, at_time, bundle).ok()
+            }
+
+            /// Simulates the transmission of a bundle like `dry_run_tx`, telling why it is rejected.
+            ///
+            /// # Arguments
+            ///
+            /// * `contact_data` - Reference to the contact information.
+            /// * `at_time` - The current time for scheduling purposes.
+            /// * `bundle` - The bundle to be transmitted.
+            ///
+            /// # Returns
+            ///
+            /// Returns `ContactManagerTxData` with transmission start and end times, or the `TxReject` reason:
+            /// `VolumeExceeded` if the budget is exhausted, `DeadlineExceeded` if the transmission ends after
+            /// the contact, `Expired` if the bundle arrives after its expiration.
+            fn dry_run_tx_detailed(
+                &self,
+                contact_data: &$crate::contact::ContactInfo,
+                at_time: $crate::types::Date,
+                bundle: &$crate::bundle::Bundle,
+            ) -> Result<$crate::contact_manager::ContactManagerTxData, $crate::contact_manager::TxReject> {
+                use $crate::contact_manager::TxReject;
 
                 // This function call should be expanded at compile time
-                let queue_size = self.get_queue_size(&bundle);
+                let queue_size = self.queue_size_of(&bundle);
 
                 if bundle.size > self.get_budget(&bundle) - queue_size {
-                    return None;
+                    return Err(TxReject::VolumeExceeded);
+                }
+                if self.rejects_overbooking(bundle) {
+                    return Err(TxReject::VolumeExceeded);
                 }
 
                 let mut contact_start = contact_data.start;
                 // add_delay case 1 : if not eto, we push the eto from the contact start time
                 if ($add_delay && $auto_update) {
-                    contact_start += queue_size / self.rate;
+                    contact_start = self
+                        .get_drain_end(contact_data.start, contact_start, queue_size)
+                        .ok_or(TxReject::DeadlineExceeded)?;
                 }
                 let mut tx_start = if (contact_start > at_time) {
                     contact_start
@@ -294,14 +824,34 @@ macro_rules! generate_prio_volume_manager {
 
                 // add_delay case 2 : eto, bundles are still in queue
                 if ($add_delay && !$auto_update) {
-                    tx_start += queue_size / self.rate;
+                    tx_start = self
+                        .get_drain_end(contact_data.start, tx_start, queue_size)
+                        .ok_or(TxReject::DeadlineExceeded)?;
                 }
 
-                let tx_end = tx_start + bundle.size / self.rate;
+                let tx_end = match &self.rate_profile {
+                    None => $crate::contact_manager::legacy::get_tx_end(
+                        self.rate,
+                        self.ramp,
+                        contact_data.start,
+                        tx_start,
+                        bundle.size,
+                    ),
+                    Some(profile) => $crate::contact_manager::legacy::get_profile_tx_end(
+                        profile,
+                        tx_start,
+                        bundle.size,
+                    )
+                    .ok_or(TxReject::DeadlineExceeded)?,
+                };
                 if tx_end > contact_data.end {
-                    return None;
+                    return Err(TxReject::DeadlineExceeded);
                 }
-                Some($crate::contact_manager::ContactManagerTxData {
+                // the bundle must not arrive after its expiration
+                if self.delay + tx_end > bundle.expiration {
+                    return Err(TxReject::Expired);
+                }
+                Ok($crate::contact_manager::ContactManagerTxData {
                     tx_start,
                     tx_end,
                     expiration: contact_data.end,
@@ -331,13 +881,64 @@ macro_rules! generate_prio_volume_manager {
             ) -> Option<$crate::contact_manager::ContactManagerTxData> {
                 let data = self.dry_run_tx(contact_data, at_time, bundle)?;
                 // Conditionally update queue size based on $auto_update
-                // Can overflow with overbooking
+                // Can overflow with overbooking, unless strict
                 if $auto_update {
                     self.enqueue(bundle);
                 }
                 return Some(data);
             }
 
+            /// Books the volume of a bundle whose dry run output is `data`, without running it again.
+            fn schedule_tx_from_dry_run(
+                &mut self,
+                _contact_data: &$crate::contact_manager::ContactInfo,
+                _at_time: $crate::types::Date,
+                bundle: &$crate::bundle::Bundle,
+                data: $crate::contact_manager::ContactManagerTxData,
+            ) -> Option<$crate::contact_manager::ContactManagerTxData> {
+                if self.rejects_overbooking(bundle) {
+                    return None;
+                }
+                if $auto_update {
+                    self.enqueue(bundle);
+                }
+                return Some(data);
+            }
+
+            /// Releases the volume booked by a previous `schedule_tx` call for this bundle.
+            ///
+            #[doc = concat!( "Only managers updating the queue volume can release it: ", stringify!($auto_update),"`.")]
+            ///
+            /// # Arguments
+            ///
+            /// * `bundle` - The bundle whose transmission was scheduled.
+            ///
+            /// # Returns
+            ///
+            /// Returns `true` if the volume was released, `false` if the manager does not book volume or if
+            /// less volume than the bundle size is booked at its priority.
+            fn unschedule_tx(&mut self, bundle: &$crate::bundle::Bundle) -> bool {
+                if !$auto_update || self.queue_size_of(bundle) < bundle.size {
+                    return false;
+                }
+                self.dequeue(bundle);
+                true
+            }
+
+            /// Returns the volume that can still be booked by bundles of a given priority, i.e. the budget of
+            /// the priority minus the volume already booked at or above this priority.
+            ///
+            /// # Arguments
+            ///
+            /// * `priority` - The priority of the bundles to consider.
+            ///
+            /// # Returns
+            ///
+            /// The remaining volume for `priority` (zero if the contact is overbooked).
+            fn remaining_volume(&self, priority: $crate::types::Priority) -> $crate::types::Volume {
+                $crate::types::Volume::max(0.0, self.get_remaining(priority))
+            }
+
             /// Initializes the segmentation manager by checking that rate and delay intervals have no gaps.
             ///
             /// # Arguments
@@ -346,22 +947,84 @@ macro_rules! generate_prio_volume_manager {
             ///
             /// # Returns
             ///
-            /// Returns `true` if initialization is successful, or `false` if there are gaps in the intervals.
+            /// Returns `true` if initialization is successful, or `false` if the rate is not positive,
+            /// the delay is negative, or there are gaps in the intervals.
             fn try_init(&mut self, contact_data: &$crate::contact::ContactInfo) -> bool {
-                self.original_volume = (contact_data.end - contact_data.start) * self.rate;
+                if self.rate <= 0.0 || self.delay < 0.0 {
+                    return false;
+                }
+                self.original_volume = match &self.rate_profile {
+                    None => $crate::contact_manager::legacy::get_tx_volume(
+                        self.rate,
+                        self.ramp,
+                        contact_data.start,
+                        contact_data.end,
+                    ),
+                    Some(profile) => {
+                        match $crate::contact_manager::legacy::get_profile_volume(profile, contact_data) {
+                            Some(volume) => volume,
+                            None => return false,
+                        }
+                    }
+                };
                 true
             }
 
+            /// Releases the booked volume and initializes the manager again.
+            ///
+            /// # Arguments
+            ///
+            /// * `contact_data` - Reference to the contact information.
+            ///
+            /// # Returns
+            ///
+            /// Returns `true` if the initialization is consistent.
+            fn reset(&mut self, contact_data: &$crate::contact::ContactInfo) -> bool {
+                self.clear_queue();
+                self.try_init(contact_data)
+            }
+
+            /// The number of priority levels, if the manager distinguishes priorities.
+            fn priority_levels(&self) -> Option<usize> {
+                self.prio_count()
+            }
+
+            /// Captures the booked volume.
+            fn snapshot(&self) -> $crate::contact_manager::ManagerState {
+                $crate::contact_manager::legacy::snapshot_queue(self.queue_size)
+            }
+
+            /// Restores the booked volume captured by `snapshot`.
+            fn restore(&mut self, state: &dyn core::any::Any) -> bool {
+                $crate::contact_manager::legacy::restore_queue(&mut self.queue_size, state)
+            }
+
             /// Returns the original volume of the object.
             ///
             /// # Returns
             ///
             /// A `Volume` representing the original volume.
-            #[cfg(feature = "first_depleted")]
             fn get_original_volume(&self) -> $crate::types::Volume {
                 self.original_volume
             }
+
+            /// Returns the volume booked at each priority, a single value if the manager does not
+            /// distinguish priorities.
+            fn get_queue_size(&self) -> &[$crate::types::Volume] {
+                self.queue_sizes()
+            }
+
+            /// Returns the maximum volume each priority can book, i.e. the budgets, or a single value,
+            /// the original volume, if the priorities are not budgeted.
+            fn get_mav(&self) -> &[$crate::types::Volume] {
+                self.volume_limits()
+            }
+
+            /// Returns the link delay.
+            fn get_delay(&self) -> $crate::types::Duration {
+                self.delay
+            }
         }
 
-    }
+    };
 }
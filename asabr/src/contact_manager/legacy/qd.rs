@@ -52,6 +52,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn earliest_tx_start_reflects_the_queue_delay() {
+        let mut manager = qd();
+        let contact = make_contact_info(C_START, C_END);
+        assert_eq!(
+            manager.earliest_tx_start(&contact, C_START, &bp0(100.0)),
+            Some(C_START),
+            "TEST FAILED: An empty queue should not delay the transmission."
+        );
+
+        manager
+            .schedule_tx(&contact, C_START, &bp0(2000.0))
+            .unwrap();
+        assert_eq!(
+            manager.earliest_tx_start(&contact, C_START, &bp0(100.0)),
+            Some(2.0),
+            "TEST FAILED: The transmission should start once the queue is drained."
+        );
+        assert_eq!(
+            manager.earliest_tx_start(&contact, C_START, &bp0(TOTAL_VOL)),
+            None,
+            "TEST FAILED: A bundle exceeding the remaining volume cannot start."
+        );
+    }
+
+    #[test]
+    fn queue_drain_integrates_the_ramp() {
+        let contact = make_contact_info(C_START, C_END);
+        let mut manager = QDManager::new(RATE, DELAY).with_ramp(2.0);
+        assert!(
+            manager.try_init(&contact),
+            "TEST FAILED: The manager should accept the contact."
+        );
+
+        manager
+            .schedule_tx(&contact, C_START, &bp0(1000.0))
+            .unwrap();
+        // the first 1000 units take the whole warm-up, instead of 1s at the nominal rate
+        let data = manager.dry_run_tx(&contact, C_START, &bp0(100.0)).unwrap();
+        assert_eq!(
+            data.tx_start, 2.0,
+            "TEST FAILED: The queue should drain at the ramped rate."
+        );
+    }
+
     #[test]
     fn late_arriving_bundle_ignores_queue_shift() {
         let mut manager = qd();
@@ -80,4 +125,79 @@ mod tests {
             "TEST FAILED: Bundle should not fit when queue shift pushes tx_end past contact end."
         );
     }
+
+    #[test]
+    fn five_priority_levels_cascade_down() {
+        let mut manager = PQDManager::<5>::new(RATE, DELAY);
+        let contact = make_contact_info(C_START, C_END);
+        assert!(
+            manager.try_init(&contact),
+            "TEST FAILED: The manager should accept the contact."
+        );
+        manager
+            .schedule_tx(&contact, C_START, &make_bundle(1000.0, 3))
+            .unwrap();
+        for priority in 0..=3 {
+            assert_eq!(
+                manager.remaining_volume(priority),
+                TOTAL_VOL - 1000.0,
+                "TEST FAILED: A p3 booking should consume the volume of p{priority}."
+            );
+        }
+        assert_eq!(
+            manager.remaining_volume(4),
+            TOTAL_VOL,
+            "TEST FAILED: A p3 booking should not consume the p4 volume."
+        );
+    }
+
+    #[test]
+    fn out_of_range_priorities_are_clamped() {
+        let mut manager = PQDManager::<5>::new(RATE, DELAY);
+        let contact = make_contact_info(C_START, C_END);
+        assert!(
+            manager.try_init(&contact),
+            "TEST FAILED: The manager should accept the contact."
+        );
+        manager
+            .schedule_tx(&contact, C_START, &make_bundle(1000.0, 9))
+            .unwrap();
+        assert_eq!(
+            manager.remaining_volume(4),
+            TOTAL_VOL - 1000.0,
+            "TEST FAILED: A p9 bundle should be booked at the highest level (p4)."
+        );
+        manager
+            .schedule_tx(&contact, C_START, &make_bundle(1000.0, -1))
+            .unwrap();
+        assert_eq!(
+            (manager.remaining_volume(0), manager.remaining_volume(1)),
+            (TOTAL_VOL - 2000.0, TOTAL_VOL - 1000.0),
+            "TEST FAILED: A negative priority should be booked at the lowest level (p0)."
+        );
+    }
+
+    #[test]
+    fn five_budgets_are_parsed() {
+        use crate::contact_plan::asabr_file_lexer::parse_from_iter;
+        use crate::node_manager::none::NoManagement;
+
+        let plan = parse_from_iter::<NoManagement, PBQDManager<5>, _>(
+            [
+                "node 0 a",
+                "node 1 b",
+                "contact 0 1 0 10 1000 1 100 200 300 400 10000",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let manager = &plan.contacts[0].manager;
+        for (priority, budget) in [100.0, 200.0, 300.0, 400.0, 10000.0].iter().enumerate() {
+            assert_eq!(
+                manager.remaining_volume(priority as i8),
+                *budget,
+                "TEST FAILED: Budget of p{priority} should be {budget}."
+            );
+        }
+    }
 }
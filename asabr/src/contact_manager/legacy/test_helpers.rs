@@ -1,5 +1,7 @@
 use crate::bundle::Bundle;
 use crate::contact::ContactInfo;
+use crate::contact_manager::legacy::RateProfile;
+use crate::contact_manager::segmentation::Segment;
 use crate::types::{DataRate, Date, Duration, Volume};
 
 pub(crate) const RATE: DataRate = 1000.0;
@@ -29,6 +31,13 @@ pub(crate) fn make_bundle(size: Volume, priority: i8) -> Bundle {
     }
 }
 
+pub(crate) fn rate_profile(segments: &[(Date, Date, DataRate)]) -> RateProfile {
+    segments
+        .iter()
+        .map(|&(start, end, val)| Segment { start, end, val })
+        .collect()
+}
+
 pub(crate) fn bp0(size: Volume) -> Bundle {
     make_bundle(size, 0)
 }
@@ -53,6 +62,29 @@ macro_rules! generate_common_tests {
             );
         }
 
+        #[test]
+        fn try_init_rejects_zero_rate_and_negative_delay() {
+            let contact = make_contact_info(C_START, C_END);
+            let mut manager = <$manager_type>::new(0.0, DELAY);
+            assert!(
+                !manager.try_init(&contact),
+                "TEST FAILED: Expected a zero rate to be rejected."
+            );
+            let mut manager = <$manager_type>::new(RATE, -1.0);
+            assert!(
+                !manager.try_init(&contact),
+                "TEST FAILED: Expected a negative delay to be rejected."
+            );
+            assert!(
+                $crate::contact::Contact::<$crate::node_manager::none::NoManagement, _>::try_new(
+                    contact,
+                    <$manager_type>::new(0.0, DELAY),
+                )
+                .is_none(),
+                "TEST FAILED: Expected no contact for a zero rate."
+            );
+        }
+
         #[test]
         fn dry_run_volume_boundary() {
             let manager = ($manager_fn)();
@@ -81,6 +113,62 @@ macro_rules! generate_common_tests {
             );
         }
 
+        #[test]
+        fn dry_run_rejects_arrival_after_expiration() {
+            let manager = ($manager_fn)();
+            let contact = make_contact_info(C_START, C_END);
+            let mut bundle = bp0(100.0);
+            // tx_end = 0.1, the delay pushes the arrival to 1.1
+            bundle.expiration = 1.0;
+            assert!(
+                manager.dry_run_tx(&contact, C_START, &bundle).is_none(),
+                "TEST FAILED: Expected None when the delay pushes the arrival past expiration."
+            );
+            bundle.expiration = 1.1;
+            assert!(
+                manager.dry_run_tx(&contact, C_START, &bundle).is_some(),
+                "TEST FAILED: Expected Some when the arrival is exactly at expiration."
+            );
+        }
+
+        #[test]
+        fn single_segment_profile_matches_scalar_rate() {
+            let contact = make_contact_info(C_START, C_END);
+            let mut scalar = ($manager_fn)();
+            let mut profiled = <$manager_type>::new_with_rate_profile(
+                rate_profile(&[(C_START, C_END, RATE)]),
+                DELAY,
+            );
+            assert!(
+                profiled.try_init(&contact),
+                "TEST FAILED: A profile covering the contact should be accepted."
+            );
+            for (at_time, size) in [(C_START, 1000.0), (2.5, 3000.0), (C_START, 4000.0), (9.0, 2000.0)] {
+                assert_eq!(
+                    scalar.dry_run_tx(&contact, at_time, &bp0(size)),
+                    profiled.dry_run_tx(&contact, at_time, &bp0(size)),
+                    "TEST FAILED: Dry runs should match for a single-segment profile."
+                );
+                assert_eq!(
+                    scalar.schedule_tx(&contact, at_time, &bp0(size)),
+                    profiled.schedule_tx(&contact, at_time, &bp0(size)),
+                    "TEST FAILED: Schedules should match for a single-segment profile."
+                );
+            }
+        }
+
+        #[test]
+        fn rate_profile_with_gap_is_rejected() {
+            let mut manager = <$manager_type>::new_with_rate_profile(
+                rate_profile(&[(C_START, 4.0, RATE), (5.0, C_END, RATE)]),
+                DELAY,
+            );
+            assert!(
+                !manager.try_init(&make_contact_info(C_START, C_END)),
+                "TEST FAILED: A profile with a gap should be rejected."
+            );
+        }
+
         #[test]
         fn dry_run_makes_same_results() {
             let manager = ($manager_fn)();
@@ -198,6 +286,213 @@ macro_rules! generate_auto_update_tests {
             );
         }
 
+        #[test]
+        fn higher_priority_overbooking_is_counted() {
+            let mut manager = ($p_manager_fn)();
+            let contact = make_contact_info(C_START, C_END);
+            manager
+                .schedule_tx(&contact, C_START, &bp0(TOTAL_VOL - 1000.0))
+                .unwrap();
+            assert_eq!(
+                manager.overbooked_volume(),
+                0.0,
+                "TEST FAILED: The contact is not overbooked yet."
+            );
+            assert!(
+                manager
+                    .schedule_tx(&contact, C_START, &bp2(5000.0))
+                    .is_some(),
+                "TEST FAILED: Expected p2 to preempt the p0 volume."
+            );
+            assert_eq!(
+                manager.overbooked_volume(),
+                4000.0,
+                "TEST FAILED: Expected the volume booked beyond the contact to be counted."
+            );
+        }
+
+        #[test]
+        fn strict_mode_rejects_overbooking() {
+            let mut manager = ($p_manager_fn)().with_strict(true);
+            let contact = make_contact_info(C_START, C_END);
+            manager
+                .schedule_tx(&contact, C_START, &bp0(TOTAL_VOL - 1000.0))
+                .unwrap();
+            let queue_size = manager.queue_size;
+            assert!(
+                manager
+                    .schedule_tx(&contact, C_START, &bp2(5000.0))
+                    .is_none(),
+                "TEST FAILED: Expected the overbooking schedule to be rejected."
+            );
+            assert_eq!(
+                (manager.queue_size, manager.overbooked_volume()),
+                (queue_size, 0.0),
+                "TEST FAILED: A rejected schedule should leave the queue unchanged."
+            );
+            assert!(
+                manager
+                    .schedule_tx(&contact, C_START, &bp2(1000.0))
+                    .is_some(),
+                "TEST FAILED: Expected the remaining volume to be bookable."
+            );
+        }
+
+        #[test]
+        fn same_prio_saturation() {
+            let mut manager = ($p_manager_fn)();
+            let contact = make_contact_info(C_START, C_END);
+            for i in 0..10 {
+                assert!(
+                    manager
+                        .schedule_tx(&contact, C_START, &bp2(1000.0))
+                        .is_some(),
+                    "TEST FAILED: Expected Some on p2 schedule {} of 10.",
+                    i + 1
+                );
+            }
+            assert!(
+                manager.dry_run_tx(&contact, C_START, &bp2(0.1)).is_none(),
+                "TEST FAILED: Expected None -> p2 bookings should consume the p2 volume."
+            );
+            assert!(
+                manager.dry_run_tx(&contact, C_START, &bp1(0.1)).is_none(),
+                "TEST FAILED: Expected None -> p2 bookings should cascade to p1."
+            );
+            assert!(
+                manager.dry_run_tx(&contact, C_START, &bp0(0.1)).is_none(),
+                "TEST FAILED: Expected None -> p2 bookings should cascade to p0."
+            );
+        }
+
+        #[test]
+        fn unschedule_tx_restores_queue() {
+            let mut manager = ($manager_fn)();
+            let contact = make_contact_info(C_START, C_END);
+            let before = manager.queue_size;
+            assert!(
+                manager
+                    .schedule_tx(&contact, C_START, &bp0(TOTAL_VOL))
+                    .is_some(),
+                "TEST FAILED: Expected Some scheduling the full volume."
+            );
+            assert!(
+                manager.unschedule_tx(&bp0(TOTAL_VOL)),
+                "TEST FAILED: Expected the booked volume to be released."
+            );
+            assert_eq!(
+                manager.queue_size, before,
+                "TEST FAILED: The queue should return to its prior value."
+            );
+            assert!(
+                !manager.unschedule_tx(&bp0(1.0)),
+                "TEST FAILED: Expected false when releasing more than the booked volume."
+            );
+
+            let mut manager = ($p_manager_fn)();
+            let before = manager.queue_size;
+            manager
+                .schedule_tx(&contact, C_START, &bp1(1000.0))
+                .unwrap();
+            assert!(
+                manager.unschedule_tx(&bp1(1000.0)),
+                "TEST FAILED: Expected the booked p1 volume to be released."
+            );
+            assert_eq!(
+                manager.queue_size, before,
+                "TEST FAILED: The queue of every priority should return to its prior value."
+            );
+        }
+
+        #[test]
+        fn reset_releases_the_booked_volume() {
+            let contact = make_contact_info(C_START, C_END);
+            let mut manager = ($p_manager_fn)();
+            let fresh = manager.queue_size;
+            manager
+                .schedule_tx(&contact, C_START, &bp1(1000.0))
+                .unwrap();
+            assert!(
+                manager.reset(&contact),
+                "TEST FAILED: Expected the manager to be initialized again."
+            );
+            assert_eq!(
+                manager.queue_size, fresh,
+                "TEST FAILED: The queue of every priority should be empty after a reset."
+            );
+            assert!(
+                manager
+                    .schedule_tx(&contact, C_START, &bp0(TOTAL_VOL))
+                    .is_some(),
+                "TEST FAILED: Expected the full volume to be available after a reset."
+            );
+        }
+
+        #[test]
+        fn restore_reproduces_the_scheduling() {
+            let contact = make_contact_info(C_START, C_END);
+            let mut manager = ($p_manager_fn)();
+            manager
+                .schedule_tx(&contact, C_START, &bp1(1000.0))
+                .unwrap();
+            let queue_size = manager.queue_size;
+            let state = manager.snapshot();
+
+            let first = manager.schedule_tx(&contact, C_START, &bp0(2000.0));
+            assert!(
+                manager.restore(state.as_ref()),
+                "TEST FAILED: Expected the manager to accept its own snapshot."
+            );
+            assert_eq!(
+                manager.queue_size, queue_size,
+                "TEST FAILED: The queue should be the one captured by the snapshot."
+            );
+            let second = manager.schedule_tx(&contact, C_START, &bp0(2000.0));
+            assert_eq!(
+                first.map(|data| (data.tx_start, data.tx_end, data.rx_end)),
+                second.map(|data| (data.tx_start, data.tx_end, data.rx_end)),
+                "TEST FAILED: The scheduling should be the same after a restore."
+            );
+            assert!(
+                !manager.restore(&()),
+                "TEST FAILED: Expected the state of another manager to be rejected."
+            );
+        }
+
+        #[test]
+        fn remaining_volume_decreases_by_bundle_size() {
+            let mut manager = ($manager_fn)();
+            let contact = make_contact_info(C_START, C_END);
+            assert_eq!(
+                manager.remaining_volume(0),
+                TOTAL_VOL,
+                "TEST FAILED: Expected the full volume to remain on a fresh manager."
+            );
+            manager
+                .schedule_tx(&contact, C_START, &bp0(1000.0))
+                .unwrap();
+            assert_eq!(
+                manager.remaining_volume(0),
+                TOTAL_VOL - 1000.0,
+                "TEST FAILED: The remaining volume should decrease by the bundle size."
+            );
+
+            let mut manager = ($p_manager_fn)();
+            manager
+                .schedule_tx(&contact, C_START, &bp1(1000.0))
+                .unwrap();
+            assert_eq!(
+                manager.remaining_volume(1),
+                TOTAL_VOL - 1000.0,
+                "TEST FAILED: The p1 remaining volume should decrease by the bundle size."
+            );
+            assert_eq!(
+                manager.remaining_volume(2),
+                TOTAL_VOL,
+                "TEST FAILED: A p1 booking should not reduce the p2 remaining volume."
+            );
+        }
+
         #[test]
         fn mid_prio_cascades_down_but_not_up() {
             let mut manager = ($p_manager_fn)();
@@ -8,6 +8,7 @@ use crate::contact_manager::legacy::evl::{EVLManager, PBEVLManager, PEVLManager}
 use crate::contact_manager::legacy::qd::{PBQDManager, PQDManager, QDManager};
 use crate::contact_manager::segmentation::pseg::PSegmentationManager;
 use crate::contact_manager::segmentation::seg::SegmentationManager;
+use crate::parsing::lookup_marker;
 use crate::{choices, parse_transparent, transparent_CM};
 
 /// The base dynamic contact wrapper, which can be parsed from &str or any type implementing the correct conversion.
@@ -51,24 +52,114 @@ impl From<StandardManagerInfo> for StandardManagersDyn {
     }
 }
 
+/// The markers announcing each kind of standard manager in a contact plan.
+///
+/// A marker must appear only once, see `parsing::find_duplicate_marker`. To extend this table,
+/// start a `parsing::MarkerTable` from it.
+pub const STANDARD_MARKERS: [(&str, StandardManagersKinds); 11] = [
+    ("seg", StandardManagersKinds::Seg),
+    ("pseg", StandardManagersKinds::PSeg),
+    ("eto", StandardManagersKinds::Eto),
+    ("peto", StandardManagersKinds::PEto),
+    ("pbeto", StandardManagersKinds::PBEto),
+    ("evl", StandardManagersKinds::Evl),
+    ("pevl", StandardManagersKinds::PEvl),
+    ("pbevl", StandardManagersKinds::PBEvl),
+    ("qd", StandardManagersKinds::Qd),
+    ("pqd", StandardManagersKinds::PQd),
+    ("pbqd", StandardManagersKinds::PBQd),
+];
+
 impl TryFrom<&str> for StandardManagersKinds {
     type Error = ();
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Ok(match value {
-            "seg" => Self::Seg,
-            "pseg" => Self::PSeg,
-            "eto" => Self::Eto,
-            "peto" => Self::PEto,
-            "pbeto" => Self::PBEto,
-            "evl" => Self::Evl,
-            "pevl" => Self::PEvl,
-            "pbevl" => Self::PBEvl,
-            "qd" => Self::Qd,
-            "pqd" => Self::PQd,
-            "pbqd" => Self::PBQd,
-            _ => return Err(()),
-        })
+        lookup_marker(&STANDARD_MARKERS, value).ok_or(())
     }
 }
 
 parse_transparent!(StandardManagersDyn, StandardManagerInfo);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::{MarkerTable, find_duplicate_marker};
+
+    #[test]
+    fn standard_markers_are_unique() {
+        assert_eq!(
+            find_duplicate_marker(&STANDARD_MARKERS),
+            None,
+            "TEST FAILED: A standard marker is registered twice."
+        );
+    }
+
+    #[test]
+    fn duplicate_marker_is_detected() {
+        let markers = [
+            ("evl", StandardManagersKinds::Evl),
+            ("qd", StandardManagersKinds::Qd),
+            ("evl", StandardManagersKinds::Eto),
+        ];
+        assert_eq!(
+            find_duplicate_marker(&markers),
+            Some("evl"),
+            "TEST FAILED: The second \"evl\" registration should be reported."
+        );
+        // without the check, the first registration silently shadows the second one
+        assert!(
+            matches!(
+                lookup_marker(&markers, "evl"),
+                Some(StandardManagersKinds::Evl)
+            ),
+            "TEST FAILED: The first registration should win the lookup."
+        );
+    }
+
+    #[test]
+    fn marker_table_refuses_duplicates() {
+        let mut table = MarkerTable::from_markers(&STANDARD_MARKERS)
+            .expect("TEST FAILED: The standard markers should be unique.");
+        assert!(
+            table.register("evl", StandardManagersKinds::Eto).is_err(),
+            "TEST FAILED: Registering \"evl\" twice should be refused."
+        );
+        assert!(
+            matches!(table.lookup("evl"), Some(StandardManagersKinds::Evl)),
+            "TEST FAILED: A refused registration should leave the table unchanged."
+        );
+        assert!(
+            MarkerTable::from_markers(&[
+                ("evl", StandardManagersKinds::Evl),
+                ("evl", StandardManagersKinds::Eto),
+            ])
+            .is_err(),
+            "TEST FAILED: A table with a repeated marker should be refused."
+        );
+    }
+
+    #[test]
+    fn marker_table_replaces_explicitly() {
+        let mut table = MarkerTable::from_markers(&STANDARD_MARKERS).unwrap();
+        assert!(
+            matches!(
+                table.replace("evl", StandardManagersKinds::Eto),
+                Some(StandardManagersKinds::Evl)
+            ),
+            "TEST FAILED: The previous registration should be returned."
+        );
+        assert!(
+            matches!(table.lookup("evl"), Some(StandardManagersKinds::Eto)),
+            "TEST FAILED: The marker should now announce the new kind."
+        );
+        assert!(
+            table.replace("myevl", StandardManagersKinds::Evl).is_none()
+                && table.register("myqd", StandardManagersKinds::Qd).is_ok(),
+            "TEST FAILED: New markers should be registered."
+        );
+        assert_eq!(
+            table.markers().len(),
+            STANDARD_MARKERS.len() + 2,
+            "TEST FAILED: Replacing should not add an entry."
+        );
+    }
+}
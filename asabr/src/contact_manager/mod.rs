@@ -1,18 +1,29 @@
 // #[cfg(feature = "first_depleted")]
 extern crate alloc;
 use alloc::boxed::Box;
-use core::fmt::Debug;
+use core::{any::Any, fmt::Debug};
 
-#[cfg(feature = "first_depleted")]
-use crate::types::Volume;
-use crate::{bundle::Bundle, contact::ContactInfo, types::Date};
+use crate::{
+    bundle::Bundle,
+    contact::ContactInfo,
+    types::{Date, Duration, Priority, Volume},
+};
 
+pub mod ack;
+pub mod buffered;
+pub mod class_rate;
+pub mod confidence;
+pub mod half_duplex;
 pub mod legacy;
 pub mod lex;
 pub mod segmentation;
 
 /// Data structure representing the transmission (tx) start, end, and related timing information.
+///
+/// With the `serde` feature, the data is (de)serialized as a map of its fields: `tx_start`,
+/// `tx_end`, `expiration`, `rx_start` and `rx_end`.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContactManagerTxData {
     /// The start time of the transmission.
     pub tx_start: Date,
@@ -26,6 +37,20 @@ pub struct ContactManagerTxData {
     pub rx_end: Date,
 }
 
+/// The reason a contact manager rejects a transmission during a dry run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TxReject {
+    /// Not enough volume is left on the contact for the bundle.
+    VolumeExceeded,
+    /// The transmission cannot be completed before the end of the contact.
+    DeadlineExceeded,
+    /// The bundle would arrive after its expiration.
+    Expired,
+}
+
+/// The resources booked on a contact, captured by `ContactManager::snapshot`.
+pub type ManagerState = Box<dyn Any>;
+
 /// Trait for managing contact resources and scheduling data transmissions.
 pub trait ContactManager {
     /// Simulate the transmission of a bundle to a contact at a given time.
@@ -46,6 +71,61 @@ pub trait ContactManager {
         bundle: &Bundle,
     ) -> Option<ContactManagerTxData>;
 
+    /// Simulate the transmission of a bundle like `dry_run_tx`, telling why it is rejected.
+    ///
+    /// The default implementation calls `dry_run_tx` and infers the reason from the dates: a
+    /// transmission requested after the contact end is `DeadlineExceeded`, a bundle expiring
+    /// before the transmission can start is `Expired`, any other rejection is `VolumeExceeded`.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The data bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `ContactManagerTxData` if the dry run is successful, the `TxReject` reason otherwise.
+    fn dry_run_tx_detailed(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Result<ContactManagerTxData, TxReject> {
+        self.dry_run_tx(contact_data, at_time, bundle)
+            .ok_or(if at_time >= contact_data.end {
+                TxReject::DeadlineExceeded
+            } else if bundle.expiration < at_time.max(contact_data.start) {
+                TxReject::Expired
+            } else {
+                TxReject::VolumeExceeded
+            })
+    }
+
+    /// Returns the soonest time the transmission of a bundle could start, without scheduling it,
+    /// e.g. to order a local queue.
+    ///
+    /// The default implementation returns the `tx_start` of `dry_run_tx`.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The data bundle to be transmitted.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns the transmission start, or `None` if the bundle cannot be transmitted.
+    fn earliest_tx_start(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<Date> {
+        self.dry_run_tx(contact_data, at_time, bundle)
+            .map(|data| data.tx_start)
+    }
+
     /// Schedule the transmission of a bundle based on the contact data and available free intervals.
     ///
     /// This method shall be called after a dry run ! Implementations might not ensure a clean behavior otherwise.
@@ -66,7 +146,62 @@ pub trait ContactManager {
         bundle: &Bundle,
     ) -> Option<ContactManagerTxData>;
 
-    /// For first depleted compatibility. Required with "first_depleted" compilation feature.
+    /// Schedule the transmission of a bundle whose dry run was just performed, reusing its result.
+    ///
+    /// This method shall only be called with the output of a `dry_run_tx` call for the same
+    /// arguments, with no other scheduling in between. The default implementation ignores `data`
+    /// and calls `schedule_tx`.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    /// * `at_time` - The current time for scheduling purposes.
+    /// * `bundle` - The bundle to be transmitted.
+    /// * `data` - The output of the dry run.
+    ///
+    /// # Returns
+    ///
+    /// Optionally returns `ContactManagerTxData` if the bundle can be transmitted.
+    fn schedule_tx_from_dry_run(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+        _data: ContactManagerTxData,
+    ) -> Option<ContactManagerTxData> {
+        self.schedule_tx(contact_data, at_time, bundle)
+    }
+
+    /// Cancels a previously scheduled transmission and releases the resources it booked.
+    ///
+    /// The default implementation does not support cancellation.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The bundle whose transmission was scheduled.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the booked resources were released, `false` otherwise.
+    fn unschedule_tx(&mut self, _bundle: &Bundle) -> bool {
+        false
+    }
+
+    /// Returns the volume that can still be booked on the contact by bundles of a given priority.
+    ///
+    /// The figure is trusted by the volume aware choices, e.g. the first depleted suppression.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority of the bundles to consider.
+    ///
+    /// # Returns
+    ///
+    /// The remaining volume for `priority`.
+    fn remaining_volume(&self, priority: Priority) -> Volume;
+
+    /// Returns the maximum volume the contact had at initialization, e.g. for first depleted
+    /// suppression or utilization reports. Required with "first_depleted" compilation feature.
     ///
     /// # Returns
     ///
@@ -74,6 +209,71 @@ pub trait ContactManager {
     #[cfg(feature = "first_depleted")]
     fn get_original_volume(&self) -> Volume;
 
+    /// Returns the maximum volume the contact had at initialization, e.g. for utilization
+    /// reports. Required with "first_depleted" compilation feature.
+    ///
+    /// Without that feature, the default implementation does not track the volume and reports
+    /// an unbounded capacity.
+    ///
+    /// # Returns
+    ///
+    /// Returns the maximum volume the contact had at initialization.
+    #[cfg(not(feature = "first_depleted"))]
+    fn get_original_volume(&self) -> Volume {
+        Volume::INFINITY
+    }
+
+    /// Returns the volume queued on the contact, indexed by priority for the managers
+    /// distinguishing priorities, a single value applying to every priority otherwise.
+    ///
+    /// The default implementation does not queue volume and returns an empty slice.
+    fn get_queue_size(&self) -> &[Volume] {
+        &[]
+    }
+
+    /// Returns the volume booked on the contact by the bundles of every priority, e.g. to report
+    /// the load of each contact (see also `Multigraph::utilization_report`).
+    ///
+    /// The default implementation reads the lowest priority queue of `get_queue_size`, which
+    /// accounts for the bundles of every priority (the queue of a priority also holds the higher
+    /// priorities). A manager without queue books the volume it no longer offers to the lowest
+    /// priority, nothing if it does not track its volume.
+    fn total_booked_volume(&self) -> Volume {
+        if let Some(&booked) = self.get_queue_size().first() {
+            return booked;
+        }
+        let original_volume = self.get_original_volume();
+        if original_volume.is_finite() {
+            Volume::max(0.0, original_volume - self.remaining_volume(0))
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the maximum available volume (MAV) of the contact, indexed by priority like
+    /// `get_queue_size`, e.g. the budgets of the priorities.
+    ///
+    /// The default implementation does not bound the volume and returns an empty slice.
+    fn get_mav(&self) -> &[Volume] {
+        &[]
+    }
+
+    /// Returns the link delay of the contact.
+    ///
+    /// The default implementation returns zero, e.g. for managers whose delay varies over the
+    /// contact or with the bundle.
+    fn get_delay(&self) -> Duration {
+        0.0
+    }
+
+    /// Returns the probability in [0, 1] that a transmission over the contact succeeds, see
+    /// `RouteStage::confidence`.
+    ///
+    /// The default implementation returns 1, the contact being considered reliable.
+    fn confidence(&self) -> f64 {
+        1.0
+    }
+
     /// For ETO compatibility. Required with "manual_queueing" compilation feature.
     ///
     /// # Arguments
@@ -112,6 +312,56 @@ pub trait ContactManager {
     ///
     /// Returns `true` if the initialization is consistent.
     fn try_init(&mut self, contact_data: &ContactInfo) -> bool;
+
+    /// Restores the contact to its state at initialization, releasing all the booked resources.
+    ///
+    /// The default implementation runs `try_init` again, the managers booking resources must
+    /// clear their bookings first.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the initialization is consistent.
+    fn reset(&mut self, contact_data: &ContactInfo) -> bool {
+        self.try_init(contact_data)
+    }
+
+    /// Returns the number of priority levels the manager distinguishes, the valid priorities
+    /// ranging from 0 to the count excluded.
+    ///
+    /// The default implementation is for managers accepting any priority.
+    fn priority_levels(&self) -> Option<usize> {
+        None
+    }
+
+    /// Captures the resources booked on the contact, to be restored with `restore`.
+    ///
+    /// The default implementation is for managers that book nothing and captures no state.
+    ///
+    /// # Returns
+    ///
+    /// The state of the manager, only meaningful to managers of the same type.
+    fn snapshot(&self) -> ManagerState {
+        Box::new(())
+    }
+
+    /// Restores the resources booked on the contact when `snapshot` was called. A state can be
+    /// restored several times.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The output of `snapshot` for this manager.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the state was restored, `false` if it was not captured by a manager of
+    /// this type.
+    fn restore(&mut self, state: &dyn Any) -> bool {
+        state.is::<()>()
+    }
 }
 
 /// Implementation of `ContactManager` for dynamic types (eg `Box<dyn ContactManager>`).
@@ -125,6 +375,26 @@ impl<T: AsMut<dyn ContactManager> + AsRef<dyn ContactManager>> ContactManager fo
     ) -> Option<ContactManagerTxData> {
         self.as_ref().dry_run_tx(contact_data, at_time, bundle)
     }
+    /// Delegates the detailed dry run method to the boxed object.
+    fn dry_run_tx_detailed(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Result<ContactManagerTxData, TxReject> {
+        self.as_ref()
+            .dry_run_tx_detailed(contact_data, at_time, bundle)
+    }
+    /// Delegates the earliest transmission start method to the boxed object.
+    fn earliest_tx_start(
+        &self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Option<Date> {
+        self.as_ref()
+            .earliest_tx_start(contact_data, at_time, bundle)
+    }
     /// Delegates the schedule method to the boxed object.
     fn schedule_tx(
         &mut self,
@@ -135,16 +405,77 @@ impl<T: AsMut<dyn ContactManager> + AsRef<dyn ContactManager>> ContactManager fo
         self.as_mut().schedule_tx(contact_data, at_time, bundle)
     }
 
+    /// Delegates the schedule_tx_from_dry_run method to the boxed object.
+    fn schedule_tx_from_dry_run(
+        &mut self,
+        contact_data: &ContactInfo,
+        at_time: Date,
+        bundle: &Bundle,
+        data: ContactManagerTxData,
+    ) -> Option<ContactManagerTxData> {
+        self.as_mut()
+            .schedule_tx_from_dry_run(contact_data, at_time, bundle, data)
+    }
+
+    /// Delegates the unschedule method to the boxed object.
+    fn unschedule_tx(&mut self, bundle: &Bundle) -> bool {
+        self.as_mut().unschedule_tx(bundle)
+    }
+
+    /// Delegates the remaining_volume method to the boxed object.
+    fn remaining_volume(&self, priority: Priority) -> Volume {
+        self.as_ref().remaining_volume(priority)
+    }
+
     /// Delegates the try_init method to the boxed object.
     fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
         self.as_mut().try_init(contact_data)
     }
 
-    #[cfg(feature = "first_depleted")]
+    /// Delegates the reset method to the boxed object.
+    fn reset(&mut self, contact_data: &ContactInfo) -> bool {
+        self.as_mut().reset(contact_data)
+    }
+
+    /// Delegates the priority_levels method to the boxed object.
+    fn priority_levels(&self) -> Option<usize> {
+        self.as_ref().priority_levels()
+    }
+
+    /// Delegates the snapshot method to the boxed object.
+    fn snapshot(&self) -> ManagerState {
+        self.as_ref().snapshot()
+    }
+
+    /// Delegates the restore method to the boxed object.
+    fn restore(&mut self, state: &dyn Any) -> bool {
+        self.as_mut().restore(state)
+    }
+
     /// Delegates the get_original_volume method to the boxed object.
     fn get_original_volume(&self) -> Volume {
         self.as_ref().get_original_volume()
     }
+    /// Delegates the get_queue_size method to the boxed object.
+    fn get_queue_size(&self) -> &[Volume] {
+        self.as_ref().get_queue_size()
+    }
+    /// Delegates the total_booked_volume method to the boxed object.
+    fn total_booked_volume(&self) -> Volume {
+        self.as_ref().total_booked_volume()
+    }
+    /// Delegates the get_mav method to the boxed object.
+    fn get_mav(&self) -> &[Volume] {
+        self.as_ref().get_mav()
+    }
+    /// Delegates the get_delay method to the boxed object.
+    fn get_delay(&self) -> Duration {
+        self.as_ref().get_delay()
+    }
+    /// Delegates the confidence method to the boxed object.
+    fn confidence(&self) -> f64 {
+        self.as_ref().confidence()
+    }
     /// Delegates the manual_enqueue method to the boxed object.
     #[cfg(feature = "manual_queueing")]
     fn manual_enqueue(&mut self, bundle: &Bundle) -> bool {
@@ -162,9 +493,18 @@ assert_impl_all! {Box<dyn ContactManager>: ContactManager}
 
 /// This macro implement the ContactManager trait for you on a wrapper struct where the element 0 is the underlying
 /// contact manager, by forwarding all calls to it
+///
+/// The long form transparent_CM!(T, items) forwards all calls but `confidence`, given by the items instead.
 #[macro_export]
 macro_rules! transparent_CM {
     ($T:ty) => {
+        $crate::transparent_CM!($T,
+            fn confidence(&self) -> f64 {
+                self.0.confidence()
+            }
+        );
+    };
+    ($T:ty, $($confidence:tt)*) => {
         impl $crate::contact_manager::ContactManager for $T {
             fn dry_run_tx(
                 &self,
@@ -175,6 +515,27 @@ macro_rules! transparent_CM {
                 self.0.dry_run_tx(contact_data, at_time, bundle)
             }
 
+            fn dry_run_tx_detailed(
+                &self,
+                contact_data: &$crate::contact::ContactInfo,
+                at_time: $crate::types::Date,
+                bundle: &$crate::bundle::Bundle,
+            ) -> Result<
+                $crate::contact_manager::ContactManagerTxData,
+                $crate::contact_manager::TxReject,
+            > {
+                self.0.dry_run_tx_detailed(contact_data, at_time, bundle)
+            }
+
+            fn earliest_tx_start(
+                &self,
+                contact_data: &$crate::contact::ContactInfo,
+                at_time: $crate::types::Date,
+                bundle: &$crate::bundle::Bundle,
+            ) -> Option<$crate::types::Date> {
+                self.0.earliest_tx_start(contact_data, at_time, bundle)
+            }
+
             fn schedule_tx(
                 &mut self,
                 contact_data: &$crate::contact::ContactInfo,
@@ -184,14 +545,65 @@ macro_rules! transparent_CM {
                 self.0.schedule_tx(contact_data, at_time, bundle)
             }
 
+            fn schedule_tx_from_dry_run(
+                &mut self,
+                contact_data: &$crate::contact::ContactInfo,
+                at_time: $crate::types::Date,
+                bundle: &$crate::bundle::Bundle,
+                data: $crate::contact_manager::ContactManagerTxData,
+            ) -> Option<$crate::contact_manager::ContactManagerTxData> {
+                self.0
+                    .schedule_tx_from_dry_run(contact_data, at_time, bundle, data)
+            }
+
+            fn unschedule_tx(&mut self, bundle: &$crate::bundle::Bundle) -> bool {
+                self.0.unschedule_tx(bundle)
+            }
+
+            fn remaining_volume(&self, priority: $crate::types::Priority) -> $crate::types::Volume {
+                self.0.remaining_volume(priority)
+            }
+
             fn try_init(&mut self, contact_data: &$crate::contact::ContactInfo) -> bool {
                 self.0.try_init(contact_data)
             }
 
-            #[cfg(feature = "first_depleted")]
+            fn reset(&mut self, contact_data: &$crate::contact::ContactInfo) -> bool {
+                self.0.reset(contact_data)
+            }
+
+            fn priority_levels(&self) -> Option<usize> {
+                self.0.priority_levels()
+            }
+
+            fn snapshot(&self) -> $crate::contact_manager::ManagerState {
+                self.0.snapshot()
+            }
+
+            fn restore(&mut self, state: &dyn core::any::Any) -> bool {
+                self.0.restore(state)
+            }
+
             fn get_original_volume(&self) -> $crate::types::Volume {
                 self.0.get_original_volume()
             }
+
+            fn get_queue_size(&self) -> &[$crate::types::Volume] {
+                self.0.get_queue_size()
+            }
+
+            fn total_booked_volume(&self) -> $crate::types::Volume {
+                self.0.total_booked_volume()
+            }
+
+            fn get_mav(&self) -> &[$crate::types::Volume] {
+                self.0.get_mav()
+            }
+
+            fn get_delay(&self) -> $crate::types::Duration {
+                self.0.get_delay()
+            }
+            $($confidence)*
             #[cfg(feature = "manual_queueing")]
             fn manual_enqueue(&mut self, bundle: &$crate::contact_manager::Bundle) -> bool {
                 self.0.manual_enqueue(bundle)
@@ -203,3 +615,164 @@ macro_rules! transparent_CM {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::{
+        buffered::BufferedManager,
+        class_rate::ClassRateManager,
+        confidence::ConfidenceManager,
+        half_duplex::HalfDuplexManager,
+        legacy::{
+            evl::{EVLManager, PBEVLManager},
+            qd::PQDManager,
+        },
+        segmentation::{Segment, seg::SegmentationManager},
+    };
+    use crate::pathfinding::test_helpers::make_bundle;
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn queues_are_read_without_downcasting() {
+        let info = ContactInfo::new(0, 1, 0.0, 100.0);
+        let segment = |val| Segment {
+            start: 0.0,
+            end: 100.0,
+            val,
+        };
+        let mut managers: Vec<(&str, Box<dyn ContactManager>)> = vec![
+            ("evl", Box::new(EVLManager::new(10.0, 1.0))),
+            ("pqd", Box::new(PQDManager::<3>::new(10.0, 2.0))),
+            (
+                "pbevl",
+                Box::new(PBEVLManager::<2>::new(10.0, 3.0, [1000.0, 400.0])),
+            ),
+            ("buffered", Box::new(BufferedManager::new(10.0, 4.0, 50.0))),
+            (
+                "half duplex",
+                Box::new(HalfDuplexManager::new(0, 10.0, 5.0)),
+            ),
+            (
+                "confidence",
+                Box::new(ConfidenceManager::new(10.0, 6.0, 0.5).unwrap()),
+            ),
+            (
+                "class rate",
+                Box::new(ClassRateManager::new(vec![(0, 10.0, 7.0)])),
+            ),
+            (
+                "seg",
+                Box::new(SegmentationManager::new(
+                    vec![segment(10.0)],
+                    vec![segment(8.0)],
+                )),
+            ),
+        ];
+        for (name, manager) in &mut managers {
+            assert!(
+                manager.try_init(&info),
+                "TEST FAILED: The {} manager should accept the contact.",
+                name
+            );
+            assert!(
+                manager
+                    .schedule_tx(&info, 0.0, &make_bundle(1, 1, 20.0, 1000.0))
+                    .is_some(),
+                "TEST FAILED: The bundle should fit on the {} contact.",
+                name
+            );
+        }
+
+        let read: Vec<_> = managers
+            .iter()
+            .map(|(name, manager)| {
+                let manager: &dyn ContactManager = manager.as_ref();
+                (
+                    *name,
+                    manager.get_queue_size().to_vec(),
+                    manager.get_mav().to_vec(),
+                    manager.get_delay(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            read,
+            vec![
+                ("evl", vec![20.0], vec![1000.0], 1.0),
+                ("pqd", vec![20.0, 20.0, 0.0], vec![1000.0], 2.0),
+                ("pbevl", vec![20.0, 20.0], vec![1000.0, 400.0], 3.0),
+                ("buffered", vec![20.0], vec![50.0], 4.0),
+                ("half duplex", vec![20.0], vec![1000.0], 5.0),
+                ("confidence", vec![20.0], vec![1000.0], 6.0),
+                ("class rate", vec![], vec![], 0.0),
+                ("seg", vec![], vec![], 0.0),
+            ],
+            "TEST FAILED: Unexpected queue, volume limits or delay."
+        );
+    }
+
+    #[test]
+    fn total_booked_volume_counts_every_priority_once() {
+        let info = ContactInfo::new(0, 1, 0.0, 100.0);
+        let managers: Vec<(&str, Box<dyn ContactManager>)> = vec![
+            ("pqd", Box::new(PQDManager::<3>::new(10.0, 2.0))),
+            (
+                "class rate",
+                Box::new(ClassRateManager::new(vec![(0, 10.0, 7.0)])),
+            ),
+        ];
+        for (name, mut manager) in managers {
+            assert!(
+                manager.try_init(&info),
+                "TEST FAILED: The {} manager should accept the contact.",
+                name
+            );
+            assert!(
+                manager
+                    .schedule_tx(&info, 0.0, &make_bundle(1, 0, 20.0, 1000.0))
+                    .is_some()
+                    && manager
+                        .schedule_tx(&info, 0.0, &make_bundle(1, 2, 30.0, 1000.0))
+                        .is_some(),
+                "TEST FAILED: Both bundles should fit on the {} contact.",
+                name
+            );
+            assert_eq!(
+                manager.total_booked_volume(),
+                50.0,
+                "TEST FAILED: The {} contact should book the combined size.",
+                name
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tx_data_json_round_trip() {
+        let data = ContactManagerTxData {
+            tx_start: 1.0,
+            tx_end: 2.0,
+            expiration: 100.0,
+            rx_start: 1.5,
+            rx_end: 2.5,
+        };
+        let json = serde_json::to_value(data).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "tx_start": 1.0,
+                "tx_end": 2.0,
+                "expiration": 100.0,
+                "rx_start": 1.5,
+                "rx_end": 2.5
+            }),
+            "TEST FAILED: The data should be a map of its fields."
+        );
+        assert_eq!(
+            serde_json::from_value::<ContactManagerTxData>(json).unwrap(),
+            data,
+            "TEST FAILED: The data should survive the JSON round trip."
+        );
+    }
+}
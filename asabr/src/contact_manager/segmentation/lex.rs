@@ -7,6 +7,7 @@ use crate::{
         Segment, pseg::PSegmentationManager, seg::SegmentationManager,
     },
     parse_single_tok, parse_transparent,
+    parsing::Unparse,
     types::{DataRate, Duration},
 };
 
@@ -57,3 +58,30 @@ impl TryFrom<SegmentInfo> for PSegmentationManager {
 }
 parse_transparent!(SegmentationManager, SegmentInfo);
 parse_transparent!(PSegmentationManager, SegmentInfo);
+
+/// Writes a list of segments, e.g. `[0 10 5, 10 20 8]`.
+fn unparse_segments<T: core::fmt::Display, W: core::fmt::Write>(
+    segments: &[Segment<T>],
+    out: &mut W,
+) -> core::fmt::Result {
+    out.write_char('[')?;
+    for (idx, segment) in segments.iter().enumerate() {
+        if idx > 0 {
+            out.write_str(", ")?;
+        }
+        segment.unparse(out)?;
+    }
+    out.write_char(']')
+}
+
+/// Writes the tokens of a `SegmentInfo`, the rates first.
+pub(super) fn unparse_segment_info<W: core::fmt::Write>(
+    rate_intervals: &[Segment<DataRate>],
+    delay_intervals: &[Segment<Duration>],
+    out: &mut W,
+) -> core::fmt::Result {
+    out.write_str("rate ")?;
+    unparse_segments(rate_intervals, out)?;
+    out.write_str(" delay ")?;
+    unparse_segments(delay_intervals, out)
+}
@@ -4,7 +4,7 @@ use alloc::vec::Vec;
 
 use crate::contact::ContactInfo;
 use crate::parse_transparent;
-use crate::parsing::Parse;
+use crate::parsing::{Parse, Unparse};
 use crate::types::{DataRate, Date, Duration, Volume};
 
 pub mod lex;
@@ -12,7 +12,7 @@ pub mod pseg;
 pub mod seg;
 
 /// A segment represents a time interval with an associated value of type `T`.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Segment<T> {
     /// The start time of the segment.
     pub start: Date,
@@ -33,6 +33,12 @@ impl<T> From<SegmentParse<T>> for Segment<T> {
 
 parse_transparent!(Segment<Tt>,SegmentParse<Tt>,Tt: Parse);
 
+impl<T: core::fmt::Display> Unparse for Segment<T> {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        write!(out, "{} {} {}", self.start, self.end, self.val)
+    }
+}
+
 /// Determines the delay based on the transmission end time (`tx_end`) and the available delay intervals.
 ///
 /// # Arguments
@@ -90,14 +96,10 @@ fn get_delays(
 ///   Must be empty on entry.
 /// * `default` - Default value assigned to the initialized segment in
 ///   `other_intervals`.
+/// * `original_volume` - Reset and populated with the total transferable
+///   volume computed from `rate_intervals`.
 /// * `info` - Contact information defining the valid time window.
 ///
-/// # Feature Flags
-///
-/// When the `first_depleted` feature is enabled, `original_volume` is reset
-/// and populated with the total transferable volume computed from
-/// `rate_intervals`.
-///
 /// # Returns
 ///
 /// Returns `true` if:
@@ -106,6 +108,7 @@ fn get_delays(
 ///
 /// Returns `false` if:
 /// - Any interval list has gaps
+/// - A rate segment is not positive or a delay segment is negative
 /// - Intervals do not exactly match the contact window
 /// - `other_intervals` is not empty
 fn try_init<T>(
@@ -113,25 +116,20 @@ fn try_init<T>(
     delay_intervals: &Vec<Segment<Duration>>,
     other_intervals: &mut Vec<Segment<T>>,
     default: T,
-    #[cfg(feature = "first_depleted")] original_volume: &mut Volume,
+    original_volume: &mut Volume,
     info: &ContactInfo,
 ) -> bool {
     // we check that we have no holes for rate segments
     let mut time = info.start;
-    #[cfg(feature = "first_depleted")]
-    {
-        *original_volume = 0.0;
-    }
+    *original_volume = 0.0;
 
     for inter in rate_intervals {
-        if inter.start != time {
+        // a segment without throughput cannot carry anything
+        if inter.start != time || inter.val <= 0.0 {
             return false;
         }
         time = inter.end;
-        #[cfg(feature = "first_depleted")]
-        {
-            *original_volume += (inter.end - inter.start) * inter.val;
-        }
+        *original_volume += (inter.end - inter.start) * inter.val;
     }
     let opt_rate_end = rate_intervals.last();
     match opt_rate_end {
@@ -146,7 +144,7 @@ fn try_init<T>(
     // we check that we have no holes for delay segments
     time = info.start;
     for inter in delay_intervals {
-        if inter.start != time {
+        if inter.start != time || inter.val < 0.0 {
             return false;
         }
         time = inter.end;
@@ -189,7 +187,7 @@ fn try_init<T>(
 ///
 /// Optionally returns the transmission end time `Date` or `None` if the volume cannot be transmitted by the deadline.
 #[inline(always)]
-fn get_tx_end(
+pub(crate) fn get_tx_end(
     rate_intervals: &Vec<Segment<DataRate>>,
     mut at_time: Date,
     mut volume: Volume,
@@ -199,6 +197,11 @@ fn get_tx_end(
         if rate_seg.end < at_time {
             continue;
         }
+        // nothing flows on this segment, skip it
+        if rate_seg.val <= 0.0 {
+            at_time = rate_seg.end;
+            continue;
+        }
 
         // try to get the volume from this segment
         let tx_end = at_time + volume / rate_seg.val;
@@ -221,6 +224,34 @@ fn get_tx_end(
     None
 }
 
+/// Calculates the volume that can be transmitted over `[start, end]`.
+///
+/// # Arguments
+///
+/// * `rate_intervals` - The rate segments defining available bandwidth over time.
+/// * `start` - The start of the interval.
+/// * `end` - The end of the interval.
+///
+/// # Returns
+///
+/// The volume transmittable in the interval.
+#[inline(always)]
+pub(crate) fn get_volume(
+    rate_intervals: &Vec<Segment<DataRate>>,
+    start: Date,
+    end: Date,
+) -> Volume {
+    let mut volume = 0.0;
+    for rate_seg in rate_intervals {
+        let seg_start = Date::max(rate_seg.start, start);
+        let seg_end = Date::min(rate_seg.end, end);
+        if seg_start < seg_end {
+            volume += (seg_end - seg_start) * rate_seg.val;
+        }
+    }
+    volume
+}
+
 /// Common constructor interface for segmentation managers.
 ///
 /// This trait allows different segmentation manager implementations
@@ -1,13 +1,12 @@
-#[cfg(feature = "first_depleted")]
-use crate::types::Volume;
 use crate::{
     bundle::Bundle,
     contact::ContactInfo,
     contact_manager::{
-        ContactManager, ContactManagerTxData,
-        segmentation::{BaseSegmentationManager, Segment},
+        ContactManager, ContactManagerTxData, ManagerState,
+        segmentation::{BaseSegmentationManager, Segment, lex::unparse_segment_info},
     },
-    types::{DataRate, Date, Duration, Priority},
+    parsing::Unparse,
+    types::{DataRate, Date, Duration, Priority, Volume},
 };
 
 extern crate alloc;
@@ -16,7 +15,8 @@ extern crate alloc;
 #[allow(unused_imports)]
 use alloc::vec;
 
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
+use core::any::Any;
 
 /// Priority-aware segmentation manager. Tracks bandwidth availability per priority level
 /// using booking intervals.
@@ -28,11 +28,16 @@ pub struct PSegmentationManager {
     rate_intervals: Vec<Segment<DataRate>>,
     /// A list of segments representing delay times associated with different intervals.
     delay_intervals: Vec<Segment<Duration>>,
-    #[cfg(feature = "first_depleted")]
     /// The total volume at initialization.
     original_volume: Volume,
 }
 
+impl Unparse for PSegmentationManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        unparse_segment_info(&self.rate_intervals, &self.delay_intervals, out)
+    }
+}
+
 impl PSegmentationManager {
     pub fn new(
         rate_intervals: Vec<Segment<DataRate>>,
@@ -44,7 +49,6 @@ impl PSegmentationManager {
             booking,
             rate_intervals,
             delay_intervals,
-            #[cfg(feature = "first_depleted")]
             original_volume: 0.0,
         }
     }
@@ -209,12 +213,29 @@ impl ContactManager for PSegmentationManager {
         Some(out)
     }
 
+    /// Returns the volume of the intervals that bundles of a given priority can use, i.e. the
+    /// intervals booked with a lower priority (or not booked at all).
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority of the bundles to consider.
+    ///
+    /// # Returns
+    ///
+    /// The volume that can still be booked by bundles of `priority`.
+    fn remaining_volume(&self, priority: Priority) -> Volume {
+        self.booking
+            .iter()
+            .filter(|seg| priority > seg.val)
+            .map(|seg| super::get_volume(&self.rate_intervals, seg.start, seg.end))
+            .sum()
+    }
+
     /// For first depleted compatibility
     ///
     /// # Returns
     ///
     /// Returns the maximum volume the contact had at initialization.
-    #[cfg(feature = "first_depleted")]
     fn get_original_volume(&self) -> Volume {
         self.original_volume
     }
@@ -234,11 +255,40 @@ impl ContactManager for PSegmentationManager {
             &self.delay_intervals,
             &mut self.booking,
             -1,
-            #[cfg(feature = "first_depleted")]
             &mut self.original_volume,
             contact_data,
         )
     }
+
+    /// Releases the booked intervals and initializes the manager again.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the initialization is consistent.
+    fn reset(&mut self, contact_data: &ContactInfo) -> bool {
+        self.booking.clear();
+        self.try_init(contact_data)
+    }
+
+    /// Captures the booked intervals.
+    fn snapshot(&self) -> ManagerState {
+        Box::new(self.booking.clone())
+    }
+
+    /// Restores the booked intervals captured by `snapshot`.
+    fn restore(&mut self, state: &dyn Any) -> bool {
+        match state.downcast_ref::<Vec<_>>() {
+            Some(booking) => {
+                self.booking.clone_from(booking);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -266,7 +316,7 @@ mod tests {
     fn start_test(
         input: Vec<InputSeg>,
         output: Vec<OutputSeg>,
-        requests: Vec<(Bundle, f64, bool)>,
+        requests: Vec<(Bundle, Date, bool)>,
     ) {
         let contact_info = ContactInfo::new(0, 1, 0.0, 200.0);
         let mut delay_segments: Vec<Segment<Date>> = Vec::new();
@@ -319,6 +369,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remaining_volume_decreases_by_bundle_size() {
+        let contact_info = ContactInfo::new(0, 1, 0.0, 100.0);
+        let mut manager = PSegmentationManager::new(
+            vec![Segment {
+                start: 0.0,
+                end: 100.0,
+                val: 10.0,
+            }],
+            vec![Segment {
+                start: 0.0,
+                end: 100.0,
+                val: 1.0,
+            }],
+        );
+        assert!(
+            manager.try_init(&contact_info),
+            "TEST FAILED: The manager should accept the contact."
+        );
+        let bundle = Bundle {
+            source: 0,
+            destinations: vec![1],
+            priority: 1,
+            size: 100.0,
+            expiration: 1000.0,
+        };
+        manager.schedule_tx(&contact_info, 0.0, &bundle).unwrap();
+        assert_eq!(
+            manager.remaining_volume(1),
+            1000.0 - bundle.size,
+            "TEST FAILED: The remaining volume should decrease by the bundle size."
+        );
+        assert_eq!(
+            manager.remaining_volume(2),
+            1000.0,
+            "TEST FAILED: Higher priorities can preempt the booking."
+        );
+    }
+
     #[test]
     fn test_single_bundle_insertion() {
         let bundle1 = Bundle {
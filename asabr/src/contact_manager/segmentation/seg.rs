@@ -1,19 +1,20 @@
-#[cfg(feature = "first_depleted")]
-use crate::types::Volume;
 use crate::{
     bundle::Bundle,
     contact::ContactInfo,
     contact_manager::{
-        ContactManager, ContactManagerTxData,
-        segmentation::{BaseSegmentationManager, Segment},
+        ContactManager, ContactManagerTxData, ManagerState,
+        segmentation::{BaseSegmentationManager, Segment, lex::unparse_segment_info},
     },
-    types::{DataRate, Date, Duration},
+    parsing::Unparse,
+    types::{DataRate, Date, Duration, Priority, Volume},
 };
 
 extern crate alloc;
 // used as macro and not module. poor detection
+use alloc::boxed::Box;
 #[allow(unused_imports)]
 use alloc::{vec, vec::Vec};
+use core::any::Any;
 /// Manages contact segments, where each segment may have a distinct data rate and delay.
 ///
 /// The `SegmentationManager` uses different segments to manage free intervals, rate intervals, and delay intervals,
@@ -26,11 +27,16 @@ pub struct SegmentationManager {
     rate_intervals: Vec<Segment<DataRate>>,
     /// A list of segments representing delay times associated with different intervals.
     delay_intervals: Vec<Segment<Duration>>,
-    #[cfg(feature = "first_depleted")]
     /// The total volume at initialization.
     original_volume: Volume,
 }
 
+impl Unparse for SegmentationManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        unparse_segment_info(&self.rate_intervals, &self.delay_intervals, out)
+    }
+}
+
 impl SegmentationManager {
     /// Creates a new [`SegmentationManager`] from the provided rate and delay intervals.
     ///
@@ -44,11 +50,6 @@ impl SegmentationManager {
     /// * `rate_intervals` - Segments describing data rates over time.
     /// * `delay_intervals` - Segments describing delay durations over time.
     ///
-    /// # Feature Flags
-    ///
-    /// When the `first_depleted` feature is enabled, the `original_volume`
-    /// field is initialized to `0.0`.
-    ///
     /// # Returns
     ///
     /// A fully initialized [`SegmentationManager`].
@@ -62,7 +63,6 @@ impl SegmentationManager {
             free_intervals,
             rate_intervals,
             delay_intervals,
-            #[cfg(feature = "first_depleted")]
             original_volume: 0.0,
         }
     }
@@ -186,6 +186,22 @@ impl ContactManager for SegmentationManager {
         })
     }
 
+    /// Returns the volume of the free intervals, the priority is not considered.
+    ///
+    /// # Arguments
+    ///
+    /// * `_priority` - The priority of the bundles to consider (unused in this implementation).
+    ///
+    /// # Returns
+    ///
+    /// The volume that can still be booked.
+    fn remaining_volume(&self, _priority: Priority) -> Volume {
+        self.free_intervals
+            .iter()
+            .map(|seg| super::get_volume(&self.rate_intervals, seg.start, seg.end))
+            .sum()
+    }
+
     /// Initializes the segmentation manager by checking that rate and delay intervals have no gaps.
     ///
     /// # Arguments
@@ -201,18 +217,46 @@ impl ContactManager for SegmentationManager {
             &self.delay_intervals,
             &mut self.free_intervals,
             (),
-            #[cfg(feature = "first_depleted")]
             &mut self.original_volume,
             contact_data,
         )
     }
 
+    /// Releases the booked intervals and initializes the manager again.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact_data` - Reference to the contact information.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the initialization is consistent.
+    fn reset(&mut self, contact_data: &ContactInfo) -> bool {
+        self.free_intervals.clear();
+        self.try_init(contact_data)
+    }
+
+    /// Captures the booked intervals.
+    fn snapshot(&self) -> ManagerState {
+        Box::new(self.free_intervals.clone())
+    }
+
+    /// Restores the booked intervals captured by `snapshot`.
+    fn restore(&mut self, state: &dyn Any) -> bool {
+        match state.downcast_ref::<Vec<_>>() {
+            Some(free_intervals) => {
+                self.free_intervals.clone_from(free_intervals);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// For first depleted compatibility
     ///
     /// # Returns
     ///
     /// Returns the maximum volume the contact had at initialization.
-    #[cfg(feature = "first_depleted")]
     fn get_original_volume(&self) -> Volume {
         self.original_volume
     }
@@ -242,7 +286,7 @@ mod tests {
         contact_end: Date,
         input: Vec<InputSeg>,
         output: Vec<OutputSeg>,
-        requests: Vec<(Bundle, f64, bool)>,
+        requests: Vec<(Bundle, Date, bool)>,
     ) {
         // Create the contact
         let contact_info = ContactInfo::new(0, 1, contact_start, contact_end);
@@ -334,6 +378,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remaining_volume_decreases_by_bundle_size() {
+        let contact_info = ContactInfo::new(0, 1, 0.0, 100.0);
+        let mut manager = SegmentationManager::new(
+            vec![
+                Segment {
+                    start: 0.0,
+                    end: 50.0,
+                    val: 10.0,
+                },
+                Segment {
+                    start: 50.0,
+                    end: 100.0,
+                    val: 20.0,
+                },
+            ],
+            vec![Segment {
+                start: 0.0,
+                end: 100.0,
+                val: 1.0,
+            }],
+        );
+        assert!(manager.try_init(&contact_info));
+        let before = manager.remaining_volume(0);
+        assert_eq!(before, 1500.0, "TEST FAILED: Expected 1500 at start.");
+
+        let bundle = Bundle {
+            source: 0,
+            destinations: vec![1],
+            priority: 0,
+            size: 100.0,
+            expiration: 1000.0,
+        };
+        manager.schedule_tx(&contact_info, 0.0, &bundle).unwrap();
+        assert_eq!(
+            manager.remaining_volume(0),
+            before - bundle.size,
+            "TEST FAILED: The remaining volume should decrease by the bundle size."
+        );
+    }
+
     #[test]
     fn test_single_bundle_insertions() {
         // Simple case: one delay segment + one rate segment
@@ -509,4 +594,40 @@ mod tests {
 
         start_test(5.0, 15.0, input, output, requests);
     }
+
+    #[test]
+    fn zero_rate_interval_is_rejected() {
+        let contact_info = ContactInfo::new(0, 1, 0.0, 20.0);
+        let rates = vec![
+            Segment {
+                start: 0.0,
+                end: 10.0,
+                val: 2.0,
+            },
+            Segment {
+                start: 10.0,
+                end: 20.0,
+                val: 0.0,
+            },
+        ];
+        let delays = vec![Segment {
+            start: 0.0,
+            end: 20.0,
+            val: 1.0,
+        }];
+
+        let mut manager = SegmentationManager::new(rates.clone(), delays.clone());
+        assert!(
+            !manager.try_init(&contact_info),
+            "TEST FAILED: A zero-rate interval must be rejected."
+        );
+        assert!(
+            crate::contact::Contact::<crate::node_manager::none::NoManagement, _>::try_new(
+                contact_info,
+                SegmentationManager::new(rates, delays),
+            )
+            .is_none(),
+            "TEST FAILED: The contact must not be created."
+        );
+    }
 }
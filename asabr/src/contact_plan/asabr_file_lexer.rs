@@ -1,14 +1,27 @@
+extern crate alloc;
+use alloc::{
+    collections::BTreeMap as HashMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+
 use crate::{
     contact_manager::ContactManager,
     contact_plan::ContactPlan,
     errors::ASABRError,
     node_manager::NodeManager,
     parsing::{LexFrom, Located, Parse},
+    types::NodeID,
 };
 
+/// The default marker starting a comment, the rest of the line is ignored.
+pub const COMMENT_MARKER: &str = "#";
+
 /// Take an iterator over strings assumed to be lines, and parse a ContactPlan from it.
 /// Templated over a NodeManager and a ContactManager, wich must be compatible with the file syntax
 /// to successfully parse from it
+///
+/// Comments start with `COMMENT_MARKER` and end with the line.
 pub fn parse_from_iter<
     NM: NodeManager + LexFrom<str>,
     CM: ContactManager + LexFrom<str>,
@@ -16,51 +29,513 @@ pub fn parse_from_iter<
 >(
     iter: I,
 ) -> Result<ContactPlan<NM, CM>, ASABRError> {
+    parse_from_iter_with_comments(iter, COMMENT_MARKER)
+}
+
+/// Same as `parse_from_iter`, with a custom comment marker (e.g. `"//"`).
+/// Comments can be on their own line or follow the tokens of a line, the line numbers reported
+/// in the errors are not affected. An empty marker is rejected, it would start a comment at the
+/// beginning of every line.
+pub fn parse_from_iter_with_comments<
+    NM: NodeManager + LexFrom<str>,
+    CM: ContactManager + LexFrom<str>,
+    I: Iterator<Item: AsRef<str>>,
+>(
+    iter: I,
+    comment_marker: &str,
+) -> Result<ContactPlan<NM, CM>, ASABRError> {
+    if comment_marker.is_empty() {
+        return Err(ASABRError::ContactPlanError(
+            "The comment marker must not be empty",
+        ));
+    }
     let mut parser = Default::default();
 
     for (linenum, data) in iter.enumerate() {
         let mut line = data.as_ref();
-        if let Some((new, _)) = line.split_once('#') {
+        if let Some((new, _)) = line.split_once(comment_marker) {
             line = new
         }
         for (toknum, word) in line.split_ascii_whitespace().enumerate() {
-            let locate = |e| {
-                ASABRError::ParsingError(Located {
-                    data: e,
-                    line: linenum,
-                    toknum,
-                })
-            };
-
             let main = word.trim_start_matches(['[', ',']);
             let diff = word.len() - main.len();
 
             for i in 0..diff {
-                ContactPlan::feed(
-                    ContactPlan::lex(&word[i..i + 1], &parser).map_err(locate)?,
-                    &mut parser,
-                )
-                .map_err(locate)?;
+                feed_token(&word[i..i + 1], &mut parser, linenum, toknum)?;
             }
 
             let main2 = main.trim_end_matches([',', ']']);
             let end = &main[main2.len()..];
 
             if !main2.is_empty() {
-                ContactPlan::feed(
-                    ContactPlan::lex(main2, &parser).map_err(locate)?,
-                    &mut parser,
-                )
-                .map_err(locate)?;
+                feed_token(main2, &mut parser, linenum, toknum)?;
             }
             for i in 0..end.len() {
-                ContactPlan::feed(
-                    ContactPlan::lex(&end[i..i + 1], &parser).map_err(locate)?,
-                    &mut parser,
-                )
-                .map_err(locate)?;
+                feed_token(&end[i..i + 1], &mut parser, linenum, toknum)?;
             }
         }
     }
     ContactPlan::parse(parser).map_err(ASABRError::ContactPlanError)
 }
+
+/// Lexes and feeds a single token, errors are located at `linenum`/`toknum` and name the field
+/// the parser was expecting.
+fn feed_token<NM: NodeManager + LexFrom<str>, CM: ContactManager + LexFrom<str>>(
+    token: &str,
+    parser: &mut <ContactPlan<NM, CM> as Parse>::Parser,
+    linenum: usize,
+    toknum: usize,
+) -> Result<(), ASABRError> {
+    let field = parser.next_field();
+    let locate = |e| {
+        ASABRError::ParsingError(Located {
+            data: e,
+            line: linenum,
+            toknum,
+            field,
+        })
+    };
+    ContactPlan::feed(ContactPlan::lex(token, parser).map_err(locate)?, parser).map_err(locate)?;
+    Ok(())
+}
+
+/// Parse a ContactPlan from an in-memory string, e.g. a plan received over the network.
+/// The string is split into lines and parsed with `parse_from_iter`, errors are located the same way.
+pub fn parse_from_str<NM: NodeManager + LexFrom<str>, CM: ContactManager + LexFrom<str>>(
+    plan: &str,
+) -> Result<ContactPlan<NM, CM>, ASABRError> {
+    parse_from_iter(plan.lines())
+}
+
+/// The id of each node name of a plan.
+pub type NodeNames = HashMap<String, NodeID>;
+
+/// Same as `parse_from_iter`, the nodes can also be referred to by name.
+///
+/// A `node` or `enode` declaration whose id is not a number is given the next real node id, the
+/// token being the name of the node (`node GS-Madrid` is read as `node <id> GS-Madrid`). The
+/// transmitter and receiver of a `contact` can then be the name of a node declared beforehand.
+/// Numeric ids keep working unchanged, the virtual nodes only accept numeric ids.
+///
+/// # Returns
+///
+/// * The contact plan, and the id of each node name.
+pub fn parse_from_iter_with_names<
+    NM: NodeManager + LexFrom<str>,
+    CM: ContactManager + LexFrom<str>,
+    I: Iterator<Item: AsRef<str>>,
+>(
+    iter: I,
+) -> Result<(ContactPlan<NM, CM>, NodeNames), ASABRError> {
+    let mut names = NodeNames::new();
+    let mut next_id: NodeID = 0;
+    let mut lines = Vec::new();
+
+    for (linenum, data) in iter.enumerate() {
+        let line = data.as_ref();
+        let line = line.split_once(COMMENT_MARKER).map_or(line, |(new, _)| new);
+        let mut tokens: Vec<String> = line.split_ascii_whitespace().map(String::from).collect();
+
+        match tokens.first().map(String::as_str) {
+            Some("node" | "enode") if tokens.len() > 1 => {
+                name_node(&mut tokens, &mut names, &mut next_id)
+            }
+            Some("contact" | "bidir") => {
+                for toknum in [1, 2] {
+                    let Some(token) = tokens.get_mut(toknum) else {
+                        continue;
+                    };
+                    if token.parse::<NodeID>().is_ok() {
+                        continue;
+                    }
+                    let id =
+                        names
+                            .get(token.as_str())
+                            .ok_or(ASABRError::ParsingError(Located {
+                                data: "Unknown node name",
+                                line: linenum,
+                                toknum,
+                                field: if toknum == 1 {
+                                    "contact transmitter id"
+                                } else {
+                                    "contact receiver id"
+                                },
+                            }))?;
+                    *token = id.to_string();
+                }
+            }
+            _ => {}
+        }
+        lines.push(tokens.join(" "));
+    }
+
+    Ok((parse_from_iter(lines.iter())?, names))
+}
+
+/// Records the name of a `node` or `enode` declaration, giving it `next_id` if its id is omitted.
+/// `next_id` never moves backwards, the ids already given are not given twice.
+fn name_node(tokens: &mut Vec<String>, names: &mut NodeNames, next_id: &mut NodeID) {
+    match tokens[1].parse::<NodeID>() {
+        Ok(id) => {
+            if let Some(name) = tokens.get(2) {
+                names.insert(name.clone(), id);
+            }
+            *next_id = (*next_id).max(id + 1);
+        }
+        Err(_) => {
+            names.insert(tokens[1].clone(), *next_id);
+            tokens.insert(1, next_id.to_string());
+            *next_id += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::node_manager::none::NoManagement;
+    use crate::parsing::CMDynStandard;
+    use std::format;
+    use std::io::{BufRead, BufReader};
+
+    const PLAN_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/contact_plans/asabr_format_static.cp"
+    );
+
+    #[test]
+    fn string_and_file_parsing_are_identical() {
+        let file = std::fs::File::open(PLAN_PATH).unwrap();
+        let from_file = parse_from_iter::<NoManagement, EVLManager, _>(
+            BufReader::new(file).lines().map(|l| l.unwrap()),
+        )
+        .unwrap();
+        let content = std::fs::read_to_string(PLAN_PATH).unwrap();
+        let from_str = parse_from_str::<NoManagement, EVLManager>(&content).unwrap();
+
+        assert!(
+            !from_str.contacts.is_empty(),
+            "TEST FAILED: Expected the plan to hold contacts."
+        );
+        assert_eq!(
+            format!("{:?}", from_file.vertices),
+            format!("{:?}", from_str.vertices),
+            "TEST FAILED: The nodes should not depend on the source of the plan."
+        );
+        assert_eq!(
+            format!("{:?}", from_file.contacts),
+            format!("{:?}", from_str.contacts),
+            "TEST FAILED: The contacts should not depend on the source of the plan."
+        );
+    }
+
+    #[test]
+    fn comments_are_ignored() {
+        let plan = parse_from_iter::<NoManagement, EVLManager, _>(
+            [
+                "# ground segment",
+                "node 0 a # station",
+                "node 1 b",
+                "#node 2 c",
+                "contact 0 1 0 10 1 1 # eclipse pass",
+                "# contact 1 0 0 10 1 1",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            (plan.vertices.len(), plan.contacts.len()),
+            (2, 1),
+            "TEST FAILED: Commented entries should be ignored."
+        );
+    }
+
+    #[test]
+    fn custom_comment_marker() {
+        let plan = parse_from_iter_with_comments::<NoManagement, EVLManager, _>(
+            [
+                "node 0 a // station",
+                "node 1 b",
+                "// contact 1 0 0 10 1 1",
+                "contact 0 1 0 10 1 1",
+            ]
+            .iter(),
+            "//",
+        )
+        .unwrap();
+        assert_eq!(
+            (plan.vertices.len(), plan.contacts.len()),
+            (2, 1),
+            "TEST FAILED: Entries after the custom marker should be ignored."
+        );
+    }
+
+    #[test]
+    fn empty_comment_marker_is_rejected() {
+        let res = parse_from_iter_with_comments::<NoManagement, EVLManager, _>(
+            ["node 0 a", "node 1 b", "contact 0 1 0 10 1 1"].iter(),
+            "",
+        );
+        assert!(
+            matches!(res, Err(ASABRError::ContactPlanError(_))),
+            "TEST FAILED: An empty marker would blank the whole plan and should be rejected."
+        );
+    }
+
+    #[test]
+    fn comments_keep_error_lines_accurate() {
+        let Err(ASABRError::ParsingError(located)) = parse_from_iter::<NoManagement, EVLManager, _>(
+            [
+                "# header",
+                "node 0 a # first",
+                "",
+                "node 1 b",
+                "contact 0 1 x 10 1 1 # broken",
+            ]
+            .iter(),
+        ) else {
+            panic!("TEST FAILED: Expected a parsing error.");
+        };
+        assert_eq!(
+            (located.line, located.toknum),
+            (4, 3),
+            "TEST FAILED: The error should point at the start time of the contact on line 4."
+        );
+    }
+
+    #[test]
+    fn string_parsing_errors_are_located() {
+        let Err(ASABRError::ParsingError(located)) =
+            parse_from_str::<NoManagement, EVLManager>("node 0 a\nnode 1 b\ncontact 0 1 x 10 1 1")
+        else {
+            panic!("TEST FAILED: Expected a parsing error.");
+        };
+        assert_eq!(
+            (located.line, located.toknum),
+            (2, 3),
+            "TEST FAILED: The error should point at the start time of the contact."
+        );
+    }
+
+    #[test]
+    fn parsing_errors_name_the_expected_field() {
+        for (plan, line, field) in [
+            (
+                "node 0 a\nnode 1 b\ncontact 0 1 x 10 1 1",
+                2,
+                "contact start time",
+            ),
+            (
+                "node 0 a\nnode 1 b\ncontact 0 y 0 10 1 1",
+                2,
+                "contact receiver id",
+            ),
+            (
+                "node 0 a\nnode 1 b\ncontact 0 1 0 10 z 1",
+                2,
+                "contact manager parameters",
+            ),
+            ("node 0 a\nnode x b", 1, "node id"),
+        ] {
+            let Err(ASABRError::ParsingError(located)) =
+                parse_from_str::<NoManagement, EVLManager>(plan)
+            else {
+                panic!("TEST FAILED: Expected a parsing error for {plan:?}.");
+            };
+            assert_eq!(
+                (located.line, located.field),
+                (line, field),
+                "TEST FAILED: The error should name the offending field of {plan:?}."
+            );
+            assert!(
+                format!("{}", ASABRError::ParsingError(located)).contains(field),
+                "TEST FAILED: The error message should name the {field}."
+            );
+        }
+    }
+
+    #[test]
+    fn decimal_values_are_only_accepted_for_real_fields() {
+        let plan = parse_from_str::<NoManagement, EVLManager>(
+            "node 0 a\nnode 1 b\ncontact 0 1 0.5 10 2.5 1",
+        )
+        .unwrap();
+        assert_eq!(
+            (
+                plan.contacts[0].info.start,
+                plan.contacts[0].manager.remaining_volume(0)
+            ),
+            (0.5, 23.75),
+            "TEST FAILED: Decimal dates and rates should be parsed."
+        );
+        assert!(
+            parse_from_str::<NoManagement, EVLManager>("node 0.5 a").is_err(),
+            "TEST FAILED: A decimal node id should be rejected."
+        );
+    }
+
+    #[test]
+    fn explicit_ids_do_not_move_the_next_id_backwards() {
+        let mut names = NodeNames::new();
+        let mut next_id = 0;
+        for line in ["node 0 a", "node b", "node 0 c", "node d"] {
+            let mut tokens = line.split(' ').map(String::from).collect();
+            name_node(&mut tokens, &mut names, &mut next_id);
+        }
+        assert_eq!(
+            (names["b"], names["d"]),
+            (1, 2),
+            "TEST FAILED: A lower explicit id should not give an id twice."
+        );
+    }
+
+    #[test]
+    fn named_nodes_map_to_the_contact_ids() {
+        let (plan, names) = parse_from_iter_with_names::<NoManagement, EVLManager, _>(
+            [
+                "node GS-Madrid",
+                "node 1 relay",
+                "node SAT-1",
+                "contact GS-Madrid SAT-1 0 10 1 1",
+                "contact SAT-1 1 10 20 1 1 # mixed",
+            ]
+            .iter(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            (names["GS-Madrid"], names["relay"], names["SAT-1"]),
+            (0, 1, 2),
+            "TEST FAILED: The names should be numbered in declaration order."
+        );
+        let pairs: Vec<_> = plan
+            .contacts
+            .iter()
+            .map(|contact| (contact.info.tx_node_id, contact.info.rx_node_id))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                (names["GS-Madrid"], names["SAT-1"]),
+                (names["SAT-1"], names["relay"])
+            ],
+            "TEST FAILED: The contacts should use the ids of the names."
+        );
+
+        let Err(ASABRError::ParsingError(located)) =
+            parse_from_iter_with_names::<NoManagement, EVLManager, _>(
+                ["node a", "contact a b 0 10 1 1"].iter(),
+            )
+        else {
+            panic!("TEST FAILED: Expected an error for an undeclared name.");
+        };
+        assert_eq!(
+            (located.line, located.toknum),
+            (1, 2),
+            "TEST FAILED: The error should point at the undeclared receiver."
+        );
+    }
+
+    #[test]
+    fn bidir_declares_both_directions() {
+        let nodes = "node 0 a\nnode 1 b\n";
+        let bidir =
+            parse_from_str::<NoManagement, EVLManager>(&format!("{nodes}bidir 0 1 0 10 8 1 2 3"))
+                .unwrap();
+        let by_hand = parse_from_str::<NoManagement, EVLManager>(&format!(
+            "{nodes}contact 0 1 0 10 8 1\ncontact 1 0 0 10 2 3"
+        ))
+        .unwrap();
+
+        let contacts: Vec<_> = bidir
+            .contacts
+            .iter()
+            .map(|contact| {
+                (
+                    contact.info.tx_node_id,
+                    contact.info.rx_node_id,
+                    contact.manager.remaining_volume(0),
+                    contact.manager.get_delay(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            contacts,
+            [(0, 1, 80.0, 1.0), (1, 0, 20.0, 3.0)],
+            "TEST FAILED: Each direction should get its own rate and delay."
+        );
+        let mut written = (String::new(), String::new());
+        crate::contact_plan::asabr_file_writer::write_plan(&bidir, &mut written.0).unwrap();
+        crate::contact_plan::asabr_file_writer::write_plan(&by_hand, &mut written.1).unwrap();
+        assert_eq!(
+            written.0, written.1,
+            "TEST FAILED: The contacts should match the hand-written ones."
+        );
+
+        let Err(ASABRError::ParsingError(located)) =
+            parse_from_str::<NoManagement, EVLManager>(&format!("{nodes}bidir 0 1 0 10 8 1 x 3"))
+        else {
+            panic!("TEST FAILED: Expected an error for the reverse rate.");
+        };
+        assert_eq!(
+            located.field, "reverse contact manager parameters",
+            "TEST FAILED: The error should name the reverse direction."
+        );
+    }
+
+    #[test]
+    fn legacy_managers_take_an_optional_ramp() {
+        let plan = parse_from_str::<NoManagement, CMDynStandard>(
+            "node 0 a\nnode 1 b\n\
+             contact 0 1 0 10 evl 8 1 ramp 2\n\
+             contact 1 0 0 10 qd 8 1\n\
+             contact 0 1 20 30 pevl 8 1 ramp 4",
+        )
+        .unwrap();
+        let volumes: Vec<_> = plan
+            .contacts
+            .iter()
+            .map(|contact| contact.manager.remaining_volume(0))
+            .collect();
+        assert_eq!(
+            volumes,
+            [72.0, 80.0, 64.0],
+            "TEST FAILED: The ramp should only apply to the contacts declaring it."
+        );
+
+        let nodes = "node 0 a\nnode 1 b\n";
+        let bidir = parse_from_str::<NoManagement, EVLManager>(&format!(
+            "{nodes}bidir 0 1 0 10 8 1 ramp 2 2 3 ramp 1"
+        ))
+        .unwrap();
+        let volumes: Vec<_> = bidir
+            .contacts
+            .iter()
+            .map(|contact| contact.manager.remaining_volume(0))
+            .collect();
+        assert_eq!(
+            volumes,
+            [72.0, 19.0],
+            "TEST FAILED: Each direction of a bidir should take its own ramp."
+        );
+        let mut written = String::new();
+        crate::contact_plan::asabr_file_writer::write_plan(&bidir, &mut written).unwrap();
+        assert!(
+            written.contains("8 1 ramp 2") && written.contains("2 3 ramp 1"),
+            "TEST FAILED: The ramps should be written back, got {written}"
+        );
+
+        assert!(
+            matches!(
+                parse_from_str::<NoManagement, EVLManager>(&format!(
+                    "{nodes}contact 0 1 0 10 8 1 ramp"
+                )),
+                Err(ASABRError::ContactPlanError(_))
+            ),
+            "TEST FAILED: A ramp keyword without duration should be rejected."
+        );
+    }
+}
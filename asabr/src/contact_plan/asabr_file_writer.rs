@@ -0,0 +1,245 @@
+extern crate alloc;
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+use core::fmt::{self, Write};
+
+use crate::{
+    contact::Contact,
+    contact_manager::ContactManager,
+    contact_plan::ContactPlan,
+    multigraph::Multigraph,
+    node::Node,
+    node_manager::NodeManager,
+    parsing::Unparse,
+    routing::RcKey,
+    types::{NodeID, NodeName},
+    vertex::Vertex,
+};
+
+/// Writes a name, or `<keyword><id>` if the name is not available (without the "debug"
+/// feature, the names are not kept).
+fn write_name<W: Write>(out: &mut W, name: &NodeName, keyword: &str, id: NodeID) -> fmt::Result {
+    let mut buffer = String::new();
+    write!(buffer, "{name}")?;
+    if buffer.is_empty() {
+        write!(out, "{keyword}{id}")
+    } else {
+        out.write_str(&buffer)
+    }
+}
+
+/// Writes the tokens of a manager, preceded by a space if there are any.
+fn write_manager<W: Write>(out: &mut W, manager: &impl Unparse) -> fmt::Result {
+    let mut buffer = String::new();
+    manager.unparse(&mut buffer)?;
+    if !buffer.is_empty() {
+        write!(out, " {buffer}")?;
+    }
+    Ok(())
+}
+
+fn write_node<NM: NodeManager + Unparse, W: Write>(
+    out: &mut W,
+    node: &Node<NM>,
+    external: bool,
+) -> fmt::Result {
+    let keyword = if external { "enode" } else { "node" };
+    write!(out, "{keyword} {} ", node.info.id)?;
+    write_name(out, &node.info.name, keyword, node.info.id)?;
+    write_manager(out, &node.manager)?;
+    out.write_char('\n')
+}
+
+fn write_vnode<W: Write>(
+    out: &mut W,
+    name: &NodeName,
+    vid: NodeID,
+    rids: &[NodeID],
+) -> fmt::Result {
+    write!(out, "vnode {vid} ")?;
+    write_name(out, name, "vnode", vid)?;
+    out.write_str(" [")?;
+    for (idx, rid) in rids.iter().enumerate() {
+        if idx > 0 {
+            out.write_str(", ")?;
+        }
+        write!(out, "{rid}")?;
+    }
+    out.write_str("]\n")
+}
+
+fn write_contact<NM: NodeManager, CM: ContactManager + Unparse, W: Write>(
+    out: &mut W,
+    contact: &Contact<NM, CM>,
+) -> fmt::Result {
+    let info = &contact.info;
+    write!(
+        out,
+        "contact {} {} {} {}",
+        info.tx_node_id, info.rx_node_id, info.start, info.end
+    )?;
+    write_manager(out, &contact.manager)?;
+    out.write_char('\n')
+}
+
+/// Writes a contact plan in the A-SABR format, `parse_from_str` reads it back into an
+/// identical plan.
+///
+/// The nodes and contacts are written in the order of the plan, one declaration per line.
+///
+/// # Parameters
+///
+/// * `plan` - The contact plan to write.
+/// * `out` - The destination, e.g. a `String`.
+///
+/// # Returns
+///
+/// * `Err(fmt::Error)` - If `out` fails, or a manager cannot be expressed in the format.
+pub fn write_plan<NM: NodeManager + Unparse, CM: ContactManager + Unparse, W: Write>(
+    plan: &ContactPlan<NM, CM>,
+    out: &mut W,
+) -> fmt::Result {
+    let rids_map = plan.vnode_map.get_vnode_to_rids_map();
+    for vertex in &plan.vertices {
+        match vertex {
+            Vertex::INode(node) => write_node(out, node, false)?,
+            Vertex::ENode(node) => write_node(out, node, true)?,
+            Vertex::VNode((name, vid)) => write_vnode(
+                out,
+                name,
+                *vid,
+                rids_map.get(vid).map(Vec::as_slice).unwrap_or_default(),
+            )?,
+        }
+    }
+    for contact in &plan.contacts {
+        write_contact(out, contact)?;
+    }
+    Ok(())
+}
+
+/// Writes the current nodes and contacts of a multigraph in the A-SABR format, e.g. to persist
+/// the plan after `Multigraph::add_contact` or `Multigraph::remove_contact`.
+///
+/// The contacts are written by transmitting node, receiving node and start time. The managers
+/// are written with their initial parameters, the bookings are not saved.
+///
+/// # Parameters
+///
+/// * `multigraph` - The multigraph to write.
+/// * `out` - The destination, e.g. a `String`.
+///
+/// # Returns
+///
+/// * `Err(fmt::Error)` - If `out` fails, a node or contact cannot be borrowed, or a manager
+///   cannot be expressed in the format.
+pub fn write_multigraph<NM: NodeManager + Unparse, CM: ContactManager + Unparse, W: Write>(
+    multigraph: &Multigraph<NM, CM>,
+    out: &mut W,
+) -> fmt::Result {
+    for node in &multigraph.real_nodes {
+        let node = node.try_borrow().map_err(|_| fmt::Error)?;
+        write_node(out, &node, multigraph.is_external(node.info.id))?;
+    }
+    for (name, vid) in &multigraph.virtual_nodes {
+        write_vnode(out, name, *vid, &multigraph.rids_of(*vid))?;
+    }
+
+    // the contacts are shared between the vertex pairs of their nodes
+    let mut seen = BTreeSet::new();
+    let mut contacts = Vec::new();
+    for sender in &multigraph.senders {
+        for receiver in &sender.receivers {
+            for contact in &receiver.contacts_to_receiver {
+                if seen.insert(RcKey::of(contact)) {
+                    contacts.push(contact.try_borrow().map_err(|_| fmt::Error)?);
+                }
+            }
+        }
+    }
+    contacts.sort_unstable_by(|a, b| {
+        (a.info.tx_node_id, a.info.rx_node_id)
+            .cmp(&(b.info.tx_node_id, b.info.rx_node_id))
+            .then(a.info.start.total_cmp(&b.info.start))
+    });
+    for contact in contacts {
+        write_contact(out, &contact)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_manager::segmentation::seg::SegmentationManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::node_manager::none::NoManagement;
+    use alloc::format;
+
+    const PLAN_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/contact_plans/asabr_format_static.cp"
+    );
+
+    #[test]
+    fn written_plan_parses_back_identically() {
+        extern crate std;
+        let content = std::fs::read_to_string(PLAN_PATH).unwrap();
+        let plan = parse_from_str::<NoManagement, EVLManager>(&content).unwrap();
+
+        let mut written = String::new();
+        write_plan(&plan, &mut written).unwrap();
+        let reparsed = parse_from_str::<NoManagement, EVLManager>(&written).unwrap();
+
+        assert_eq!(
+            format!("{:?}", reparsed.vertices),
+            format!("{:?}", plan.vertices),
+            "TEST FAILED: The nodes should survive the round trip."
+        );
+        assert_eq!(
+            format!("{:?}", reparsed.contacts),
+            format!("{:?}", plan.contacts),
+            "TEST FAILED: The contacts should survive the round trip."
+        );
+    }
+
+    #[test]
+    fn written_multigraph_keeps_its_changes() {
+        let plan = parse_from_str::<NoManagement, SegmentationManager>(
+            "node 0 a
+            node 1 b
+            enode 2 c
+            vnode 3 v [1, 2]
+            contact 0 1 0 100 rate [0 50 10, 50 100 20] delay [0 100 1]
+            contact 1 2 0 100 rate [0 100 10] delay [0 100 2]
+            contact 1 0 200 300 rate [200 300 10] delay [200 300 1]",
+        )
+        .unwrap();
+        let mut multigraph = Multigraph::new(plan).unwrap();
+        multigraph.remove_contact(1, 0, 200.0);
+
+        let mut written = String::new();
+        write_multigraph(&multigraph, &mut written).unwrap();
+        let reparsed =
+            Multigraph::new(parse_from_str::<NoManagement, SegmentationManager>(&written).unwrap())
+                .unwrap();
+        let mut rewritten = String::new();
+        write_multigraph(&reparsed, &mut rewritten).unwrap();
+
+        assert_eq!(
+            rewritten, written,
+            "TEST FAILED: The written multigraph should parse back identically."
+        );
+        assert!(
+            written.contains("enode 2 ")
+                && written.contains("vnode 3 ")
+                && written.contains("[1, 2]"),
+            "TEST FAILED: The external and virtual nodes should be written."
+        );
+        assert_eq!(
+            written.matches("contact").count(),
+            2,
+            "TEST FAILED: The removed contact should not be written."
+        );
+    }
+}
@@ -115,6 +115,21 @@ impl<NM: NodeManager, CM: ContactManager> Builder<NM, CM> {
         Ok(())
     }
 
+    /// Adds the contact from `info.tx_node_id` to `info.rx_node_id` and the contact in the other
+    /// direction over the same window, as two contacts declared one after the other.
+    fn add_bidir_contact(
+        &mut self,
+        info: ContactInfo,
+        forward: CM,
+        backward: CM,
+    ) -> Result<(), &'static str> {
+        let reverse = ContactInfo::new(info.rx_node_id, info.tx_node_id, info.start, info.end);
+        self.add_contact(Contact::try_new(info, forward).ok_or("Could not build the contact")?)?;
+        self.add_contact(
+            Contact::try_new(reverse, backward).ok_or("Could not build the reverse contact")?,
+        )
+    }
+
     fn add_virtual_node(&mut self, vnode: VirtualNodeInfo) -> Result<(), &'static str> {
         self.check_new_virtual_id(vnode.vid)?;
         for rid in &vnode.rids {
@@ -146,6 +161,9 @@ impl<NM: NodeManager, CM: ContactManager> Builder<NM, CM> {
 #[derive(Clone, Copy)]
 pub enum ASABRPlanInfoKind {
     Contact,
+    /// A contact in both directions over the same window, with the manager parameters of each
+    /// direction: `bidir <a> <b> <start> <end> <a to b parameters> <b to a parameters>`.
+    Bidir,
     Node,
     ENode,
     VNode,
@@ -158,6 +176,7 @@ impl TryFrom<&str> for ASABRPlanInfoKind {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         Ok(match value {
             "contact" => Self::Contact,
+            "bidir" => Self::Bidir,
             "node" => Self::Node,
             "enode" => Self::ENode,
             "vnode" => Self::VNode,
@@ -175,11 +194,16 @@ enum InBuild<NM: NodeManager + Parse, CM: ContactManager + Parse> {
     NM(RealNodeType, NodeInfo, NM::Parser),
     Contact(<ContactInfo as Parse>::Parser),
     CM(ContactInfo, CM::Parser),
+    Bidir(<ContactInfo as Parse>::Parser),
+    /// The manager of the forward direction once parsed, then the one of the reverse direction.
+    BidirCM(ContactInfo, Option<CM>, CM::Parser),
 }
 
 pub struct ASABRParser<NM: NodeManager + Parse, CM: ContactManager + Parse> {
     builder: Builder<NM, CM>,
     in_build: InBuild<NM, CM>,
+    /// The number of tokens fed since the keyword of the current declaration.
+    fed: usize,
 }
 
 impl<NM: NodeManager + Parse, CM: ContactManager + Parse> Default for ASABRParser<NM, CM> {
@@ -187,6 +211,52 @@ impl<NM: NodeManager + Parse, CM: ContactManager + Parse> Default for ASABRParse
         Self {
             builder: Builder::new(),
             in_build: InBuild::None,
+            fed: 0,
+        }
+    }
+}
+
+impl<NM: NodeManager + Parse, CM: ContactManager + Parse> ASABRParser<NM, CM> {
+    /// Adds the contact whose manager is being parsed, the `CM` or `BidirCM` declaration ending.
+    fn finish_contact(&mut self) -> Result<(), &'static str> {
+        match mem::replace(&mut self.in_build, InBuild::None) {
+            InBuild::CM(contact, sub) => self.builder.add_contact(
+                Contact::try_new(contact, CM::parse(sub)?).ok_or("Could not build the contact")?,
+            ),
+            InBuild::BidirCM(info, Some(forward), sub) => {
+                self.builder
+                    .add_bidir_contact(info, forward, CM::parse(sub)?)
+            }
+            _ => Err(INVALID_STATE),
+        }
+    }
+
+    /// Names the field the next token is expected to hold, to give context to the errors.
+    pub(crate) fn next_field(&self) -> &'static str {
+        const CONTACT_FIELDS: [&str; 4] = [
+            "contact transmitter id",
+            "contact receiver id",
+            "contact start time",
+            "contact end time",
+        ];
+        const NODE_FIELDS: [&str; 2] = ["node id", "node name"];
+        const VNODE_FIELDS: [&str; 2] = ["virtual node id", "virtual node name"];
+
+        match self.in_build {
+            InBuild::None => "declaration keyword",
+            InBuild::Contact(_) | InBuild::Bidir(_) => {
+                CONTACT_FIELDS.get(self.fed).copied().unwrap_or("contact")
+            }
+            InBuild::CM(..) => "contact manager parameters",
+            // a complete forward manager ends at the first token it cannot take
+            InBuild::BidirCM(_, None, ref p) if !CM::complete(p) => "contact manager parameters",
+            InBuild::BidirCM(..) => "reverse contact manager parameters",
+            InBuild::RNode(..) => NODE_FIELDS.get(self.fed).copied().unwrap_or("node"),
+            InBuild::NM(..) => "node manager parameters",
+            InBuild::VNode(_) => VNODE_FIELDS
+                .get(self.fed)
+                .copied()
+                .unwrap_or("virtual node real ids"),
         }
     }
 }
@@ -197,6 +267,8 @@ pub enum ASABRTokens<NMTok: Clone, CMTok: Clone> {
     RNode(<NodeInfo as Parse>::Token),
     NM(NMTok),
     CM(CMTok),
+    /// The first token of the reverse manager of a `bidir` declaration, ending the forward one.
+    ReverseCM(CMTok),
     Contact(<ContactInfo as Parse>::Token),
     Keywords(ASABRPlanInfoKind),
 }
@@ -205,19 +277,28 @@ impl<NM: NodeManager + Parse, CM: ContactManager + Parse> Parse for ContactPlan<
     type Token = ASABRTokens<NM::Token, CM::Token>;
     type Parser = ASABRParser<NM, CM>;
 
-    fn parse(p: Self::Parser) -> Result<Self, &'static str> {
-        match p.in_build {
-            InBuild::None => p.builder.build(),
-            _ => Err(EOF),
+    fn parse(mut p: Self::Parser) -> Result<Self, &'static str> {
+        match &p.in_build {
+            InBuild::None => {}
+            InBuild::CM(_, sub) | InBuild::BidirCM(_, Some(_), sub) if CM::complete(sub) => {
+                p.finish_contact()?
+            }
+            _ => return Err(EOF),
         }
+        p.builder.build()
     }
 
     fn feed(tok: Self::Token, parser: &mut Self::Parser) -> Result<bool, &'static str> {
+        parser.fed = match parser.in_build {
+            InBuild::None => 0,
+            _ => parser.fed + 1,
+        };
         match (&mut parser.in_build, tok) {
             (InBuild::None, ASABRTokens::Keywords(kind)) => match kind {
                 ASABRPlanInfoKind::Contact => {
                     parser.in_build = InBuild::Contact(Default::default())
                 }
+                ASABRPlanInfoKind::Bidir => parser.in_build = InBuild::Bidir(Default::default()),
                 ASABRPlanInfoKind::Node => {
                     parser.in_build = InBuild::RNode(RealNodeType::Node, Default::default())
                 }
@@ -276,15 +357,45 @@ impl<NM: NodeManager + Parse, CM: ContactManager + Parse> Parse for ContactPlan<
             }
             (InBuild::CM(_, sub), ASABRTokens::CM(tok)) => {
                 if CM::feed(tok, sub)? {
-                    let InBuild::CM(contact, sub) =
-                        mem::replace(&mut parser.in_build, InBuild::None)
-                    else {
-                        unreachable!();
-                    };
-                    parser.builder.add_contact(
-                        Contact::try_new(contact, CM::parse(sub)?)
-                            .ok_or("Could not build the contact")?,
-                    )?
+                    parser.finish_contact()?
+                }
+            }
+            // the optional trailing tokens of the manager were omitted
+            (InBuild::CM(_, sub), ASABRTokens::Keywords(kind))
+            | (InBuild::BidirCM(_, Some(_), sub), ASABRTokens::Keywords(kind))
+                if CM::complete(sub) =>
+            {
+                parser.finish_contact()?;
+                return Self::feed(ASABRTokens::Keywords(kind), parser);
+            }
+            (InBuild::BidirCM(_, forward @ None, sub), ASABRTokens::ReverseCM(tok))
+                if CM::complete(sub) =>
+            {
+                *forward = Some(CM::parse(mem::take(sub))?);
+                return Self::feed(ASABRTokens::CM(tok), parser);
+            }
+            (InBuild::Bidir(sub), ASABRTokens::Contact(tok)) => {
+                if ContactInfo::feed(tok, sub)? {
+                    let info = ContactInfo::parse(*sub)?;
+                    if CM::NOFEED {
+                        parser.builder.add_bidir_contact(
+                            info,
+                            CM::parse(Default::default())?,
+                            CM::parse(Default::default())?,
+                        )?;
+                        parser.in_build = InBuild::None
+                    } else {
+                        parser.in_build = InBuild::BidirCM(info, None, Default::default());
+                    }
+                }
+            }
+            (InBuild::BidirCM(_, forward, sub), ASABRTokens::CM(tok)) => {
+                if CM::feed(tok, sub)? {
+                    if forward.is_none() {
+                        *forward = Some(CM::parse(mem::take(sub))?);
+                    } else {
+                        parser.finish_contact()?
+                    }
                 }
             }
             (InBuild::NM(_, _, sub), ASABRTokens::NM(tok)) => {
@@ -323,7 +434,20 @@ where
             InBuild::RNode(_, p) => ASABRTokens::RNode(NodeInfo::lex(t, p)?),
             InBuild::NM(_, _, p) => ASABRTokens::NM(NM::lex(t, p)?),
             InBuild::Contact(p) => ASABRTokens::Contact(ContactInfo::lex(t, p)?),
-            InBuild::CM(_, p) => ASABRTokens::CM(CM::lex(t, p)?),
+            InBuild::Bidir(p) => ASABRTokens::Contact(ContactInfo::lex(t, p)?),
+            // a complete manager ends if the token does not fit its optional trailing tokens
+            InBuild::CM(_, p) | InBuild::BidirCM(_, Some(_), p) => match CM::lex(t, p) {
+                Err(e) if CM::complete(p) => {
+                    ASABRTokens::Keywords(ASABRPlanInfoKind::lex(t, &None).map_err(|_| e)?)
+                }
+                tok => ASABRTokens::CM(tok?),
+            },
+            InBuild::BidirCM(_, None, p) => match CM::lex(t, p) {
+                Err(_) if CM::complete(p) => {
+                    ASABRTokens::ReverseCM(CM::lex(t, &Default::default())?)
+                }
+                tok => ASABRTokens::CM(tok?),
+            },
         })
     }
 }
@@ -0,0 +1,182 @@
+use crate::{
+    contact::Contact,
+    contact_manager::ContactManager,
+    contact_plan::{ContactPlan, manage_aliases},
+    errors::ASABRError,
+    node_manager::{NodeManager, none::NoManagement},
+    types::{DataRate, Date, Duration, NodeID},
+};
+
+extern crate alloc;
+use alloc::{collections::BTreeMap as HashMap, vec, vec::Vec};
+
+/// The columns expected in a CSV contact plan, in the default order.
+pub const CSV_COLUMNS: [&str; 6] = ["tx_node", "rx_node", "start", "end", "rate", "delay"];
+
+#[derive(Debug)]
+pub struct CsvContactData {
+    tx_start: Date,
+    tx_end: Date,
+    tx_node_id: NodeID,
+    rx_node_id: NodeID,
+    data_rate: DataRate,
+    delay: Duration,
+}
+
+pub trait FromCsvContactData<NM: NodeManager, CM: ContactManager> {
+    fn csv_convert(data: &CsvContactData) -> Option<Contact<NoManagement, CM>>;
+}
+
+impl_contact_conversions!(FromCsvContactData, csv_convert, &CsvContactData);
+
+pub struct CsvContactPlan {}
+
+/// Maps each of the `CSV_COLUMNS` to its position in a header row.
+fn read_header(fields: &[&str]) -> Result<[usize; 6], ASABRError> {
+    let mut positions = [0; 6];
+    for (position, column) in positions.iter_mut().zip(CSV_COLUMNS) {
+        *position = fields.iter().position(|field| *field == column).ok_or(
+            ASABRError::ContactPlanError("The CSV header misses a required column"),
+        )?;
+    }
+    Ok(positions)
+}
+
+fn parse_field<T: core::str::FromStr>(fields: &[&str], position: usize) -> Result<T, ASABRError> {
+    fields
+        .get(position)
+        .ok_or(ASABRError::ContactPlanError("Missing field in a CSV row"))?
+        .parse()
+        .map_err(|_| ASABRError::ContactPlanError("Invalid number in a CSV row"))
+}
+
+impl CsvContactPlan {
+    /// Parses a contact plan from CSV lines, one contact per row.
+    ///
+    /// If the first row contains the `tx_node` column name, it is read as a header giving the
+    /// position of each of the `CSV_COLUMNS` (other columns are ignored). Otherwise, the rows
+    /// follow the `CSV_COLUMNS` order. Empty lines and lines starting with `#` are skipped.
+    ///
+    /// The nodes are named after the `tx_node`/`rx_node` fields and numbered in order of
+    /// appearance.
+    pub fn parse<
+        'a,
+        NM: NodeManager,
+        CM: FromCsvContactData<NM, CM> + ContactManager,
+        T: Iterator<Item = &'a str>,
+    >(
+        content: T,
+    ) -> Result<ContactPlan<NoManagement, CM>, ASABRError> {
+        let mut map_id_map = HashMap::new();
+        let mut positions = [0, 1, 2, 3, 4, 5];
+        let mut first_row = true;
+
+        let mut contacts = vec![];
+        let mut vertices = vec![];
+
+        for line in content {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<_> = line.split(',').map(str::trim).collect();
+
+            if first_row {
+                first_row = false;
+                if fields.contains(&CSV_COLUMNS[0]) {
+                    positions = read_header(&fields)?;
+                    continue;
+                }
+            }
+
+            let [tx, rx, start, end, rate, delay] = positions;
+            let tx_name = fields
+                .get(tx)
+                .ok_or(ASABRError::ContactPlanError("Missing field in a CSV row"))?;
+            let rx_name = fields
+                .get(rx)
+                .ok_or(ASABRError::ContactPlanError("Missing field in a CSV row"))?;
+            let data = CsvContactData {
+                tx_start: parse_field(&fields, start)?,
+                tx_end: parse_field(&fields, end)?,
+                tx_node_id: manage_aliases(&mut map_id_map, tx_name, &mut vertices),
+                rx_node_id: manage_aliases(&mut map_id_map, rx_name, &mut vertices),
+                data_rate: parse_field(&fields, rate)?,
+                delay: parse_field(&fields, delay)?,
+            };
+            contacts.push(
+                CM::csv_convert(&data)
+                    .ok_or(ASABRError::ContactPlanError("Invalid contact in a CSV row"))?,
+            );
+        }
+
+        Ok(ContactPlan::new(vertices, contacts, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use std::format;
+
+    #[test]
+    fn csv_matches_asabr_plan() {
+        let csv = "\
+            start,end,tx_node,rx_node,delay,rate,comment
+            0,100,gs,sat,1,1000,first pass
+            # eclipse
+            50,150,sat,relay,2,500,
+            200,300,relay,gs,3,750,last pass";
+        let from_csv = CsvContactPlan::parse::<NoManagement, EVLManager, _>(csv.lines()).unwrap();
+        let from_asabr = parse_from_str::<NoManagement, EVLManager>(
+            "node 0 gs
+            node 1 sat
+            node 2 relay
+            contact 0 1 0 100 1000 1
+            contact 1 2 50 150 500 2
+            contact 2 0 200 300 750 3",
+        )
+        .unwrap();
+
+        assert_eq!(
+            format!("{:?}", from_csv.vertices),
+            format!("{:?}", from_asabr.vertices),
+            "TEST FAILED: The CSV nodes should match the A-SABR plan."
+        );
+        assert_eq!(
+            format!("{:?}", from_csv.contacts),
+            format!("{:?}", from_asabr.contacts),
+            "TEST FAILED: The CSV contacts should match the A-SABR plan."
+        );
+    }
+
+    #[test]
+    fn csv_without_header_uses_default_order() {
+        let plan =
+            CsvContactPlan::parse::<NoManagement, EVLManager, _>(["0,1,0,100,1000,1"].into_iter())
+                .unwrap();
+        let info = plan.contacts[0].info;
+        assert_eq!(
+            (info.tx_node_id, info.rx_node_id, info.start, info.end),
+            (0, 1, 0.0, 100.0),
+            "TEST FAILED: The row should follow the default column order."
+        );
+    }
+
+    #[test]
+    fn csv_header_missing_column_is_rejected() {
+        assert!(
+            matches!(
+                CsvContactPlan::parse::<NoManagement, EVLManager, _>(
+                    ["tx_node,rx_node,start,end,rate", "0,1,0,100,1000"].into_iter()
+                ),
+                Err(ASABRError::ContactPlanError(_))
+            ),
+            "TEST FAILED: A header without the delay column should be rejected."
+        );
+    }
+}
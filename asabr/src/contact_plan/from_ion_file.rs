@@ -1,20 +1,11 @@
 use crate::{
-    contact::{Contact, ContactInfo},
-    contact_manager::{
-        ContactManager,
-        legacy::{
-            eto::{ETOManager, PETOManager},
-            evl::{EVLManager, PEVLManager},
-            qd::{PQDManager, QDManager},
-        },
-        segmentation::{Segment, seg::SegmentationManager},
-    },
-    contact_plan::ContactPlan,
+    contact::Contact,
+    contact_manager::ContactManager,
+    contact_plan::{ContactPlan, manage_aliases},
     errors::ASABRError,
-    node::{Node, NodeInfo},
     node_manager::{NodeManager, none::NoManagement},
+    parsing::Located,
     types::{DataRate, Date, Duration, NodeID},
-    vertex::Vertex,
 };
 
 extern crate alloc;
@@ -62,107 +53,124 @@ struct IONRangeData {
     delay: Duration,
 }
 
-fn contact_info_from_tvg_data(data: &IONContactData) -> ContactInfo {
-    ContactInfo::new(data.tx_node_id, data.rx_node_id, data.tx_start, data.tx_end)
-}
-
 pub trait FromIONContactData<NM: NodeManager, CM: ContactManager> {
     fn ion_convert(data: &IONContactData) -> Option<Contact<NoManagement, CM>>;
 }
 
-macro_rules! generate_for_evl_variants {
-    ($nm_name:ident, $cm_name:ident) => {
-        impl FromIONContactData<$nm_name, $cm_name> for $cm_name {
-            fn ion_convert(data: &IONContactData) -> Option<Contact<$nm_name, $cm_name>> {
-                let contact_info = contact_info_from_tvg_data(&data);
-                let manager = $cm_name::new(data.data_rate, data.delay);
-                return Contact::try_new(contact_info, manager);
-            }
-        }
-    };
-}
-
-generate_for_evl_variants!(NoManagement, EVLManager);
-generate_for_evl_variants!(NoManagement, ETOManager);
-generate_for_evl_variants!(NoManagement, QDManager);
-generate_for_evl_variants!(NoManagement, PEVLManager);
-generate_for_evl_variants!(NoManagement, PETOManager);
-generate_for_evl_variants!(NoManagement, PQDManager);
-
-impl FromIONContactData<NoManagement, SegmentationManager> for SegmentationManager {
-    fn ion_convert(data: &IONContactData) -> Option<Contact<NoManagement, SegmentationManager>> {
-        let contact_info = contact_info_from_tvg_data(data);
-        let manager = SegmentationManager::new(
-            vec![Segment::<DataRate> {
-                start: data.tx_start,
-                end: data.tx_end,
-                val: data.data_rate,
-            }],
-            vec![Segment::<Duration> {
-                start: data.tx_start,
-                end: data.tx_end,
-                val: data.delay,
-            }],
-        );
-        Contact::try_new(contact_info, manager)
-    }
-}
+impl_contact_conversions!(FromIONContactData, ion_convert, &IONContactData);
 
 pub struct IONContactPlan {}
 
-fn manage_aliases<'a>(
-    map_id_map: &mut HashMap<&'a str, NodeID>,
-    candidate_name: &'a str,
-    vertices: &mut Vec<Vertex<NoManagement>>,
-) -> NodeID {
-    if let Some(value) = map_id_map.get(candidate_name) {
-        *value
-    } else {
-        let next = map_id_map.len() as NodeID;
-        map_id_map.insert(candidate_name, next);
-        vertices.push(Vertex::INode(
-            Node::try_new(
-                NodeInfo {
-                    id: next as NodeID,
-                    name: candidate_name.into(),
-                    excluded: false,
-                },
-                NoManagement {},
-            )
-            .unwrap(),
-        ));
-        next
-    }
-}
+/// The commands skipped while parsing an ION plan, located in the input.
+pub type IONWarnings = Vec<Located<&'static str>>;
 
 fn manage_contacts(
     contact_map: &mut HashMap<NodeID, HashMap<NodeID, Vec<IONContactData>>>,
     contact: IONContactData,
 ) {
-    let tx_node_id = contact.tx_node_id;
-    let rx_node_id = contact.rx_node_id;
-
-    if let Some(inner_map) = contact_map.get_mut(&tx_node_id) {
-        inner_map
-            .entry(rx_node_id)
-            .or_insert_with(Vec::new)
-            .push(contact);
-    } else {
-        let mut inner_map = HashMap::new();
-        inner_map.insert(rx_node_id, vec![contact]);
-        contact_map.insert(tx_node_id, inner_map);
+    contact_map
+        .entry(contact.tx_node_id)
+        .or_default()
+        .entry(contact.rx_node_id)
+        .or_default()
+        .push(contact);
+}
+
+/// The fields of the `a contact` and `a range` commands, after the command and object words.
+const CONTACT_FIELDS: [&str; 5] = [
+    "contact start time",
+    "contact end time",
+    "contact transmitter node",
+    "contact receiver node",
+    "contact data rate",
+];
+const RANGE_FIELDS: [&str; 5] = [
+    "range start time",
+    "range end time",
+    "range transmitter node",
+    "range receiver node",
+    "range one-way light time",
+];
+
+/// Locates `data` at the `toknum`-th word of the `linenum`-th line, `fields` naming the words
+/// following the command and object words.
+fn locate(
+    data: &'static str,
+    linenum: usize,
+    toknum: usize,
+    fields: &[&'static str],
+) -> Located<&'static str> {
+    Located {
+        data,
+        line: linenum,
+        toknum,
+        field: toknum
+            .checked_sub(2)
+            .and_then(|idx| fields.get(idx))
+            .copied()
+            .unwrap_or(""),
     }
 }
 
-fn get_confidence(vec: &[&str]) -> f32 {
+/// Returns the `toknum`-th word of a line.
+fn get_word<'a>(
+    words: &[&'a str],
+    linenum: usize,
+    toknum: usize,
+    fields: &[&'static str],
+) -> Result<&'a str, ASABRError> {
+    words
+        .get(toknum)
+        .copied()
+        .ok_or(ASABRError::ParsingError(locate(
+            "Missing field",
+            linenum,
+            toknum,
+            fields,
+        )))
+}
+
+/// Parses the `toknum`-th word of a line, ION relative times (e.g. `+60`) are read as numbers.
+fn parse_word<T: core::str::FromStr>(
+    words: &[&str],
+    linenum: usize,
+    toknum: usize,
+    fields: &[&'static str],
+) -> Result<T, ASABRError> {
+    get_word(words, linenum, toknum, fields)?
+        .parse()
+        .map_err(|_| ASABRError::ParsingError(locate("Invalid number", linenum, toknum, fields)))
+}
+
+fn get_confidence(vec: &[&str], linenum: usize) -> Result<f32, ASABRError> {
     if vec.len() >= 8 {
-        vec[7].parse::<f32>().unwrap()
+        parse_word(vec, linenum, 7, &[])
     } else {
-        1.0
+        Ok(1.0)
     }
 }
 
+/// Finds the range covering a contact. A range declared for the opposite direction applies if
+/// none is declared for the direction of the contact, as ION ranges are symmetric by default.
+fn find_range<'a>(
+    ranges: &'a [IONRangeData],
+    contact: &IONContactData,
+) -> Option<&'a IONRangeData> {
+    let covering = |tx_node_id, rx_node_id| {
+        ranges.iter().find(|range| {
+            range.tx_node_id == tx_node_id
+                && range.rx_node_id == rx_node_id
+                && range.tx_start <= contact.tx_start
+                && contact.tx_end <= range.tx_end
+        })
+    };
+    covering(contact.tx_node_id, contact.rx_node_id)
+        .or_else(|| covering(contact.rx_node_id, contact.tx_node_id))
+}
+
 impl IONContactPlan {
+    /// Parses a contact plan from the lines of an ION command file (`.ionrc`), see
+    /// `parse_with_warnings`. The skipped commands are silently ignored.
     pub fn parse<
         'a,
         NM: NodeManager,
@@ -171,18 +179,42 @@ impl IONContactPlan {
     >(
         content: T,
     ) -> Result<ContactPlan<NoManagement, CM>, ASABRError> {
-        let reader = content;
+        Self::parse_with_warnings::<NM, CM, T>(content).map(|(plan, _)| plan)
+    }
+
+    /// Parses a contact plan from the lines of an ION command file (`.ionrc`).
+    ///
+    /// The `a contact <start> <end> <tx> <rx> <rate> [<confidence>]` commands declare the
+    /// contacts, and the `a range <start> <end> <tx> <rx> <owlt>` commands give their delays: the
+    /// delay of a contact is the one-way light time of the range covering it. The nodes are
+    /// named after the ION node numbers and numbered in order of appearance. Lines starting
+    /// with `#` are ignored.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((ContactPlan, IONWarnings))` - The plan, and a warning for each
+    ///   unsupported command that was skipped.
+    /// * `Err(ASABRError)` - If a command is malformed (`ParsingError`) or a contact has no
+    ///   range (`ContactPlanError`).
+    pub fn parse_with_warnings<
+        'a,
+        NM: NodeManager,
+        CM: FromIONContactData<NM, CM> + ContactManager,
+        T: Iterator<Item = &'a str>,
+    >(
+        content: T,
+    ) -> Result<(ContactPlan<NoManagement, CM>, IONWarnings), ASABRError> {
         let mut map_id_map = HashMap::new();
 
         let mut ranges = vec![];
         let mut contact_info_map: HashMap<NodeID, HashMap<NodeID, Vec<IONContactData>>> =
             HashMap::new();
 
-        let mut contact_count = 0;
         let mut contacts = vec![];
         let mut vertices = vec![];
+        let mut warnings = vec![];
 
-        for line in reader {
+        for (linenum, line) in content.enumerate() {
             // Skip lines starting with '#'
             if line.trim_start().starts_with('#') {
                 continue;
@@ -193,77 +225,180 @@ impl IONContactPlan {
                 continue;
             }
 
-            if words[0] != "a" {
-                continue;
-            }
+            match (words[0], words.get(1).copied()) {
+                ("a", Some("contact")) => {
+                    let fields = &CONTACT_FIELDS;
+                    let tx_start: Date = parse_word(&words, linenum, 2, fields)?;
+                    let tx_end: Date = parse_word(&words, linenum, 3, fields)?;
+                    let tx_name = get_word(&words, linenum, 4, fields)?;
+                    let rx_name = get_word(&words, linenum, 5, fields)?;
+                    let data_rate: DataRate = parse_word(&words, linenum, 6, fields)?;
+                    let confidence = get_confidence(words.as_slice(), linenum)?;
+                    let tx_node_id = manage_aliases(&mut map_id_map, tx_name, &mut vertices);
+                    let rx_node_id = manage_aliases(&mut map_id_map, rx_name, &mut vertices);
 
-            if words[1] == "contact" {
-                let tx_start: Date = words[2].parse().unwrap();
-                let tx_end: Date = words[3].parse().unwrap();
-                let tx_node_id = manage_aliases(&mut map_id_map, words[4], &mut vertices);
-                let rx_node_id = manage_aliases(&mut map_id_map, words[5], &mut vertices);
-                let data_rate: DataRate = words[6].parse().unwrap();
-                let confidence = get_confidence(words.as_slice());
-                contact_count += 1;
-
-                manage_contacts(
-                    &mut contact_info_map,
-                    IONContactData {
+                    manage_contacts(
+                        &mut contact_info_map,
+                        IONContactData {
+                            tx_start,
+                            tx_end,
+                            tx_node_id,
+                            rx_node_id,
+                            data_rate,
+                            delay: 0.0,
+                            _confidence: confidence,
+                        },
+                    );
+                }
+                ("a", Some("range")) => {
+                    let fields = &RANGE_FIELDS;
+                    let tx_start: Date = parse_word(&words, linenum, 2, fields)?;
+                    let tx_end: Date = parse_word(&words, linenum, 3, fields)?;
+                    let tx_name = get_word(&words, linenum, 4, fields)?;
+                    let rx_name = get_word(&words, linenum, 5, fields)?;
+                    let delay: Duration = parse_word(&words, linenum, 6, fields)?;
+                    let tx_node_id = manage_aliases(&mut map_id_map, tx_name, &mut vertices);
+                    let rx_node_id = manage_aliases(&mut map_id_map, rx_name, &mut vertices);
+                    ranges.push(IONRangeData {
                         tx_start,
                         tx_end,
                         tx_node_id,
                         rx_node_id,
-                        data_rate,
-                        delay: 0.0,
-                        _confidence: confidence,
-                    },
-                );
-            }
-            if words[1] == "range" {
-                let tx_start: Date = words[2].parse().unwrap();
-                let tx_end: Date = words[3].parse().unwrap();
-                let tx_node_id = manage_aliases(&mut map_id_map, words[4], &mut vertices);
-                let rx_node_id = manage_aliases(&mut map_id_map, words[5], &mut vertices);
-                let delay: Duration = words[6].parse().unwrap();
-                ranges.push(IONRangeData {
-                    tx_start,
-                    tx_end,
-                    tx_node_id,
-                    rx_node_id,
-                    delay,
-                });
+                        delay,
+                    });
+                }
+                _ => warnings.push(locate("Unsupported command skipped", linenum, 0, &[])),
             }
         }
 
         for map in contact_info_map.values_mut() {
-            for contacts in map.values_mut() {
-                contacts.sort_unstable();
-            }
-        }
-
-        for range in &ranges {
-            if let Some(tx_map) = contact_info_map.get_mut(&range.tx_node_id)
-                && let Some(contact_vec) = tx_map.get_mut(&range.rx_node_id)
-            {
+            for contact_vec in map.values_mut() {
+                contact_vec.sort_unstable();
                 for contact in contact_vec.iter_mut() {
-                    if range.tx_start <= contact.tx_start && contact.tx_end <= range.tx_end {
-                        contact.delay = range.delay;
-                        contacts.push(CM::ion_convert(contact).unwrap());
-                    } else {
-                        return Err(ASABRError::ContactPlanError(
-                            "This parser only supports one range per contact",
-                        ));
-                    }
+                    contact.delay = find_range(&ranges, contact)
+                        .ok_or(ASABRError::ContactPlanError(
+                            "At least one contact has no range",
+                        ))?
+                        .delay;
+                    contacts.push(CM::ion_convert(contact).ok_or(ASABRError::ContactPlanError(
+                        "Invalid contact in the ION plan",
+                    ))?);
                 }
             }
         }
 
-        if contacts.len() != contact_count {
-            return Err(ASABRError::ContactPlanError(
-                "At least one contact has no range",
-            ));
-        }
+        Ok((ContactPlan::new(vertices, contacts, None), warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use std::format;
+
+    const PLAN_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/contact_plans/ion_format.cp"
+    );
 
-        Ok(ContactPlan::new(vertices, contacts, None))
+    #[test]
+    fn ion_file_delays_come_from_the_ranges() {
+        let content = std::fs::read_to_string(PLAN_PATH).unwrap();
+        let from_ion =
+            IONContactPlan::parse::<NoManagement, EVLManager, _>(content.lines()).unwrap();
+        let from_asabr = parse_from_str::<NoManagement, EVLManager>(
+            "node 0 1
+            node 1 2
+            node 2 3
+            node 3 4
+            node 4 5
+            node 5 6
+            contact 0 1 60 7260 10000 10
+            contact 1 2 60 7260 15000 15
+            contact 2 3 60 7260 20000 20
+            contact 3 4 60 7260 25000 25
+            contact 4 5 60 7260 30000 30",
+        )
+        .unwrap();
+
+        assert_eq!(
+            from_ion.contacts.len(),
+            5,
+            "TEST FAILED: Expected one contact per `a contact` command."
+        );
+        assert_eq!(
+            format!("{:?}", from_ion.contacts),
+            format!("{:?}", from_asabr.contacts),
+            "TEST FAILED: The contact delays should be the one-way light times of the ranges."
+        );
+    }
+
+    #[test]
+    fn ion_ranges_are_matched_per_period_and_direction() {
+        let ion = "\
+            ## begin ionadmin
+            1 1 ''
+            a contact +0 +100 1 2 1000
+            a contact +200 +300 1 2 1000
+            a contact +0 +100 2 1 500
+            a range +0 +100 1 2 1
+            a range +200 +300 1 2 2
+            s
+            ## end ionadmin";
+        let (from_ion, warnings) =
+            IONContactPlan::parse_with_warnings::<NoManagement, EVLManager, _>(ion.lines())
+                .unwrap();
+        let from_asabr = parse_from_str::<NoManagement, EVLManager>(
+            "node 0 1
+            node 1 2
+            contact 0 1 0 100 1000 1
+            contact 0 1 200 300 1000 2
+            contact 1 0 0 100 500 1",
+        )
+        .unwrap();
+
+        assert_eq!(
+            format!("{:?}", from_ion.contacts),
+            format!("{:?}", from_asabr.contacts),
+            "TEST FAILED: Each contact should take the delay of the range covering it, in either direction."
+        );
+        assert_eq!(
+            warnings.iter().map(|w| w.line).collect::<Vec<_>>(),
+            vec![1, 7],
+            "TEST FAILED: The unsupported commands should be reported."
+        );
+    }
+
+    #[test]
+    fn ion_contact_without_range_is_rejected() {
+        assert!(
+            matches!(
+                IONContactPlan::parse::<NoManagement, EVLManager, _>(
+                    ["a contact +0 +100 1 2 1000", "a range +0 +50 1 2 1"].into_iter()
+                ),
+                Err(ASABRError::ContactPlanError(_))
+            ),
+            "TEST FAILED: A contact not covered by a range should be rejected."
+        );
+    }
+
+    #[test]
+    fn ion_malformed_command_is_located() {
+        let Err(ASABRError::ParsingError(located)) =
+            IONContactPlan::parse::<NoManagement, EVLManager, _>(
+                ["a range +0 +100 1 2 1", "a contact +0 +100 1 2 fast"].into_iter(),
+            )
+        else {
+            panic!("TEST FAILED: Expected a parsing error.");
+        };
+        assert_eq!(
+            (located.line, located.toknum, located.field),
+            (1, 6, "contact data rate"),
+            "TEST FAILED: The error should point at the data rate of the contact."
+        );
     }
 }
@@ -1,16 +1,8 @@
 use crate::{
-    contact::{Contact, ContactInfo},
-    contact_manager::{
-        ContactManager,
-        legacy::{
-            eto::{ETOManager, PETOManager},
-            evl::{EVLManager, PEVLManager},
-            qd::{PQDManager, QDManager},
-        },
-        segmentation::{Segment, seg::SegmentationManager},
-    },
+    contact::Contact,
+    contact_manager::ContactManager,
     contact_plan::ContactPlan,
-    errors::ASABRError,
+    errors::{ASABRError, TVGUtilError},
     node::{Node, NodeInfo},
     node_manager::{NodeManager, none::NoManagement},
     types::{DataRate, Date, Duration, NodeID},
@@ -18,9 +10,14 @@ use crate::{
 };
 
 extern crate alloc;
-use alloc::{collections::BTreeMap as HashMap, vec, vec::Vec};
+use alloc::{
+    collections::BTreeMap as HashMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 #[derive(Debug)]
 pub struct TVGUtilContactData {
@@ -33,55 +30,126 @@ pub struct TVGUtilContactData {
     _confidence: f32,
 }
 
-fn contact_info_from_tvg_data(data: &TVGUtilContactData) -> ContactInfo {
-    ContactInfo::new(data.tx_node_id, data.rx_node_id, data.tx_start, data.tx_end)
-}
-
 pub trait FromTVGUtilContactData<NM: NodeManager, CM: ContactManager> {
     fn tvg_convert(data: TVGUtilContactData) -> Option<Contact<NoManagement, CM>>;
 }
 
-macro_rules! generate_for_evl_variants {
-    ($nm_name:ident, $cm_name:ident) => {
-        impl FromTVGUtilContactData<$nm_name, $cm_name> for $cm_name {
-            fn tvg_convert(data: TVGUtilContactData) -> Option<Contact<$nm_name, $cm_name>> {
-                let contact_info = contact_info_from_tvg_data(&data);
-                let manager = $cm_name::new(data.data_rate, data.delay);
-                return Contact::try_new(contact_info, manager);
-            }
-        }
-    };
+impl_contact_conversions!(FromTVGUtilContactData, tvg_convert, TVGUtilContactData);
+
+pub struct TVGUtilContactPlan {}
+
+/// A JSON element and its path from the root, to locate the errors.
+struct JsonNode<'a> {
+    value: &'a Value,
+    path: String,
 }
 
-generate_for_evl_variants!(NoManagement, EVLManager);
-generate_for_evl_variants!(NoManagement, ETOManager);
-generate_for_evl_variants!(NoManagement, QDManager);
-generate_for_evl_variants!(NoManagement, PEVLManager);
-generate_for_evl_variants!(NoManagement, PETOManager);
-generate_for_evl_variants!(NoManagement, PQDManager);
-
-impl FromTVGUtilContactData<NoManagement, SegmentationManager> for SegmentationManager {
-    fn tvg_convert(data: TVGUtilContactData) -> Option<Contact<NoManagement, SegmentationManager>> {
-        let contact_info = contact_info_from_tvg_data(&data);
-        let manager = SegmentationManager::new(
-            vec![Segment::<DataRate> {
-                start: data.tx_start,
-                end: data.tx_end,
-                val: data.data_rate,
-            }],
-            vec![Segment::<Duration> {
-                start: data.tx_start,
-                end: data.tx_end,
-                val: data.delay,
-            }],
-        );
-        Contact::try_new(contact_info, manager)
+impl<'a> JsonNode<'a> {
+    fn root(value: &'a Value) -> Self {
+        Self {
+            value,
+            path: "$".to_string(),
+        }
     }
-}
 
-pub struct TVGUtilContactPlan {}
+    /// Returns the `name` field of this object.
+    fn field(&self, name: &'static str) -> Result<JsonNode<'a>, TVGUtilError> {
+        let value = self
+            .as_object()?
+            .get(name)
+            .ok_or_else(|| TVGUtilError::MissingField {
+                path: self.path.clone(),
+                field: name,
+            })?;
+        Ok(JsonNode {
+            value,
+            path: format!("{}.{}", self.path, name),
+        })
+    }
+
+    /// Returns the `index`-th entry of this array, `name` describing the expected entry.
+    fn index(&self, index: usize, name: &'static str) -> Result<JsonNode<'a>, TVGUtilError> {
+        let value = self
+            .as_array()?
+            .get(index)
+            .ok_or_else(|| TVGUtilError::MissingField {
+                path: self.path.clone(),
+                field: name,
+            })?;
+        Ok(JsonNode {
+            value,
+            path: format!("{}[{}]", self.path, index),
+        })
+    }
+
+    /// Returns the entries of this array.
+    fn items(&self) -> Result<impl Iterator<Item = JsonNode<'a>>, TVGUtilError> {
+        let path = self.path.clone();
+        Ok(self
+            .as_array()?
+            .iter()
+            .enumerate()
+            .map(move |(index, value)| JsonNode {
+                value,
+                path: format!("{path}[{index}]"),
+            }))
+    }
+
+    fn unexpected(&self, expected: &'static str) -> TVGUtilError {
+        TVGUtilError::UnexpectedType {
+            path: self.path.clone(),
+            expected,
+        }
+    }
+
+    fn as_object(&self) -> Result<&'a Map<String, Value>, TVGUtilError> {
+        self.value
+            .as_object()
+            .ok_or_else(|| self.unexpected("an object"))
+    }
+
+    fn as_array(&self) -> Result<&'a Vec<Value>, TVGUtilError> {
+        self.value
+            .as_array()
+            .ok_or_else(|| self.unexpected("an array"))
+    }
+
+    fn as_str(&self) -> Result<&'a str, TVGUtilError> {
+        self.value
+            .as_str()
+            .ok_or_else(|| self.unexpected("a string"))
+    }
+
+    fn as_f64(&self) -> Result<f64, TVGUtilError> {
+        self.value
+            .as_f64()
+            .ok_or_else(|| self.unexpected("a number"))
+    }
+}
 
 impl TVGUtilContactPlan {
+    /// Parses a contact plan from the content of a tvgutil JSON file, see `parse`.
+    ///
+    /// # Returns
+    ///
+    /// * `Err(ASABRError::TVGUtilError)` - With `TVGUtilError::InvalidJson` if `content` is not
+    ///   valid JSON, or as `parse` otherwise.
+    pub fn parse_str<NM: NodeManager, CM: FromTVGUtilContactData<NM, CM> + ContactManager>(
+        content: &str,
+    ) -> Result<ContactPlan<NoManagement, CM>, ASABRError> {
+        let json_data = serde_json::from_str(content).map_err(|_| TVGUtilError::InvalidJson)?;
+        Self::parse::<NM, CM>(json_data)
+    }
+
+    /// Parses a contact plan from a tvgutil JSON document.
+    ///
+    /// The nodes are the keys of the `vertices` object, numbered in order. Each entry of the
+    /// `edges` array gives its pair of `vertices` and its `contacts`.
+    ///
+    /// # Returns
+    ///
+    /// * `Err(ASABRError::TVGUtilError)` - If the document does not follow the tvgutil schema,
+    ///   the error gives the JSON path of the offending element.
     pub fn parse<NM: NodeManager, CM: FromTVGUtilContactData<NM, CM> + ContactManager>(
         json_data: serde_json::Value,
     ) -> Result<ContactPlan<NoManagement, CM>, ASABRError> {
@@ -90,10 +158,8 @@ impl TVGUtilContactPlan {
 
         let mut map_id_map: HashMap<&str, NodeID> = HashMap::new();
 
-        let parsed: Value = json_data;
-        let json_nodes = parsed["vertices"]
-            .as_object()
-            .ok_or(ASABRError::ContactPlanError("no \"vertice\" in json"))?;
+        let root = JsonNode::root(&json_data);
+        let json_nodes = root.field("vertices")?.as_object()?;
 
         for (node_id, (node_name, _node_data)) in json_nodes.iter().enumerate() {
             map_id_map.insert(node_name, node_id as NodeID);
@@ -110,38 +176,44 @@ impl TVGUtilContactPlan {
             ));
         }
 
-        let json_contacts = parsed["edges"]
-            .as_array()
-            .ok_or(ASABRError::ContactPlanError("no \"edge\" in json"))?;
-        for nodes_pair in json_contacts {
-            let data = nodes_pair.as_object().unwrap();
-            let pair = data["vertices"].as_array().unwrap();
-            let tx_node_id = map_id_map.get(pair[0].as_str().unwrap()).unwrap();
-            let rx_node_id = map_id_map.get(pair[1].as_str().unwrap()).unwrap();
-
-            for contact_data in data["contacts"].as_array().unwrap() {
-                let contact_array = contact_data.as_array().unwrap();
-                let start = contact_array[2].as_f64().unwrap() as Date;
-                let end = contact_array[3].as_f64().unwrap() as Date;
-                let first_level_array = contact_array[4].as_array().unwrap();
-                let second_level_array = first_level_array[0].as_array().unwrap();
-                let confidence = second_level_array[1].as_f64().unwrap() as f32;
-                let third_level_array = second_level_array[2].as_array().unwrap();
-                let fourth_level_array = third_level_array[0].as_array().unwrap();
-                let data_rate = fourth_level_array[1].as_f64().unwrap() as DataRate;
-                let delay = fourth_level_array[2].as_f64().unwrap() as Duration;
+        let node_id = |node: JsonNode| -> Result<NodeID, TVGUtilError> {
+            map_id_map
+                .get(node.as_str()?)
+                .copied()
+                .ok_or(TVGUtilError::UnknownNode { path: node.path })
+        };
+
+        for edge in root.field("edges")?.items()? {
+            let pair = edge.field("vertices")?;
+            let tx_node_id = node_id(pair.index(0, "transmitting node")?)?;
+            let rx_node_id = node_id(pair.index(1, "receiving node")?)?;
+
+            for contact_data in edge.field("contacts")?.items()? {
+                let start = contact_data.index(2, "start time")?.as_f64()? as Date;
+                let end = contact_data.index(3, "end time")?.as_f64()? as Date;
+                let generation = contact_data
+                    .index(4, "characteristics")?
+                    .index(0, "characteristic")?;
+                let confidence = generation.index(1, "confidence")?.as_f64()? as f32;
+                let segment = generation
+                    .index(2, "rate segments")?
+                    .index(0, "rate segment")?;
+                let data_rate = segment.index(1, "data rate")?.as_f64()? as DataRate;
+                let delay = segment.index(2, "delay")?.as_f64()? as Duration;
 
                 let tvgcontact = TVGUtilContactData {
                     tx_start: start,
                     tx_end: end,
-                    tx_node_id: *tx_node_id,
-                    rx_node_id: *rx_node_id,
+                    tx_node_id,
+                    rx_node_id,
                     delay,
                     data_rate,
                     _confidence: confidence,
                 };
 
-                let contact = CM::tvg_convert(tvgcontact).unwrap();
+                let contact = CM::tvg_convert(tvgcontact).ok_or(TVGUtilError::InvalidContact {
+                    path: contact_data.path.clone(),
+                })?;
 
                 contacts.push(contact);
             }
@@ -149,3 +221,81 @@ impl TVGUtilContactPlan {
         Ok(ContactPlan::new(vertices, contacts, None))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+
+    fn parse_str(content: &str) -> Result<ContactPlan<NoManagement, EVLManager>, ASABRError> {
+        TVGUtilContactPlan::parse_str::<NoManagement, EVLManager>(content)
+    }
+
+    const VALID_PLAN: &str = r#"{
+        "vertices": {"a": ["b"], "b": ["a"]},
+        "edges": [{"vertices": ["a", "b"], "contacts": [
+            ["a", "b", 0.0, 100.0, [[0.0, 1.0, [[0.0, 9600.0, 1.0]]]]]
+        ]}]
+    }"#;
+
+    #[test]
+    fn tvgutil_plan_is_parsed() {
+        let plan = parse_str(VALID_PLAN).unwrap();
+        let info = plan.contacts[0].info;
+        assert_eq!(
+            (
+                plan.vertices.len(),
+                info.tx_node_id,
+                info.rx_node_id,
+                info.end
+            ),
+            (2, 0, 1, 100.0),
+            "TEST FAILED: Expected a single contact from a to b."
+        );
+    }
+
+    #[test]
+    fn tvgutil_missing_contacts_is_named() {
+        let err =
+            parse_str(r#"{"vertices": {"a": [], "b": []}, "edges": [{"vertices": ["a", "b"]}]}"#)
+                .err()
+                .expect("TEST FAILED: Expected the missing contacts to be rejected.");
+        let ASABRError::TVGUtilError(err) = err else {
+            panic!("TEST FAILED: Expected a TVGUtilError, got {err:?}.");
+        };
+        assert_eq!(
+            err,
+            TVGUtilError::MissingField {
+                path: "$.edges[0]".to_string(),
+                field: "contacts"
+            },
+            "TEST FAILED: The error should name the missing field and its location."
+        );
+    }
+
+    #[test]
+    fn tvgutil_errors_are_located() {
+        let cases = [
+            ("{\"vertices\": ", TVGUtilError::InvalidJson),
+            (
+                &VALID_PLAN.replace("9600.0", "\"fast\""),
+                TVGUtilError::UnexpectedType {
+                    path: "$.edges[0].contacts[0][4][0][2][0][1]".to_string(),
+                    expected: "a number",
+                },
+            ),
+            (
+                &VALID_PLAN.replace("[\"a\", \"b\"]", "[\"a\", \"c\"]"),
+                TVGUtilError::UnknownNode {
+                    path: "$.edges[0].vertices[1]".to_string(),
+                },
+            ),
+        ];
+        for (content, expected) in cases {
+            assert!(
+                matches!(parse_str(content), Err(ASABRError::TVGUtilError(ref err)) if *err == expected),
+                "TEST FAILED: Expected {expected:?}."
+            );
+        }
+    }
+}
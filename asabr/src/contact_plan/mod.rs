@@ -1,15 +1,99 @@
 extern crate alloc;
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap as HashMap, vec, vec::Vec};
 
 use crate::contact::Contact;
 use crate::contact_manager::ContactManager;
-use crate::node_manager::NodeManager;
+use crate::node::{Node, NodeInfo};
+use crate::node_manager::{NodeManager, none::NoManagement};
+use crate::types::{Date, NodeID};
 use crate::vertex::Vertex;
 use crate::vnode::VirtualNodeMap;
 
+/// Implements the contact conversion trait `$trait` of a plan format for the legacy managers
+/// built from a rate and a delay, and for `SegmentationManager` with a single segment.
+///
+/// `$data` is the parameter type of `$method`, exposing the `tx_node_id`, `rx_node_id`,
+/// `tx_start`, `tx_end`, `data_rate` and `delay` fields.
+macro_rules! impl_contact_conversions {
+    ($trait:ident, $method:ident, $data:ty) => {
+        impl_contact_conversions!(
+            $trait,
+            $method,
+            $data,
+            [
+                $crate::contact_manager::legacy::evl::EVLManager,
+                $crate::contact_manager::legacy::eto::ETOManager,
+                $crate::contact_manager::legacy::qd::QDManager,
+                $crate::contact_manager::legacy::evl::PEVLManager,
+                $crate::contact_manager::legacy::eto::PETOManager,
+                $crate::contact_manager::legacy::qd::PQDManager
+            ]
+        );
+
+        impl
+            $trait<
+                $crate::node_manager::none::NoManagement,
+                $crate::contact_manager::segmentation::seg::SegmentationManager,
+            > for $crate::contact_manager::segmentation::seg::SegmentationManager
+        {
+            fn $method(
+                data: $data,
+            ) -> Option<
+                $crate::contact::Contact<
+                    $crate::node_manager::none::NoManagement,
+                    $crate::contact_manager::segmentation::seg::SegmentationManager,
+                >,
+            > {
+                use $crate::contact_manager::segmentation::Segment;
+                let contact_info = $crate::contact::ContactInfo::new(
+                    data.tx_node_id,
+                    data.rx_node_id,
+                    data.tx_start,
+                    data.tx_end,
+                );
+                let manager = $crate::contact_manager::segmentation::seg::SegmentationManager::new(
+                    alloc::vec![Segment::<$crate::types::DataRate> {
+                        start: data.tx_start,
+                        end: data.tx_end,
+                        val: data.data_rate,
+                    }],
+                    alloc::vec![Segment::<$crate::types::Duration> {
+                        start: data.tx_start,
+                        end: data.tx_end,
+                        val: data.delay,
+                    }],
+                );
+                $crate::contact::Contact::try_new(contact_info, manager)
+            }
+        }
+    };
+    ($trait:ident, $method:ident, $data:ty, [$($cm:ty),*]) => {
+        $(
+            impl $trait<$crate::node_manager::none::NoManagement, $cm> for $cm {
+                fn $method(
+                    data: $data,
+                ) -> Option<$crate::contact::Contact<$crate::node_manager::none::NoManagement, $cm>>
+                {
+                    let contact_info = $crate::contact::ContactInfo::new(
+                        data.tx_node_id,
+                        data.rx_node_id,
+                        data.tx_start,
+                        data.tx_end,
+                    );
+                    let manager = <$cm>::new(data.data_rate, data.delay);
+                    $crate::contact::Contact::try_new(contact_info, manager)
+                }
+            }
+        )*
+    };
+}
+
 pub mod asabr_file_lexer;
+pub mod asabr_file_writer;
 pub mod from_asabr_lexer;
+pub mod from_csv_file;
 pub mod from_ion_file;
+#[cfg(feature = "serde")]
 pub mod from_tvgutil_file;
 
 /// Represents a contact plan and associated management information.
@@ -51,3 +135,214 @@ impl<NM: NodeManager, CM: ContactManager> ContactPlan<NM, CM> {
         }
     }
 }
+
+/// Returns the ID of the node named `candidate_name`, declaring it in `vertices` with the next
+/// ID if the name was not seen yet, for the plan formats naming their nodes in the contacts.
+fn manage_aliases<'a>(
+    map_id_map: &mut HashMap<&'a str, NodeID>,
+    candidate_name: &'a str,
+    vertices: &mut Vec<Vertex<NoManagement>>,
+) -> NodeID {
+    if let Some(value) = map_id_map.get(candidate_name) {
+        *value
+    } else {
+        let next = map_id_map.len() as NodeID;
+        map_id_map.insert(candidate_name, next);
+        vertices.push(Vertex::INode(
+            Node::try_new(
+                NodeInfo {
+                    id: next,
+                    name: candidate_name.into(),
+                    excluded: false,
+                },
+                NoManagement {},
+            )
+            .unwrap(),
+        ));
+        next
+    }
+}
+
+/// Extracts the subset of a contact plan that can be used by routes starting from `source`.
+///
+/// A contact is kept if its transmitting node can be reached before the end of the contact,
+/// following time-respecting sequences of contacts that start before `horizon`. Link delays are
+/// ignored, so the selection is optimistic. A node is kept if it is the source or if it is
+/// reached by a kept contact.
+///
+/// The kept real nodes are renumbered contiguously (preserving their relative order) so that
+/// the result can be loaded into a `Multigraph`, and the contacts are updated accordingly.
+/// Virtual nodes are not carried over.
+///
+/// # Parameters
+///
+/// * `vertices` - The vertices of the original plan.
+/// * `contacts` - The contacts of the original plan.
+/// * `source` - The node from which the routes start.
+/// * `horizon` - Contacts starting after this date are dropped.
+///
+/// # Returns
+///
+/// * `ContactPlan<NM, CM>` - The pruned contact plan.
+pub fn prune_to_reachable<NM: NodeManager, CM: ContactManager>(
+    vertices: Vec<Vertex<NM>>,
+    contacts: Vec<Contact<NM, CM>>,
+    source: NodeID,
+    horizon: Date,
+) -> ContactPlan<NM, CM> {
+    let id_count = contacts
+        .iter()
+        .map(|c| c.info.tx_node_id.max(c.info.rx_node_id) as usize + 1)
+        .chain(core::iter::once(source as usize + 1))
+        .max()
+        .unwrap_or(0);
+
+    // earliest known arrival time at each node, relaxed until no improvement is found
+    let mut earliest: Vec<Option<Date>> = vec![None; id_count];
+    earliest[source as usize] = Some(Date::MIN);
+    let usable = |contact: &Contact<NM, CM>, earliest: &[Option<Date>]| match earliest
+        [contact.info.tx_node_id as usize]
+    {
+        Some(at_time) if contact.info.start <= horizon && at_time < contact.info.end => {
+            Some(at_time.max(contact.info.start))
+        }
+        _ => None,
+    };
+
+    let mut updated = true;
+    while updated {
+        updated = false;
+        for contact in &contacts {
+            if let Some(arrival) = usable(contact, &earliest) {
+                let rx = contact.info.rx_node_id as usize;
+                if earliest[rx].is_none_or(|known| arrival < known) {
+                    earliest[rx] = Some(arrival);
+                    updated = true;
+                }
+            }
+        }
+    }
+
+    let mut new_ids: Vec<Option<NodeID>> = vec![None; id_count];
+    let mut kept_vertices = Vec::new();
+    for vertex in vertices {
+        let (mut node, is_inode) = match vertex {
+            Vertex::INode(node) => (node, true),
+            Vertex::ENode(node) => (node, false),
+            Vertex::VNode(_) => continue,
+        };
+        let id = node.info.id as usize;
+        if id >= id_count || earliest[id].is_none() {
+            continue;
+        }
+        let new_id = kept_vertices.len() as NodeID;
+        new_ids[id] = Some(new_id);
+        node.info.id = new_id;
+        kept_vertices.push(match is_inode {
+            true => Vertex::INode(node),
+            false => Vertex::ENode(node),
+        });
+    }
+
+    let mut kept_contacts = Vec::new();
+    for mut contact in contacts {
+        if usable(&contact, &earliest).is_none() {
+            continue;
+        }
+        let (Some(tx), Some(rx)) = (
+            new_ids[contact.info.tx_node_id as usize],
+            new_ids[contact.info.rx_node_id as usize],
+        ) else {
+            continue;
+        };
+        contact.info.tx_node_id = tx;
+        contact.info.rx_node_id = rx;
+        kept_contacts.push(contact);
+    }
+
+    ContactPlan::new(kept_vertices, kept_contacts, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multigraph::Multigraph;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::*;
+
+    fn vertex_ids(plan: &ContactPlan<NoManagement, impl ContactManager>) -> Vec<NodeID> {
+        plan.vertices
+            .iter()
+            .map(|v| match v {
+                Vertex::INode(node) | Vertex::ENode(node) => node.info.id,
+                Vertex::VNode((_, id)) => *id,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn prune_drops_unreachable_subgraph() {
+        // A(0) -> B(2) -> C(3) is reachable, X(1) -> Y(4) is isolated,
+        // C -> X closes before C is reached, A -> Y starts after the horizon.
+        let plan = prune_to_reachable(
+            vec![
+                make_vertex(0, "A", NoManagement {}),
+                make_vertex(1, "X", NoManagement {}),
+                make_vertex(2, "B", NoManagement {}),
+                make_vertex(3, "C", NoManagement {}),
+                make_vertex(4, "Y", NoManagement {}),
+            ],
+            vec![
+                make_contact::<NoManagement>(0, 2, 0.0, 10.0, 1.0, 0.0),
+                make_contact::<NoManagement>(2, 3, 20.0, 30.0, 1.0, 0.0),
+                make_contact::<NoManagement>(1, 4, 0.0, 10.0, 1.0, 0.0),
+                make_contact::<NoManagement>(3, 1, 5.0, 8.0, 1.0, 0.0),
+                make_contact::<NoManagement>(0, 4, 200.0, 210.0, 1.0, 0.0),
+            ],
+            0,
+            100.0,
+        );
+
+        assert_eq!(
+            vertex_ids(&plan),
+            vec![0, 1, 2],
+            "TEST FAILED: Only A, B and C should be kept, renumbered contiguously."
+        );
+        let links: Vec<(NodeID, NodeID)> = plan
+            .contacts
+            .iter()
+            .map(|c| (c.info.tx_node_id, c.info.rx_node_id))
+            .collect();
+        assert_eq!(
+            links,
+            vec![(0, 1), (1, 2)],
+            "TEST FAILED: Only the A -> B and B -> C contacts should be kept."
+        );
+        assert!(
+            Multigraph::new(plan).is_ok(),
+            "TEST FAILED: The pruned plan should be loadable."
+        );
+    }
+
+    #[test]
+    fn prune_keeps_fully_reachable_plan() {
+        let plan = prune_to_reachable(
+            vec![
+                make_vertex(0, "A", NoManagement {}),
+                make_vertex(1, "B", NoManagement {}),
+                make_vertex(2, "C", NoManagement {}),
+            ],
+            vec![
+                make_contact::<NoManagement>(0, 1, 0.0, 2000.0, 100.0, 1.0),
+                make_contact::<NoManagement>(1, 2, 0.0, 2000.0, 100.0, 1.0),
+            ],
+            0,
+            Date::MAX,
+        );
+        assert_eq!(
+            (plan.vertices.len(), plan.contacts.len()),
+            (3, 2),
+            "TEST FAILED: Every node and contact should be kept."
+        );
+    }
+}
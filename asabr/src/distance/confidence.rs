@@ -0,0 +1,105 @@
+extern crate alloc;
+use core::cmp::Ordering;
+
+use crate::{contact_manager::ContactManager, node_manager::NodeManager, route_stage::RouteStage};
+
+use super::{Distance, sabr::SABR};
+
+/// A distance preferring the routes whose contacts are the most likely to succeed.
+///
+/// `MaxConfidence` compares the products of the contact confidences (see `RouteStage::confidence`),
+/// the higher the better, and falls back to the `SABR` rules for routes of equal confidence. As
+/// confidences are at most 1, extending a route never improves it, which keeps the Dijkstra
+/// searches valid.
+#[derive(Debug)]
+pub struct MaxConfidence {}
+
+impl<NM: NodeManager, CM: ContactManager> Distance<NM, CM> for MaxConfidence {
+    /// Compares two `RouteStage` instances by decreasing confidence, then by the `SABR` rules.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to compare.
+    /// - `second`: The second route stage to compare.
+    ///
+    /// # Returns
+    /// - `Ordering::Less` if `first` is more confident, or as confident and better for `SABR`.
+    /// - `Ordering::Greater` if `second` is.
+    /// - `Ordering::Equal` otherwise.
+    fn cmp(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> Ordering {
+        second
+            .confidence
+            .total_cmp(&first.confidence)
+            .then_with(|| <SABR as Distance<NM, CM>>::cmp(first, second))
+    }
+
+    /// Checks if two `RouteStage` instances have the same confidence and are equal for `SABR`.
+    fn eq(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> bool {
+        first.confidence == second.confidence && <SABR as Distance<NM, CM>>::eq(first, second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::confidence::ConfidenceManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::errors::ASABRError;
+    use crate::multigraph::Multigraph;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::vertex::VertexID;
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    /// The relay of the route to node 3, and the confidence of the route.
+    fn route_to_3<D: Distance<NoManagement, ConfidenceManager>>()
+    -> Result<(VertexID, f64), ASABRError> {
+        // through 1: slow but reliable (0.9 * 0.8), through 2: fast but unreliable (0.5 * 1)
+        let plan = parse_from_str::<NoManagement, ConfidenceManager>(
+            "node 0 a
+            node 1 b
+            node 2 c
+            node 3 d
+            contact 0 1 0 100 10 5 0.9
+            contact 1 3 0 100 10 5 0.8
+            contact 0 2 0 100 10 1 0.5
+            contact 2 3 0 100 10 1 1",
+        )
+        .unwrap();
+        let multigraph = Rc::new(RefCell::new(Multigraph::new(plan)?));
+        let mut pathfinding = NodeParentingTreeExcl::<_, _, D>::new(multigraph);
+        let tree = pathfinding.get_next(0.0, 0, &make_bundle(3, 0, 1.0, 1000.0), &[])?;
+        let route = tree.by_destination[3]
+            .as_ref()
+            .expect("TEST FAILED: Expected a route to node 3.")
+            .borrow();
+        let relay = route.via.as_ref().unwrap().parent_route.borrow().to_node;
+        Ok((relay, route.confidence))
+    }
+
+    #[test]
+    fn most_confident_route_is_preferred() -> Result<(), ASABRError> {
+        let (relay, confidence) = route_to_3::<MaxConfidence>()?;
+        assert_eq!(
+            relay, 1,
+            "TEST FAILED: The route through 1 is the most confident."
+        );
+        assert!(
+            (confidence - 0.9 * 0.8).abs() < 1e-12,
+            "TEST FAILED: The route confidence should be the product of its contacts', got {confidence}."
+        );
+
+        let (relay, confidence) = route_to_3::<SABR>()?;
+        assert_eq!(
+            relay, 2,
+            "TEST FAILED: SABR should prefer the faster route."
+        );
+        assert!(
+            (confidence - 0.5).abs() < 1e-12,
+            "TEST FAILED: The route confidence should be the product of its contacts', got {confidence}."
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use core::{cmp::Ordering, marker::PhantomData};
+
+use crate::{contact_manager::ContactManager, node_manager::NodeManager, route_stage::RouteStage};
+
+use super::Distance;
+
+/// The signature of a comparison function usable as a distance.
+pub type DistanceFn<NM, CM> = fn(&RouteStage<NM, CM>, &RouteStage<NM, CM>) -> Ordering;
+
+/// A trait providing the comparison function of a `CustomDistance`.
+///
+/// The distances are resolved at compile time, the function is thus attached to a type rather
+/// than stored in the pathfinding. The `custom_distance!` macro generates such a type from a
+/// non-capturing closure.
+pub trait DistanceCmp<NM: NodeManager, CM: ContactManager> {
+    /// The comparison function, see `Distance::cmp`.
+    const CMP: DistanceFn<NM, CM>;
+}
+
+/// A distance delegating the comparison of the `RouteStage`s to a user-supplied function.
+///
+/// Two stages are equal if the function returns `Ordering::Equal`. `CustomDistance` does not
+/// implement `HybridParentingOrd`, it can be used with node and contact parenting.
+///
+/// ```ignore
+/// custom_distance!(Margin, |first, second| {
+///     (first.expiration - first.at_time).total_cmp(&(second.expiration - second.at_time))
+/// });
+///
+/// let pathfinding = NodeParentingTreeExcl::<NM, CM, CustomDistance<Margin>>::new(multigraph);
+/// ```
+#[derive(Debug)]
+pub struct CustomDistance<C> {
+    #[doc(hidden)]
+    _phantom: PhantomData<C>,
+}
+
+impl<NM: NodeManager, CM: ContactManager, C: DistanceCmp<NM, CM>> Distance<NM, CM>
+    for CustomDistance<C>
+{
+    #[inline(always)]
+    fn cmp(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> Ordering {
+        (C::CMP)(first, second)
+    }
+
+    #[inline(always)]
+    fn eq(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> bool {
+        (C::CMP)(first, second) == Ordering::Equal
+    }
+}
+
+/// Declares a type implementing `DistanceCmp` with the given non-capturing closure, to be used as
+/// `CustomDistance<$name>`.
+///
+/// The closure receives two `&RouteStage<NM, CM>` and returns their `Ordering`, it must be valid
+/// for any node and contact managers.
+#[macro_export]
+macro_rules! custom_distance {
+    ($name:ident, $cmp:expr) => {
+        #[derive(Debug)]
+        pub struct $name {}
+
+        impl<NM: $crate::node_manager::NodeManager, CM: $crate::contact_manager::ContactManager>
+            $crate::distance::custom::DistanceCmp<NM, CM> for $name
+        {
+            const CMP: $crate::distance::custom::DistanceFn<NM, CM> = $cmp;
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::test_support::GraphBuilder;
+    use crate::types::Date;
+
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    custom_distance!(MinMargin, |first, second| {
+        (first.expiration - first.at_time).total_cmp(&(second.expiration - second.at_time))
+    });
+
+    /// Returns the arrival time at node 1, through either a long direct contact arriving at 1.0,
+    /// or node 2 with short contacts arriving at 2.0.
+    fn arrival<P: Pathfinding<NoManagement, EVLManager>>() -> Date {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 200.0)
+            .delay(1.0)
+            .contact(0, 2, 0.0, 50.0)
+            .delay(1.0)
+            .contact(2, 1, 0.0, 50.0)
+            .delay(1.0)
+            .plan();
+        let graph = crate::multigraph::Multigraph::new(plan).unwrap();
+        let tree = P::new(Rc::new(RefCell::new(graph)))
+            .get_next(0.0, 0, &make_bundle(1, 0, 0.0, 1000.0), &[])
+            .unwrap();
+        tree.by_destination[1].as_ref().unwrap().borrow().at_time
+    }
+
+    #[test]
+    fn closure_selects_the_smallest_expiration_margin() {
+        assert_eq!(
+            arrival::<NodeParentingTreeExcl<NoManagement, EVLManager, SABR>>(),
+            1.0,
+            "TEST FAILED: SABR should select the earliest arrival."
+        );
+        assert_eq!(
+            arrival::<NodeParentingTreeExcl<NoManagement, EVLManager, CustomDistance<MinMargin>>>(),
+            2.0,
+            "TEST FAILED: The closure should select the path ending first."
+        );
+    }
+}
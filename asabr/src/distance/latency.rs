@@ -0,0 +1,162 @@
+use core::cmp::Ordering;
+
+use crate::{
+    contact_manager::ContactManager, node_manager::NodeManager,
+    pathfinding::hybrid_parenting::HybridParentingOrd, route_stage::RouteStage,
+};
+
+use super::Distance;
+
+/// A struct allowing to use a pure earliest arrival distance, where the hop count is ignored.
+///
+/// `Latency` is used to implement the `Distance` trait, providing a comparison method
+/// for determining the order of `RouteStage` instances based on their `at_time` (i.e. arrival
+/// time), the `expiration` breaking the ties.
+#[derive(Debug)]
+pub struct Latency {}
+
+impl<NM: NodeManager, CM: ContactManager> Distance<NM, CM> for Latency {
+    /// Compares two `RouteStage` instances to determine their ordering based on
+    /// the arrival time only.
+    ///
+    /// The comparison follows these rules, in descending order of priority:
+    /// 1. `at_time`: The `RouteStage` with a later `at_time` is considered greater.
+    /// 2. `expiration`: If `at_time` is equal, the one with a lower `expiration` is greater.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to compare.
+    /// - `second`: The second route stage to compare.
+    ///
+    /// # Returns
+    /// - `Ordering::Greater` if `first` is considered greater than `second` based on the criteria.
+    /// - `Ordering::Less` if `second` is considered greater than `first`.
+    /// - `Ordering::Equal` if both stages are equal by all criteria.
+    ///
+    /// # Performance
+    /// This function is marked with `#[inline(always)]` for potential performance optimizations.
+    #[inline(always)]
+    fn cmp(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> Ordering {
+        if first.at_time > second.at_time {
+            return Ordering::Greater;
+        } else if first.at_time < second.at_time {
+            return Ordering::Less;
+        } else if first.expiration < second.expiration {
+            return Ordering::Greater;
+        } else if first.expiration > second.expiration {
+            return Ordering::Less;
+        }
+        Ordering::Equal
+    }
+
+    /// Checks if two `RouteStage` instances are equal based on specific criteria.
+    ///
+    /// Equality is determined by the following criteria:
+    /// - `at_time`: Both instances must have the same `at_time`.
+    /// - `expiration`: Both instances must have the same `expiration`.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to check for equality.
+    /// - `second`: The second route stage to check for equality.
+    ///
+    /// # Returns
+    /// - `true` if `first` and `second` meet the criteria for equality.
+    /// - `false` otherwise.
+    ///
+    /// # Performance
+    /// This function is marked with `#[inline(always)]` for potential performance optimizations.
+    #[inline(always)]
+    fn eq(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> bool {
+        first.at_time == second.at_time && first.expiration == second.expiration
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> HybridParentingOrd<NM, CM> for Latency {
+    /// For Latency, there is no secondary metric to consider.
+    fn can_retain(_prop: &RouteStage<NM, CM>, _known: &RouteStage<NM, CM>) -> bool {
+        false
+    }
+    /// Ignore expiration constraints to prioritize performance.
+    fn must_prune(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        prop.at_time <= known.at_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_plan::ContactPlan;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::route_storage::cache::TreeCache;
+    use crate::routing::Router;
+    use crate::routing::aliases::{
+        SpsnNodeParenting, SpsnNodeParentingHop, SpsnNodeParentingLatency,
+    };
+    use crate::test_support::GraphBuilder;
+    use crate::types::{Date, HopCount};
+    use alloc::boxed::Box;
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    /// A direct contact arriving at 10.0, and a three hop path arriving at 3.0.
+    fn plan() -> ContactPlan<NoManagement, EVLManager> {
+        GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 3, 0.0, 100.0)
+            .delay(10.0)
+            .contact(0, 1, 0.0, 100.0)
+            .delay(1.0)
+            .contact(1, 2, 0.0, 100.0)
+            .delay(1.0)
+            .contact(2, 3, 0.0, 100.0)
+            .delay(1.0)
+            .plan()
+    }
+
+    fn route_to_3(mut router: Box<dyn Router<NoManagement, EVLManager>>) -> (Date, HopCount) {
+        let output = router
+            .route(0, &make_bundle(3, 0, 0.0, 1000.0), 0.0, &[])
+            .unwrap()
+            .unwrap();
+        let (_, route) = output.lazy_get_for_unicast(3).unwrap();
+        let route = route.borrow();
+        (route.at_time, route.hop_count)
+    }
+
+    fn cache() -> Rc<RefCell<TreeCache<NoManagement, EVLManager>>> {
+        Rc::new(RefCell::new(TreeCache::new(false, false, 10)))
+    }
+
+    #[test]
+    fn latency_prefers_the_faster_path_over_fewer_hops() {
+        let latency = route_to_3(Box::new(
+            SpsnNodeParentingLatency::new(plan(), cache(), false).unwrap(),
+        ));
+        let sabr = route_to_3(Box::new(
+            SpsnNodeParenting::new(plan(), cache(), false).unwrap(),
+        ));
+        let hop = route_to_3(Box::new(
+            SpsnNodeParentingHop::new(plan(), cache(), false).unwrap(),
+        ));
+
+        assert_eq!(
+            latency,
+            (3.0, 3),
+            "TEST FAILED: Latency should pick the three hop path."
+        );
+        assert_eq!(
+            sabr, latency,
+            "TEST FAILED: SABR should agree with Latency."
+        );
+        assert_eq!(
+            hop,
+            (10.0, 1),
+            "TEST FAILED: Hop should pick the direct contact."
+        );
+    }
+}
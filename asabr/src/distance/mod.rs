@@ -8,8 +8,12 @@ use core::{cell::RefCell, marker::PhantomData};
 use crate::node_manager::NodeManager;
 use crate::{contact_manager::ContactManager, route_stage::RouteStage};
 
+pub mod confidence;
+pub mod custom;
 pub mod hop;
+pub mod latency;
 pub mod sabr;
+pub mod volume;
 
 /// A trait that allows RouteStages to define custom distance comparison strategies.
 ///
@@ -0,0 +1,196 @@
+extern crate alloc;
+use core::{cmp::Ordering, marker::PhantomData};
+
+use crate::{
+    contact_manager::ContactManager, node_manager::NodeManager,
+    pathfinding::hybrid_parenting::HybridParentingOrd, route_stage::RouteStage,
+};
+
+use super::{Distance, hop::Hop, sabr::SABR};
+
+/// A distance breaking the ties of `D` on the volume left on the first hop of the routes.
+///
+/// The routes are compared with `D` first. When `D` finds them equal, the route whose first hop
+/// contact has the most volume left is preferred, e.g. to spread the load over the neighbors
+/// instead of an arbitrary choice. The volume is the one recorded when the first hop was made,
+/// see `RouteStage::first_hop_volume`.
+///
+/// The contacts between the same two nodes are chosen while building the hop, before the
+/// distance applies: the tie-break is between routes through different nodes.
+#[derive(Debug)]
+pub struct VolumeTieBreak<D> {
+    #[doc(hidden)]
+    _distance: PhantomData<D>,
+}
+
+/// The `SABR` distance, breaking the remaining ties on the volume left on the first hop.
+pub type SabrVolumeTieBreak = VolumeTieBreak<SABR>;
+
+/// The `Hop` distance, breaking the remaining ties on the volume left on the first hop.
+pub type HopVolumeTieBreak = VolumeTieBreak<Hop>;
+
+impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> Distance<NM, CM>
+    for VolumeTieBreak<D>
+{
+    /// Compares two `RouteStage` instances with `D`, then by decreasing first hop volume.
+    ///
+    /// # Parameters
+    /// - `first`: The first route stage to compare.
+    /// - `second`: The second route stage to compare.
+    ///
+    /// # Returns
+    /// - `Ordering::Less` if `first` is better for `D`, or equal with more volume on its first hop.
+    /// - `Ordering::Greater` if `second` is.
+    /// - `Ordering::Equal` otherwise.
+    fn cmp(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> Ordering {
+        D::cmp(first, second)
+            .then_with(|| second.first_hop_volume.total_cmp(&first.first_hop_volume))
+    }
+
+    /// Checks if two `RouteStage` instances are equal for `D` with the same first hop volume.
+    fn eq(first: &RouteStage<NM, CM>, second: &RouteStage<NM, CM>) -> bool {
+        D::eq(first, second) && first.first_hop_volume == second.first_hop_volume
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager, D: HybridParentingOrd<NM, CM>> HybridParentingOrd<NM, CM>
+    for VolumeTieBreak<D>
+{
+    /// Follows `D`, the tie-break does not change which routes are kept.
+    fn can_retain(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        D::can_retain(prop, known)
+    }
+
+    /// Follows `D`.
+    fn must_prune(prop: &RouteStage<NM, CM>, known: &RouteStage<NM, CM>) -> bool {
+        D::must_prune(prop, known)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::Bundle;
+    use crate::contact::SharedContact;
+    use crate::contact_manager::legacy::evl::{EVLManager, PEVLManager};
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::errors::ASABRError;
+    use crate::multigraph::Multigraph;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    type Graph<CM> = Rc<RefCell<Multigraph<NoManagement, CM>>>;
+
+    /// Two identical routes to node 3, through 1 or 2.
+    const TIED_ROUTES: &str = "node 0 a
+        node 1 b
+        node 2 c
+        node 3 d
+        contact 0 1 0 100 10 1
+        contact 0 2 0 100 10 1
+        contact 1 3 0 100 10 1
+        contact 2 3 0 100 10 1";
+
+    /// The first hop contact of the route to node 3.
+    fn first_hop_to_3<CM: ContactManager, D: Distance<NoManagement, CM>>(
+        multigraph: &Graph<CM>,
+        bundle: &Bundle,
+    ) -> Result<SharedContact<NoManagement, CM>, ASABRError> {
+        let mut pathfinding = NodeParentingTreeExcl::<_, _, D>::new(multigraph.clone());
+        let tree = pathfinding.get_next(0.0, 0, bundle, &[])?;
+        let route = tree.by_destination[3]
+            .as_ref()
+            .expect("TEST FAILED: Expected a route to node 3.")
+            .borrow();
+        let relay = route.via.as_ref().unwrap().parent_route.borrow();
+        Ok(relay.get_via_contact().unwrap())
+    }
+
+    #[test]
+    fn more_volume_on_the_first_hop_breaks_the_tie() -> Result<(), ASABRError> {
+        let plan = parse_from_str::<NoManagement, EVLManager>(TIED_ROUTES).unwrap();
+        let multigraph = Rc::new(RefCell::new(Multigraph::new(plan)?));
+        let bundle = make_bundle(3, 0, 10.0, 1000.0);
+
+        // book half of the first hop of the route SABR picks among the tied ones
+        let picked = first_hop_to_3::<_, SABR>(&multigraph, &bundle)?;
+        {
+            let mut contact = picked.borrow_mut();
+            let info = contact.info;
+            contact
+                .manager
+                .schedule_tx(&info, 0.0, &make_bundle(1, 0, 500.0, 1000.0))
+                .expect("TEST FAILED: The booking should fit.");
+        }
+
+        assert!(
+            Rc::ptr_eq(&first_hop_to_3::<_, SABR>(&multigraph, &bundle)?, &picked),
+            "TEST FAILED: SABR ignores the volume of the tied routes."
+        );
+        assert!(
+            !Rc::ptr_eq(
+                &first_hop_to_3::<_, SabrVolumeTieBreak>(&multigraph, &bundle)?,
+                &picked
+            ),
+            "TEST FAILED: The first hop with more volume should win the tie."
+        );
+        assert!(
+            !Rc::ptr_eq(
+                &first_hop_to_3::<_, HopVolumeTieBreak>(&multigraph, &bundle)?,
+                &picked
+            ),
+            "TEST FAILED: The Hop variant should break the tie the same way."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn the_tie_is_broken_on_the_volume_of_the_bundle_priority() -> Result<(), ASABRError> {
+        let plan = parse_from_str::<NoManagement, PEVLManager<3>>(TIED_ROUTES).unwrap();
+        let multigraph = Rc::new(RefCell::new(Multigraph::new(plan)?));
+        let bundle = make_bundle(3, 2, 10.0, 1000.0);
+
+        // the low priority booking leaves the whole volume to the priority 2 bundles
+        let low = first_hop_to_3::<_, SABR>(&multigraph, &bundle)?;
+        let high = {
+            let multigraph = multigraph.borrow();
+            let first_hops = &multigraph.senders[0].receivers;
+            first_hops
+                .iter()
+                .map(|receiver| receiver.contacts_to_receiver[0].clone())
+                .find(|contact| !Rc::ptr_eq(contact, &low))
+                .expect("TEST FAILED: Expected two first hops.")
+        };
+        for (contact, priority, size) in [(&low, 0, 600.0), (&high, 2, 300.0)] {
+            let mut contact = contact.borrow_mut();
+            let info = contact.info;
+            contact
+                .manager
+                .schedule_tx(&info, 0.0, &make_bundle(1, priority, size, 1000.0))
+                .expect("TEST FAILED: The booking should fit.");
+        }
+
+        assert!(
+            Rc::ptr_eq(
+                &first_hop_to_3::<_, SabrVolumeTieBreak>(&multigraph, &bundle)?,
+                &low
+            ),
+            "TEST FAILED: The first hop with more volume for priority 2 should win the tie."
+        );
+        assert!(
+            Rc::ptr_eq(
+                &first_hop_to_3::<_, SabrVolumeTieBreak>(
+                    &multigraph,
+                    &make_bundle(3, 0, 10.0, 1000.0)
+                )?,
+                &high
+            ),
+            "TEST FAILED: The first hop with more volume for priority 0 should win the tie."
+        );
+        Ok(())
+    }
+}
@@ -1,8 +1,12 @@
+extern crate alloc;
+use alloc::string::String;
+
 use core::cell::{BorrowError, BorrowMutError};
 use core::error::Error;
 use core::fmt;
 
 use crate::parsing::Located;
+use crate::types::{Date, NodeID, Priority};
 
 #[derive(Debug)]
 pub enum ASABRError {
@@ -12,6 +16,115 @@ pub enum ASABRError {
     ContactPlanError(&'static str),
     MulticastUnsupportedError,
     ParsingError(Located<&'static str>),
+    PersistenceError(&'static str),
+    ResourceError(&'static str),
+    RoutingError(RoutingError),
+    TVGUtilError(TVGUtilError),
+}
+
+/// Failures that can occur while a route selected by a dry run is being scheduled.
+#[derive(Debug, PartialEq)]
+pub enum RoutingError {
+    /// The schedule of a hop failed while the dry run allowed it, e.g. because the resources
+    /// changed in between.
+    DryRunScheduleMismatch,
+    /// The scheduled route could not be associated with its first hop contact.
+    MissingFirstHop,
+    /// Following the tree from its source leads back to an already visited stage, e.g. because
+    /// of a faulty pathfinding.
+    CyclicTree { to_node: NodeID },
+    /// The priority of the bundle is not a level distinguished by the contact managers.
+    InvalidPriority { priority: Priority, levels: usize },
+    /// The resources booked by a failed routing operation could not be restored, the manager of
+    /// a contact rejecting its own snapshot.
+    RollbackFailed,
+    /// The router cannot exclude contacts, see `Router::route_with_constraints`.
+    ContactExclusionUnsupported,
+    /// The router has no current time, see `Router::now`.
+    NoCurrentTime,
+}
+
+impl From<RoutingError> for ASABRError {
+    fn from(err: RoutingError) -> Self {
+        ASABRError::RoutingError(err)
+    }
+}
+
+impl fmt::Display for RoutingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RoutingError::DryRunScheduleMismatch => {
+                write!(f, "the schedule disagrees with the dry run")
+            }
+            RoutingError::MissingFirstHop => write!(f, "the first hop could not be tracked"),
+            RoutingError::CyclicTree { to_node } => {
+                write!(
+                    f,
+                    "the tree loops back to the stage reaching node {to_node}"
+                )
+            }
+            RoutingError::InvalidPriority { priority, levels } => {
+                write!(
+                    f,
+                    "the priority {priority} is not one of the {levels} supported levels"
+                )
+            }
+            RoutingError::RollbackFailed => {
+                write!(f, "the bookings of a failed operation could not be undone")
+            }
+            RoutingError::ContactExclusionUnsupported => {
+                write!(f, "the router cannot exclude contacts")
+            }
+            RoutingError::NoCurrentTime => write!(f, "the router has no current time"),
+        }
+    }
+}
+
+/// Failures that can occur while reading a tvgutil contact plan.
+///
+/// The `path` of an element is its JSON path from the root, e.g. `$.edges[2].contacts[0]`.
+#[derive(Debug, PartialEq)]
+pub enum TVGUtilError {
+    /// The content is not valid JSON.
+    InvalidJson,
+    /// The element at `path` misses an expected field or array entry.
+    MissingField { path: String, field: &'static str },
+    /// The element at `path` is not of the `expected` kind, e.g. a string instead of a number.
+    UnexpectedType {
+        path: String,
+        expected: &'static str,
+    },
+    /// The node name at `path` is not declared in the vertices.
+    UnknownNode { path: String },
+    /// The contact at `path` cannot be built by the contact manager.
+    InvalidContact { path: String },
+}
+
+impl From<TVGUtilError> for ASABRError {
+    fn from(err: TVGUtilError) -> Self {
+        ASABRError::TVGUtilError(err)
+    }
+}
+
+impl fmt::Display for TVGUtilError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TVGUtilError::InvalidJson => write!(f, "the content is not valid JSON"),
+            TVGUtilError::MissingField { path, field } => {
+                write!(f, "missing \"{field}\" at {path}")
+            }
+            TVGUtilError::UnexpectedType { path, expected } => {
+                write!(f, "expected {expected} at {path}")
+            }
+            TVGUtilError::UnknownNode { path } => {
+                write!(
+                    f,
+                    "unknown node at {path}, it is not declared in the vertices"
+                )
+            }
+            TVGUtilError::InvalidContact { path } => write!(f, "invalid contact at {path}"),
+        }
+    }
 }
 
 impl From<BorrowError> for ASABRError {
@@ -36,12 +149,65 @@ impl fmt::Display for ASABRError {
             ASABRError::MulticastUnsupportedError => {
                 write!(f, "Multicast is Unsupported in A-SABR")
             }
-            ASABRError::ParsingError(Located { data, line, toknum }) => write!(
+            ASABRError::ParsingError(Located {
+                data,
+                line,
+                toknum,
+                field,
+            }) => {
+                write!(
+                    f,
+                    "Parsing Error encountered at line {line} tocken {toknum} in A-SABR: {data}",
+                )?;
+                if !field.is_empty() {
+                    write!(f, " (expected the {field})")?;
+                }
+                Ok(())
+            }
+            ASABRError::PersistenceError(s) => write!(f, "PersistenceError in A-SABR: {}", s),
+            ASABRError::ResourceError(s) => write!(f, "ResourceError in A-SABR: {}", s),
+            ASABRError::RoutingError(ref err) => write!(f, "RoutingError in A-SABR: {}", err),
+            ASABRError::TVGUtilError(ref err) => write!(f, "TVGUtilError in A-SABR: {}", err),
+        }
+    }
+}
+
+impl Error for ASABRError {}
+
+/// Describes why a `RoutingOutput` failed its consistency check.
+#[derive(Debug, PartialEq)]
+pub enum InconsistencyError {
+    /// The receiving node of a hop is not the transmitting node of the following hop.
+    NodeDiscontinuity {
+        rx_node: NodeID,
+        next_tx_node: NodeID,
+    },
+    /// A hop reaches its node earlier than the hop it follows.
+    TimeRegression { from: Date, to: Date },
+    /// The first contact of a route is not the first hop it is registered under.
+    FirstHopMismatch { to_node: NodeID },
+}
+
+impl fmt::Display for InconsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            InconsistencyError::NodeDiscontinuity {
+                rx_node,
+                next_tx_node,
+            } => write!(
                 f,
-                "Parsing Error encountered at line {line} tocken {toknum} in A-SABR: {data}",
+                "InconsistencyError in A-SABR: hop received by node {rx_node} is followed by a hop transmitted by node {next_tx_node}"
+            ),
+            InconsistencyError::TimeRegression { from, to } => write!(
+                f,
+                "InconsistencyError in A-SABR: arrival time goes back from {from} to {to}"
+            ),
+            InconsistencyError::FirstHopMismatch { to_node } => write!(
+                f,
+                "InconsistencyError in A-SABR: route to node {to_node} does not start with its first hop contact"
             ),
         }
     }
 }
 
-impl Error for ASABRError {}
+impl Error for InconsistencyError {}
@@ -43,5 +43,9 @@ pub mod parsing;
 
 pub mod utils;
 
+/// Module containing the builders used to set up the tests.
+#[cfg(test)]
+pub(crate) mod test_support;
+
 #[macro_use]
 extern crate static_assertions;
@@ -57,10 +57,8 @@ fn main() -> Result<(), ASABRError> {
     let out = spsn.route(0, &b, 0.0, &Vec::new());
 
     if let Ok(Some(out)) = out {
-        for (_contact, dest_routes) in out.first_hops.values() {
-            for route_rc in dest_routes {
-                println!("{}", route_rc.borrow());
-            }
+        for (_contact, route_rc) in out.first_hop_entries() {
+            println!("{}", route_rc.borrow());
         }
     }
 
@@ -1,17 +1,26 @@
 extern crate alloc;
 
-use alloc::{collections::BTreeMap as HashMap, rc::Rc, vec, vec::Vec};
+use alloc::{
+    collections::{BTreeMap as HashMap, BTreeSet},
+    rc::Rc,
+    vec,
+    vec::Vec,
+};
 use core::cell::RefCell;
+use core::cmp::Ordering;
 use core::fmt::Display;
 
 use super::node::Node;
-use crate::contact::Contact;
-use crate::contact_manager::ContactManager;
+use crate::contact::{Contact, ContactInfo, SharedContact};
+use crate::contact_manager::{ContactManager, ManagerState};
 use crate::contact_plan::ContactPlan;
 use crate::errors::ASABRError;
 use crate::node_manager::NodeManager;
+use crate::routing::RcKey;
+use crate::routing::diagnostics::SharedTxDiagnostics;
 use crate::types::*;
 use crate::vertex::{VNode, Vertex, VertexID};
+use crate::vnode::VirtualNodeMap;
 
 /// Represents a sender node in a routing system, with associated receivers.
 ///
@@ -45,14 +54,43 @@ pub struct Receiver<NM: NodeManager, CM: ContactManager> {
     pub contacts_to_receiver: Vec<Rc<RefCell<Contact<NM, CM>>>>,
     /// The index of the next contact to be checked for relevance.
     pub next: RefCell<usize>,
+    /// The latest end of the contacts up to each index, nondecreasing, for the binary search of
+    /// the first valid contact.
+    end_bounds: Vec<Date>,
 }
 
 impl<NM: NodeManager, CM: ContactManager> Receiver<NM, CM> {
+    /// Creates a receiver reached through `contacts`, sorted by start time.
+    fn new(vertex_id: VertexID, contacts: Vec<Rc<RefCell<Contact<NM, CM>>>>) -> Self {
+        let mut receiver = Self {
+            vertex_id,
+            contacts_to_receiver: contacts,
+            next: 0.into(),
+            end_bounds: Vec::new(),
+        };
+        receiver.update_end_bounds();
+        receiver
+    }
+
+    /// Recomputes the end bounds, to be called once the contacts changed.
+    fn update_end_bounds(&mut self) {
+        let mut bound = Date::MIN;
+        self.end_bounds.clear();
+        for contact in &self.contacts_to_receiver {
+            bound = bound.max(contact.borrow().info.end);
+            self.end_bounds.push(bound);
+        }
+    }
+
     /// Lazily prunes outdated contacts and returns the index of the first valid contact.
     ///
-    /// This method iterates over `contacts_to_receiver`, starting from the index stored in `self.next`.
-    /// It checks if each contact is still valid based on its expiration time. Once a valid contact
-    /// is found, it updates `self.next` and returns the index of this contact.
+    /// This method looks for the first contact of `contacts_to_receiver` still valid based on its
+    /// expiration time, starting from the index stored in `self.next`. Once a valid contact is
+    /// found, it updates `self.next` and returns the index of this contact.
+    ///
+    /// The contacts ending before `current_time` are skipped with a binary search over the latest
+    /// end of the contacts up to each index. The contacts are sorted by start time, so their ends
+    /// are usually sorted too; if a contact ends after the next ones, they are checked one by one.
     ///
     /// # Parameters
     /// - `current_time`: The current time against which contact expiration is checked.
@@ -62,6 +100,19 @@ impl<NM: NodeManager, CM: ContactManager> Receiver<NM, CM> {
     /// - `None`: If no valid contact is found.
     pub fn lazy_prune_and_get_first_idx(&self, current_time: Date) -> Option<usize> {
         let mut next_mut = self.next.borrow_mut();
+        if let Some(bounds) = self.end_bounds.get(*next_mut..)
+            && self.end_bounds.len() == self.contacts_to_receiver.len()
+        {
+            let idx = *next_mut + bounds.partition_point(|&bound| bound <= current_time);
+            if idx == self.contacts_to_receiver.len() {
+                return None;
+            }
+            // if the bound is reached at the next index, it may be from an earlier contact
+            if idx > *next_mut || self.contacts_to_receiver[idx].borrow().info.end > current_time {
+                *next_mut = idx;
+                return Some(idx);
+            }
+        }
         for (idx, contact) in self.contacts_to_receiver.iter().enumerate().skip(*next_mut) {
             if contact.borrow().info.end > current_time {
                 *next_mut = idx;
@@ -89,6 +140,77 @@ impl<NM: NodeManager, CM: ContactManager> Receiver<NM, CM> {
     }
 }
 
+/// An inconsistency of the contacts of a multigraph, reported by `Multigraph::validate`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValidationIssue {
+    /// Two contacts from the same transmitter to the same receiver have overlapping windows.
+    OverlappingContacts {
+        tx: NodeID,
+        rx: NodeID,
+        first_start: Date,
+        second_start: Date,
+    },
+    /// The contact ends when it starts (or before).
+    ZeroDurationContact { tx: NodeID, rx: NodeID, start: Date },
+    /// The contact refers to a node that is not a real node of the multigraph.
+    UnknownNode { tx: NodeID, rx: NodeID, start: Date },
+}
+
+/// The reason a contact was rejected by `Multigraph::new_checked`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RejectionReason {
+    /// The contact ends when it starts (or before).
+    InvalidTimes,
+    /// The contact manager refused the contact, e.g. for a rate that is not positive.
+    ManagerInit,
+    /// The contact refers to a node that is not a real node of the plan.
+    UnknownNode,
+}
+
+/// A contact that was not loaded by `Multigraph::new_checked`.
+#[derive(Debug, Clone, Copy)]
+pub struct RejectedContact {
+    /// The position of the contact in the input.
+    pub index: usize,
+    /// The information of the contact.
+    pub info: ContactInfo,
+    /// Why the contact was rejected.
+    pub reason: RejectionReason,
+}
+
+/// Returns the fewest priority levels of two, `None` standing for any priority.
+fn min_levels(current: Option<usize>, other: Option<usize>) -> Option<usize> {
+    match (current, other) {
+        (Some(current), Some(other)) => Some(current.min(other)),
+        (current, other) => current.or(other),
+    }
+}
+
+/// The use of the volume of a contact, reported by `Multigraph::utilization_report`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactUtilization {
+    /// The transmitting node.
+    pub tx: NodeID,
+    /// The receiving node.
+    pub rx: NodeID,
+    /// The start of the contact window.
+    pub start: Date,
+    /// The end of the contact window.
+    pub end: Date,
+    /// The volume booked on the contact.
+    pub scheduled_volume: Volume,
+    /// The volume of the contact at initialization, infinite if the manager does not track it.
+    pub original_volume: Volume,
+    /// The booked share of the original volume, between 0 and 1 (0 if the volume is not tracked).
+    pub fraction: Float,
+}
+
+/// The resources booked on the contacts of a multigraph, see `Multigraph::snapshot`.
+pub struct MultigraphSnapshot<NM: NodeManager, CM: ContactManager> {
+    /// The contacts with the state of their manager, each contact appearing once.
+    states: Vec<(SharedContact<NM, CM>, ManagerState)>,
+}
+
 /// Represents a multigraph structure, where each node can have multiple connections.
 #[derive(Debug)]
 pub struct Multigraph<NM: NodeManager, CM: ContactManager> {
@@ -99,6 +221,35 @@ pub struct Multigraph<NM: NodeManager, CM: ContactManager> {
     /// The total number of nodes in the multigraph.
     pub virtual_nodes: Vec<VNode>,
     vertex_count: usize,
+    /// The virtual nodes each real node belongs to.
+    vnodes_for_rid: NodeIDMap,
+    /// Flags the external nodes, that are neither senders nor receivers.
+    is_external: Vec<bool>,
+    /// If set, the contacts are only usable for transmissions ending by this date.
+    pub horizon: Option<Date>,
+    /// If set, the routes cannot take more hops than this.
+    ///
+    /// The limit is checked when a hop is made, it does not change which routes the pathfinding
+    /// keeps: the node and contact parenting keep the best route to each node or contact, so a
+    /// route found later but with fewer hops is discarded, and the nodes only reachable through
+    /// it are reported unreachable. The hybrid parenting keeps the routes with fewer hops (with
+    /// the `SABR` and `Hop` distances) and finds them.
+    pub max_hops: Option<HopCount>,
+    /// If set, the routes cannot visit a vertex twice.
+    pub loop_free: bool,
+    /// If set, the hops arriving after the expiration of the bundle are not made. The trees then
+    /// depend on the expiration of the bundle they are built for, `Spsn` bypasses its route
+    /// storage and its unicast guard.
+    pub prune_expired: bool,
+    /// If set, the contacts rejected during pathfinding are recorded there.
+    pub diagnostics: Option<SharedTxDiagnostics>,
+    /// If set, the node parenting pathfinding reuses the route stages of its previous search
+    /// once they are no longer referenced, instead of allocating new ones.
+    pub recycle_stages: bool,
+    /// The fewest priority levels distinguished by a contact manager, if any distinguishes them.
+    priority_levels: Option<usize>,
+    /// The contacts currently flagged as excluded.
+    excluded_contacts: Vec<Rc<RefCell<Contact<NM, CM>>>>,
 }
 
 impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
@@ -119,7 +270,7 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
     /// * `Self` - A new instance of `Multigraph`.
     pub fn new(contact_plan: ContactPlan<NM, CM>) -> Result<Self, ASABRError> {
         // work area
-        let vnodes_for_rid = contact_plan.vnode_map.get_rid_to_vnodes_map();
+        let vnodes_for_rid = contact_plan.vnode_map.get_rid_to_vnodes_map().clone();
         let vertex_count = contact_plan.vertices.len();
         let virtual_node_count = contact_plan.vnode_map.get_vnode_to_rids_map().len();
         let real_node_count = vertex_count - virtual_node_count;
@@ -165,8 +316,10 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
             });
         }
 
+        let mut priority_levels = None;
         // Fill contacts into vertex Sender and Receiver pairs (including vnodes) in the map.
         for contact in contact_plan.contacts {
+            priority_levels = min_levels(priority_levels, contact.manager.priority_levels());
             let real_tx_id = contact.get_tx_node_id();
             let real_rx_id = contact.get_rx_node_id();
 
@@ -208,11 +361,7 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
                     // Tx/Rx node ID.
                     contacts.sort_unstable_by(|a, b| a.borrow().cmp_by_start(&b.borrow()))
                 }
-                let recver = Receiver {
-                    vertex_id: r,
-                    contacts_to_receiver: contacts,
-                    next: 0.into(),
-                };
+                let recver = Receiver::new(r, contacts);
                 senders[t as usize].receivers.push(recver);
             }
             senders[t as usize].receivers.shrink_to_fit();
@@ -224,9 +373,536 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
             real_nodes: nodes,
             virtual_nodes,
             vertex_count,
+            vnodes_for_rid,
+            is_external,
+            horizon: None,
+            max_hops: None,
+            loop_free: false,
+            prune_expired: false,
+            priority_levels,
+            diagnostics: None,
+            recycle_stages: false,
+            excluded_contacts: Vec::new(),
         })
     }
 
+    /// Creates a new `Multigraph`, building the contacts from their information and manager
+    /// and reporting the ones that cannot be built instead of dropping them silently.
+    ///
+    /// # Parameters
+    ///
+    /// * `vertices` - The vertices of the plan, sorted by ID (see `ContactPlan`).
+    /// * `contacts` - The information and the manager of each contact.
+    /// * `vnode_map` - The virtual nodes and the nodes they label, if any.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Self, Vec<RejectedContact>))` - The multigraph built from the valid contacts, and
+    ///   the rejected contacts in input order.
+    /// * `Err(ASABRError)` - If the multigraph cannot be built.
+    pub fn new_checked(
+        vertices: Vec<Vertex<NM>>,
+        contacts: Vec<(ContactInfo, CM)>,
+        vnode_map: Option<VirtualNodeMap>,
+    ) -> Result<(Self, Vec<RejectedContact>), ASABRError> {
+        let real_node_count = vertices
+            .iter()
+            .filter(|vertex| !matches!(vertex, Vertex::VNode(_)))
+            .count();
+        let mut built = Vec::with_capacity(contacts.len());
+        let mut rejected = Vec::new();
+
+        for (index, (info, manager)) in contacts.into_iter().enumerate() {
+            let reason = if info.tx_node_id as usize >= real_node_count
+                || info.rx_node_id as usize >= real_node_count
+            {
+                RejectionReason::UnknownNode
+            } else if !info.try_init() {
+                RejectionReason::InvalidTimes
+            } else {
+                match Contact::try_new(info, manager) {
+                    Some(contact) => {
+                        built.push(contact);
+                        continue;
+                    }
+                    None => RejectionReason::ManagerInit,
+                }
+            };
+            rejected.push(RejectedContact {
+                index,
+                info,
+                reason,
+            });
+        }
+
+        let multigraph = Self::new(ContactPlan::new(vertices, built, vnode_map))?;
+        Ok((multigraph, rejected))
+    }
+
+    /// Returns the sender/receiver vertex pairs a contact between two real nodes belongs to.
+    fn vertex_pairs(&self, real_tx_id: NodeID, real_rx_id: NodeID) -> Vec<(VertexID, VertexID)> {
+        let mut pairs = Vec::new();
+        for t in self
+            .vnodes_for_rid
+            .get(&real_tx_id)
+            .into_iter()
+            .flatten()
+            .chain(core::iter::once(&real_tx_id))
+        {
+            for r in self
+                .vnodes_for_rid
+                .get(&real_rx_id)
+                .into_iter()
+                .flatten()
+                .chain(core::iter::once(&real_rx_id))
+            {
+                if !self.is_external[*t as usize] && !self.is_external[*r as usize] && t != r {
+                    pairs.push((*t, *r));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Adds a contact to the multigraph, keeping the contacts of each receiver sorted.
+    ///
+    /// The trees and routes stored beforehand were computed without this contact, callers
+    /// should clear their storage (`TreeStorage::clear` or `RouteStorage::clear`) to take it
+    /// into account.
+    ///
+    /// # Parameters
+    ///
+    /// * `contact` - The contact to add, between two real nodes of the multigraph.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the contact was added.
+    /// * `Err(ASABRError)` - If one of the contact's nodes is unknown.
+    pub fn add_contact(&mut self, contact: Contact<NM, CM>) -> Result<(), ASABRError> {
+        let real_tx_id = contact.get_tx_node_id();
+        let real_rx_id = contact.get_rx_node_id();
+        let real_node_count = self.real_nodes.len();
+        if real_tx_id as usize >= real_node_count || real_rx_id as usize >= real_node_count {
+            return Err(ASABRError::ContactPlanError(
+                "The contact refers to an unknown node",
+            ));
+        }
+
+        self.priority_levels = min_levels(self.priority_levels, contact.manager.priority_levels());
+        let contact_rc = Rc::new(RefCell::new(contact));
+        for (t, r) in self.vertex_pairs(real_tx_id, real_rx_id) {
+            let receivers = &mut self.senders[t as usize].receivers;
+            let idx = match receivers.iter().position(|recv| recv.vertex_id == r) {
+                Some(idx) => idx,
+                None => {
+                    receivers.push(Receiver::new(r, Vec::new()));
+                    receivers.len() - 1
+                }
+            };
+            let receiver = &mut receivers[idx];
+            let by_node_id = (t as usize) < real_node_count && (r as usize) < real_node_count;
+            let new_contact = contact_rc.borrow();
+            let pos = receiver.contacts_to_receiver.partition_point(|other| {
+                let other = other.borrow();
+                let ordering = if by_node_id {
+                    other.cmp(&new_contact)
+                } else {
+                    other.cmp_by_start(&new_contact)
+                };
+                ordering != Ordering::Greater
+            });
+            receiver
+                .contacts_to_receiver
+                .insert(pos, contact_rc.clone());
+            let next = receiver.next.get_mut();
+            *next = (*next).min(pos);
+            receiver.update_end_bounds();
+        }
+        Ok(())
+    }
+
+    /// Removes the contacts from `tx` to `rx` starting at `start`.
+    ///
+    /// The multigraph does not know the storage of the router, the trees and routes stored
+    /// beforehand may still use the removed contacts. Callers should then drop them with
+    /// `TreeStorage::invalidate_contact` or `RouteStorage::invalidate_contact`.
+    ///
+    /// # Parameters
+    ///
+    /// * `tx` - The transmitting node of the contact.
+    /// * `rx` - The receiving node of the contact.
+    /// * `start` - The start time of the contact.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if a contact was removed, `false` if no such contact exists.
+    pub fn remove_contact(&mut self, tx: NodeID, rx: NodeID, start: Date) -> bool {
+        let real_node_count = self.real_nodes.len();
+        if tx as usize >= real_node_count || rx as usize >= real_node_count {
+            return false;
+        }
+
+        let mut removed = false;
+        for (t, r) in self.vertex_pairs(tx, rx) {
+            let Some(receiver) = self.senders[t as usize]
+                .receivers
+                .iter_mut()
+                .find(|recv| recv.vertex_id == r)
+            else {
+                continue;
+            };
+            let next = receiver.next.get_mut();
+            let first_checked = *next;
+            let mut idx = 0;
+            receiver.contacts_to_receiver.retain(|contact| {
+                let info = contact.borrow().info;
+                let keep = !(info.tx_node_id == tx && info.rx_node_id == rx && info.start == start);
+                if !keep {
+                    removed = true;
+                    if idx < first_checked {
+                        *next -= 1;
+                    }
+                }
+                idx += 1;
+                keep
+            });
+            receiver.update_end_bounds();
+        }
+        removed
+    }
+
+    /// Returns the contacts from `tx` to `rx`, in start time order.
+    ///
+    /// The contacts are read from the sorted contacts of a receiver, the real vertex pair being
+    /// preferred to the virtual ones. The contacts removed or purged are not returned, the
+    /// contacts ended but not purged yet are.
+    ///
+    /// # Parameters
+    ///
+    /// * `tx` - The transmitting node.
+    /// * `rx` - The receiving node.
+    ///
+    /// # Returns
+    ///
+    /// * An iterator over the contacts, empty if the nodes are unknown or have no contacts.
+    pub fn contacts_between(
+        &self,
+        tx: NodeID,
+        rx: NodeID,
+    ) -> impl Iterator<Item = SharedContact<NM, CM>> + '_ {
+        let real_node_count = self.real_nodes.len();
+        let receiver = if tx as usize >= real_node_count || rx as usize >= real_node_count {
+            None
+        } else {
+            // the real pair comes last, a virtual receiver also holds the contacts of other nodes
+            self.vertex_pairs(tx, rx)
+                .into_iter()
+                .rev()
+                .find_map(|(t, r)| {
+                    self.senders[t as usize]
+                        .receivers
+                        .iter()
+                        .find(|recv| recv.vertex_id == r)
+                })
+        };
+        receiver
+            .into_iter()
+            .flat_map(|recv| recv.contacts_to_receiver.iter())
+            .filter(move |contact| {
+                let info = contact.borrow().info;
+                info.tx_node_id == tx && info.rx_node_id == rx
+            })
+            .cloned()
+    }
+
+    /// Returns the number of contacts transmitted by `node`.
+    ///
+    /// Only the real vertex pairs are counted, a contact shared with virtual nodes is counted
+    /// once. The contacts to or from external nodes are not part of the multigraph.
+    ///
+    /// # Parameters
+    ///
+    /// * `node` - The transmitting node.
+    ///
+    /// # Returns
+    ///
+    /// * The number of outbound contacts, zero if the node is unknown.
+    pub fn out_degree(&self, node: NodeID) -> usize {
+        let real_node_count = self.real_nodes.len();
+        if node as usize >= real_node_count {
+            return 0;
+        }
+        self.senders[node as usize]
+            .receivers
+            .iter()
+            .filter(|recv| (recv.vertex_id as usize) < real_node_count)
+            .map(|recv| recv.contacts_to_receiver.len())
+            .sum()
+    }
+
+    /// Returns the number of contacts received by `node`, see `out_degree`.
+    ///
+    /// # Parameters
+    ///
+    /// * `node` - The receiving node.
+    ///
+    /// # Returns
+    ///
+    /// * The number of inbound contacts, zero if the node is unknown.
+    pub fn in_degree(&self, node: NodeID) -> usize {
+        let real_node_count = self.real_nodes.len();
+        if node as usize >= real_node_count {
+            return 0;
+        }
+        self.senders[..real_node_count]
+            .iter()
+            .flat_map(|sender| sender.receivers.iter())
+            .filter(|recv| recv.vertex_id == node)
+            .map(|recv| recv.contacts_to_receiver.len())
+            .sum()
+    }
+
+    /// Returns the real nodes that neither transmit nor receive any contact, e.g. to catch a
+    /// destination disconnected from the rest of the plan.
+    ///
+    /// # Returns
+    ///
+    /// * The isolated nodes, in increasing ID order.
+    pub fn isolated_nodes(&self) -> Vec<NodeID> {
+        let real_node_count = self.real_nodes.len();
+        let mut connected = vec![false; real_node_count];
+        for sender in &self.senders[..real_node_count] {
+            for recv in &sender.receivers {
+                if (recv.vertex_id as usize) < real_node_count
+                    && !recv.contacts_to_receiver.is_empty()
+                {
+                    connected[sender.vertex_id as usize] = true;
+                    connected[recv.vertex_id as usize] = true;
+                }
+            }
+        }
+        (0..real_node_count as NodeID)
+            .filter(|node| !connected[*node as usize])
+            .collect()
+    }
+
+    /// Releases the resources booked on every contact, see `ContactManager::reset`.
+    ///
+    /// The contacts removed or purged beforehand are not restored. The trees and routes stored
+    /// by a router are not cleared, see e.g. `Spsn::reset`. The node managers have no reset
+    /// hook and are left as is, a node manager keeping bookings must be reset by its owner.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every contact was reset.
+    /// * `Err(ASABRError)` - If a contact cannot be borrowed, `ASABRError::ResourceError` if it
+    ///   cannot be initialized again.
+    pub fn reset_resources(&mut self) -> Result<(), ASABRError> {
+        for sender in &self.senders {
+            for receiver in &sender.receivers {
+                for contact in &receiver.contacts_to_receiver {
+                    let mut contact = contact.try_borrow_mut()?;
+                    let info = contact.info;
+                    contact.generation += 1;
+                    if !contact.manager.reset(&info) {
+                        return Err(ASABRError::ResourceError(
+                            "A contact cannot be initialized again",
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Clears the suppression flag of every contact (`contact_suppression` feature).
+    ///
+    /// The suppressions are otherwise cleared by the pathfinding that set them, this recovers
+    /// from an interrupted search. See also `Pathfinding::clear_suppressions`.
+    #[cfg(feature = "contact_suppression")]
+    pub fn clear_suppressions(&mut self) -> Result<(), ASABRError> {
+        for sender in &self.senders {
+            for receiver in &sender.receivers {
+                for contact in &receiver.contacts_to_receiver {
+                    contact.try_borrow_mut()?.suppressed = false;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the contacts currently suppressed (`contact_suppression` feature).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(NodeID, NodeID, Date)>)` - The (tx, rx, start) of each suppressed contact.
+    /// * `Err(ASABRError)` - If a contact cannot be borrowed.
+    #[cfg(feature = "contact_suppression")]
+    pub fn suppressed_contacts(&self) -> Result<Vec<(NodeID, NodeID, Date)>, ASABRError> {
+        let mut seen = BTreeSet::new();
+        let mut suppressed = Vec::new();
+        for sender in &self.senders {
+            for receiver in &sender.receivers {
+                for contact in &receiver.contacts_to_receiver {
+                    if !seen.insert(RcKey::of(contact)) {
+                        continue;
+                    }
+                    let contact = contact.try_borrow()?;
+                    if contact.suppressed {
+                        let info = contact.info;
+                        suppressed.push((info.tx_node_id, info.rx_node_id, info.start));
+                    }
+                }
+            }
+        }
+        Ok(suppressed)
+    }
+
+    /// Reports the volume booked on every contact against its original volume, e.g. after
+    /// routing a traffic set.
+    ///
+    /// The booked volume accounts for the bookings of every priority once (see
+    /// `ContactManager::total_booked_volume` and `ContactManager::get_original_volume`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ContactUtilization>)` - The utilization of each contact, in (tx, rx, start) order.
+    /// * `Err(ASABRError)` - If a contact cannot be borrowed.
+    pub fn utilization_report(&self) -> Result<Vec<ContactUtilization>, ASABRError> {
+        let mut seen = BTreeSet::new();
+        let mut report = Vec::new();
+        for sender in &self.senders {
+            for receiver in &sender.receivers {
+                for contact in &receiver.contacts_to_receiver {
+                    if !seen.insert(RcKey::of(contact)) {
+                        continue;
+                    }
+                    let contact = contact.try_borrow()?;
+                    let original_volume = contact.manager.get_original_volume();
+                    let scheduled_volume = contact.manager.total_booked_volume();
+                    let fraction = if original_volume.is_finite() && original_volume > 0.0 {
+                        scheduled_volume / original_volume
+                    } else {
+                        0.0
+                    };
+                    report.push(ContactUtilization {
+                        tx: contact.info.tx_node_id,
+                        rx: contact.info.rx_node_id,
+                        start: contact.info.start,
+                        end: contact.info.end,
+                        scheduled_volume,
+                        original_volume,
+                        fraction,
+                    });
+                }
+            }
+        }
+        report.sort_by(|a, b| {
+            (a.tx, a.rx)
+                .cmp(&(b.tx, b.rx))
+                .then(a.start.total_cmp(&b.start))
+        });
+        Ok(report)
+    }
+
+    /// Captures the resources booked on every contact, see `ContactManager::snapshot`.
+    ///
+    /// The node managers are not captured.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(MultigraphSnapshot)` - The states of the contacts, to be restored with `restore`.
+    /// * `Err(ASABRError)` - If a contact cannot be borrowed.
+    pub fn snapshot(&self) -> Result<MultigraphSnapshot<NM, CM>, ASABRError> {
+        let mut seen = BTreeSet::new();
+        let mut states = Vec::new();
+        for sender in &self.senders {
+            for receiver in &sender.receivers {
+                for contact in &receiver.contacts_to_receiver {
+                    if !seen.insert(RcKey::of(contact)) {
+                        continue;
+                    }
+                    let state = contact.try_borrow()?.manager.snapshot();
+                    states.push((contact.clone(), state));
+                }
+            }
+        }
+        Ok(MultigraphSnapshot { states })
+    }
+
+    /// Restores the resources booked on the contacts when `snapshot` was called, the subsequent
+    /// scheduling decisions are the ones that would have followed the snapshot. A snapshot can
+    /// be restored several times.
+    ///
+    /// The contacts removed or purged since the snapshot are restored but not added back, and
+    /// the contacts added since the snapshot are left untouched. Like for `reset_resources`, the
+    /// node managers are not part of the snapshot and are left as is.
+    ///
+    /// # Parameters
+    ///
+    /// * `snapshot` - A snapshot of this multigraph.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every contact was restored.
+    /// * `Err(ASABRError)` - If a contact cannot be borrowed, `ASABRError::ResourceError` if it
+    ///   rejects its state.
+    pub fn restore(&mut self, snapshot: &MultigraphSnapshot<NM, CM>) -> Result<(), ASABRError> {
+        for (contact, state) in &snapshot.states {
+            let mut contact = contact.try_borrow_mut()?;
+            contact.generation += 1;
+            if !contact.manager.restore(state.as_ref()) {
+                return Err(ASABRError::ResourceError(
+                    "A contact state cannot be restored",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Permanently removes the contacts ending before `now`, and the receivers left without
+    /// contacts.
+    ///
+    /// Unlike the lazy pruning performed during pathfinding, this releases the memory of the
+    /// expired contacts, a long-lived agent can call it periodically.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The current time.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of contacts removed from the receivers (a contact shared by
+    ///   virtual nodes is counted once per receiver).
+    pub fn purge_before(&mut self, now: Date) -> usize {
+        let mut removed = 0;
+        for sender in &mut self.senders {
+            for receiver in &mut sender.receivers {
+                let next = receiver.next.get_mut();
+                let first_checked = *next;
+                let mut idx = 0;
+                receiver.contacts_to_receiver.retain(|contact| {
+                    let keep = contact.borrow().info.end >= now;
+                    if !keep {
+                        removed += 1;
+                        if idx < first_checked {
+                            *next -= 1;
+                        }
+                    }
+                    idx += 1;
+                    keep
+                });
+                receiver.contacts_to_receiver.shrink_to_fit();
+                receiver.update_end_bounds();
+            }
+            sender
+                .receivers
+                .retain(|receiver| !receiver.contacts_to_receiver.is_empty());
+            sender.receivers.shrink_to_fit();
+        }
+        removed
+    }
+
     /// Applies exclusions to the nodes based on the provided sorted exclusions.
     ///
     /// Marks nodes as excluded if their index is in the `exclusions` list, otherwise unmarks them.
@@ -256,6 +932,118 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
         Ok(())
     }
 
+    /// Applies exclusions to the contacts, a contact being identified by its transmitting node,
+    /// receiving node and start time.
+    ///
+    /// Marks the matching contacts as excluded and unmarks the contacts excluded by a previous
+    /// call, exclusions that match no contact are ignored. Call with an empty list to clear them.
+    ///
+    /// # Parameters
+    ///
+    /// * `exclusions: &[(NodeID, NodeID, Date)]` - The (tx, rx, start) of the contacts to exclude.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If all exclusions were applied successfully.
+    /// - Err(ASABRError)`: If a contact cannot be mutably borrowed.
+    pub fn prepare_for_contact_exclusions(
+        &mut self,
+        exclusions: &[(NodeID, NodeID, Date)],
+    ) -> Result<(), ASABRError> {
+        for contact in self.excluded_contacts.drain(..) {
+            contact.try_borrow_mut()?.excluded = false;
+        }
+
+        let real_node_count = self.real_nodes.len();
+        for &(tx, rx, start) in exclusions {
+            if tx as usize >= real_node_count || rx as usize >= real_node_count {
+                continue;
+            }
+            // the contacts are shared between the vertex pairs, the first match is enough
+            let found = self.vertex_pairs(tx, rx).into_iter().find_map(|(t, r)| {
+                self.senders[t as usize]
+                    .receivers
+                    .iter()
+                    .find(|recv| recv.vertex_id == r)?
+                    .contacts_to_receiver
+                    .iter()
+                    .find(|contact| {
+                        let info = contact.borrow().info;
+                        info.tx_node_id == tx && info.rx_node_id == rx && info.start == start
+                    })
+                    .cloned()
+            });
+            if let Some(contact) = found {
+                contact.try_borrow_mut()?.excluded = true;
+                self.excluded_contacts.push(contact);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the consistency of the contacts, e.g. before routing over a plan from an external
+    /// tool.
+    ///
+    /// Reports the contacts of the same transmitter/receiver pair whose windows overlap, the
+    /// contacts that do not last, and the contacts referring to unknown nodes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If no issue was found.
+    /// * `Err(Vec<ValidationIssue>)` - The issues found, each overlap being reported once.
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+        let real_node_count = self.real_nodes.len();
+        let mut seen = BTreeSet::new();
+        let mut by_pair: HashMap<(NodeID, NodeID), Vec<ContactInfo>> = HashMap::new();
+        let mut issues = Vec::new();
+
+        // the contacts are shared between the vertex pairs of their nodes
+        for sender in &self.senders {
+            for receiver in &sender.receivers {
+                for contact in &receiver.contacts_to_receiver {
+                    if !seen.insert(RcKey::of(contact)) {
+                        continue;
+                    }
+                    let info = contact.borrow().info;
+                    let (tx, rx, start) = (info.tx_node_id, info.rx_node_id, info.start);
+                    if tx as usize >= real_node_count || rx as usize >= real_node_count {
+                        issues.push(ValidationIssue::UnknownNode { tx, rx, start });
+                    }
+                    if info.end <= info.start {
+                        issues.push(ValidationIssue::ZeroDurationContact { tx, rx, start });
+                    }
+                    by_pair.entry((tx, rx)).or_default().push(info);
+                }
+            }
+        }
+
+        for ((tx, rx), mut infos) in by_pair {
+            infos.sort_unstable_by(|a, b| a.start.total_cmp(&b.start));
+            // the contact ending the latest among the ones already checked
+            let mut latest: Option<ContactInfo> = None;
+            for info in infos {
+                if let Some(previous) = latest {
+                    if info.start < previous.end {
+                        issues.push(ValidationIssue::OverlappingContacts {
+                            tx,
+                            rx,
+                            first_start: previous.start,
+                            second_start: info.start,
+                        });
+                    }
+                    if info.end <= previous.end {
+                        continue;
+                    }
+                }
+                latest = Some(info);
+            }
+        }
+
+        if issues.is_empty() {
+            return Ok(());
+        }
+        Err(issues)
+    }
+
     /// Retrieves the total number of vertices in the multigraph.
     ///
     /// # Returns
@@ -264,6 +1052,45 @@ impl<NM: NodeManager, CM: ContactManager> Multigraph<NM, CM> {
     pub fn get_vertex_count(&self) -> usize {
         self.vertex_count
     }
+
+    /// Returns the real node `id`, e.g. to read the state of its manager.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Rc<RefCell<Node<NM>>>>` - The node, `None` if `id` is not a real node.
+    pub fn node(&self, id: NodeID) -> Option<Rc<RefCell<Node<NM>>>> {
+        self.real_nodes.get(id as usize).cloned()
+    }
+
+    /// Returns the number of real nodes, the valid IDs for `node` being below it.
+    pub fn node_count(&self) -> usize {
+        self.real_nodes.len()
+    }
+
+    /// Returns the number of priority levels the bundles can use, see
+    /// `ContactManager::priority_levels`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The fewest levels distinguished by a contact manager, `None` if no
+    ///   manager distinguishes priorities. The contacts removed since are still accounted for.
+    pub fn priority_levels(&self) -> Option<usize> {
+        self.priority_levels
+    }
+
+    /// Returns `true` if the real node `id` was declared as an external node (`enode`).
+    pub fn is_external(&self, id: NodeID) -> bool {
+        self.is_external.get(id as usize).copied().unwrap_or(false)
+    }
+
+    /// Returns the real nodes the virtual node `vid` labels, in increasing order.
+    pub fn rids_of(&self, vid: NodeID) -> Vec<NodeID> {
+        self.vnodes_for_rid
+            .iter()
+            .filter(|(_, vids)| vids.contains(&vid))
+            .map(|(rid, _)| *rid)
+            .collect()
+    }
 }
 
 impl<NM: NodeManager, CM: ContactManager> Display for Multigraph<NM, CM> {
@@ -309,3 +1136,423 @@ impl<NM: NodeManager, CM: ContactManager> Display for Multigraph<NM, CM> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::{make_bundle, make_contact};
+    use crate::test_support::GraphBuilder;
+
+    type Tree = NodeParentingTreeExcl<NoManagement, EVLManager, SABR>;
+
+    fn chain() -> Rc<RefCell<Multigraph<NoManagement, EVLManager>>> {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .rate(10.0)
+            .plan();
+        Rc::new(RefCell::new(Multigraph::new(plan).unwrap()))
+    }
+
+    fn reaches(pathfinding: &mut Tree, dest: NodeID) -> bool {
+        pathfinding
+            .get_next(0.0, 0, &make_bundle(dest, 0, 10.0, 1000.0), &[])
+            .unwrap()
+            .by_destination[dest as usize]
+            .is_some()
+    }
+
+    #[test]
+    fn added_contact_opens_a_new_path() {
+        let graph = chain();
+        let mut pathfinding = Tree::new(graph.clone());
+        assert!(
+            !reaches(&mut pathfinding, 2),
+            "TEST FAILED: Node 2 should not be reachable yet."
+        );
+
+        graph
+            .borrow_mut()
+            .add_contact(make_contact(1, 2, 10.0, 100.0, 10.0, 0.0))
+            .unwrap();
+        assert!(
+            reaches(&mut pathfinding, 2),
+            "TEST FAILED: The added contact should reach node 2."
+        );
+    }
+
+    #[test]
+    fn contacts_stay_sorted_after_insertion() {
+        let graph = chain();
+        graph
+            .borrow_mut()
+            .add_contact(make_contact(0, 1, 50.0, 60.0, 10.0, 0.0))
+            .unwrap();
+        graph
+            .borrow_mut()
+            .add_contact(make_contact(0, 1, 20.0, 30.0, 10.0, 0.0))
+            .unwrap();
+
+        let graph = graph.borrow();
+        let starts: Vec<Date> = graph.senders[0].receivers[0]
+            .contacts_to_receiver
+            .iter()
+            .map(|contact| contact.borrow().info.start)
+            .collect();
+        assert_eq!(
+            starts,
+            vec![0.0, 20.0, 50.0],
+            "TEST FAILED: The contacts should be sorted by start time."
+        );
+    }
+
+    #[test]
+    fn contacts_between_are_in_start_order() {
+        let graph = chain();
+        graph
+            .borrow_mut()
+            .add_contact(make_contact(0, 1, 150.0, 200.0, 10.0, 0.0))
+            .unwrap();
+        graph
+            .borrow_mut()
+            .add_contact(make_contact(0, 1, 120.0, 130.0, 10.0, 0.0))
+            .unwrap();
+        graph
+            .borrow_mut()
+            .add_contact(make_contact(1, 2, 10.0, 20.0, 10.0, 0.0))
+            .unwrap();
+
+        let graph = graph.borrow();
+        let windows: Vec<(Date, Date)> = graph
+            .contacts_between(0, 1)
+            .map(|contact| (contact.borrow().info.start, contact.borrow().info.end))
+            .collect();
+        assert_eq!(
+            windows,
+            vec![(0.0, 100.0), (120.0, 130.0), (150.0, 200.0)],
+            "TEST FAILED: Expected the windows from node 0 to node 1 in start order."
+        );
+        assert_eq!(
+            graph.contacts_between(2, 0).count(),
+            0,
+            "TEST FAILED: Node 2 has no contact to node 0."
+        );
+        assert_eq!(
+            graph.contacts_between(0, 7).count(),
+            0,
+            "TEST FAILED: Node 7 is not a node of the multigraph."
+        );
+    }
+
+    #[test]
+    fn contact_to_unknown_node_is_rejected() {
+        assert!(
+            chain()
+                .borrow_mut()
+                .add_contact(make_contact(0, 7, 0.0, 10.0, 10.0, 0.0))
+                .is_err(),
+            "TEST FAILED: A contact to an unknown node should be rejected."
+        );
+    }
+
+    #[test]
+    fn purged_contacts_are_no_longer_used() {
+        let graph = chain();
+        graph
+            .borrow_mut()
+            .add_contact(make_contact(0, 1, 0.0, 10.0, 10.0, 0.0))
+            .unwrap();
+        graph
+            .borrow_mut()
+            .add_contact(make_contact(1, 2, 0.0, 5.0, 10.0, 0.0))
+            .unwrap();
+        let mut pathfinding = Tree::new(graph.clone());
+
+        assert_eq!(
+            graph.borrow_mut().purge_before(20.0),
+            2,
+            "TEST FAILED: The two short contacts should be purged."
+        );
+        assert_eq!(
+            graph.borrow().senders[1].receivers.len(),
+            0,
+            "TEST FAILED: Node 1 should have no receiver left."
+        );
+        assert!(
+            !reaches(&mut pathfinding, 2),
+            "TEST FAILED: Node 2 was only reachable through a purged contact."
+        );
+        let tree = pathfinding
+            .get_next(20.0, 0, &make_bundle(1, 0, 10.0, 1000.0), &[])
+            .unwrap();
+        assert_eq!(
+            tree.by_destination[1].as_ref().unwrap().borrow().at_time,
+            21.0,
+            "TEST FAILED: The remaining contact should still be used."
+        );
+    }
+
+    #[test]
+    fn removed_contact_breaks_the_route() {
+        let graph = chain();
+        let mut pathfinding = Tree::new(graph.clone());
+        assert!(
+            reaches(&mut pathfinding, 1),
+            "TEST FAILED: Node 1 should be reachable."
+        );
+
+        assert!(
+            !graph.borrow_mut().remove_contact(0, 1, 5.0),
+            "TEST FAILED: No contact starts at 5.0."
+        );
+        assert!(
+            graph.borrow_mut().remove_contact(0, 1, 0.0),
+            "TEST FAILED: The contact should be removed."
+        );
+        assert!(
+            !reaches(&mut pathfinding, 1),
+            "TEST FAILED: Node 1 should not be reachable anymore."
+        );
+    }
+
+    #[test]
+    fn validate_reports_overlapping_contacts() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(0, 1, 50.0, 150.0)
+            // touching the previous window is fine
+            .contact(0, 1, 150.0, 200.0)
+            .contact(1, 0, 50.0, 150.0)
+            .plan();
+        let multigraph = Multigraph::new(plan).unwrap();
+
+        assert_eq!(
+            multigraph.validate(),
+            Err(vec![ValidationIssue::OverlappingContacts {
+                tx: 0,
+                rx: 1,
+                first_start: 0.0,
+                second_start: 50.0,
+            }]),
+            "TEST FAILED: Only the overlap of the 0->1 contacts should be reported."
+        );
+        assert_eq!(
+            Multigraph::new(
+                GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+                    .node(0)
+                    .node(1)
+                    .contact(0, 1, 0.0, 100.0)
+                    .plan()
+            )
+            .unwrap()
+            .validate(),
+            Ok(()),
+            "TEST FAILED: A single contact is consistent."
+        );
+    }
+
+    #[test]
+    fn new_checked_reports_the_rejected_contacts() -> Result<(), ASABRError> {
+        use crate::pathfinding::test_helpers::make_vertex;
+
+        let (multigraph, rejected) = Multigraph::<NoManagement, EVLManager>::new_checked(
+            vec![
+                make_vertex(0, "a", NoManagement {}),
+                make_vertex(1, "b", NoManagement {}),
+            ],
+            vec![
+                (
+                    ContactInfo::new(0, 1, 0.0, 100.0),
+                    EVLManager::new(10.0, 1.0),
+                ),
+                (
+                    ContactInfo::new(1, 0, 0.0, 100.0),
+                    EVLManager::new(0.0, 1.0),
+                ),
+                (
+                    ContactInfo::new(0, 1, 200.0, 150.0),
+                    EVLManager::new(10.0, 1.0),
+                ),
+                (
+                    ContactInfo::new(0, 2, 0.0, 100.0),
+                    EVLManager::new(10.0, 1.0),
+                ),
+            ],
+            None,
+        )?;
+
+        let reported: Vec<(usize, RejectionReason)> = rejected
+            .iter()
+            .map(|rejection| (rejection.index, rejection.reason))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (1, RejectionReason::ManagerInit),
+                (2, RejectionReason::InvalidTimes),
+                (3, RejectionReason::UnknownNode),
+            ],
+            "TEST FAILED: The three invalid contacts should be reported."
+        );
+        assert_eq!(
+            multigraph.contacts_between(0, 1).count(),
+            1,
+            "TEST FAILED: The valid contact should be loaded."
+        );
+        assert_eq!(
+            multigraph.contacts_between(1, 0).count(),
+            0,
+            "TEST FAILED: The zero-rate contact should not be loaded."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn isolated_node_is_reported() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(0, 1, 200.0, 300.0)
+            .contact(1, 2, 0.0, 100.0)
+            .plan();
+        let multigraph = Multigraph::new(plan).unwrap();
+
+        assert_eq!(
+            (multigraph.out_degree(0), multigraph.in_degree(0)),
+            (2, 0),
+            "TEST FAILED: Node 0 transmits two contacts."
+        );
+        assert_eq!(
+            (multigraph.out_degree(1), multigraph.in_degree(1)),
+            (1, 2),
+            "TEST FAILED: Node 1 receives two contacts and transmits one."
+        );
+        assert_eq!(
+            (multigraph.out_degree(3), multigraph.in_degree(3)),
+            (0, 0),
+            "TEST FAILED: Node 3 has no contacts."
+        );
+        assert_eq!(
+            multigraph.isolated_nodes(),
+            vec![3],
+            "TEST FAILED: Only node 3 is isolated."
+        );
+    }
+
+    #[test]
+    fn first_valid_contact_skips_the_expired_ones() {
+        // 200 short contacts, the one starting at 500 lasting until 1800
+        let contacts: Vec<_> = (0..200)
+            .map(|i| {
+                let start = 10.0 * i as Date;
+                let end = if i == 50 { 1800.0 } else { start + 5.0 };
+                Rc::new(RefCell::new(make_contact::<NoManagement>(
+                    0, 1, start, end, 1.0, 0.0,
+                )))
+            })
+            .collect();
+        let first_valid = |time: Date| {
+            contacts
+                .iter()
+                .position(|contact| contact.borrow().info.end > time)
+        };
+
+        let receiver = Receiver::new(1, contacts.clone());
+        for time in [
+            0.0, 3.0, 7.0, 420.0, 505.0, 777.0, 1799.0, 1800.0, 1990.0, 2000.0,
+        ] {
+            assert_eq!(
+                receiver.lazy_prune_and_get_first_idx(time),
+                first_valid(time),
+                "TEST FAILED: Wrong first valid contact at {}.",
+                time
+            );
+        }
+        // a single late lookup skips all the expired contacts at once
+        assert_eq!(
+            Receiver::new(1, contacts.clone()).lazy_prune_and_get_first_idx(1850.0),
+            Some(185),
+            "TEST FAILED: The first valid contact should be found from scratch."
+        );
+    }
+
+    #[test]
+    fn utilization_report_shows_the_loaded_contact() {
+        // 0 -> 1 carries 100 units of volume, 1 -> 2 carries 200
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 2, 0.0, 200.0)
+            .plan();
+        let multigraph = Multigraph::new(plan).unwrap();
+        let contact = multigraph.contacts_between(0, 1).next().unwrap().clone();
+        for size in [10.0, 20.0, 30.0] {
+            let mut contact = contact.borrow_mut();
+            let info = contact.info;
+            contact
+                .manager
+                .schedule_tx(&info, 0.0, &make_bundle(1, 0, size, 1000.0))
+                .expect("TEST FAILED: The bundle should fit.");
+        }
+
+        let report = multigraph.utilization_report().unwrap();
+        assert_eq!(
+            report,
+            vec![
+                ContactUtilization {
+                    tx: 0,
+                    rx: 1,
+                    start: 0.0,
+                    end: 100.0,
+                    scheduled_volume: 60.0,
+                    original_volume: 100.0,
+                    fraction: 0.6,
+                },
+                ContactUtilization {
+                    tx: 1,
+                    rx: 2,
+                    start: 0.0,
+                    end: 200.0,
+                    scheduled_volume: 0.0,
+                    original_volume: 200.0,
+                    fraction: 0.0,
+                },
+            ],
+            "TEST FAILED: Only the contact to node 1 should be loaded."
+        );
+    }
+
+    #[test]
+    fn nodes_are_reached_by_id() {
+        let graph = chain();
+        let graph = graph.borrow();
+        assert_eq!(
+            graph.node_count(),
+            3,
+            "TEST FAILED: The chain has three real nodes."
+        );
+        assert_eq!(
+            graph.node(2).map(|node| node.borrow().info.id),
+            Some(2),
+            "TEST FAILED: Node 2 should be returned."
+        );
+        assert!(
+            graph.node(3).is_none(),
+            "TEST FAILED: An ID out of range should give no node."
+        );
+    }
+}
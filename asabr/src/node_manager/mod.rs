@@ -1,7 +1,10 @@
 extern crate alloc;
 
+#[cfg(any(feature = "node_proc", feature = "node_tx", feature = "node_rx"))]
 use crate::{bundle::Bundle, types::Date};
 pub mod none;
+pub mod proc_delay;
+pub mod storage;
 
 /// A trait for managing and scheduling operations on nodes in a network.
 ///
@@ -69,6 +72,16 @@ pub trait NodeManager {
     #[cfg(feature = "node_proc")]
     fn schedule_process(&self, at_time: Date, bundle: &mut Bundle) -> Date;
 
+    /// Whether the bundles are processed at the source of their route. Otherwise,
+    /// `dry_run_process` and `schedule_process` are only called for the bundles in transit, the
+    /// source sending its bundles from the routing time.
+    ///
+    /// The default implementation processes every bundle.
+    #[cfg(feature = "node_proc")]
+    fn processes_at_source(&self) -> bool {
+        true
+    }
+
     /// Schedules the transmission of a `Bundle` within a specified time window.
     ///
     /// This method schedules the actual transmission of a bundle, checking if it can be
@@ -124,6 +137,11 @@ impl<T: AsRef<dyn NodeManager>> NodeManager for T {
     fn schedule_process(&self, at_time: Date, bundle: &mut Bundle) -> Date {
         self.as_ref().schedule_process(at_time, bundle)
     }
+    /// Delegates the processes_at_source method to the boxed object.
+    #[cfg(feature = "node_proc")]
+    fn processes_at_source(&self) -> bool {
+        self.as_ref().processes_at_source()
+    }
     /// Delegates the schedule method to the boxed object.
     #[cfg(feature = "node_tx")]
     fn schedule_tx(
@@ -172,6 +190,11 @@ macro_rules! transparent_NM {
             fn schedule_process(&self, at_time: Date, bundle: &mut Bundle) -> Date {
                 self.0.schedule_process(at_time, bundle)
             }
+            /// Delegates the processes_at_source method to the boxed object.
+            #[cfg(feature = "node_proc")]
+            fn processes_at_source(&self) -> bool {
+                self.0.processes_at_source()
+            }
             /// Delegates the schedule method to the boxed object.
             #[cfg(feature = "node_tx")]
             fn schedule_tx(
@@ -1,6 +1,6 @@
-use crate::empty_parse;
 #[cfg(any(feature = "node_proc", feature = "node_tx", feature = "node_rx"))]
 use crate::{bundle::Bundle, types::Date};
+use crate::{empty_parse, parsing::Unparse};
 
 use super::NodeManager;
 
@@ -9,6 +9,12 @@ use super::NodeManager;
 pub struct NoManagement {}
 empty_parse!(NoManagement);
 
+impl Unparse for NoManagement {
+    fn unparse<W: core::fmt::Write>(&self, _out: &mut W) -> core::fmt::Result {
+        Ok(())
+    }
+}
+
 /// This manager has no effect.
 impl NodeManager for NoManagement {
     #[cfg(feature = "node_proc")]
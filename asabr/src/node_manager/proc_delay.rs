@@ -0,0 +1,181 @@
+extern crate alloc;
+
+#[cfg(any(feature = "node_proc", feature = "node_tx", feature = "node_rx"))]
+use crate::{bundle::Bundle, types::Date};
+use crate::{parse_transparent, parsing::Unparse, types::Duration};
+
+use super::NodeManager;
+
+/// A manager for nodes taking a fixed time to process the bundles they forward (`node_proc`
+/// feature).
+///
+/// The bundles are sent without delay from the source of their route, see
+/// `NodeManager::processes_at_source`.
+#[derive(Debug)]
+pub struct ProcDelayManager {
+    /// The time taken to process a bundle.
+    proc_delay: Duration,
+}
+
+impl ProcDelayManager {
+    /// Creates a new `ProcDelayManager`.
+    ///
+    /// # Arguments
+    ///
+    /// * `proc_delay` - The time taken to process a bundle in transit.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `ProcDelayManager`.
+    pub fn new(proc_delay: Duration) -> Self {
+        Self { proc_delay }
+    }
+
+    /// Returns the time taken to process a bundle in transit.
+    pub fn proc_delay(&self) -> Duration {
+        self.proc_delay
+    }
+}
+
+impl NodeManager for ProcDelayManager {
+    /// Delays the bundle by the processing time.
+    #[cfg(feature = "node_proc")]
+    fn dry_run_process(&self, at_time: Date, _bundle: &mut Bundle) -> Date {
+        at_time + self.proc_delay
+    }
+
+    /// The node has no transmission constraint.
+    #[cfg(feature = "node_tx")]
+    fn dry_run_tx(&self, _waiting_since: Date, _start: Date, _end: Date, _bundle: &Bundle) -> bool {
+        true
+    }
+
+    /// The node has no reception constraint.
+    #[cfg(feature = "node_rx")]
+    fn dry_run_rx(&self, _start: Date, _end: Date, _bundle: &Bundle) -> bool {
+        true
+    }
+
+    /// Delays the bundle by the processing time.
+    #[cfg(feature = "node_proc")]
+    fn schedule_process(&self, at_time: Date, _bundle: &mut Bundle) -> Date {
+        at_time + self.proc_delay
+    }
+
+    /// The bundles created by the node are not delayed.
+    #[cfg(feature = "node_proc")]
+    fn processes_at_source(&self) -> bool {
+        false
+    }
+
+    /// The node has no transmission constraint.
+    #[cfg(feature = "node_tx")]
+    fn schedule_tx(
+        &mut self,
+        _waiting_since: Date,
+        _start: Date,
+        _end: Date,
+        _bundle: &Bundle,
+    ) -> bool {
+        true
+    }
+
+    /// The node has no reception constraint.
+    #[cfg(feature = "node_rx")]
+    fn schedule_rx(&mut self, _start: Date, _end: Date, _bundle: &Bundle) -> bool {
+        true
+    }
+}
+
+parse_transparent!(ProcDelayManager, Duration);
+
+impl From<Duration> for ProcDelayManager {
+    fn from(proc_delay: Duration) -> Self {
+        ProcDelayManager::new(proc_delay)
+    }
+}
+
+impl Unparse for ProcDelayManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        write!(out, "{}", self.proc_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::vertex::Vertex;
+    use alloc::string::String;
+
+    #[test]
+    fn delay_is_parsed_and_written_back() {
+        let plan = parse_from_str::<ProcDelayManager, EVLManager>(
+            "node 0 a 0
+            node 1 b 2.5",
+        )
+        .unwrap();
+        let Vertex::INode(node) = &plan.vertices[1] else {
+            panic!("TEST FAILED: Node 1 should be an interior node.");
+        };
+        assert_eq!(
+            node.manager.proc_delay(),
+            2.5,
+            "TEST FAILED: The processing delay should be parsed."
+        );
+
+        let mut unparsed = String::new();
+        node.manager.unparse(&mut unparsed).unwrap();
+        assert_eq!(
+            unparsed, "2.5",
+            "TEST FAILED: The processing delay should be written back."
+        );
+    }
+
+    /// Routes a bundle from node 0 to node 3 over a 3-hop path, returning its arrival time.
+    #[cfg(feature = "node_proc")]
+    fn arrival_with_delay(proc_delay: Duration) -> Date {
+        use crate::pathfinding::test_helpers::make_bundle;
+        use crate::route_storage::cache::TreeCache;
+        use crate::routing::Router;
+        use crate::routing::aliases::SpsnNodeParenting;
+        use alloc::{format, rc::Rc};
+        use core::cell::RefCell;
+
+        let plan = parse_from_str::<ProcDelayManager, EVLManager>(&format!(
+            "node 0 a {proc_delay}
+            node 1 b {proc_delay}
+            node 2 c {proc_delay}
+            node 3 d {proc_delay}
+            contact 0 1 0 100 10 1
+            contact 1 2 0 100 10 1
+            contact 2 3 0 100 10 1"
+        ))
+        .unwrap();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let output = router
+            .route(0, &make_bundle(3, 0, 10.0, 1000.0), 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Node 3 should be reached.");
+        output.route_metrics[&3].arrival_time
+    }
+
+    #[cfg(feature = "node_proc")]
+    #[test]
+    fn relays_delay_the_bundles_in_transit() {
+        // 3 hops of 1 s transmission and 1 s delay each
+        assert_eq!(
+            arrival_with_delay(0.0),
+            6.0,
+            "TEST FAILED: Without processing, expected an arrival at 6.0."
+        );
+        // the two relays add their processing time, not the source
+        assert_eq!(
+            arrival_with_delay(2.5),
+            11.0,
+            "TEST FAILED: With processing, expected an arrival at 11.0."
+        );
+    }
+}
@@ -0,0 +1,209 @@
+extern crate alloc;
+use core::cell::Cell;
+
+#[cfg(any(feature = "node_proc", feature = "node_tx", feature = "node_rx"))]
+use crate::{bundle::Bundle, types::Date};
+use crate::{parse_transparent, parsing::Unparse, types::Volume};
+
+use super::NodeManager;
+
+/// A manager for relays of limited storage: a bundle is only received if the volume stored by
+/// the node leaves room for it.
+///
+/// The stored volume grows with each scheduled reception (`node_rx` feature) and is released
+/// once the bundle is processed to be forwarded (`node_proc` feature). Without the `node_rx`
+/// feature, the capacity is not enforced.
+#[derive(Debug)]
+pub struct StorageManager {
+    /// The volume the node can store.
+    capacity: Volume,
+    /// The volume currently stored, released by the processing hook that only borrows the manager.
+    occupancy: Cell<Volume>,
+}
+
+impl StorageManager {
+    /// Creates a new `StorageManager` storing nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The volume the node can store.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `StorageManager`.
+    pub fn new(capacity: Volume) -> Self {
+        Self {
+            capacity,
+            occupancy: Cell::new(0.0),
+        }
+    }
+
+    /// Returns the volume the node can store.
+    pub fn capacity(&self) -> Volume {
+        self.capacity
+    }
+
+    /// Returns the volume currently stored.
+    pub fn occupancy(&self) -> Volume {
+        self.occupancy.get()
+    }
+
+    /// Returns `true` if a bundle of `size` fits in the remaining storage.
+    pub fn can_store(&self, size: Volume) -> bool {
+        self.occupancy.get() + size <= self.capacity
+    }
+}
+
+impl NodeManager for StorageManager {
+    /// The processing takes no time.
+    #[cfg(feature = "node_proc")]
+    fn dry_run_process(&self, at_time: Date, _bundle: &mut Bundle) -> Date {
+        at_time
+    }
+
+    /// The node has no transmission constraint.
+    #[cfg(feature = "node_tx")]
+    fn dry_run_tx(&self, _waiting_since: Date, _start: Date, _end: Date, _bundle: &Bundle) -> bool {
+        true
+    }
+
+    /// The bundle is received if it fits in the remaining storage.
+    #[cfg(feature = "node_rx")]
+    fn dry_run_rx(&self, _start: Date, _end: Date, bundle: &Bundle) -> bool {
+        self.can_store(bundle.size)
+    }
+
+    /// The bundle is forwarded on, its volume is released (the source did not store it, the
+    /// occupancy does not go below zero).
+    #[cfg(feature = "node_proc")]
+    fn schedule_process(&self, at_time: Date, bundle: &mut Bundle) -> Date {
+        self.occupancy
+            .set(Volume::max(0.0, self.occupancy.get() - bundle.size));
+        at_time
+    }
+
+    /// The node has no transmission constraint.
+    #[cfg(feature = "node_tx")]
+    fn schedule_tx(
+        &mut self,
+        _waiting_since: Date,
+        _start: Date,
+        _end: Date,
+        _bundle: &Bundle,
+    ) -> bool {
+        true
+    }
+
+    /// Stores the bundle if it fits in the remaining storage.
+    #[cfg(feature = "node_rx")]
+    fn schedule_rx(&mut self, _start: Date, _end: Date, bundle: &Bundle) -> bool {
+        if !self.can_store(bundle.size) {
+            return false;
+        }
+        self.occupancy.set(self.occupancy.get() + bundle.size);
+        true
+    }
+}
+
+parse_transparent!(StorageManager, Volume);
+
+impl From<Volume> for StorageManager {
+    fn from(capacity: Volume) -> Self {
+        StorageManager::new(capacity)
+    }
+}
+
+impl Unparse for StorageManager {
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result {
+        write!(out, "{}", self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::vertex::Vertex;
+    use alloc::string::String;
+
+    #[test]
+    fn capacity_bounds_the_storable_bundles() {
+        let plan = parse_from_str::<StorageManager, EVLManager>(
+            "node 0 a 1000
+            node 1 b 5",
+        )
+        .unwrap();
+        let Vertex::INode(node) = &plan.vertices[1] else {
+            panic!("TEST FAILED: Node 1 should be an interior node.");
+        };
+        let manager = &node.manager;
+        assert_eq!(
+            (manager.capacity(), manager.occupancy()),
+            (5.0, 0.0),
+            "TEST FAILED: The node should store nothing out of its parsed capacity."
+        );
+        assert!(
+            manager.can_store(5.0) && !manager.can_store(5.5),
+            "TEST FAILED: Only the bundles fitting in the capacity can be stored."
+        );
+
+        let mut unparsed = String::new();
+        manager.unparse(&mut unparsed).unwrap();
+        assert_eq!(
+            unparsed, "5",
+            "TEST FAILED: The capacity should be written back."
+        );
+    }
+
+    #[cfg(feature = "node_rx")]
+    #[test]
+    fn full_relay_is_bypassed() {
+        use crate::pathfinding::test_helpers::make_bundle;
+        use crate::route_storage::cache::TreeCache;
+        use crate::routing::Router;
+        use crate::routing::aliases::SpsnNodeParenting;
+        use alloc::rc::Rc;
+        use core::cell::RefCell;
+
+        // relay 1 is faster but too small for the bundle, relay 2 can store it
+        let plan = parse_from_str::<StorageManager, EVLManager>(
+            "node 0 src 1000
+            node 1 small 5
+            node 2 large 1000
+            node 3 dst 1000
+            contact 0 1 0 100 10 1
+            contact 1 3 0 100 10 1
+            contact 0 2 0 100 10 5
+            contact 2 3 0 100 10 5",
+        )
+        .unwrap();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        let output = router
+            .route(0, &make_bundle(3, 0, 10.0, 1000.0), 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Node 3 should be reached through relay 2.");
+        let (contact, _) = output.first_hops.values().next().unwrap();
+        assert_eq!(
+            contact.borrow().info.rx_node_id,
+            2,
+            "TEST FAILED: The bundle does not fit in relay 1."
+        );
+
+        // the whole route is scheduled, relay 2 forwards the bundle on if processing is modeled
+        let expected = if cfg!(feature = "node_proc") {
+            0.0
+        } else {
+            10.0
+        };
+        let multigraph = router.get_multigraph();
+        let relay = multigraph.borrow().node(2).unwrap();
+        assert_eq!(
+            relay.borrow().manager.occupancy(),
+            expected,
+            "TEST FAILED: Relay 2 should store the bundle until it is forwarded."
+        );
+    }
+}
@@ -5,14 +5,15 @@ use alloc::vec::Vec;
 use replace_with::replace_with_or_default_and_return as replace_with;
 
 pub use crate::contact_manager::lex::StandardManagersDyn as CMDynStandard;
-use crate::types::AnyNumber;
+use crate::errors::ASABRError;
+use crate::types::{AnyFloat, AnyNumber};
 
 /// re-export of types for which it is usefull to implement Parse<T> TryInto<T> in order to parse a full contact plan.
 pub mod parsables {
     pub use super::Delimiter;
-    pub use crate::contact_manager::lex::StandardManagersKinds;
+    pub use crate::contact_manager::lex::{STANDARD_MARKERS, StandardManagersKinds};
     pub use crate::contact_plan::from_asabr_lexer::ASABRPlanInfoKind;
-    pub use crate::types::{AnyNumber, NodeID, NodeName};
+    pub use crate::types::{AnyFloat, AnyNumber, NodeID, NodeName};
 }
 
 // ***
@@ -32,6 +33,7 @@ pub mod parsables {
 /// - Vec<T> when T: Parse  (Lexing require T and Delimiter)
 /// - [T;N] when T: Parse
 /// - Tupples, up to 20 elements each Parse-able
+/// - Trailing<T, U> when T and U are Parse-able, for optional trailing data
 pub trait Parse: Sized {
     /// The kind of tokens needeed to build Self. Usually, a fitting enum.
     /// Implementing LexFrom will require to produce these
@@ -58,6 +60,13 @@ pub trait Parse: Sized {
     /// It is considered a logic error to feed something wich already returned true or
     /// have the NOFEED flag set
     fn feed(tok: Self::Token, parser: &mut Self::Parser) -> Result<bool, &'static str>;
+
+    /// Whether the parser already holds a complete value while still accepting tokens, e.g. an
+    /// optional trailing field. If the next token cannot be lexed for this parser, `parse` can be
+    /// called instead of feeding it.
+    fn complete(_p: &Self::Parser) -> bool {
+        false
+    }
 }
 
 /// Trait making it possible to construct Self from some T (&T really),
@@ -71,6 +80,16 @@ pub trait LexFrom<T: ?Sized>: Parse {
     fn lex(t: &T, p: &Self::Parser) -> Result<Self::Token, &'static str>;
 }
 
+/// The reverse of `Parse`: writes the tokens that `LexFrom<str>` and `Parse` read back into an
+/// equal value, separated by whitespaces. Used to write contact plans in the A-SABR format.
+///
+/// Implementations return `core::fmt::Error` if the value cannot be represented by its tokens,
+/// e.g. a manager built programmatically with options the syntax does not express.
+pub trait Unparse {
+    /// Writes the tokens of `self` to `out`, without leading or trailing whitespace.
+    fn unparse<W: core::fmt::Write>(&self, out: &mut W) -> core::fmt::Result;
+}
+
 /// Error typically fired when there is a conversion error while trying to produce a token.
 pub const ETYPE: &str = "Wrong type for the next token.";
 /// Error fired on end of stream if feed did not yet return true.
@@ -88,6 +107,8 @@ pub struct Located<T> {
     pub data: T,
     pub(crate) line: usize,
     pub(crate) toknum: usize,
+    /// The field the token was expected to hold, empty if unknown.
+    pub(crate) field: &'static str,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -123,10 +144,19 @@ impl<T> Located<T> {
             data: e,
             line: self.line,
             toknum: self.toknum,
+            field: self.field,
         }
     }
 }
 
+/// A value followed by optional trailing data, e.g. an optional field ending a declaration.
+///
+/// The parser is `complete` once the value is parsed, the trailing data being parsed only if
+/// its tokens follow. The first token of `U` should not be lexable as the next token expected
+/// by the enclosing parser, e.g. by starting `U` with a keyword.
+#[derive(Clone, Copy, Debug)]
+pub struct Trailing<T, U>(pub T, pub Option<U>);
+
 /// Used to parse Tupples. Avoid using directly
 #[doc(hidden)]
 #[derive(Clone, Copy, Debug)]
@@ -135,6 +165,13 @@ pub enum Partial<T1: Parse, T2: Parse> {
     Fst(T1, T2::Parser),
 }
 
+/// Parser type for trailing data. Avoid using directly
+#[doc(hidden)]
+pub struct TrailingParser<T: Parse, U: Parse> {
+    partial: Partial<T, U>,
+    trailing: bool,
+}
+
 /// Token type for lists of T. Avoid using directly
 #[doc(hidden)]
 #[derive(Clone, Copy, Debug)]
@@ -200,6 +237,39 @@ impl<T1: Parse, T2: Parse> Parse for (T1, T2) {
         })
     }
     const NOFEED: bool = T1::NOFEED && T2::NOFEED;
+
+    fn complete(p: &Self::Parser) -> bool {
+        match p {
+            Partial::None(_) => false,
+            Partial::Fst(_, p) => T2::complete(p),
+        }
+    }
+}
+
+impl<T: Parse, U: Parse> Parse for Trailing<T, U> {
+    type Token = Either<T::Token, U::Token>;
+    type Parser = TrailingParser<T, U>;
+    const NOFEED: bool = T::NOFEED;
+
+    fn parse(p: Self::Parser) -> Result<Self, &'static str> {
+        match p.partial {
+            Partial::None(sub) => Ok(Trailing(T::parse(sub)?, None)),
+            Partial::Fst(value, _) if !p.trailing => Ok(Trailing(value, None)),
+            Partial::Fst(value, sub) => Ok(Trailing(value, Some(U::parse(sub)?))),
+        }
+    }
+
+    fn feed(tok: Self::Token, parser: &mut Self::Parser) -> Result<bool, &'static str> {
+        if let Either::Right(_) = tok {
+            parser.trailing = true;
+        }
+        // the value alone never ends the parsing, see `complete`
+        Ok(<(T, U)>::feed(tok, &mut parser.partial)? && parser.trailing)
+    }
+
+    fn complete(p: &Self::Parser) -> bool {
+        !p.trailing && matches!(p.partial, Partial::Fst(..))
+    }
 }
 
 impl<T: Parse> Parse for Vec<T> {
@@ -317,6 +387,15 @@ where
         }
     }
 }
+impl<T: Parse, U: Parse, Src: ?Sized> LexFrom<Src> for Trailing<T, U>
+where
+    T: LexFrom<Src>,
+    U: LexFrom<Src>,
+{
+    fn lex(t: &Src, p: &Self::Parser) -> Result<Self::Token, &'static str> {
+        <(T, U)>::lex(t, &p.partial)
+    }
+}
 impl<T1: Parse, T2: Parse, Src: ?Sized> LexFrom<Src> for (T1, T2)
 where
     T1: LexFrom<Src>,
@@ -330,6 +409,108 @@ where
     }
 }
 
+/// Returns the first marker registered more than once in a marker table, if any.
+///
+/// A marker table maps the keywords found in a contact plan to the kinds of a `choices!` enum.
+/// Lookups stop at the first matching entry, so a repeated marker silently shadows the entries
+/// registered after it. Use this function to reject such tables.
+pub fn find_duplicate_marker<K>(markers: &[(&'static str, K)]) -> Option<&'static str> {
+    markers
+        .iter()
+        .enumerate()
+        .find(|(i, (marker, _))| markers[..*i].iter().any(|(other, _)| other == marker))
+        .map(|(_, (marker, _))| *marker)
+}
+
+/// Looks a marker up in a marker table, the first matching entry wins.
+pub fn lookup_marker<K: Clone>(markers: &[(&'static str, K)], value: &str) -> Option<K> {
+    markers
+        .iter()
+        .find(|(marker, _)| *marker == value)
+        .map(|(_, kind)| kind.clone())
+}
+
+/// A marker table built at runtime, where each marker appears only once.
+///
+/// Unlike a constant table checked with `find_duplicate_marker`, a marker cannot be registered
+/// twice by mistake: `register` refuses an existing marker, and `replace` overwrites it
+/// explicitly.
+#[derive(Debug, Clone)]
+pub struct MarkerTable<K> {
+    markers: Vec<(&'static str, K)>,
+}
+
+impl<K: Clone> Default for MarkerTable<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone> MarkerTable<K> {
+    /// Creates an empty marker table.
+    pub fn new() -> Self {
+        Self {
+            markers: Vec::new(),
+        }
+    }
+
+    /// Creates a marker table registering each entry of `markers` in turn, e.g. to extend
+    /// `STANDARD_MARKERS`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(MarkerTable<K>)` - The table holding `markers`.
+    /// * `Err(ASABRError)` - If a marker appears twice in `markers`.
+    pub fn from_markers(markers: &[(&'static str, K)]) -> Result<Self, ASABRError> {
+        let mut table = Self::new();
+        for (marker, kind) in markers {
+            table.register(marker, kind.clone())?;
+        }
+        Ok(table)
+    }
+
+    /// Registers `kind` under `marker`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If `marker` was not registered yet.
+    /// * `Err(ASABRError)` - If `marker` is already registered, the table is left unchanged.
+    pub fn register(&mut self, marker: &'static str, kind: K) -> Result<(), ASABRError> {
+        if self.lookup(marker).is_some() {
+            return Err(ASABRError::ContactPlanError(
+                "The marker is already registered",
+            ));
+        }
+        self.markers.push((marker, kind));
+        Ok(())
+    }
+
+    /// Registers `kind` under `marker`, overwriting the previous registration if any.
+    ///
+    /// # Returns
+    ///
+    /// The kind previously registered under `marker`, if any.
+    pub fn replace(&mut self, marker: &'static str, kind: K) -> Option<K> {
+        match self.markers.iter_mut().find(|(other, _)| *other == marker) {
+            Some((_, previous)) => Some(mem::replace(previous, kind)),
+            None => {
+                self.markers.push((marker, kind));
+                None
+            }
+        }
+    }
+
+    /// Returns the kind registered under `value`, if any.
+    pub fn lookup(&self, value: &str) -> Option<K> {
+        lookup_marker(&self.markers, value)
+    }
+
+    /// Returns the registered markers, in registration order.
+    pub fn markers(&self) -> &[(&'static str, K)] {
+        &self.markers
+    }
+}
+
 // ***
 // # Macros
 // ***
@@ -400,7 +581,9 @@ macro_rules! parse_single_tok {
 
 parse_single_tok!(u16, AnyNumber);
 parse_single_tok!(i8, AnyNumber);
-parse_single_tok!(f64, AnyNumber);
+parse_single_tok!(f64, AnyFloat);
+#[cfg(feature = "small_floats")]
+parse_single_tok!(f32, AnyFloat);
 parse_single_tok!(Delimiter);
 
 /// choices!(modname,ResultName,[List]) where List is a comma-separated list of (Name, Type)
@@ -466,6 +649,12 @@ macro_rules! choices {
                         _ => Err(INVALID_STATE),
                     }
                 }
+                fn complete(p: &Self::Parser) -> bool {
+                    match p {
+                        Parser::None => false,
+                        $(Parser::$names(p) => <$types as Parse>::complete(p),)*
+                    }
+                }
             }
             impl<T: ?Sized> LexFrom<T> for $name
             where
@@ -507,6 +696,10 @@ macro_rules! parse_transparent {
             fn feed(tok: Self::Token, parser: &mut Self::Parser) -> core::result::Result<bool, &'static str> {
                 <$TF as $crate::parsing::Parse>::feed(tok, parser)
             }
+
+            fn complete(p: &Self::Parser) -> bool {
+                <$TF as $crate::parsing::Parse>::complete(p)
+            }
         }
 
         impl<$($($Template)* ,)? T: ?Sized> $crate::parsing::LexFrom<T> for $TD
@@ -538,6 +731,9 @@ macro_rules! tupples_joy {
                 <( ($( $T, )*), $Tf) as Parse>::feed(tok,parser)
 
             }
+            fn complete(p: &Self::Parser) -> bool {
+                <( ($( $T, )*), $Tf) as Parse>::complete(p)
+            }
         }
         impl<$($T:Parse, )* $Tf:Parse,T:?Sized> LexFrom<T> for ($($T,)* $Tf)
         where
@@ -561,6 +757,25 @@ impl<T1: Parse, T2: Parse> Default for Partial<T1, T2> {
         Self::None(Default::default())
     }
 }
+impl<T: Parse, U: Parse> core::fmt::Debug for TrailingParser<T, U>
+where
+    Partial<T, U>: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TrailingParser")
+            .field("partial", &self.partial)
+            .field("trailing", &self.trailing)
+            .finish()
+    }
+}
+impl<T: Parse, U: Parse> Default for TrailingParser<T, U> {
+    fn default() -> Self {
+        Self {
+            partial: Default::default(),
+            trailing: false,
+        }
+    }
+}
 impl<T: Parse> Default for VecBuilder<T> {
     fn default() -> Self {
         VecBuilder {
@@ -17,7 +17,7 @@ use crate::{
     types::{Date, NodeID},
 };
 
-use super::{PathFindingOutput, Pathfinding, try_make_hop};
+use super::{HopSettings, PathFindingOutput, Pathfinding, try_make_hop};
 
 macro_rules! define_contact_graph {
     ($name:ident, $is_tree_output:tt, $with_exclusions:tt) => {
@@ -131,6 +131,7 @@ macro_rules! define_contact_graph {
                 tree.by_destination[source as usize] = Some(source_route.clone());
                 priority_queue.push(Reverse(DistanceWrapper::new(Rc::clone(&source_route))));
 
+                let settings = HopSettings::of(&graph);
                 while let Some(Reverse(DistanceWrapper(from_route, _))) = priority_queue.pop() {
                     if from_route.borrow().is_disabled {
                         continue;
@@ -169,6 +170,7 @@ macro_rules! define_contact_graph {
                                 receiver.vertex_id,
                                 &receiver.contacts_to_receiver,
                                 &graph.real_nodes,
+                                settings,
                             ) {
                                 let mut push = false;
                                 if let Some(hop) = &route_proposition.via {
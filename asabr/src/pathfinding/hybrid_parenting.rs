@@ -134,7 +134,7 @@ impl<NM: NodeManager, CM: ContactManager> HybridParentingWorkArea<NM, CM> {
     }
 }
 
-use super::{PathFindingOutput, Pathfinding, try_make_hop};
+use super::{HopSettings, PathFindingOutput, Pathfinding, try_make_hop};
 
 /// Attempts to insert a new route proposal into the pathfinding output tree.
 ///
@@ -311,6 +311,7 @@ macro_rules! define_mpt {
                 tree.by_destination[source as usize].push(source_route.clone());
                 priority_queue.push(Reverse(DistanceWrapper::new(Rc::clone(&source_route))));
 
+                let settings = HopSettings::of(&graph);
                 while let Some(Reverse(DistanceWrapper(from_route, _))) = priority_queue.pop() {
                     if from_route.borrow().is_disabled {
                         continue;
@@ -342,6 +343,7 @@ macro_rules! define_mpt {
                                 receiver.vertex_id,
                                 &receiver.contacts_to_receiver,
                                 &graph.real_nodes,
+                                settings,
                             )
                             // This transforms a prop in the stack to a prop in the heap
                             && let Some(new_route) =
@@ -8,6 +8,11 @@ use crate::{
 /// Compares the original transmission volume of two `Contact`s and determines if the first
 /// contact (`a`) had less volume than the second contact (`b`) after initialization.
 ///
+/// Among the contacts of a route, the one with the least volume is the first to be depleted and
+/// limits the route. `FirstDepleted` suppresses it to search for the next route, so that the
+/// alternative routes stored by CGR avoid the scarcest contacts and preserve their capacity. On
+/// equal volumes, the contact closest to the destination is suppressed.
+///
 /// # Parameters
 ///
 /// * `a` - A reference to the first `Contact` to compare.
@@ -25,3 +30,60 @@ fn had_less_volume_than<NM: NodeManager, CM: ContactManager>(
 }
 
 create_new_alternative_path_variant!(FirstDepleted, had_less_volume_than);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::errors::ASABRError;
+    use crate::multigraph::Multigraph;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingPath;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::test_support::GraphBuilder;
+    use alloc::rc::Rc;
+    use alloc::vec;
+    use core::cell::RefCell;
+
+    type Pf =
+        FirstDepleted<NoManagement, EVLManager, NodeParentingPath<NoManagement, EVLManager, SABR>>;
+
+    #[test]
+    fn smaller_volume_contact_is_suppressed() -> Result<(), ASABRError> {
+        // the first route takes the 0 -> 1 contact of volume 100, then the 1 -> 2 contact of
+        // volume 1000, a second 0 -> 1 contact of volume 198 opens at 1.0
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 2, 0.0, 100.0)
+            .rate(10.0)
+            .contact(0, 1, 1.0, 100.0)
+            .rate(2.0)
+            .plan();
+        let mut pathfinding = Pf::new(Rc::new(RefCell::new(Multigraph::new(plan)?)));
+        let bundle = make_bundle(2, 0, 1.0, 1000.0);
+
+        let first = pathfinding.get_next(0.0, 0, &bundle, &[])?;
+        let route = first.by_destination[2].clone().unwrap();
+        assert_eq!(
+            route.borrow().path()?,
+            vec![(0, 1, 0.0), (1, 2, 1.0)],
+            "TEST FAILED: The first route should take the contact opening at 0.0."
+        );
+
+        let second = pathfinding.get_next(0.0, 0, &bundle, &[])?;
+        let route = second.by_destination[2]
+            .clone()
+            .expect("TEST FAILED: The 1 -> 2 contact should not be suppressed.");
+        assert_eq!(
+            route.borrow().path()?,
+            vec![(0, 1, 1.0), (1, 2, 1.5)],
+            "TEST FAILED: The second route should avoid the smaller 0 -> 1 contact."
+        );
+        Ok(())
+    }
+}
@@ -203,6 +203,24 @@ macro_rules! create_new_alternative_path_variant {
             fn get_multigraph(&self) -> alloc::rc::Rc<core::cell::RefCell<$crate::multigraph::Multigraph<NM, CM>>> {
                 return self.pathfinding.get_multigraph();
             }
+
+            /// Get the statistics of the last `get_next` call of the underlying pathfinding.
+            ///
+            /// # Returns
+            ///
+            /// * The statistics of the last search.
+            fn last_stats(&self) -> $crate::pathfinding::PathfindingStats {
+                return self.pathfinding.last_stats();
+            }
+
+            /// Resets the suppression flag of the contacts suppressed for every destination, the
+            /// contacts to suppress in the next searches are kept.
+            fn clear_suppressions(&mut self) -> Result<(), $crate::errors::ASABRError> {
+                for contact in self.suppression_map.iter().flatten() {
+                    contact.try_borrow_mut()?.suppressed = false;
+                }
+                self.pathfinding.clear_suppressions()
+            }
         }
     };
 }
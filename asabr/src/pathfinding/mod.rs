@@ -11,7 +11,9 @@ use crate::node::{Node, SharedNode};
 use crate::node_manager::NodeManager;
 use crate::route_stage::ViaHop;
 use crate::route_stage::{RouteStage, SharedRouteStage};
-use crate::types::{Date, NodeID};
+use crate::routing::RcKey;
+use crate::routing::diagnostics::SharedTxDiagnostics;
+use crate::types::{Date, HopCount, NodeID};
 use crate::vertex::VertexID;
 
 #[cfg(feature = "contact_work_area")]
@@ -21,7 +23,7 @@ pub mod hybrid_parenting;
 pub mod limiting_contact;
 pub mod node_parenting;
 #[cfg(test)]
-mod test_helpers;
+pub(crate) mod test_helpers;
 
 /// Data structure that holds the results of a pathfinding operation.
 ///
@@ -94,6 +96,33 @@ impl<NM: NodeManager, CM: ContactManager> PathFindingOutput<NM, CM> {
     }
 }
 
+/// The amount of work done by a pathfinding operation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PathfindingStats {
+    /// The number of nodes popped from the priority queue and explored.
+    pub nodes_expanded: usize,
+    /// The number of contacts offered to the hop evaluation, after lazy pruning.
+    pub contacts_evaluated: usize,
+    /// The number of route stages pushed to the priority queue, the source included.
+    pub queue_pushes: usize,
+    /// Whether the output was taken from a cache, no search being run.
+    pub from_cache: bool,
+    /// The number of route stages allocated, the ones recycled from a previous search excluded.
+    pub stages_allocated: usize,
+}
+
+impl core::ops::AddAssign for PathfindingStats {
+    /// Sums the counters, e.g. over the searches of a routing operation. The output is from
+    /// cache only if both are.
+    fn add_assign(&mut self, other: Self) {
+        self.nodes_expanded += other.nodes_expanded;
+        self.contacts_evaluated += other.contacts_evaluated;
+        self.queue_pushes += other.queue_pushes;
+        self.from_cache &= other.from_cache;
+        self.stages_allocated += other.stages_allocated;
+    }
+}
+
 /// The `Pathfinding` trait provides the interface for implementing a pathfinding algorithm.
 /// It requires methods for creating a new instance and determining the next hop in a route.
 ///
@@ -140,6 +169,192 @@ pub trait Pathfinding<NM: NodeManager, CM: ContactManager> {
     ///
     /// * A shared pointer to the multigraph.
     fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>>;
+
+    /// Get the statistics of the last `get_next` call.
+    ///
+    /// # Returns
+    ///
+    /// * The statistics of the last search, zeroed if the implementation does not track them.
+    fn last_stats(&self) -> PathfindingStats {
+        PathfindingStats::default()
+    }
+
+    /// Resets the suppression flag of the contacts suppressed by the previous `get_next` calls.
+    ///
+    /// Only the flags are reset: the contacts an algorithm plans to suppress in its next searches
+    /// are kept. The default implementation is for algorithms suppressing no contacts.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the flags were reset.
+    /// * `Err(ASABRError)` - If a contact cannot be borrowed.
+    fn clear_suppressions(&mut self) -> Result<(), ASABRError> {
+        Ok(())
+    }
+}
+
+/// The multigraph-wide settings applied by `try_make_hop`.
+#[derive(Clone, Copy, Default)]
+struct HopSettings<'a> {
+    /// If set, a contact is only usable if the transmission ends by this date.
+    horizon: Option<Date>,
+    /// If set, no hop is made from a route stage reached after this number of hops.
+    max_hops: Option<HopCount>,
+    /// If set, no hop is made to a vertex already on the route.
+    loop_free: bool,
+    /// If set, no hop is made if it arrives after the bundle expiration.
+    prune_expired: bool,
+    /// If set, the dry run rejections are recorded there.
+    diagnostics: Option<&'a SharedTxDiagnostics>,
+}
+
+impl<'a> HopSettings<'a> {
+    /// Reads the settings of a multigraph.
+    fn of<NM: NodeManager, CM: ContactManager>(multigraph: &'a Multigraph<NM, CM>) -> Self {
+        Self {
+            horizon: multigraph.horizon,
+            max_hops: multigraph.max_hops,
+            loop_free: multigraph.loop_free,
+            prune_expired: multigraph.prune_expired,
+            diagnostics: multigraph.diagnostics.as_ref(),
+        }
+    }
+}
+
+/// Recycles the route stages of a search for the next one (see `Multigraph::recycle_stages`).
+///
+/// The stages of the previous search are only recycled if none of them is referenced from outside
+/// the search, i.e. if its output and every route built from it were dropped. Otherwise they are
+/// left to their holders, and the next search allocates new ones.
+pub(crate) struct StagePool<NM: NodeManager, CM: ContactManager> {
+    /// If unset, the stages are neither tracked nor recycled.
+    enabled: bool,
+    /// The stages handed out by the current search.
+    in_use: Vec<SharedRouteStage<NM, CM>>,
+    /// The reset stages, ready to be handed out again.
+    free: Vec<SharedRouteStage<NM, CM>>,
+    /// The stages referenced by the stages in use, once per reference. Kept across the searches
+    /// to reuse its allocation.
+    internal: Vec<RcKey>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> StagePool<NM, CM> {
+    /// Creates an empty pool.
+    pub(crate) fn new() -> Self {
+        Self {
+            enabled: false,
+            in_use: Vec::new(),
+            free: Vec::new(),
+            internal: Vec::new(),
+        }
+    }
+
+    /// Prepares the pool for a new search, recycling the stages of the previous one if possible.
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - Whether the stages of the new search should be recycled afterwards.
+    pub(crate) fn begin(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.in_use.clear();
+            self.free.clear();
+            return;
+        }
+
+        // the references a stage of the search holds on another one (parent or next stage)
+        self.internal.clear();
+        for stage in &self.in_use {
+            let Ok(stage) = stage.try_borrow() else {
+                self.in_use.clear();
+                return;
+            };
+            let parent = stage.via.as_ref().map(|via| &via.parent_route);
+            for shared in parent
+                .into_iter()
+                .chain(stage.next_for_destination.values())
+            {
+                self.internal.push(RcKey::of(shared));
+            }
+        }
+        self.internal.sort_unstable();
+        let internal = &self.internal;
+        let unshared = self.in_use.iter().all(|stage| {
+            let key = RcKey::of(stage);
+            let count = internal.partition_point(|other| *other <= key)
+                - internal.partition_point(|other| *other < key);
+            Rc::strong_count(stage) == 1 + count
+        });
+
+        let stages = core::mem::take(&mut self.in_use);
+        if unshared {
+            for stage in stages {
+                stage.borrow_mut().reset();
+                self.free.push(stage);
+            }
+        }
+    }
+
+    /// Shares a stage, reusing the allocation of a recycled one if available.
+    ///
+    /// # Parameters
+    ///
+    /// * `stage` - The stage to share.
+    /// * `stats` - The statistics of the search, counting the allocations.
+    ///
+    /// # Returns
+    ///
+    /// * The shared stage.
+    pub(crate) fn wrap(
+        &mut self,
+        stage: RouteStage<NM, CM>,
+        stats: &mut PathfindingStats,
+    ) -> SharedRouteStage<NM, CM> {
+        let shared = match self.free.pop() {
+            Some(shared) => {
+                *shared.borrow_mut() = stage;
+                shared
+            }
+            None => {
+                stats.stages_allocated += 1;
+                Rc::new(RefCell::new(stage))
+            }
+        };
+        if self.enabled {
+            self.in_use.push(shared.clone());
+        }
+        shared
+    }
+}
+
+/// Checks whether a route goes through a vertex, from its source to `stage` included.
+///
+/// # Parameters
+///
+/// * `stage` - The last stage of the route.
+/// * `vertex` - The vertex to look for.
+///
+/// # Returns
+///
+/// `true` if a stage of the route reaches `vertex`.
+fn route_visits<NM: NodeManager, CM: ContactManager>(
+    stage: &SharedRouteStage<NM, CM>,
+    vertex: VertexID,
+) -> bool {
+    let mut curr = stage.clone();
+    loop {
+        let parent = {
+            let stage = curr.borrow();
+            if stage.to_node == vertex {
+                return true;
+            }
+            let Some(via) = &stage.via else {
+                return false;
+            };
+            via.parent_route.clone()
+        };
+        curr = parent;
+    }
 }
 
 /// Attempts to make a hop (i.e., a transmission between nodes) for the given route stage and bundle,
@@ -152,6 +367,7 @@ pub trait Pathfinding<NM: NodeManager, CM: ContactManager> {
 /// * `bundle` - A reference to the `Bundle` that is being routed.
 /// * `contacts` - A vector of reference-counted, mutable `Contact`s representing available transmission opportunities.
 /// * `nodes` - A reference to the vector of reference-counted, mutable `Node`s of the Multigraph.
+/// * `settings` - The multigraph-wide settings, see `HopSettings`.
 ///
 /// # Returns
 ///
@@ -163,6 +379,7 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
     receiver_id: VertexID,
     contacts: &[Rc<RefCell<Contact<NM, CM>>>],
     nodes: &[Rc<RefCell<Node<NM>>>],
+    settings: HopSettings,
 ) -> Option<RouteStage<NM, CM>> {
     let mut final_data_opt: Option<(
         ContactManagerTxData,
@@ -178,6 +395,14 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
     let bundle_to_consider = _bundle;
 
     let sndr_route_borrowed = sndr_route.borrow();
+    if let Some(max_hops) = settings.max_hops
+        && sndr_route_borrowed.hop_count >= max_hops
+    {
+        return None;
+    }
+    if settings.loop_free && route_visits(sndr_route, receiver_id) {
+        return None;
+    }
 
     for (idx, contact) in contacts.iter().enumerate().skip(first_contact_index) {
         let contact_borrowed = contact.borrow();
@@ -189,6 +414,10 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
             continue;
         }
 
+        if contact_borrowed.excluded {
+            continue;
+        }
+
         if let Some((final_data, _, _, _)) = final_data_opt
             && contact_borrowed.info.start > final_data.rx_end
         {
@@ -196,19 +425,40 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
         };
 
         #[cfg(feature = "node_proc")]
-        let sending_time = tx_node
-            .borrow()
-            .manager
-            .dry_run_process(sndr_route_borrowed.at_time, &mut bundle_to_consider);
+        let sending_time = {
+            let tx_manager = &tx_node.borrow().manager;
+            if sndr_route_borrowed.hop_count == 0 && !tx_manager.processes_at_source() {
+                sndr_route_borrowed.at_time
+            } else {
+                tx_manager.dry_run_process(sndr_route_borrowed.at_time, &mut bundle_to_consider)
+            }
+        };
         #[cfg(not(feature = "node_proc"))]
         let sending_time = sndr_route_borrowed.at_time;
 
-        if let Some(hop) = contact_borrowed.manager.dry_run_tx(
-            &contact_borrowed.info,
-            sending_time,
-            #[allow(clippy::needless_borrow)] //depend on feature
-            &bundle_to_consider,
-        ) {
+        #[allow(clippy::needless_borrow)] //depend on feature
+        let dry_run = match settings.diagnostics {
+            None => contact_borrowed.manager.dry_run_tx(
+                &contact_borrowed.info,
+                sending_time,
+                &bundle_to_consider,
+            ),
+            Some(diagnostics) => contact_borrowed
+                .manager
+                .dry_run_tx_detailed(&contact_borrowed.info, sending_time, &bundle_to_consider)
+                .map_err(|reason| diagnostics.borrow_mut().record(receiver_id, reason))
+                .ok(),
+        };
+        if let Some(hop) = dry_run {
+            if let Some(horizon) = settings.horizon
+                && hop.tx_end > horizon
+            {
+                continue;
+            }
+            if settings.prune_expired && hop.rx_end > bundle_to_consider.expiration {
+                continue;
+            }
+
             let tx_node = &nodes[contact_borrowed.info.tx_node_id as usize];
             let rx_node = &nodes[contact_borrowed.info.rx_node_id as usize];
 
@@ -243,6 +493,7 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
     }
 
     if let Some((final_data, tx_node, rx_node, index)) = final_data_opt {
+        let priority = bundle_to_consider.priority;
         let selected_contact = &contacts[index];
         let mut route_proposition: RouteStage<NM, CM> = RouteStage::new(
             final_data.rx_end,
@@ -257,9 +508,17 @@ fn try_make_hop<NM: NodeManager, CM: ContactManager>(
             bundle_to_consider,
         );
 
+        route_proposition.tx_start = final_data.tx_start;
         route_proposition.hop_count = sndr_route_borrowed.hop_count + 1;
         route_proposition.cumulative_delay =
             sndr_route_borrowed.cumulative_delay + final_data.rx_end - final_data.tx_end;
+        let selected_manager = &selected_contact.borrow().manager;
+        route_proposition.confidence =
+            sndr_route_borrowed.confidence * selected_manager.confidence();
+        route_proposition.first_hop_volume = match sndr_route_borrowed.hop_count {
+            0 => selected_manager.remaining_volume(priority),
+            _ => sndr_route_borrowed.first_hop_volume,
+        };
         route_proposition.expiration = Date::min(
             final_data.expiration - sndr_route_borrowed.cumulative_delay,
             sndr_route_borrowed.expiration,
@@ -299,6 +558,7 @@ mod tests {
             receiver_id,
             contacts,
             nodes,
+            HopSettings::default(),
         )
     }
 
@@ -517,6 +777,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn loop_free_refuses_the_hop_back() {
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+        let nodes = vec![
+            make_node_rc(0, "A", NoManagement {}),
+            make_node_rc(1, "B", NoManagement {}),
+        ];
+        let forth = vec![make_contact_rc::<NoManagement>(0, 1, 0.0, 100.0, 1.0, 0.0)];
+        let back = vec![make_contact_rc::<NoManagement>(1, 0, 0.0, 100.0, 1.0, 0.0)];
+        let at_b = Rc::new(RefCell::new(
+            run_hop(0, &source, &bundle, 1, &forth, &nodes).unwrap(),
+        ));
+
+        let settings = HopSettings {
+            loop_free: true,
+            ..Default::default()
+        };
+        assert!(
+            try_make_hop(0, &at_b, &bundle, 0, &back, &nodes, settings).is_none(),
+            "TEST FAILED: Node A is already on the route."
+        );
+        assert!(
+            run_hop(0, &at_b, &bundle, 0, &back, &nodes).is_some(),
+            "TEST FAILED: The hop back is allowed by default."
+        );
+    }
+
+    #[test]
+    fn loop_free_trees_visit_each_node_once() -> Result<(), ASABRError> {
+        use crate::distance::hop::Hop;
+        use crate::multigraph::Multigraph;
+        use crate::pathfinding::hybrid_parenting::HybridParentingTreeExcl;
+        use crate::test_support::GraphBuilder;
+
+        // back-and-forth contacts between 0 and 1, 2 and 3 are reached through them
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 0, 0.0, 100.0)
+            .contact(0, 2, 50.0, 100.0)
+            .contact(1, 3, 0.0, 100.0)
+            .contact(3, 1, 0.0, 100.0)
+            .contact(1, 2, 80.0, 100.0)
+            .plan();
+        let multigraph = Rc::new(RefCell::new(Multigraph::new(plan)?));
+        multigraph.borrow_mut().loop_free = true;
+        let mut pathfinding =
+            HybridParentingTreeExcl::<NoManagement, EVLManager, Hop>::new(multigraph.clone());
+        let tree = pathfinding.get_next(0.0, 0, &make_bundle(2, 0, 1.0, 2000.0), &[])?;
+
+        for dest in 1..4 {
+            let mut visited = Vec::new();
+            let mut curr = tree.by_destination[dest]
+                .clone()
+                .expect("TEST FAILED: Every node should be reached.");
+            loop {
+                let parent = {
+                    let stage = curr.borrow();
+                    assert!(
+                        !visited.contains(&stage.to_node),
+                        "TEST FAILED: The route to {dest} visits node {} twice.",
+                        stage.to_node
+                    );
+                    visited.push(stage.to_node);
+                    match &stage.via {
+                        Some(via) => via.parent_route.clone(),
+                        None => break,
+                    }
+                };
+                curr = parent;
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_best_contact_selected_1_hop() {
         let bundle = make_bundle(1, 1, 100.0, 2000.0);
@@ -1,6 +1,6 @@
 extern crate alloc;
-use alloc::{collections::BinaryHeap, rc::Rc};
-use core::{cell::RefCell, cmp::Ordering, cmp::Reverse, marker::PhantomData};
+use alloc::{collections::BinaryHeap, rc::Rc, vec::Vec};
+use core::{cell::RefCell, cmp::Ordering, cmp::Reverse};
 
 use crate::{
     bundle::Bundle,
@@ -9,11 +9,13 @@ use crate::{
     errors::ASABRError,
     multigraph::Multigraph,
     node_manager::NodeManager,
-    route_stage::RouteStage,
+    route_stage::{RouteStage, SharedRouteStage},
     types::{Date, NodeID},
 };
 
-use super::{PathFindingOutput, Pathfinding, try_make_hop};
+use super::{
+    HopSettings, PathFindingOutput, Pathfinding, PathfindingStats, StagePool, try_make_hop,
+};
 
 macro_rules! define_node_graph {
     ($name:ident, $is_tree_output:tt, $with_exclusions:tt) => {
@@ -29,8 +31,12 @@ macro_rules! define_node_graph {
         pub struct $name<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> {
             /// The node multigraph for contact access.
             graph: Rc<RefCell<Multigraph<NM, CM>>>,
-            #[doc(hidden)]
-            _phantom_distance: PhantomData<D>,
+            /// The statistics of the last search.
+            stats: PathfindingStats,
+            /// The route stages recycled between the searches.
+            pool: StagePool<NM, CM>,
+            /// The priority queue of the search, kept to reuse its buffer.
+            queue: BinaryHeap<Reverse<DistanceWrapper<NM, CM, D>>>,
         }
 
         impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> Pathfinding<NM, CM>
@@ -48,7 +54,9 @@ macro_rules! define_node_graph {
             fn new(multigraph: Rc<RefCell<Multigraph<NM, CM>>>) -> Self {
                 Self {
                     graph: multigraph,
-                    _phantom_distance: PhantomData,
+                    stats: PathfindingStats::default(),
+                    pool: StagePool::new(),
+                    queue: BinaryHeap::new(),
                 }
             }
 
@@ -76,38 +84,33 @@ macro_rules! define_node_graph {
                 excluded_nodes_sorted: &[NodeID],
             ) -> Result<PathFindingOutput<NM, CM>, ASABRError> {
                 let mut graph = self.graph.try_borrow_mut()?;
+                self.stats = PathfindingStats::default();
+                self.pool.begin(graph.recycle_stages);
 
                 if $with_exclusions {
                     graph.prepare_for_exclusions_sorted(excluded_nodes_sorted)?;
                 }
-                let source_route: Rc<RefCell<RouteStage<NM, CM>>> =
-                    Rc::new(RefCell::new(RouteStage::new(
+                let source_route: Rc<RefCell<RouteStage<NM, CM>>> = self.pool.wrap(
+                    RouteStage::new(
                         current_time,
                         source,
                         None,
                         #[cfg(feature = "node_proc")]
                         bundle.clone(),
-                    )));
-                let mut tree: PathFindingOutput<NM, CM> = PathFindingOutput::new(
-                    bundle,
-                    source_route.clone(),
-                    excluded_nodes_sorted,
-                    graph.senders.len(),
+                    ),
+                    &mut self.stats,
                 );
+                // moved into the output, so it cannot be kept between the searches
+                let mut by_destination: Vec<Option<SharedRouteStage<NM, CM>>> =
+                    alloc::vec![None; graph.senders.len()];
+                by_destination[source as usize] = Some(source_route.clone());
 
-                let mut priority_queue: BinaryHeap<Reverse<DistanceWrapper<NM, CM, D>>> =
-                    BinaryHeap::new();
-
-                for node_id in 0..graph.get_vertex_count() {
-                    if node_id == source as usize {
-                        tree.by_destination[node_id as usize] = Some(source_route.clone());
-                    } else {
-                        tree.by_destination[node_id as usize] = None;
-                    }
-                }
-
+                let priority_queue = &mut self.queue;
+                priority_queue.clear();
                 priority_queue.push(Reverse(DistanceWrapper::new(Rc::clone(&source_route))));
+                self.stats.queue_pushes += 1;
 
+                let settings = HopSettings::of(&graph);
                 while let Some(Reverse(DistanceWrapper(from_route, _))) = priority_queue.pop() {
                     if from_route.borrow().is_disabled {
                         continue;
@@ -118,6 +121,7 @@ macro_rules! define_node_graph {
                             break;
                         }
                     }
+                    self.stats.nodes_expanded += 1;
 
                     let sender = &graph.senders[tx_node_id as usize];
 
@@ -130,17 +134,22 @@ macro_rules! define_node_graph {
 
                         if let Some(first_contact_index) =
                             receiver.lazy_prune_and_get_first_idx(current_time)
-                            && let Some(route_proposition) = try_make_hop(
-                                first_contact_index,
-                                &from_route,
-                                bundle,
-                                receiver.vertex_id,
-                                &receiver.contacts_to_receiver,
-                                &graph.real_nodes,
-                            )
+                            && let Some(route_proposition) = {
+                                self.stats.contacts_evaluated +=
+                                    receiver.contacts_to_receiver.len() - first_contact_index;
+                                try_make_hop(
+                                    first_contact_index,
+                                    &from_route,
+                                    bundle,
+                                    receiver.vertex_id,
+                                    &receiver.contacts_to_receiver,
+                                    &graph.real_nodes,
+                                    settings,
+                                )
+                            }
                         {
                             let idx = receiver.vertex_id as usize;
-                            let push = match tree.by_destination[idx].as_ref() {
+                            let push = match by_destination[idx].as_ref() {
                                 Some(known_route_ref) => {
                                     let mut known_route = known_route_ref.try_borrow_mut()?;
                                     if D::cmp(&route_proposition, &known_route) == Ordering::Less {
@@ -154,15 +163,23 @@ macro_rules! define_node_graph {
                             };
 
                             if push {
-                                let route_ref = Rc::new(RefCell::new(route_proposition));
-                                tree.by_destination[idx] = Some(route_ref.clone());
+                                let route_ref = self.pool.wrap(route_proposition, &mut self.stats);
+                                by_destination[idx] = Some(route_ref.clone());
                                 priority_queue.push(Reverse(DistanceWrapper::new(route_ref)));
+                                self.stats.queue_pushes += 1;
                             }
                         }
                     }
                 }
-
-                Ok(tree)
+                // the stages left in the queue must not outlive the search
+                priority_queue.clear();
+
+                Ok(PathFindingOutput {
+                    bundle: bundle.clone(),
+                    source: source_route,
+                    excluded_nodes_sorted: excluded_nodes_sorted.to_vec(),
+                    by_destination,
+                })
             }
 
             /// Get a shared pointer to the multigraph.
@@ -173,6 +190,15 @@ macro_rules! define_node_graph {
             fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>> {
                 return self.graph.clone();
             }
+
+            /// Get the statistics of the last `get_next` call.
+            ///
+            /// # Returns
+            ///
+            /// * The nodes expanded, contacts evaluated and queue pushes of the last search.
+            fn last_stats(&self) -> PathfindingStats {
+                self.stats
+            }
         }
     };
 }
@@ -185,11 +211,14 @@ define_node_graph!(NodeParentingPathExcl, false, true);
 mod tests {
     use super::*;
     use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_manager::segmentation::seg::SegmentationManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
     use crate::distance::hop::Hop;
     use crate::distance::sabr::SABR;
     use crate::node_manager::none::NoManagement;
     use crate::pathfinding::ASABRError;
     use crate::pathfinding::test_helpers::*;
+    use alloc::{vec, vec::Vec};
 
     #[test]
     fn test_a_to_c_tree() -> Result<(), ASABRError> {
@@ -447,4 +476,178 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn stats_count_the_expanded_nodes() -> Result<(), ASABRError> {
+        // A reaches C directly, through B and through D, D being the fastest relay
+        let mg = five_contact_graph_test()?;
+        let bundle = make_bundle(2, 1, 1.0, 2000.0);
+
+        let mut tree = NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg.clone());
+        tree.get_next(0.0, 0, &bundle, &[][..])?;
+        // A, B, D and C are expanded, the C stages reached directly and through B are
+        // disabled once improved
+        assert_eq!(
+            tree.last_stats(),
+            PathfindingStats {
+                nodes_expanded: 4,
+                contacts_evaluated: 5,
+                queue_pushes: 6,
+                from_cache: false,
+                stages_allocated: 6,
+            },
+            "TEST FAILED: Unexpected tree search statistics."
+        );
+
+        let mut path = NodeParentingPathExcl::<NoManagement, EVLManager, SABR>::new(mg.clone());
+        path.get_next(0.0, 0, &bundle, &[][..])?;
+        // the search stops once C is popped
+        assert_eq!(
+            path.last_stats().nodes_expanded,
+            3,
+            "TEST FAILED: The path search should not expand the destination."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn recycled_stages_are_not_reallocated() -> Result<(), ASABRError> {
+        let mg = five_contact_graph_test()?;
+        let bundle = make_bundle(2, 1, 1.0, 2000.0);
+        let arrivals = |output: &PathFindingOutput<NoManagement, EVLManager>| {
+            output
+                .by_destination
+                .iter()
+                .map(|stage| stage.as_ref().map(|stage| stage.borrow().at_time))
+                .collect::<Vec<_>>()
+        };
+
+        let mut plain = NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg.clone());
+        let expected = arrivals(&plain.get_next(0.0, 0, &bundle, &[][..])?);
+
+        mg.borrow_mut().recycle_stages = true;
+        let mut tree = NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg.clone());
+        let mut allocated = Vec::new();
+        for _ in 0..2 {
+            let output = tree.get_next(0.0, 0, &bundle, &[][..])?;
+            allocated.push(tree.last_stats().stages_allocated);
+            assert_eq!(
+                arrivals(&output),
+                expected,
+                "TEST FAILED: Recycling should not change the tree."
+            );
+        }
+        assert_eq!(
+            allocated,
+            vec![6, 0],
+            "TEST FAILED: The second search should reuse the stages of the first."
+        );
+
+        // the stages of a tree still held cannot be recycled
+        let _held = tree.get_next(0.0, 0, &bundle, &[][..])?;
+        tree.get_next(0.0, 0, &bundle, &[][..])?;
+        assert_eq!(
+            tree.last_stats().stages_allocated,
+            6,
+            "TEST FAILED: The stages of the held tree should not be reused."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reused_buffers_give_the_same_trees() -> Result<(), ASABRError> {
+        let mg = five_contact_graph_test()?;
+        let paths = |output: &PathFindingOutput<NoManagement, EVLManager>| {
+            output
+                .by_destination
+                .iter()
+                .map(|stage| {
+                    stage.as_ref().map(|stage| {
+                        let stage = stage.borrow();
+                        (stage.to_node, stage.at_time, stage.hop_count)
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        // the path searches stop early, leaving stages in the queue
+        let searches = [
+            (0, 2, &[][..]),
+            (0, 1, &[3][..]),
+            (1, 2, &[][..]),
+            (0, 2, &[1][..]),
+        ];
+
+        let mut tree = NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg.clone());
+        let mut path = NodeParentingPathExcl::<NoManagement, EVLManager, SABR>::new(mg.clone());
+        for _ in 0..2 {
+            for (source, dest, excluded) in searches {
+                let bundle = make_bundle(dest, 1, 1.0, 2000.0);
+                let mut fresh_tree =
+                    NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg.clone());
+                let mut fresh_path =
+                    NodeParentingPathExcl::<NoManagement, EVLManager, SABR>::new(mg.clone());
+                assert_eq!(
+                    paths(&tree.get_next(0.0, source, &bundle, excluded)?),
+                    paths(&fresh_tree.get_next(0.0, source, &bundle, excluded)?),
+                    "TEST FAILED: Reusing the buffers should not change the tree."
+                );
+                assert_eq!(
+                    paths(&path.get_next(0.0, source, &bundle, excluded)?),
+                    paths(&fresh_path.get_next(0.0, source, &bundle, excluded)?),
+                    "TEST FAILED: Reusing the buffers should not change the path."
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn prune_expired_stops_the_expansion() -> Result<(), ASABRError> {
+        // a chain of 10 s hops, the segmentation managers do not check the bundle expiration
+        let plan = parse_from_str::<NoManagement, SegmentationManager>(
+            "node 0 a
+            node 1 b
+            node 2 c
+            node 3 d
+            node 4 e
+            contact 0 1 0 100 rate [0 100 10] delay [0 100 10]
+            contact 1 2 0 100 rate [0 100 10] delay [0 100 10]
+            contact 2 3 0 100 rate [0 100 10] delay [0 100 10]
+            contact 3 4 0 100 rate [0 100 10] delay [0 100 10]",
+        )?;
+        let mg = Rc::new(RefCell::new(Multigraph::new(plan)?));
+        let bundle = make_bundle(1, 1, 1.0, 15.0);
+
+        let mut tree =
+            NodeParentingTreeExcl::<NoManagement, SegmentationManager, SABR>::new(mg.clone());
+        let full = tree.get_next(0.0, 0, &bundle, &[][..])?;
+        let full_stats = tree.last_stats();
+
+        mg.borrow_mut().prune_expired = true;
+        let pruned = tree.get_next(0.0, 0, &bundle, &[][..])?;
+        assert_eq!(
+            (full_stats.nodes_expanded, tree.last_stats().nodes_expanded),
+            (5, 2),
+            "TEST FAILED: Only the source and node 1 arrive before the expiration."
+        );
+        assert_eq!(
+            pruned.by_destination[1]
+                .as_ref()
+                .map(|route| route.borrow().at_time),
+            full.by_destination[1]
+                .as_ref()
+                .map(|route| route.borrow().at_time),
+            "TEST FAILED: The route to node 1 should not change."
+        );
+        assert!(
+            full.by_destination[4].is_some()
+                && pruned.by_destination[2..].iter().all(Option::is_none),
+            "TEST FAILED: The nodes reached after the expiration should be pruned."
+        );
+
+        Ok(())
+    }
 }
@@ -14,7 +14,7 @@ use crate::pathfinding::ASABRError;
 use crate::pathfinding::NodeID;
 use crate::pathfinding::PathFindingOutput;
 use crate::route_stage::{RouteStage, SharedRouteStage};
-use crate::types::Date;
+use crate::types::{DataRate, Date, Duration, Volume};
 use crate::vertex::Vertex;
 use crate::vnode::VirtualNodeMap;
 use alloc::collections::BTreeMap as HashMap;
@@ -23,47 +23,50 @@ use alloc::vec;
 use alloc::vec::Vec;
 use core::cell::RefCell;
 
+#[cfg(any(feature = "node_proc", feature = "node_tx", feature = "node_rx"))]
 #[derive(Debug)]
 pub(crate) struct MockNodeManager {
+    #[cfg(feature = "node_tx")]
     pub tx_ok: bool,
+    #[cfg(feature = "node_rx")]
     pub rx_ok: bool,
+    #[cfg(feature = "node_proc")]
     pub process_output: Date,
 }
 
+#[cfg(any(feature = "node_proc", feature = "node_tx", feature = "node_rx"))]
 impl MockNodeManager {
     pub(crate) fn accepting() -> Self {
         Self {
+            #[cfg(feature = "node_tx")]
             tx_ok: true,
+            #[cfg(feature = "node_rx")]
             rx_ok: true,
+            #[cfg(feature = "node_proc")]
             process_output: 0.0,
         }
     }
     #[cfg(feature = "node_tx")]
     pub(crate) fn refusing_tx() -> Self {
-        Self {
-            tx_ok: false,
-            rx_ok: true,
-            process_output: 0.0,
-        }
+        let mut manager = Self::accepting();
+        manager.tx_ok = false;
+        manager
     }
     #[cfg(feature = "node_rx")]
     pub(crate) fn refusing_rx() -> Self {
-        Self {
-            tx_ok: true,
-            rx_ok: false,
-            process_output: 0.0,
-        }
+        let mut manager = Self::accepting();
+        manager.rx_ok = false;
+        manager
     }
     #[cfg(feature = "node_proc")]
     pub(crate) fn processing(process_output: Date) -> Self {
-        Self {
-            tx_ok: true,
-            rx_ok: true,
-            process_output,
-        }
+        let mut manager = Self::accepting();
+        manager.process_output = process_output;
+        manager
     }
 }
 
+#[cfg(any(feature = "node_proc", feature = "node_tx", feature = "node_rx"))]
 impl NodeManager for MockNodeManager {
     #[cfg(feature = "node_proc")]
     fn dry_run_process(&self, _at_time: Date, _bundle: &mut Bundle) -> Date {
@@ -116,10 +119,10 @@ pub(crate) fn make_node_rc<NM: NodeManager>(id: u16, name: &str, nm: NM) -> Rc<R
 pub(crate) fn make_contact<NM: NodeManager>(
     tx: u16,
     rx: u16,
-    start: f64,
-    end: f64,
-    rate: f64,
-    delay: f64,
+    start: Date,
+    end: Date,
+    rate: DataRate,
+    delay: Duration,
 ) -> Contact<NM, EVLManager> {
     Contact::try_new(
         ContactInfo::new(tx, rx, start, end),
@@ -131,10 +134,10 @@ pub(crate) fn make_contact<NM: NodeManager>(
 pub(crate) fn make_contact_rc<NM: NodeManager>(
     tx: u16,
     rx: u16,
-    start: f64,
-    end: f64,
-    rate: f64,
-    delay: f64,
+    start: Date,
+    end: Date,
+    rate: DataRate,
+    delay: Duration,
 ) -> Rc<RefCell<Contact<NM, EVLManager>>> {
     Rc::new(RefCell::new(make_contact(tx, rx, start, end, rate, delay)))
 }
@@ -153,7 +156,7 @@ pub(crate) fn make_source<NM: NodeManager>(
     )))
 }
 
-pub(crate) fn make_bundle(dest: NodeID, priority: i8, size: f64, expiration: f64) -> Bundle {
+pub(crate) fn make_bundle(dest: NodeID, priority: i8, size: Volume, expiration: Date) -> Bundle {
     Bundle {
         source: 0,
         destinations: vec![dest],
@@ -166,7 +169,7 @@ pub(crate) fn make_bundle(dest: NodeID, priority: i8, size: f64, expiration: f64
 pub(crate) fn assert_time_hop(
     res: &PathFindingOutput<NoManagement, EVLManager>,
     dest: usize,
-    expected_time: f64,
+    expected_time: Date,
     expected_hop: u16,
     distance: &str,
 ) {
@@ -266,7 +269,7 @@ pub(crate) struct HopContext<NM: NodeManager> {
     pub nodes: Vec<Rc<RefCell<Node<NM>>>>,
 }
 
-pub(crate) fn make_hop_context(size: f64) -> HopContext<NoManagement> {
+pub(crate) fn make_hop_context(size: Volume) -> HopContext<NoManagement> {
     let bundle = make_bundle(1, 1, size, 2000.0);
     let source = make_source::<NoManagement>(0.0, 0, &bundle);
     let tx = make_node_rc(0, "A", NoManagement {});
@@ -1,18 +1,20 @@
 extern crate alloc;
 
-use alloc::{collections::BTreeMap as HashMap, rc::Rc, vec::Vec};
+use alloc::{collections::BTreeMap as HashMap, format, rc::Rc, string::String, vec::Vec};
 
 use crate::bundle::Bundle;
-use crate::contact::Contact;
-use crate::contact_manager::ContactManager;
-use crate::errors::ASABRError;
+use crate::contact::{Contact, SharedContact};
+use crate::contact_manager::{ContactManager, ContactManagerTxData};
+use crate::errors::{ASABRError, RoutingError};
 use crate::node::Node;
 use crate::node_manager::NodeManager;
-use crate::types::{Date, Duration, HopCount, NodeID};
+use crate::types::{Date, Duration, HopCount, NodeID, Priority, Volume};
 use crate::vertex::VertexID;
 use cfg_if::cfg_if;
 use core::cell::RefCell;
+use core::cmp::Ordering;
 use core::fmt::Display;
+use core::hash::{Hash, Hasher};
 
 /// Represents an intermediate hop in a route, typically used for multi-hop communication or routing.
 ///
@@ -30,6 +32,32 @@ pub struct ViaHop<NM: NodeManager, CM: ContactManager> {
     pub rx_node: Rc<RefCell<Node<NM>>>,
 }
 
+/// The output of a contact manager dry run, with the inputs it was computed for.
+#[derive(Debug)]
+pub(crate) struct DryRunOutput {
+    /// The sending time of the dry run.
+    at_time: Date,
+    /// The size of the bundle considered.
+    size: Volume,
+    /// The priority of the bundle considered.
+    priority: Priority,
+    /// The generation of the contact when the dry run was performed.
+    generation: u64,
+    /// The transmission computed by the contact manager.
+    data: ContactManagerTxData,
+}
+
+impl DryRunOutput {
+    /// Checks whether the output still holds for a transmission of `bundle` at `at_time`, i.e.
+    /// the same transmission on a contact that was not changed since the dry run.
+    fn holds_for(&self, at_time: Date, bundle: &Bundle, generation: u64) -> bool {
+        self.at_time == at_time
+            && self.size == bundle.size
+            && self.priority == bundle.priority
+            && self.generation == generation
+    }
+}
+
 impl<NM: NodeManager, CM: ContactManager> Clone for ViaHop<NM, CM> {
     fn clone(&self) -> Self {
         ViaHop {
@@ -55,6 +83,8 @@ pub struct RouteStage<NM: NodeManager, CM: ContactManager> {
     pub to_node: VertexID,
     /// The time at which this route stage is considered to be valid or relevant.
     pub at_time: Date,
+    /// The start time of the transmission reaching this stage, `at_time` for the source stage.
+    pub tx_start: Date,
     /// A flag that indicates if this stage of the route is disabled.
     pub is_disabled: bool,
     /// An optional `ViaHop` that stores information about the intermediate hops that lead to this stage.
@@ -63,6 +93,12 @@ pub struct RouteStage<NM: NodeManager, CM: ContactManager> {
     pub hop_count: HopCount,
     /// The cumulative delay incurred on the path to this stage, often used for routing optimizations.
     pub cumulative_delay: Duration,
+    /// The probability that the bundle reaches this stage, i.e. the product of the confidences of
+    /// the contacts leading to it (see `ContactManager::confidence`), 1 for the source stage.
+    pub confidence: f64,
+    /// The volume left for the priority of the bundle on the first hop contact of the route when
+    /// the route was found (see `ContactManager::remaining_volume`), unbounded for the source stage.
+    pub first_hop_volume: Volume,
     /// The time at which this route stage expires, indicating when it is no longer valid.
     pub expiration: Date,
     /// A flag indicating whether the route has been fully initialized and is ready for routing.
@@ -71,6 +107,10 @@ pub struct RouteStage<NM: NodeManager, CM: ContactManager> {
     #[derivative(Debug = "ignore")]
     // avoid cyclic print with debug formatting
     pub next_for_destination: HashMap<NodeID, SharedRouteStage<NM, CM>>,
+    /// The output of the last successful dry run, with the inputs it was computed for.
+    /// It is consumed by `schedule` to avoid running the contact manager a second time, as long
+    /// as the transmission and the contact's generation are unchanged.
+    pub(crate) tx_data: Option<DryRunOutput>,
 
     #[cfg(feature = "node_proc")]
     /// The stage of the bundle that arrives at to_node
@@ -79,6 +119,67 @@ pub struct RouteStage<NM: NodeManager, CM: ContactManager> {
 
 pub type SharedRouteStage<NM, CM> = Rc<RefCell<RouteStage<NM, CM>>>;
 
+/// The identity of a route stage by value: the node it reaches, when, and in how many hops.
+///
+/// Unlike `RcKey`, two distinct stages reaching the same node at the same time in the same number
+/// of hops share a key, e.g. the identical sub-routes of a tree reached through different parents.
+/// The times are compared by their bit pattern to be hashed and totally ordered.
+#[derive(Debug, Clone, Copy)]
+pub struct StageKey {
+    to_node: NodeID,
+    at_time: Date,
+    hop_count: HopCount,
+}
+
+impl StageKey {
+    /// Returns the key of `stage`, see `RouteStage::key`.
+    pub fn of<NM: NodeManager, CM: ContactManager>(stage: &RouteStage<NM, CM>) -> Self {
+        Self::from(stage.key())
+    }
+}
+
+impl From<(NodeID, Date, HopCount)> for StageKey {
+    fn from((to_node, at_time, hop_count): (NodeID, Date, HopCount)) -> Self {
+        Self {
+            to_node,
+            // adding 0.0 turns -0.0 into 0.0, the two must share a key
+            at_time: at_time + 0.0,
+            hop_count,
+        }
+    }
+}
+
+impl PartialEq for StageKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for StageKey {}
+
+impl PartialOrd for StageKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for StageKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_node
+            .cmp(&other.to_node)
+            .then_with(|| self.at_time.total_cmp(&other.at_time))
+            .then_with(|| self.hop_count.cmp(&other.hop_count))
+    }
+}
+
+impl Hash for StageKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_node.hash(state);
+        self.at_time.to_bits().hash(state);
+        self.hop_count.hash(state);
+    }
+}
+
 impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
     /// Creates a new `RouteStage` with the specified parameters.
     ///
@@ -100,18 +201,32 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
         Self {
             to_node,
             at_time,
+            tx_start: at_time,
             is_disabled: false,
             via: via_hop,
             hop_count: 0,
             cumulative_delay: 0.0,
+            confidence: 1.0,
+            first_hop_volume: Volume::INFINITY,
             expiration: Date::MAX,
             route_initialized: false,
             next_for_destination: HashMap::new(),
+            tx_data: None,
             #[cfg(feature = "node_proc")]
             bundle,
         }
     }
 
+    /// Clears the links of the stage to the other stages and its scheduling data, e.g. before
+    /// recycling its allocation.
+    pub fn reset(&mut self) {
+        self.is_disabled = false;
+        self.via = None;
+        self.route_initialized = false;
+        self.next_for_destination.clear();
+        self.tx_data = None;
+    }
+
     pub fn clone_work_area(&self) -> RouteStage<NM, CM> {
         let mut route = Self::new(
             self.at_time,
@@ -120,10 +235,13 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
             #[cfg(feature = "node_proc")]
             self.bundle.clone(),
         );
+        route.tx_start = self.tx_start;
         route.is_disabled = self.is_disabled;
         route.via = self.via.clone();
         route.hop_count = self.hop_count;
         route.cumulative_delay = self.cumulative_delay;
+        route.confidence = self.confidence;
+        route.first_hop_volume = self.first_hop_volume;
         route.expiration = self.expiration;
 
         route
@@ -160,8 +278,11 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
     /// This function schedules the transmission by interacting with the contact manager and the nodes
     /// in the `node_list`. If node management is enabled (features node_rx, node_tx, and node_proc),
     /// the nodes will be queried for their transmission and reception schedules. The function will
-    /// return Ok(()) if the scheduling is successful and the bundle is scheduled, or an error if
-    /// any failure occurs.
+    /// return the booked transmission if the scheduling is successful, or an error if any failure
+    /// occurs.
+    ///
+    /// If the last dry run of this stage was performed for the same sending time, its output is
+    /// handed to the contact manager instead of being computed again.
     ///
     /// # Arguments
     ///
@@ -170,9 +291,13 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If the scheduling was successful.
+    /// * `Ok(ContactManagerTxData)` - The transmission booked on the contact, if the scheduling was successful.
     /// * `Err(ASABRError)` - If the scheduling failed due to any reason, such as a faulty dry run or an issue with the contact manager.
-    pub fn schedule(&mut self, at_time: Date, bundle: &Bundle) -> Result<(), ASABRError> {
+    pub fn schedule(
+        &mut self,
+        at_time: Date,
+        bundle: &Bundle,
+    ) -> Result<ContactManagerTxData, ASABRError> {
         let Some(via) = &self.via else {
             return Err(ASABRError::ScheduleError("No via hop for"));
         };
@@ -197,49 +322,67 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
 
         cfg_if! {
             if #[cfg(feature = "node_proc")] {
-                let sending_time = tx_node
-                    .manager
-                    .schedule_process(at_time, &mut bundle_to_consider);
+                // the sender is the source of the route
+                let sending_time = if self.hop_count == 1 && !tx_node.manager.processes_at_source() {
+                    at_time
+                } else {
+                    tx_node.manager.schedule_process(at_time, &mut bundle_to_consider)
+                };
             } else {
                 let sending_time = at_time;
             }
         }
+        let generation = contact_borrowed.generation;
         #[allow(clippy::needless_borrow)]
-        let Some(res) =
-            contact_borrowed
+        let cached = self
+            .tx_data
+            .take()
+            .filter(|output| output.holds_for(sending_time, &bundle_to_consider, generation));
+        #[allow(clippy::needless_borrow)]
+        let scheduled = match cached {
+            Some(output) => contact_borrowed.manager.schedule_tx_from_dry_run(
+                &info,
+                sending_time,
+                &bundle_to_consider,
+                output.data,
+            ),
+            None => contact_borrowed
                 .manager
-                .schedule_tx(&info, sending_time, &bundle_to_consider)
-        else {
-            return Err(ASABRError::ScheduleError("Faulty dry run"));
+                .schedule_tx(&info, sending_time, &bundle_to_consider),
         };
+        let Some(res) = scheduled else {
+            return Err(RoutingError::DryRunScheduleMismatch.into());
+        };
+        contact_borrowed.generation += 1;
 
         #[cfg(feature = "node_tx")]
         if !tx_node
             .manager
             .schedule_tx(sending_time, res.tx_start, res.tx_end, &bundle_to_consider)
         {
-            return Err(ASABRError::ScheduleError("Faulty dry run"));
+            return Err(RoutingError::DryRunScheduleMismatch.into());
         }
 
         let arrival_time = res.rx_end;
 
         if arrival_time > bundle_to_consider.expiration {
-            return Err(ASABRError::ScheduleError("Faulty dry run"));
+            return Err(RoutingError::DryRunScheduleMismatch.into());
         }
         #[cfg(feature = "node_rx")]
         if !rx_node
             .manager
             .schedule_rx(res.rx_start, res.rx_end, &bundle_to_consider)
         {
-            return Err(ASABRError::ScheduleError("Faulty dry run"));
+            return Err(RoutingError::DryRunScheduleMismatch.into());
         }
 
         self.at_time = arrival_time;
+        self.tx_start = res.tx_start;
         #[cfg(feature = "node_proc")]
         {
             self.bundle = bundle_to_consider;
         }
-        Ok(())
+        Ok(res)
     }
 
     /// Performs a dry run to simulate the transmission of a `bundle` through a network without actually
@@ -259,7 +402,7 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
     /// # Returns
     ///
     /// * `Ok(true)` - If the dry run was successful and the bundle can be transmitted according to the simulation.
-    /// * `Ok(false)` - If the dry run fails, such as due to an excluded node, invalid timing, or any other condition preventing transmission.
+    /// * `Ok(false)` - If the dry run fails, such as due to an excluded node or contact, invalid timing, or any other condition preventing transmission.
     /// * `Err(ASABRError)` - If a borrowing error occurred.
     pub fn dry_run(
         &mut self,
@@ -267,6 +410,7 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
         bundle: &Bundle,
         with_exclusions: bool,
     ) -> Result<bool, ASABRError> {
+        self.tx_data = None;
         let Some(via) = &self.via else {
             return Ok(false);
         };
@@ -274,6 +418,10 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
         let contact_borrowed = via.contact.try_borrow_mut()?;
         let info = contact_borrowed.info;
 
+        if contact_borrowed.excluded {
+            return Ok(false);
+        }
+
         if with_exclusions {
             {
                 let node = via.rx_node.borrow();
@@ -297,9 +445,12 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
         let rx_node = via.rx_node.try_borrow_mut()?;
         cfg_if! {
             if #[cfg(feature = "node_proc")] {
-                let sending_time = tx_node
-                    .manager
-                    .dry_run_process(at_time, &mut bundle_to_consider);
+                // the sender is the source of the route
+                let sending_time = if self.hop_count == 1 && !tx_node.manager.processes_at_source() {
+                    at_time
+                } else {
+                    tx_node.manager.dry_run_process(at_time, &mut bundle_to_consider)
+                };
             } else {
                 let sending_time = at_time;
             }
@@ -336,6 +487,14 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
         }
 
         self.at_time = arrival_time;
+        self.tx_start = res.tx_start;
+        self.tx_data = Some(DryRunOutput {
+            at_time: sending_time,
+            size: bundle_to_consider.size,
+            priority: bundle_to_consider.priority,
+            generation: contact_borrowed.generation,
+            data: res,
+        });
         #[cfg(feature = "node_proc")]
         {
             self.bundle = bundle_to_consider;
@@ -343,6 +502,98 @@ impl<NM: NodeManager, CM: ContactManager> RouteStage<NM, CM> {
         Ok(true)
     }
 
+    /// Returns the hops from the source to this stage.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(NodeID, NodeID, Date)>)` - The transmitting node, receiving node and
+    ///   transmission start of each hop, in route order.
+    /// * `Err(ASABRError)` - If a stage or a contact of the route cannot be borrowed.
+    pub fn path(&self) -> Result<Vec<(NodeID, NodeID, Date)>, ASABRError> {
+        let mut path = Vec::with_capacity(self.hop_count as usize);
+        let mut next = self.hop(&mut path)?;
+        while let Some(stage) = next {
+            next = stage.try_borrow()?.hop(&mut path)?;
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Appends the hop reaching this stage to `path`, returning the parent stage.
+    fn hop(
+        &self,
+        path: &mut Vec<(NodeID, NodeID, Date)>,
+    ) -> Result<Option<SharedRouteStage<NM, CM>>, ASABRError> {
+        let Some(via) = &self.via else {
+            return Ok(None);
+        };
+        let info = via.contact.try_borrow()?.info;
+        path.push((info.tx_node_id, info.rx_node_id, self.tx_start));
+        Ok(Some(via.parent_route.clone()))
+    }
+
+    /// Returns the contact of the route limiting `bundle` the most, i.e. the one with the least
+    /// volume remaining for the priority of `bundle`, e.g. for capacity analysis.
+    ///
+    /// The route is walked from this stage to the source like `path`, the ties go to the hop
+    /// closest to the source.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(contact))` - The bottleneck contact, `None` for a source stage.
+    /// * `Err(ASABRError)` - If a stage or a contact of the route cannot be borrowed.
+    pub fn bottleneck(&self, bundle: &Bundle) -> Result<Option<SharedContact<NM, CM>>, ASABRError> {
+        let Some(via) = &self.via else {
+            return Ok(None);
+        };
+        let remaining = |contact: &SharedContact<NM, CM>| -> Result<Volume, ASABRError> {
+            Ok(contact
+                .try_borrow()?
+                .manager
+                .remaining_volume(bundle.priority))
+        };
+        let mut bottleneck = (remaining(&via.contact)?, via.contact.clone());
+        let mut next = via.parent_route.clone();
+        loop {
+            let stage = next.try_borrow()?;
+            let Some(via) = &stage.via else {
+                break;
+            };
+            let volume = remaining(&via.contact)?;
+            if volume <= bottleneck.0 {
+                bottleneck = (volume, via.contact.clone());
+            }
+            let parent = via.parent_route.clone();
+            drop(stage);
+            next = parent;
+        }
+        Ok(Some(bottleneck.1))
+    }
+
+    /// Describes the stage on one line, its arrival and the contact reaching it, e.g. for logging.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The description, as `node 2 at t=5 (2 hop(s)) via contact 1 -> 2 [0, 100]`.
+    pub fn summary(&self) -> String {
+        match &self.via {
+            Some(via) => format!(
+                "node {} at t={} ({} hop(s)) via {}",
+                self.to_node,
+                self.at_time,
+                self.hop_count,
+                via.contact.borrow().summary()
+            ),
+            None => format!("node {} at t={} (source)", self.to_node, self.at_time),
+        }
+    }
+
+    /// Returns the node reached by the stage, the arrival time and the hop count, as last
+    /// computed (see `StageKey`).
+    pub fn key(&self) -> (NodeID, Date, HopCount) {
+        (self.to_node, self.at_time, self.hop_count)
+    }
+
     pub fn get_via_contact(&self) -> Option<Rc<RefCell<Contact<NM, CM>>>> {
         if let Some(via) = &self.via {
             return Some(via.contact.clone());
@@ -370,7 +621,7 @@ impl<NM: NodeManager, CM: ContactManager> Display for RouteStage<NM, CM> {
         // }
         //
         fn back<CM: ContactManager, NM: NodeManager>(
-            backtrace: &mut Vec<(u16, f64, u16)>,
+            backtrace: &mut Vec<(u16, Date, u16)>,
             route: &RouteStage<NM, CM>,
         ) {
             backtrace.push((route.to_node, route.at_time, route.hop_count));
@@ -389,3 +640,282 @@ impl<NM: NodeManager, CM: ContactManager> Display for RouteStage<NM, CM> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact::ContactInfo;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::{make_bundle, make_contact_rc, make_node_rc};
+    use core::cell::Cell;
+
+    /// An EVL manager counting its dry runs, including the ones performed by `schedule_tx`.
+    struct CountingManager {
+        inner: EVLManager,
+        dry_runs: Rc<Cell<usize>>,
+    }
+
+    impl ContactManager for CountingManager {
+        fn dry_run_tx(
+            &self,
+            contact_data: &ContactInfo,
+            at_time: Date,
+            bundle: &Bundle,
+        ) -> Option<ContactManagerTxData> {
+            self.dry_runs.set(self.dry_runs.get() + 1);
+            self.inner.dry_run_tx(contact_data, at_time, bundle)
+        }
+
+        fn schedule_tx(
+            &mut self,
+            contact_data: &ContactInfo,
+            at_time: Date,
+            bundle: &Bundle,
+        ) -> Option<ContactManagerTxData> {
+            let data = self.dry_run_tx(contact_data, at_time, bundle)?;
+            self.inner
+                .schedule_tx_from_dry_run(contact_data, at_time, bundle, data)
+        }
+
+        fn schedule_tx_from_dry_run(
+            &mut self,
+            contact_data: &ContactInfo,
+            at_time: Date,
+            bundle: &Bundle,
+            data: ContactManagerTxData,
+        ) -> Option<ContactManagerTxData> {
+            self.inner
+                .schedule_tx_from_dry_run(contact_data, at_time, bundle, data)
+        }
+
+        fn remaining_volume(&self, priority: Priority) -> Volume {
+            self.inner.remaining_volume(priority)
+        }
+
+        fn get_original_volume(&self) -> Volume {
+            self.inner.get_original_volume()
+        }
+
+        fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+            self.inner.try_init(contact_data)
+        }
+    }
+
+    fn make_stage(
+        dry_runs: Rc<Cell<usize>>,
+        _bundle: &Bundle,
+    ) -> RouteStage<NoManagement, CountingManager> {
+        let contact = Rc::new(RefCell::new(
+            Contact::try_new(
+                ContactInfo::new(0, 1, 0.0, 100.0),
+                CountingManager {
+                    inner: EVLManager::new(10.0, 1.0),
+                    dry_runs,
+                },
+            )
+            .unwrap(),
+        ));
+        let source = Rc::new(RefCell::new(RouteStage::new(
+            0.0,
+            0,
+            None,
+            #[cfg(feature = "node_proc")]
+            _bundle.clone(),
+        )));
+        let via = ViaHop {
+            contact,
+            parent_route: source,
+            tx_node: make_node_rc(0, "A", NoManagement {}),
+            rx_node: make_node_rc(1, "B", NoManagement {}),
+        };
+        RouteStage::new(
+            0.0,
+            1,
+            Some(via),
+            #[cfg(feature = "node_proc")]
+            _bundle.clone(),
+        )
+    }
+
+    #[test]
+    fn schedule_reuses_the_dry_run_output() {
+        let dry_runs = Rc::new(Cell::new(0));
+        let bundle = make_bundle(1, 0, 10.0, 1000.0);
+        let mut stage = make_stage(dry_runs.clone(), &bundle);
+
+        assert!(stage.dry_run(0.0, &bundle, false).unwrap());
+        assert_eq!(dry_runs.get(), 1, "TEST FAILED: Expected a single dry run.");
+        stage.schedule(0.0, &bundle).unwrap();
+        assert_eq!(
+            dry_runs.get(),
+            1,
+            "TEST FAILED: The schedule should reuse the output of the dry run."
+        );
+        assert_eq!(
+            stage.at_time, 2.0,
+            "TEST FAILED: Expected an arrival at 2.0."
+        );
+        assert_eq!(
+            stage
+                .get_via_contact()
+                .unwrap()
+                .borrow()
+                .manager
+                .inner
+                .remaining_volume(0),
+            990.0 as Volume,
+            "TEST FAILED: The schedule should book the bundle volume."
+        );
+    }
+
+    #[test]
+    fn schedule_at_another_time_runs_the_manager_again() {
+        let dry_runs = Rc::new(Cell::new(0));
+        let bundle = make_bundle(1, 0, 10.0, 1000.0);
+        let mut stage = make_stage(dry_runs.clone(), &bundle);
+
+        assert!(stage.dry_run(0.0, &bundle, false).unwrap());
+        stage.schedule(5.0, &bundle).unwrap();
+        assert_eq!(
+            dry_runs.get(),
+            2,
+            "TEST FAILED: A schedule at another time should not reuse the dry run."
+        );
+        assert_eq!(
+            stage.at_time, 7.0,
+            "TEST FAILED: Expected an arrival at 7.0."
+        );
+    }
+
+    #[test]
+    fn schedule_after_another_booking_runs_the_manager_again() {
+        let dry_runs = Rc::new(Cell::new(0));
+        let bundle = make_bundle(1, 0, 10.0, 1000.0);
+        let mut first = make_stage(dry_runs.clone(), &bundle);
+        let mut second = RouteStage::new(
+            0.0,
+            1,
+            first.via.clone(),
+            #[cfg(feature = "node_proc")]
+            bundle.clone(),
+        );
+
+        assert!(first.dry_run(0.0, &bundle, false).unwrap());
+        assert!(second.dry_run(0.0, &bundle, false).unwrap());
+        second.schedule(0.0, &bundle).unwrap();
+        assert_eq!(
+            dry_runs.get(),
+            2,
+            "TEST FAILED: The first schedule should reuse its dry run."
+        );
+        first.schedule(0.0, &bundle).unwrap();
+        assert_eq!(
+            dry_runs.get(),
+            3,
+            "TEST FAILED: A dry run made before another booking should not be reused."
+        );
+        assert_eq!(
+            first
+                .get_via_contact()
+                .unwrap()
+                .borrow()
+                .manager
+                .inner
+                .remaining_volume(0),
+            980.0 as Volume,
+            "TEST FAILED: Both bookings should be counted."
+        );
+    }
+
+    #[test]
+    fn schedule_of_another_bundle_runs_the_manager_again() {
+        let dry_runs = Rc::new(Cell::new(0));
+        let bundle = make_bundle(1, 0, 10.0, 1000.0);
+        let mut stage = make_stage(dry_runs.clone(), &bundle);
+
+        assert!(stage.dry_run(0.0, &bundle, false).unwrap());
+        stage
+            .schedule(0.0, &make_bundle(1, 0, 20.0, 1000.0))
+            .unwrap();
+        assert_eq!(
+            dry_runs.get(),
+            2,
+            "TEST FAILED: A dry run of another bundle size should not be reused."
+        );
+        assert_eq!(
+            stage.at_time, 3.0,
+            "TEST FAILED: Expected an arrival at 3.0 for the larger bundle."
+        );
+    }
+
+    #[test]
+    fn summary_shows_the_arrival_and_contact() {
+        let bundle = make_bundle(1, 0, 10.0, 1000.0);
+        let mut stage = make_stage(Rc::new(Cell::new(0)), &bundle);
+        stage.hop_count = 1;
+        stage.schedule(0.0, &bundle).unwrap();
+
+        assert_eq!(
+            stage.summary(),
+            "node 1 at t=2 (1 hop(s)) via contact 0 -> 1 [0, 100]",
+            "TEST FAILED: Unexpected stage summary."
+        );
+        assert_eq!(
+            stage.via.as_ref().unwrap().parent_route.borrow().summary(),
+            "node 0 at t=0 (source)",
+            "TEST FAILED: Unexpected source stage summary."
+        );
+    }
+
+    #[test]
+    fn bottleneck_is_the_tightest_contact() {
+        let bundle = make_bundle(2, 0, 10.0, 1000.0);
+        // 0 -> 1 carries 1000, 1 -> 2 only 100
+        let wide = make_contact_rc::<NoManagement>(0, 1, 0.0, 100.0, 10.0, 1.0);
+        let tight = make_contact_rc::<NoManagement>(1, 2, 0.0, 100.0, 1.0, 1.0);
+        let stage = |to_node, via| {
+            Rc::new(RefCell::new(RouteStage::new(
+                0.0,
+                to_node,
+                via,
+                #[cfg(feature = "node_proc")]
+                bundle.clone(),
+            )))
+        };
+        let source = stage(0, None);
+        let relay = stage(
+            1,
+            Some(ViaHop {
+                contact: wide.clone(),
+                parent_route: source.clone(),
+                tx_node: make_node_rc(0, "A", NoManagement {}),
+                rx_node: make_node_rc(1, "B", NoManagement {}),
+            }),
+        );
+        let dest = stage(
+            2,
+            Some(ViaHop {
+                contact: tight.clone(),
+                parent_route: relay.clone(),
+                tx_node: make_node_rc(1, "B", NoManagement {}),
+                rx_node: make_node_rc(2, "C", NoManagement {}),
+            }),
+        );
+
+        let bottleneck = dest.borrow().bottleneck(&bundle).unwrap();
+        assert!(
+            bottleneck.is_some_and(|contact| Rc::ptr_eq(&contact, &tight)),
+            "TEST FAILED: The second contact should be the bottleneck."
+        );
+        let bottleneck = relay.borrow().bottleneck(&bundle).unwrap();
+        assert!(
+            bottleneck.is_some_and(|contact| Rc::ptr_eq(&contact, &wide)),
+            "TEST FAILED: A one-hop route is limited by its only contact."
+        );
+        assert!(
+            source.borrow().bottleneck(&bundle).unwrap().is_none(),
+            "TEST FAILED: A source stage has no contact."
+        );
+    }
+}
@@ -9,11 +9,24 @@ use crate::{
     errors::ASABRError,
     node_manager::NodeManager,
     pathfinding::PathFindingOutput,
+    route_stage::{RouteStage, SharedRouteStage},
     routing::{dry_run_multicast, dry_run_unicast_tree},
     types::{Date, NodeID},
 };
 
-use super::TreeStorage;
+use super::{TreeStorage, path_uses_contact};
+
+/// The strategy used by a `TreeCache` to decide if a stored tree can serve a request
+/// with regard to the excluded nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExclusionMatching {
+    /// The exclusion list of the tree must be identical to the one of the request.
+    Exact,
+    /// The exclusion list of the request may be a superset of the one of the tree, provided
+    /// that none of the additional excluded nodes appear on the tree's routes to the bundle's
+    /// destinations.
+    Superset,
+}
 
 /// A cache for storing pathfinding output entries, enabling efficient retrieval and management.
 ///
@@ -29,6 +42,10 @@ pub struct TreeCache<NM: NodeManager, CM: ContactManager> {
     max_entries: usize,
     /// A deque of reference-counted mutable references to `PathfindingOutput` instances stored in the cache.
     trees: VecDeque<Rc<RefCell<PathFindingOutput<NM, CM>>>>,
+    /// The strategy used to match the exclusion lists of the stored trees.
+    exclusion_matching: ExclusionMatching,
+    /// The estimated memory, in bytes, the stored trees may use (no limit if `None`).
+    memory_budget: Option<usize>,
 
     // for compilation
     #[doc(hidden)]
@@ -53,10 +70,129 @@ impl<NM: NodeManager, CM: ContactManager> TreeCache<NM, CM> {
             check_priority,
             max_entries,
             trees: VecDeque::new(),
+            exclusion_matching: ExclusionMatching::Exact,
+            memory_budget: None,
             // for compilation
             _phantom_nm: PhantomData,
         }
     }
+
+    /// Sets the strategy used to match the exclusion list of a stored tree with the one of
+    /// a request (`ExclusionMatching::Exact` by default).
+    ///
+    /// # Parameters
+    ///
+    /// * `exclusion_matching` - The matching strategy to use.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `TreeCache<NM, CM>`.
+    pub fn with_exclusion_matching(mut self, exclusion_matching: ExclusionMatching) -> Self {
+        self.exclusion_matching = exclusion_matching;
+        self
+    }
+
+    /// Limits the estimated memory used by the stored trees, on top of `max_entries`.
+    ///
+    /// When a tree is stored, the oldest trees are evicted until the estimated footprint of
+    /// the stored trees fits in the budget (see `estimate_footprint`).
+    ///
+    /// # Parameters
+    ///
+    /// * `bytes` - The memory budget, in bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `TreeCache<NM, CM>`.
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// Estimates the memory used by a tree, in bytes.
+    ///
+    /// The estimate counts the tree itself, its per-destination table and its reached stages. It
+    /// ignores the heap data owned by the stages, but grows with the number of nodes and stages.
+    ///
+    /// # Parameters
+    ///
+    /// * `tree` - The tree to estimate.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The estimated footprint of the tree.
+    pub fn estimate_footprint(tree: &PathFindingOutput<NM, CM>) -> usize {
+        // the reference counts are allocated along with each stage
+        let stage_size = size_of::<RefCell<RouteStage<NM, CM>>>() + 2 * size_of::<usize>();
+        let reached = tree.by_destination.iter().flatten().count();
+        size_of::<PathFindingOutput<NM, CM>>()
+            + tree.by_destination.len() * size_of::<Option<SharedRouteStage<NM, CM>>>()
+            + tree.excluded_nodes_sorted.len() * size_of::<NodeID>()
+            + reached * stage_size
+    }
+
+    /// Iterates over the stored trees, from the oldest to the newest.
+    #[cfg(feature = "serde")]
+    pub(crate) fn trees(&self) -> impl Iterator<Item = &Rc<RefCell<PathFindingOutput<NM, CM>>>> {
+        self.trees.iter()
+    }
+
+    /// Checks if a stored tree can be used with the requested exclusions.
+    ///
+    /// # Parameters
+    ///
+    /// * `tree` - The stored tree.
+    /// * `bundle` - The bundle to route, its destinations are the only routes considered.
+    /// * `excluded_nodes_sorted` - The exclusion list of the request.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the tree is compatible with the requested exclusions.
+    fn exclusions_match(
+        &self,
+        tree: &PathFindingOutput<NM, CM>,
+        bundle: &Bundle,
+        excluded_nodes_sorted: &[NodeID],
+    ) -> bool {
+        if tree.excluded_nodes_sorted == excluded_nodes_sorted {
+            return true;
+        }
+        if self.exclusion_matching == ExclusionMatching::Exact {
+            return false;
+        }
+        if tree
+            .excluded_nodes_sorted
+            .iter()
+            .any(|node| !excluded_nodes_sorted.contains(node))
+        {
+            return false;
+        }
+
+        let is_extra = |node: NodeID| {
+            excluded_nodes_sorted.contains(&node) && !tree.excluded_nodes_sorted.contains(&node)
+        };
+
+        for dest in &bundle.destinations {
+            let Some(Some(route)) = tree.by_destination.get(*dest as usize) else {
+                continue;
+            };
+            let mut curr = route.clone();
+            loop {
+                let parent = {
+                    let stage = curr.borrow();
+                    let Some(via) = &stage.via else {
+                        break;
+                    };
+                    if is_extra(stage.to_node) || is_extra(via.rx_node.borrow().info.id) {
+                        return false;
+                    }
+                    via.parent_route.clone()
+                };
+                curr = parent;
+            }
+        }
+        true
+    }
 }
 
 impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for TreeCache<NM, CM> {
@@ -93,7 +229,7 @@ impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for TreeCache<NM,
             {
                 continue;
             }
-            if tree.borrow().excluded_nodes_sorted != excluded_nodes_sorted {
+            if !self.exclusions_match(&tree.borrow(), bundle, excluded_nodes_sorted) {
                 continue;
             }
             match multicast {
@@ -115,7 +251,8 @@ impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for TreeCache<NM,
 
     /// Stores a pathfinding output tree in the cache. Replaces a tree for a known exclusion list.
     ///
-    /// If the cache exceeds its maximum entry limit, the oldest entry is removed.
+    /// If the cache exceeds its maximum entry limit, the oldest entry is removed. If a memory
+    /// budget is set, the oldest entries are removed until the stored trees fit in it.
     ///
     /// # Parameters
     ///
@@ -138,5 +275,208 @@ impl<NM: NodeManager, CM: ContactManager> TreeStorage<NM, CM> for TreeCache<NM,
         if self.trees.len() > self.max_entries {
             self.trees.pop_front();
         }
+
+        if let Some(budget) = self.memory_budget {
+            let mut footprint: usize = self
+                .trees
+                .iter()
+                .map(|tree| Self::estimate_footprint(&tree.borrow()))
+                .sum();
+            while footprint > budget
+                && let Some(oldest) = self.trees.pop_front()
+            {
+                footprint -= Self::estimate_footprint(&oldest.borrow());
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.trees.clear();
+    }
+
+    /// Drops the stored trees in which a destination is reached through the contact.
+    fn invalidate_contact(&mut self, tx: NodeID, rx: NodeID, start: Date) {
+        self.trees.retain(|tree| {
+            !tree
+                .borrow()
+                .by_destination
+                .iter()
+                .flatten()
+                .any(|stage| path_uses_contact(stage, tx, rx, start))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::*;
+
+    // In the five contact graph, the best route from A(0) to C(2) goes through D(3), not B(1).
+    fn cache_with_unexcluded_tree(
+        exclusion_matching: ExclusionMatching,
+    ) -> Result<(TreeCache<NoManagement, EVLManager>, Bundle), ASABRError> {
+        let mg = five_contact_graph_test()?;
+        let mut pathfinding = NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg);
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let tree = pathfinding.get_next(0.0, 0, &bundle, &[])?;
+
+        let mut cache =
+            TreeCache::new(false, false, 10).with_exclusion_matching(exclusion_matching);
+        cache.store(&bundle, Rc::new(RefCell::new(tree)));
+        Ok((cache, bundle))
+    }
+
+    #[test]
+    fn size_check_reuses_trees_only_when_feasible_and_not_shadowed() -> Result<(), ASABRError> {
+        // every contact carries 200000
+        let mg = five_contact_graph_test()?;
+        let mut pathfinding = NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg);
+        let bundle = make_bundle(2, 0, 10.0, 2000.0);
+        let tree = pathfinding.get_next(0.0, 0, &bundle, &[])?;
+        let mut cache = TreeCache::new(true, false, 10);
+        cache.store(&bundle, Rc::new(RefCell::new(tree)));
+
+        for (size, reused, reason) in [
+            (10.0, true, "the same bundle should reuse the tree"),
+            (
+                5.0,
+                false,
+                "the tree may miss the routes of a smaller bundle",
+            ),
+            (
+                50.0,
+                true,
+                "the larger bundle still fits the routes of the tree",
+            ),
+        ] {
+            let (tree, _) = cache.select(&make_bundle(2, 0, size, 2000.0), 0.0, &[])?;
+            assert_eq!(
+                tree.is_some(),
+                reused,
+                "TEST FAILED: For size {size}, {reason}."
+            );
+        }
+        // with node_proc, the dry run considers the bundles attached to the stages of the tree
+        #[cfg(not(feature = "node_proc"))]
+        assert!(
+            cache
+                .select(&make_bundle(2, 0, 300000.0, 2000.0), 0.0, &[])?
+                .0
+                .is_none(),
+            "TEST FAILED: The routes of the tree cannot carry the bundle."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn superset_exclusions_reuse_tree_when_off_path() -> Result<(), ASABRError> {
+        let (cache, bundle) = cache_with_unexcluded_tree(ExclusionMatching::Superset)?;
+        let (tree, _) = cache.select(&bundle, 0.0, &[1])?;
+        assert!(
+            tree.is_some(),
+            "TEST FAILED: The tree should be reused, B is not on the route to C."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn superset_exclusions_reject_tree_when_on_path() -> Result<(), ASABRError> {
+        let (cache, bundle) = cache_with_unexcluded_tree(ExclusionMatching::Superset)?;
+        let (tree, _) = cache.select(&bundle, 0.0, &[3])?;
+        assert!(
+            tree.is_none(),
+            "TEST FAILED: The tree should be rejected, D is on the route to C."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn exact_exclusions_reject_different_list() -> Result<(), ASABRError> {
+        let (cache, bundle) = cache_with_unexcluded_tree(ExclusionMatching::Exact)?;
+        let (tree, _) = cache.select(&bundle, 0.0, &[1])?;
+        assert!(
+            tree.is_none(),
+            "TEST FAILED: The exact strategy should not reuse a tree with a different exclusion list."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn memory_budget_evicts_by_size() -> Result<(), ASABRError> {
+        let mg = five_contact_graph_test()?;
+        let mut pathfinding = NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg);
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let mut tree_for = |excluded: &[NodeID]| -> Result<_, ASABRError> {
+            Ok(Rc::new(RefCell::new(
+                pathfinding.get_next(0.0, 0, &bundle, excluded)?,
+            )))
+        };
+        // every node is reached, vs. only two nodes
+        let large = tree_for(&[])?;
+        let small_a = tree_for(&[1, 3])?;
+        let small_b = tree_for(&[1, 2])?;
+        let large_size = TreeCache::estimate_footprint(&large.borrow());
+        let small_size = TreeCache::estimate_footprint(&small_a.borrow());
+        assert!(
+            small_size < large_size,
+            "TEST FAILED: The estimate should grow with the number of reached nodes."
+        );
+
+        let mut cache =
+            TreeCache::new(false, false, 10).with_memory_budget(large_size + small_size);
+        cache.store(&bundle, large);
+        cache.store(&bundle, small_a);
+        assert_eq!(
+            cache.trees.len(),
+            2,
+            "TEST FAILED: Both trees fit in the budget."
+        );
+
+        cache.store(&bundle, small_b);
+        assert_eq!(
+            cache.trees.len(),
+            2,
+            "TEST FAILED: The large tree should be evicted to make room."
+        );
+        assert!(
+            cache
+                .trees
+                .iter()
+                .all(|tree| !tree.borrow().excluded_nodes_sorted.is_empty()),
+            "TEST FAILED: The oldest tree, the large one, should be the one evicted."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalidate_contact_drops_only_the_trees_using_it() -> Result<(), ASABRError> {
+        let mg = five_contact_graph_test()?;
+        let mut pathfinding = NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(mg);
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let mut cache = TreeCache::new(false, false, 10);
+        // B is reached through the A->B contact only without exclusions
+        for excluded in [&[][..], &[1]] {
+            let tree = pathfinding.get_next(0.0, 0, &bundle, excluded)?;
+            cache.store(&bundle, Rc::new(RefCell::new(tree)));
+        }
+
+        cache.invalidate_contact(0, 1, 0.0);
+        assert_eq!(
+            cache.trees.len(),
+            1,
+            "TEST FAILED: Only the tree using the A->B contact should be dropped."
+        );
+        assert_eq!(
+            cache.trees[0].borrow().excluded_nodes_sorted,
+            [1],
+            "TEST FAILED: The tree excluding B should be kept."
+        );
+        Ok(())
     }
 }
@@ -4,6 +4,9 @@ use alloc::{collections::BTreeMap as HashMap, rc::Rc, vec::Vec};
 use core::cell::RefCell;
 
 pub mod cache;
+pub mod multi_table;
+#[cfg(feature = "serde")]
+pub mod persistence;
 pub mod table;
 
 use crate::{
@@ -14,11 +17,37 @@ use crate::{
     node_manager::NodeManager,
     pathfinding::{PathFindingOutput, SharedPathFindingOutput},
     route_stage::SharedRouteStage,
+    routing::dry_run_unicast_path,
     types::{Date, NodeID, Priority, Volume},
 };
 
 type ReachableNodes = Option<Vec<NodeID>>;
 
+/// Checks if the path from the source to `stage` uses the contact from `tx` to `rx` starting
+/// at `start`.
+fn path_uses_contact<NM: NodeManager, CM: ContactManager>(
+    stage: &SharedRouteStage<NM, CM>,
+    tx: NodeID,
+    rx: NodeID,
+    start: Date,
+) -> bool {
+    let mut curr = stage.clone();
+    loop {
+        let parent = {
+            let stage = curr.borrow();
+            let Some(via) = &stage.via else {
+                return false;
+            };
+            let info = via.contact.borrow().info;
+            if info.tx_node_id == tx && info.rx_node_id == rx && info.start == start {
+                return true;
+            }
+            via.parent_route.clone()
+        };
+        curr = parent;
+    }
+}
+
 /// A trait for managing tree storage and retrieval.
 ///
 /// This trait defines methods for loading and storing pathfinding output
@@ -50,6 +79,25 @@ pub trait TreeStorage<NM: NodeManager, CM: ContactManager> {
     /// * `bundle` - A bundle copy for which the tree was created.
     /// * `tree` - A reference-counted mutable reference to the `PathfindingOutput` to store.
     fn store(&mut self, bundle: &Bundle, tree: Rc<RefCell<PathFindingOutput<NM, CM>>>);
+
+    /// Removes all the stored trees, e.g. after a change of the contact plan.
+    ///
+    /// The default implementation does nothing, for the storages keeping no trees between the
+    /// calls. A storage keeping trees must override it, `invalidate_contact` relies on it.
+    fn clear(&mut self) {}
+
+    /// Drops the stored trees using the contact from `tx` to `rx` starting at `start`, to
+    /// be called when that contact changes (e.g. after `Multigraph::remove_contact`).
+    ///
+    /// The default implementation clears the storage.
+    ///
+    /// # Parameters
+    /// * `tx` - The transmitting node of the contact.
+    /// * `rx` - The receiving node of the contact.
+    /// * `start` - The start time of the contact.
+    fn invalidate_contact(&mut self, _tx: NodeID, _rx: NodeID, _start: Date) {
+        self.clear();
+    }
 }
 
 #[derive(Debug)]
@@ -69,6 +117,55 @@ impl<NM: NodeManager, CM: ContactManager> Route<NM, CM> {
             destination_stage,
         })
     }
+
+    /// Checks that the route can still carry `bundle` from `at_time`, by running the dry run
+    /// again along the stored path, without the node exclusions.
+    ///
+    /// The resources of the contacts may change without the storage being notified, e.g. when
+    /// the queues of the managers are updated by external processes.
+    ///
+    /// # Parameters
+    /// * `bundle` - The bundle to carry.
+    /// * `at_time` - The time the bundle is ready at the source.
+    ///
+    /// # Returns
+    /// * `Ok(true)` if every hop of the path accepts the bundle, `Ok(false)` otherwise, or an
+    ///   error if a stage or a contact cannot be borrowed.
+    pub fn is_still_feasible(&self, bundle: &Bundle, at_time: Date) -> Result<bool, ASABRError> {
+        Ok(dry_run_unicast_path(bundle, at_time, self.source_stage.clone(), false)?.is_some())
+    }
+
+    /// Checks that the route can no longer carry any bundle from `at_time`: a contact of the
+    /// path has ended, or has no volume left even for the highest priority.
+    ///
+    /// Unlike `is_still_feasible`, the outcome does not depend on a bundle, a route too small
+    /// or too slow for a bundle may still suit the next ones.
+    ///
+    /// # Parameters
+    /// * `at_time` - The current time.
+    ///
+    /// # Returns
+    /// * `Ok(true)` if a contact of the path is expired or depleted, `Ok(false)` otherwise, or an
+    ///   error if a stage or a contact cannot be borrowed.
+    pub fn is_dead(&self, at_time: Date) -> Result<bool, ASABRError> {
+        let mut curr = self.destination_stage.clone();
+        loop {
+            let parent = {
+                let stage = curr.try_borrow()?;
+                let Some(via) = &stage.via else {
+                    return Ok(false);
+                };
+                let contact = via.contact.try_borrow()?;
+                if contact.info.end < at_time
+                    || contact.manager.remaining_volume(Priority::MAX) <= 0.0
+                {
+                    return Ok(true);
+                }
+                via.parent_route.clone()
+            };
+            curr = parent;
+        }
+    }
 }
 
 impl<NM: NodeManager, CM: ContactManager> Clone for Route<NM, CM> {
@@ -107,6 +204,25 @@ pub trait RouteStorage<NM: NodeManager, CM: ContactManager> {
     ) -> Result<Option<Route<NM, CM>>, ASABRError>;
 
     fn store(&mut self, bundle: &Bundle, route: Route<NM, CM>);
+
+    /// Removes all the stored routes, e.g. after a change of the contact plan.
+    ///
+    /// The default implementation does nothing, for the storages keeping no routes between the
+    /// calls. A storage keeping routes must override it, `invalidate_contact` relies on it.
+    fn clear(&mut self) {}
+
+    /// Drops the stored routes using the contact from `tx` to `rx` starting at `start`, to
+    /// be called when that contact changes (e.g. after `Multigraph::remove_contact`).
+    ///
+    /// The default implementation clears the storage.
+    ///
+    /// # Parameters
+    /// * `tx` - The transmitting node of the contact.
+    /// * `rx` - The receiving node of the contact.
+    /// * `start` - The start time of the contact.
+    fn invalidate_contact(&mut self, _tx: NodeID, _rx: NodeID, _start: Date) {
+        self.clear();
+    }
 }
 
 /// A struct that manages limits and conditions for scheduling based on bundle characteristics.
@@ -164,6 +280,30 @@ impl Guard {
         unreachable_count == bundle.destinations.len()
     }
 
+    /// Returns the size limit known for a destination at the priority of the given bundle.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle` - A reference to the `Bundle` whose priority is considered.
+    /// * `dest` - The destination `NodeID`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Volume>` - The size of the smallest bundle that failed to reach `dest`, if any.
+    pub fn known_limit(&self, bundle: &Bundle, dest: NodeID) -> Option<Volume> {
+        let priority = if self.with_priorities {
+            bundle.priority
+        } else {
+            0
+        };
+        self.known_limits.get(&(dest, priority)).copied()
+    }
+
+    /// Forgets the known limits, e.g. once the resources they were learned from are released.
+    pub fn clear(&mut self) {
+        self.known_limits.clear();
+    }
+
     /// Adds a new size limit for a specific destination based on the given bundle.
     ///
     /// If the new size limit is larger than the current limit for the destination and priority,
@@ -187,3 +327,67 @@ impl Guard {
         self.known_limits.insert((dest, priority), bundle.size);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::routing::{Router, spsn::Spsn};
+    use crate::test_support::GraphBuilder;
+
+    /// A storage keeping no trees, relying on the default `clear`.
+    struct NoStorage;
+
+    impl TreeStorage<NoManagement, EVLManager> for NoStorage {
+        fn select(
+            &self,
+            _bundle: &Bundle,
+            _curr_time: Date,
+            _excluded_nodes_sorted: &[NodeID],
+        ) -> Result<
+            (
+                Option<SharedPathFindingOutput<NoManagement, EVLManager>>,
+                ReachableNodes,
+            ),
+            ASABRError,
+        > {
+            Ok((None, None))
+        }
+
+        fn store(
+            &mut self,
+            _bundle: &Bundle,
+            _tree: Rc<RefCell<PathFindingOutput<NoManagement, EVLManager>>>,
+        ) {
+        }
+    }
+
+    #[test]
+    fn storage_without_trees_uses_the_default_clear() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let storage = Rc::new(RefCell::new(NoStorage));
+        let mut router = Spsn::<
+            NoManagement,
+            EVLManager,
+            NodeParentingTreeExcl<NoManagement, EVLManager, SABR>,
+            NoStorage,
+        >::new(plan, storage, false)
+        .unwrap();
+        let bundle = make_bundle(1, 0, 1.0, 1000.0);
+
+        router.route(0, &bundle, 0.0, &[]).unwrap();
+        router.reset().unwrap();
+        assert!(
+            router.route(0, &bundle, 0.0, &[]).unwrap().is_some(),
+            "TEST FAILED: The router should route again after a reset."
+        );
+    }
+}
@@ -0,0 +1,281 @@
+extern crate alloc;
+
+use alloc::{rc::Rc, vec, vec::Vec};
+
+use core::{cell::RefCell, cmp::Ordering, marker::PhantomData};
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    distance::Distance,
+    errors::ASABRError,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    routing::{RcKey, dry_run_unicast_path},
+    types::{Date, NodeID},
+};
+
+use super::{Route, RouteStorage, path_uses_contact};
+
+/// A routing table that keeps several routes per destination and spreads the load over the
+/// equal-cost ones (ECMP).
+///
+/// Up to `max_routes` routes are kept for each destination, the oldest being evicted first. On
+/// `select`, the routes that are as good as the best one according to `D` are returned in turn
+/// (round-robin).
+///
+/// # Type Parameters
+/// - `NM`: A type implementing `NodeManager`, responsible for managing nodes.
+/// - `CM`: A type implementing `ContactManager`, handling contacts within the network.
+/// - `D`: A type implementing `Distance<NM, CM>`, providing a distance metric for route comparison.
+#[derive(Debug)]
+pub struct MultiRouteTable<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> {
+    /// The routes of each destination, from the oldest to the newest.
+    tables: Vec<Vec<Route<NM, CM>>>,
+    /// For each destination, the index from which the next equal-cost route is searched.
+    cursors: Vec<usize>,
+    /// The maximum number of routes kept for a destination.
+    max_routes: usize,
+    #[doc(hidden)]
+    _phantom_nm: PhantomData<NM>,
+    #[doc(hidden)]
+    _phantom_distance: PhantomData<D>,
+}
+
+/// Returns the contacts of a route, from the source to the destination.
+fn route_contacts<NM: NodeManager, CM: ContactManager>(route: &Route<NM, CM>) -> Vec<RcKey> {
+    let mut contacts = Vec::new();
+    let mut curr = route.destination_stage.clone();
+    loop {
+        let parent = {
+            let stage = curr.borrow();
+            let Some(via) = &stage.via else {
+                break;
+            };
+            contacts.push(RcKey::of(&via.contact));
+            via.parent_route.clone()
+        };
+        curr = parent;
+    }
+    contacts.reverse();
+    contacts
+}
+
+impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> MultiRouteTable<NM, CM, D> {
+    /// Creates a new, empty `MultiRouteTable`.
+    ///
+    /// # Parameters
+    /// - `max_routes`: The maximum number of routes kept for a destination.
+    ///
+    /// # Returns
+    /// A new instance of `MultiRouteTable`.
+    pub fn new(max_routes: usize) -> Self {
+        Self {
+            tables: Vec::new(),
+            cursors: Vec::new(),
+            max_routes,
+            // for compilation
+            _phantom_nm: PhantomData,
+            _phantom_distance: PhantomData,
+        }
+    }
+
+    /// Makes room for the routes of `dest`.
+    fn ensure_destination(&mut self, dest: NodeID) {
+        if self.tables.len() < 1 + dest as usize {
+            self.tables.resize((dest + 1) as usize, vec![]);
+            self.cursors.resize((dest + 1) as usize, 0);
+        }
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM, CM>
+    for MultiRouteTable<NM, CM, D>
+{
+    /// Stores a new route for the destination of the bundle.
+    ///
+    /// A stored route using the same contacts is replaced. If more than `max_routes` routes are
+    /// kept for the destination, the oldest one is evicted.
+    ///
+    /// # Parameters
+    /// - `bundle`: The bundle whose destination will determine the storage index.
+    /// - `route`: The `Route<NM, CM>` to be stored.
+    fn store(&mut self, bundle: &Bundle, route: Route<NM, CM>) {
+        let dest = bundle.destinations[0];
+        self.ensure_destination(dest);
+
+        let routes = &mut self.tables[dest as usize];
+        let contacts = route_contacts(&route);
+        if let Some(idx) = routes
+            .iter()
+            .position(|known| route_contacts(known) == contacts)
+        {
+            routes[idx] = route;
+            return;
+        }
+
+        routes.push(route);
+        if routes.len() > self.max_routes {
+            routes.remove(0);
+            let cursor = &mut self.cursors[dest as usize];
+            *cursor = cursor.saturating_sub(1);
+        }
+    }
+
+    /// Selects a route for a bundle, rotating over the best routes of equal cost.
+    ///
+    /// The expired routes and the routes no bundle can use anymore (see `Route::is_dead`) are
+    /// removed, the others failing the dry run are only skipped. Among the routes passing a dry
+    /// run, the ones that are as good as the best according to `D` are candidates, the first
+    /// candidate found from the position following the last selected route is returned.
+    ///
+    /// # Parameters
+    /// - `bundle`: The bundle for which a route is being selected.
+    /// - `curr_time`: The current time, used in route evaluation.
+    /// - `multigraph`: A reference to the multigraph.
+    /// - `excluded_nodes_sorted`: A list of nodes to exclude from routing.
+    ///
+    /// # Returns
+    /// - `Result<Option<Route<NM, CM>>, ASABRError>`: An optional `Route` if a suitable route is found,
+    ///   or an error if the operation fails.
+    fn select(
+        &mut self,
+        bundle: &Bundle,
+        curr_time: Date,
+        multigraph: Rc<RefCell<Multigraph<NM, CM>>>,
+        excluded_nodes_sorted: &[NodeID],
+    ) -> Result<Option<Route<NM, CM>>, ASABRError> {
+        let dest = bundle.destinations[0];
+        self.ensure_destination(dest);
+
+        let routes = &mut self.tables[dest as usize];
+        let cursor = &mut self.cursors[dest as usize];
+        let mut kept = Vec::with_capacity(routes.len());
+        for route in routes.iter() {
+            kept.push(
+                curr_time <= route.destination_stage.borrow().expiration
+                    && !route.is_dead(curr_time)?,
+            );
+        }
+        let before_cursor = kept[..(*cursor).min(kept.len())]
+            .iter()
+            .filter(|keep| !**keep)
+            .count();
+        let mut flags = kept.iter();
+        routes.retain(|_| *flags.next().unwrap());
+        *cursor -= before_cursor.min(*cursor);
+
+        multigraph
+            .try_borrow_mut()?
+            .prepare_for_exclusions_sorted(excluded_nodes_sorted)?;
+
+        let mut candidates = Vec::new();
+        for (idx, route) in routes.iter().enumerate() {
+            if let Some(stage) =
+                dry_run_unicast_path(bundle, curr_time, route.source_stage.clone(), true)?
+            {
+                candidates.push((idx, stage));
+            }
+        }
+
+        let Some(best) = candidates
+            .iter()
+            .map(|(_, stage)| stage)
+            .min_by(|a, b| D::cmp(&a.borrow(), &b.borrow()))
+            .cloned()
+        else {
+            return Ok(None);
+        };
+        let equal_cost: Vec<usize> = candidates
+            .iter()
+            .filter(|(_, stage)| D::cmp(&stage.borrow(), &best.borrow()) == Ordering::Equal)
+            .map(|(idx, _)| *idx)
+            .collect();
+
+        let selected = equal_cost
+            .iter()
+            .find(|idx| **idx >= *cursor)
+            .unwrap_or(&equal_cost[0]);
+        *cursor = selected + 1;
+        Ok(Some(routes[*selected].clone()))
+    }
+
+    fn clear(&mut self) {
+        self.tables.clear();
+        self.cursors.clear();
+    }
+
+    /// Drops the stored routes using the contact.
+    fn invalidate_contact(&mut self, tx: NodeID, rx: NodeID, start: Date) {
+        for routes in &mut self.tables {
+            routes.retain(|route| !path_uses_contact(&route.destination_stage, tx, rx, start));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::route_stage::RouteStage;
+    use crate::test_support::GraphBuilder;
+
+    /// Returns the relay of a two-hop route.
+    fn relay_of(route: &Route<NoManagement, EVLManager>) -> NodeID {
+        let stage = route.destination_stage.borrow();
+        stage.via.as_ref().unwrap().parent_route.borrow().to_node
+    }
+
+    #[test]
+    fn equal_cost_routes_are_selected_in_turn() -> Result<(), ASABRError> {
+        // two relays offering the same arrival time
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 3, 0.0, 100.0)
+            .contact(0, 2, 0.0, 100.0)
+            .contact(2, 3, 0.0, 100.0)
+            .plan();
+        let multigraph = Rc::new(RefCell::new(Multigraph::new(plan)?));
+        let mut pathfinding =
+            NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(multigraph.clone());
+        let bundle = make_bundle(3, 0, 1.0, 1000.0);
+
+        let mut table = MultiRouteTable::<NoManagement, EVLManager, SABR>::new(4);
+        for excluded in [[2], [1]] {
+            let tree = pathfinding.get_next(0.0, 0, &bundle, &excluded)?;
+            let route = Route::from_tree(Rc::new(RefCell::new(tree)), 3).unwrap();
+            RouteStage::init_route(route.destination_stage.clone())?;
+            // storing a route twice keeps a single copy
+            table.store(&bundle, route.clone());
+            table.store(&bundle, route);
+        }
+        assert_eq!(
+            table.tables[3].len(),
+            2,
+            "TEST FAILED: Identical routes should be stored once."
+        );
+
+        let mut relays = Vec::new();
+        for _ in 0..4 {
+            let route = table
+                .select(&bundle, 0.0, multigraph.clone(), &[])?
+                .expect("TEST FAILED: Expected a route to node 3.");
+            relays.push(relay_of(&route));
+        }
+        assert_eq!(
+            relays,
+            vec![1, 2, 1, 2],
+            "TEST FAILED: The equal-cost routes should alternate."
+        );
+        Ok(())
+    }
+}
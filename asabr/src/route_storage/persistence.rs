@@ -0,0 +1,346 @@
+extern crate alloc;
+use alloc::{collections::BTreeMap as HashMap, rc::Rc, string::String, vec, vec::Vec};
+use core::cell::RefCell;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    errors::ASABRError,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    pathfinding::PathFindingOutput,
+    route_stage::{RouteStage, SharedRouteStage, ViaHop},
+    routing::RcKey,
+    types::{Date, Duration, HopCount, NodeID, Priority, Volume},
+    vertex::VertexID,
+};
+
+use super::{TreeStorage, cache::TreeCache};
+
+/// A stage of a stored tree, the contact it is reached through is identified by its
+/// transmitting node, receiving node and start time.
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedStage {
+    to_node: VertexID,
+    at_time: Date,
+    tx_start: Date,
+    hop_count: HopCount,
+    cumulative_delay: Duration,
+    confidence: f64,
+    /// `None` if unbounded, the infinite values cannot be written.
+    first_hop_volume: Option<Volume>,
+    expiration: Date,
+    /// The index of the parent stage and the (tx, rx, start) of the contact, `None` for the source.
+    via: Option<(usize, NodeID, NodeID, Date)>,
+}
+
+/// A stored tree, its stages are listed parents first, the source being the first stage.
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedTree {
+    source: NodeID,
+    destinations: Vec<NodeID>,
+    priority: Priority,
+    size: Volume,
+    expiration: Date,
+    excluded_nodes_sorted: Vec<NodeID>,
+    stages: Vec<SerializedStage>,
+    /// The index of the stage reaching each vertex, if any.
+    by_destination: Vec<Option<usize>>,
+}
+
+impl SerializedTree {
+    fn new<NM: NodeManager, CM: ContactManager>(
+        tree: &PathFindingOutput<NM, CM>,
+    ) -> Result<Self, ASABRError> {
+        let mut indices: HashMap<RcKey, usize> = HashMap::new();
+        let mut stages = Vec::new();
+
+        let mut add_stage = |stage: &SharedRouteStage<NM, CM>| -> Result<usize, ASABRError> {
+            // the chain up to the first stage already listed, or the source
+            let mut chain = vec![stage.clone()];
+            loop {
+                let last = chain.last().unwrap().clone();
+                if indices.contains_key(&RcKey::of(&last)) {
+                    chain.pop();
+                    break;
+                }
+                let Some(via) = &last.try_borrow()?.via else {
+                    break;
+                };
+                chain.push(via.parent_route.clone());
+            }
+            for stage in chain.iter().rev() {
+                let stage_ref = stage.try_borrow()?;
+                let via = match &stage_ref.via {
+                    Some(via) => {
+                        let info = via.contact.try_borrow()?.info;
+                        let parent = indices[&RcKey::of(&via.parent_route)];
+                        Some((parent, info.tx_node_id, info.rx_node_id, info.start))
+                    }
+                    None => None,
+                };
+                indices.insert(RcKey::of(stage), stages.len());
+                stages.push(SerializedStage {
+                    to_node: stage_ref.to_node,
+                    at_time: stage_ref.at_time,
+                    tx_start: stage_ref.tx_start,
+                    hop_count: stage_ref.hop_count,
+                    cumulative_delay: stage_ref.cumulative_delay,
+                    confidence: stage_ref.confidence,
+                    first_hop_volume: Some(stage_ref.first_hop_volume)
+                        .filter(|volume| volume.is_finite()),
+                    expiration: stage_ref.expiration,
+                    via,
+                });
+            }
+            Ok(indices[&RcKey::of(stage)])
+        };
+
+        add_stage(&tree.source)?;
+        let by_destination = tree
+            .by_destination
+            .iter()
+            .map(|route| route.as_ref().map(&mut add_stage).transpose())
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            source: tree.bundle.source,
+            destinations: tree.bundle.destinations.clone(),
+            priority: tree.bundle.priority,
+            size: tree.bundle.size,
+            expiration: tree.bundle.expiration,
+            excluded_nodes_sorted: tree.excluded_nodes_sorted.clone(),
+            stages,
+            by_destination,
+        })
+    }
+
+    /// Rebuilds the tree over the contacts of `multigraph`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<PathFindingOutput<NM, CM>>` - The tree, or `None` if a stage cannot be rebuilt,
+    ///   e.g. because its contact is absent from `multigraph`.
+    fn rebuild<NM: NodeManager, CM: ContactManager>(
+        &self,
+        multigraph: &Multigraph<NM, CM>,
+    ) -> Option<PathFindingOutput<NM, CM>> {
+        let bundle = Bundle {
+            source: self.source,
+            destinations: self.destinations.clone(),
+            priority: self.priority,
+            size: self.size,
+            expiration: self.expiration,
+        };
+
+        let mut stages: Vec<SharedRouteStage<NM, CM>> = Vec::with_capacity(self.stages.len());
+        for serialized in &self.stages {
+            let via = match serialized.via {
+                Some((parent, tx, rx, start)) => {
+                    let parent_route = stages.get(parent)?.clone();
+                    let from_vertex = parent_route.borrow().to_node;
+                    let contact = multigraph
+                        .senders
+                        .get(from_vertex as usize)?
+                        .receivers
+                        .iter()
+                        .find(|receiver| receiver.vertex_id == serialized.to_node)?
+                        .contacts_to_receiver
+                        .iter()
+                        .find(|contact| {
+                            let info = contact.borrow().info;
+                            info.tx_node_id == tx && info.rx_node_id == rx && info.start == start
+                        })?
+                        .clone();
+                    Some(ViaHop {
+                        contact,
+                        parent_route,
+                        tx_node: multigraph.real_nodes.get(tx as usize)?.clone(),
+                        rx_node: multigraph.real_nodes.get(rx as usize)?.clone(),
+                    })
+                }
+                None => None,
+            };
+            let mut stage = RouteStage::new(
+                serialized.at_time,
+                serialized.to_node,
+                via,
+                #[cfg(feature = "node_proc")]
+                bundle.clone(),
+            );
+            stage.tx_start = serialized.tx_start;
+            stage.hop_count = serialized.hop_count;
+            stage.cumulative_delay = serialized.cumulative_delay;
+            stage.confidence = serialized.confidence;
+            stage.first_hop_volume = serialized.first_hop_volume.unwrap_or(Volume::INFINITY);
+            stage.expiration = serialized.expiration;
+            stages.push(Rc::new(RefCell::new(stage)));
+        }
+
+        let source = stages.first()?.clone();
+        let mut tree = PathFindingOutput::new(
+            &bundle,
+            source,
+            &self.excluded_nodes_sorted,
+            self.by_destination.len(),
+        );
+        for (route, index) in tree.by_destination.iter_mut().zip(&self.by_destination) {
+            if let Some(index) = index {
+                *route = Some(stages.get(*index)?.clone());
+            }
+        }
+        Some(tree)
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> TreeCache<NM, CM> {
+    /// Appends the stored trees to `out`, as JSON.
+    ///
+    /// Only the skeleton of the trees is saved: the bundle constraints, the exclusion list and
+    /// the stages, whose contacts are identified by their transmitting node, receiving node
+    /// and start time.
+    ///
+    /// # Parameters
+    ///
+    /// * `out` - The string to write to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the trees were saved.
+    /// * `Err(ASABRError)` - If a tree cannot be borrowed, `ASABRError::PersistenceError` if the
+    ///   trees cannot be serialized.
+    pub fn save_to(&self, out: &mut String) -> Result<(), ASABRError> {
+        let trees = self
+            .trees()
+            .map(|tree| SerializedTree::new(&*tree.try_borrow()?))
+            .collect::<Result<Vec<_>, ASABRError>>()?;
+        out.push_str(
+            &serde_json::to_string(&trees)
+                .map_err(|_| ASABRError::PersistenceError("The trees cannot be serialized"))?,
+        );
+        Ok(())
+    }
+
+    /// Stores the trees saved by `save_to`, rebuilt over the contacts of `multigraph`.
+    ///
+    /// The trees referencing a contact absent from `multigraph` are dropped. The trees are
+    /// stored in turn, the usual replacement and eviction rules apply.
+    ///
+    /// # Parameters
+    ///
+    /// * `content` - The output of `save_to`.
+    /// * `multigraph` - The multigraph the trees will be used with, e.g. from `Spsn::get_multigraph`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of trees stored.
+    /// * `Err(ASABRError::PersistenceError)` - If `content` is not a valid output of `save_to`.
+    pub fn load_from(
+        &mut self,
+        content: &str,
+        multigraph: &Multigraph<NM, CM>,
+    ) -> Result<usize, ASABRError> {
+        let trees: Vec<SerializedTree> = serde_json::from_str(content)
+            .map_err(|_| ASABRError::PersistenceError("Invalid tree cache content"))?;
+        let mut loaded = 0;
+        for serialized in &trees {
+            if let Some(tree) = serialized.rebuild(multigraph) {
+                self.store(&tree.bundle.clone(), Rc::new(RefCell::new(tree)));
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::routing::Router;
+    use crate::routing::aliases::SpsnNodeParenting;
+    use crate::test_support::GraphBuilder;
+
+    type Cache = Rc<RefCell<TreeCache<NoManagement, EVLManager>>>;
+
+    fn router_and_cache() -> (SpsnNodeParenting<NoManagement, EVLManager>, Cache) {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 2, 10.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let router = SpsnNodeParenting::new(plan, cache.clone(), false).unwrap();
+        (router, cache)
+    }
+
+    #[test]
+    fn reloaded_tree_is_selected() {
+        let bundle = make_bundle(2, 0, 1.0, 1000.0);
+        let (mut router, cache) = router_and_cache();
+        router.route(0, &bundle, 0.0, &[]).unwrap();
+        let mut saved = String::new();
+        cache.borrow().save_to(&mut saved).unwrap();
+
+        let (router, cache) = router_and_cache();
+        let loaded = cache
+            .borrow_mut()
+            .load_from(&saved, &router.get_multigraph().borrow())
+            .unwrap();
+        assert_eq!(
+            loaded, 1,
+            "TEST FAILED: Expected the saved tree to be loaded."
+        );
+
+        let (tree, _) = cache.borrow().select(&bundle, 0.0, &[]).unwrap();
+        let tree = tree.expect("TEST FAILED: Expected the reloaded tree to be selected.");
+        let tree = tree.borrow();
+        let route = tree.by_destination[2].as_ref().unwrap().borrow();
+        assert_eq!(
+            (route.hop_count, route.at_time),
+            (2, 11.0),
+            "TEST FAILED: The reloaded route should match the saved one."
+        );
+    }
+
+    #[test]
+    fn trees_with_unknown_contacts_are_dropped() {
+        let bundle = make_bundle(2, 0, 1.0, 1000.0);
+        let (mut router, cache) = router_and_cache();
+        router.route(0, &bundle, 0.0, &[]).unwrap();
+        let mut saved = String::new();
+        cache.borrow().save_to(&mut saved).unwrap();
+
+        let (router, cache) = router_and_cache();
+        router
+            .get_multigraph()
+            .borrow_mut()
+            .remove_contact(1, 2, 10.0);
+        let loaded = cache
+            .borrow_mut()
+            .load_from(&saved, &router.get_multigraph().borrow())
+            .unwrap();
+        assert_eq!(
+            loaded, 0,
+            "TEST FAILED: The tree uses a removed contact and should be dropped."
+        );
+    }
+
+    #[test]
+    fn invalid_content_is_a_persistence_error() {
+        let (router, cache) = router_and_cache();
+        let res = cache
+            .borrow_mut()
+            .load_from("not a tree cache", &router.get_multigraph().borrow());
+        assert!(
+            matches!(res, Err(ASABRError::PersistenceError(_))),
+            "TEST FAILED: Expected a persistence error for an invalid content."
+        );
+    }
+}
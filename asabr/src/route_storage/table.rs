@@ -5,12 +5,17 @@ use alloc::{rc::Rc, vec, vec::Vec};
 use core::{cell::RefCell, cmp::Ordering, marker::PhantomData};
 
 use crate::{
-    bundle::Bundle, contact_manager::ContactManager, distance::Distance, errors::ASABRError,
-    multigraph::Multigraph, node_manager::NodeManager, routing::dry_run_unicast_path,
-    types::NodeID,
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    distance::Distance,
+    errors::ASABRError,
+    multigraph::Multigraph,
+    node_manager::NodeManager,
+    routing::dry_run_unicast_path,
+    types::{Date, NodeID},
 };
 
-use super::{Route, RouteStorage};
+use super::{Route, RouteStorage, path_uses_contact};
 
 /// A routing table that stores the routes for each destination.
 ///
@@ -84,7 +89,9 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM,
     ///
     /// This function evaluates available routes to the bundle's destination, choosing the
     /// route that is most favorable according to the current time, mutligraph. Routes are
-    /// compared to find the best candidate, which is then returned.
+    /// compared to find the best candidate, which is then returned. The routes failing the dry
+    /// run for this bundle are skipped, only the expired routes and the routes no bundle can use
+    /// anymore (see `Route::is_dead`) are discarded.
     ///
     /// Apply the exclusions to the node objects before calling this function.
     ///
@@ -101,7 +108,7 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM,
     fn select(
         &mut self,
         bundle: &Bundle,
-        curr_time: crate::types::Date,
+        curr_time: Date,
         multigraph: Rc<RefCell<Multigraph<NM, CM>>>,
         excluded_nodes_sorted: &[NodeID],
     ) -> Result<Option<Route<NM, CM>>, ASABRError> {
@@ -119,7 +126,9 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM,
             let should_remove = {
                 let route = &routes[i];
 
-                if curr_time > route.destination_stage.borrow().expiration {
+                if curr_time > route.destination_stage.borrow().expiration
+                    || route.is_dead(curr_time)?
+                {
                     true
                 } else {
                     // apply exclusions
@@ -159,4 +168,225 @@ impl<NM: NodeManager, CM: ContactManager, D: Distance<NM, CM>> RouteStorage<NM,
 
         Ok(best_route_option)
     }
+
+    fn clear(&mut self) {
+        self.tables.clear();
+    }
+
+    /// Drops the stored routes using the contact.
+    fn invalidate_contact(&mut self, tx: NodeID, rx: NodeID, start: Date) {
+        for routes in &mut self.tables {
+            routes.retain(|route| !path_uses_contact(&route.destination_stage, tx, rx, start));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_iter;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::parsing::CMDynStandard;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::route_stage::RouteStage;
+    use crate::routing::Router;
+    use crate::test_support::GraphBuilder;
+    use crate::types::Priority;
+
+    const PLAN: [&str; 5] = [
+        "node 0 a",
+        "node 1 b",
+        "node 2 c",
+        "contact 0 1 0 100 evl 100 1 # first hop",
+        "contact 1 2 10 100 evl 100 1",
+    ];
+
+    fn route_with<R: Router<NoManagement, CMDynStandard>>(router: &mut R) {
+        let bundle = make_bundle(2, 0, 10.0, 1000.0);
+        let output = router
+            .route(0, &bundle, 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Expected a route from node 0 to node 2.");
+        let (_, route) = output
+            .lazy_get_for_unicast(2)
+            .expect("TEST FAILED: Expected a route stage for node 2.");
+        assert!(
+            route.borrow().at_time >= 11.0,
+            "TEST FAILED: Expected an arrival after the second contact opening."
+        );
+    }
+
+    #[test]
+    fn volcgr_routes_with_default_table() {
+        let plan = parse_from_iter::<NoManagement, CMDynStandard, _>(PLAN.iter()).unwrap();
+        let table = Rc::new(RefCell::new(RoutingTable::new()));
+        let mut router =
+            crate::routing::aliases::VolCgrNodeParenting::new(plan, table.clone()).unwrap();
+        route_with(&mut router);
+    }
+
+    #[test]
+    fn cgr_routes_with_default_table_without_suppression() {
+        // the limiting contact aliases need "contact_suppression", Cgr itself does not
+        let plan = parse_from_iter::<NoManagement, CMDynStandard, _>(PLAN.iter()).unwrap();
+        let table = Rc::new(RefCell::new(RoutingTable::<_, _, SABR>::new()));
+        let mut router = crate::routing::cgr::Cgr::<
+            _,
+            _,
+            crate::pathfinding::node_parenting::NodeParentingPathExcl<_, _, SABR>,
+            _,
+        >::new(plan, table.clone())
+        .unwrap();
+        route_with(&mut router);
+    }
+
+    #[cfg(feature = "contact_suppression")]
+    #[test]
+    fn cgr_routes_with_default_table() {
+        let plan = parse_from_iter::<NoManagement, CMDynStandard, _>(PLAN.iter()).unwrap();
+        let table = Rc::new(RefCell::new(RoutingTable::new()));
+        let mut router =
+            crate::routing::aliases::CgrFirstEndingHybridParenting::new(plan, table.clone())
+                .unwrap();
+        route_with(&mut router);
+    }
+
+    #[test]
+    fn invalidate_contact_drops_only_the_routes_using_it() -> Result<(), ASABRError> {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 3, 0.0, 100.0)
+            .contact(0, 2, 0.0, 100.0)
+            .contact(2, 3, 0.0, 100.0)
+            .plan();
+        let multigraph = Rc::new(RefCell::new(Multigraph::new(plan)?));
+        let mut pathfinding =
+            NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(multigraph.clone());
+        let bundle = make_bundle(3, 0, 1.0, 1000.0);
+        let mut table = RoutingTable::<NoManagement, EVLManager, SABR>::new();
+        // one route through each relay
+        for excluded in [[2], [1]] {
+            let tree = pathfinding.get_next(0.0, 0, &bundle, &excluded)?;
+            let route = Route::from_tree(Rc::new(RefCell::new(tree)), 3).unwrap();
+            RouteStage::init_route(route.destination_stage.clone())?;
+            table.store(&bundle, route);
+        }
+
+        table.invalidate_contact(1, 3, 0.0);
+        assert_eq!(
+            table.tables[3].len(),
+            1,
+            "TEST FAILED: Only the route through node 1 should be dropped."
+        );
+        let route = table
+            .select(&bundle, 0.0, multigraph, &[])?
+            .expect("TEST FAILED: The route through node 2 should remain.");
+        let stage = route.destination_stage.borrow();
+        assert_eq!(
+            stage.via.as_ref().unwrap().parent_route.borrow().to_node,
+            2,
+            "TEST FAILED: The remaining route should go through node 2."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_dead_routes_are_dropped() -> Result<(), ASABRError> {
+        // a fast route through node 1, a slow one through node 2
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .rate(10.0)
+            .contact(1, 3, 0.0, 100.0)
+            .rate(10.0)
+            .contact(0, 2, 0.0, 100.0)
+            .rate(10.0)
+            .delay(5.0)
+            .contact(2, 3, 0.0, 100.0)
+            .rate(10.0)
+            .plan();
+        let table = Rc::new(RefCell::new(
+            RoutingTable::<NoManagement, EVLManager, SABR>::new(),
+        ));
+        let mut router = crate::routing::aliases::VolCgrNodeParenting::new(plan, table.clone())?;
+        type Router = crate::routing::aliases::VolCgrNodeParenting<NoManagement, EVLManager>;
+        // routes a bundle to node 3, returning the first hop and the relay
+        let route_to_3 = |router: &mut Router| {
+            let output = router
+                .route(0, &make_bundle(3, 0, 10.0, 1000.0), 0.0, &[])?
+                .expect("TEST FAILED: Node 3 should be reached.");
+            let (first_hop, stage) = output.lazy_get_for_unicast(3).unwrap();
+            let relay = stage
+                .borrow()
+                .via
+                .as_ref()
+                .unwrap()
+                .parent_route
+                .borrow()
+                .to_node;
+            Ok::<_, ASABRError>((first_hop, relay))
+        };
+        let (first_hop, relay) = route_to_3(&mut router)?;
+        assert_eq!(relay, 1, "TEST FAILED: The fast route goes through node 1.");
+
+        // a bundle expiring before any arrival does not discard the stored route (with
+        // node_proc, the dry runs of a stored route use the bundle it was computed for)
+        #[cfg(not(feature = "node_proc"))]
+        {
+            assert!(
+                router
+                    .route(0, &make_bundle(3, 0, 10.0, 1.5), 0.0, &[])?
+                    .is_none(),
+                "TEST FAILED: The short-lived bundle cannot be routed."
+            );
+            assert!(
+                !table.borrow().tables[3].is_empty(),
+                "TEST FAILED: The route should be kept for the next bundles."
+            );
+        }
+        assert_eq!(
+            route_to_3(&mut router)?.1,
+            1,
+            "TEST FAILED: The next bundle should still go through node 1."
+        );
+        assert!(
+            router.last_stats().from_cache,
+            "TEST FAILED: The stored route should be used again."
+        );
+
+        // an external process books the whole first hop, the route is dead for every bundle
+        {
+            let mut contact = first_hop.borrow_mut();
+            let info = contact.info;
+            let left = contact.manager.remaining_volume(Priority::MAX);
+            contact
+                .manager
+                .schedule_tx(&info, 0.0, &make_bundle(1, 0, left, 1000.0))
+                .expect("TEST FAILED: The first hop should be fully booked.");
+        }
+        assert_eq!(
+            route_to_3(&mut router)?.1,
+            2,
+            "TEST FAILED: The route should be computed again through node 2."
+        );
+        assert!(
+            table.borrow().tables[3].iter().all(|route| {
+                let stage = route.destination_stage.borrow();
+                stage.via.as_ref().unwrap().parent_route.borrow().to_node == 2
+            }),
+            "TEST FAILED: The dead route through node 1 should be dropped."
+        );
+        Ok(())
+    }
 }
@@ -3,7 +3,7 @@ extern crate alloc;
 use crate::{
     contact_manager::ContactManager,
     contact_plan::ContactPlan,
-    distance::{hop::Hop, sabr::SABR},
+    distance::{hop::Hop, latency::Latency, sabr::SABR},
     errors::ASABRError,
     node_manager::NodeManager,
     pathfinding::{
@@ -138,6 +138,26 @@ pub type CgrFirstDepletedContactParentingHop<NM, CM> = Cgr<
     RoutingTable<NM, CM, Hop>,
 >;
 
+pub type SpsnHybridParentingLatency<NM, CM> =
+    Spsn<NM, CM, HybridParentingTreeExcl<NM, CM, Latency>, TreeCache<NM, CM>>;
+
+pub type SpsnNodeParentingLatency<NM, CM> =
+    Spsn<NM, CM, NodeParentingTreeExcl<NM, CM, Latency>, TreeCache<NM, CM>>;
+
+#[cfg(feature = "contact_work_area")]
+pub type SpsnContactParentingLatency<NM, CM> =
+    Spsn<NM, CM, ContactParentingTreeExcl<NM, CM, Latency>, TreeCache<NM, CM>>;
+
+pub type VolCgrHybridParentingLatency<NM, CM> =
+    VolCgr<NM, CM, HybridParentingPathExcl<NM, CM, Latency>, RoutingTable<NM, CM, Latency>>;
+
+pub type VolCgrNodeParentingLatency<NM, CM> =
+    VolCgr<NM, CM, NodeParentingPathExcl<NM, CM, Latency>, RoutingTable<NM, CM, Latency>>;
+
+#[cfg(feature = "contact_work_area")]
+pub type VolCgrContactParentingLatency<NM, CM> =
+    VolCgr<NM, CM, ContactParentingPathExcl<NM, CM, Latency>, RoutingTable<NM, CM, Latency>>;
+
 macro_rules! register_cgr_router {
     ($router:ident, $router_name:literal, $test_name_variable:ident, $contact_plan:ident) => {
         if $test_name_variable == $router_name {
@@ -246,6 +266,37 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
             check_priority,
             max_entries
         );
+
+        register_spsn_router!(
+            SpsnNodeParentingLatency,
+            "SpsnNodeParentingLatency",
+            router_type,
+            contact_plan,
+            check_size,
+            check_priority,
+            max_entries
+        );
+
+        register_spsn_router!(
+            SpsnHybridParentingLatency,
+            "SpsnHybridParentingLatency",
+            router_type,
+            contact_plan,
+            check_size,
+            check_priority,
+            max_entries
+        );
+
+        #[cfg(feature = "contact_work_area")]
+        register_spsn_router!(
+            SpsnContactParentingLatency,
+            "SpsnContactParentingLatency",
+            router_type,
+            contact_plan,
+            check_size,
+            check_priority,
+            max_entries
+        );
     }
 
     register_cgr_router!(
@@ -292,6 +343,28 @@ pub fn build_generic_router<NM: NodeManager + 'static, CM: ContactManager + 'sta
         contact_plan
     );
 
+    register_cgr_router!(
+        VolCgrNodeParentingLatency,
+        "VolCgrNodeParentingLatency",
+        router_type,
+        contact_plan
+    );
+
+    register_cgr_router!(
+        VolCgrHybridParentingLatency,
+        "VolCgrHybridParentingLatency",
+        router_type,
+        contact_plan
+    );
+
+    #[cfg(feature = "contact_work_area")]
+    register_cgr_router!(
+        VolCgrContactParentingLatency,
+        "VolCgrContactParentingLatency",
+        router_type,
+        contact_plan
+    );
+
     #[cfg(feature = "contact_suppression")]
     register_cgr_router!(
         CgrFirstEndingHybridParentingHop,
@@ -2,25 +2,33 @@ use crate::{
     bundle::Bundle,
     contact_manager::ContactManager,
     contact_plan::ContactPlan,
+    distance::Distance,
     errors::ASABRError,
     multigraph::Multigraph,
     node_manager::NodeManager,
-    pathfinding::Pathfinding,
+    pathfinding::{Pathfinding, PathfindingStats},
     route_stage::RouteStage,
     route_storage::{Route, RouteStorage},
     types::{Date, NodeID},
 };
 extern crate alloc;
 
-use alloc::rc::Rc;
+use alloc::{rc::Rc, vec::Vec};
 use core::{cell::RefCell, marker::PhantomData};
 
-use super::{Router, RoutingOutput, dry_run_unicast_path, schedule_unicast_path};
+use super::{
+    RcKey, Router, RoutingOutput, check_priority, dry_run_unicast_path, has_known_destinations,
+    schedule_unicast_path, with_excluded_contacts,
+};
 
 pub struct Cgr<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: RouteStorage<NM, CM>>
 {
     route_storage: Rc<RefCell<S>>,
     pathfinding: P,
+    /// The pathfinding statistics of the last routing operation.
+    stats: PathfindingStats,
+    /// The current time used by `route_now`.
+    now: Date,
 
     // for compilation
     #[doc(hidden)]
@@ -39,7 +47,18 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: RouteStorag
         curr_time: Date,
         excluded_nodes: &[NodeID],
     ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
-        if bundle.expiration < curr_time {
+        self.stats = PathfindingStats::default();
+        check_priority(bundle, &self.pathfinding.get_multigraph())?;
+        // the contacts suppressed by an interrupted search must not be skipped for this bundle
+        self.pathfinding.clear_suppressions()?;
+        #[cfg(feature = "contact_suppression")]
+        self.pathfinding
+            .get_multigraph()
+            .try_borrow_mut()?
+            .clear_suppressions()?;
+        if bundle.expiration < curr_time
+            || !has_known_destinations(bundle, &self.pathfinding.get_multigraph())?
+        {
             return Ok(None);
         }
 
@@ -49,6 +68,24 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: RouteStorag
 
         Err(ASABRError::MulticastUnsupportedError)
     }
+
+    fn route_with_constraints(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+        excluded_contacts: &[(NodeID, NodeID, Date)],
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        let multigraph = self.pathfinding.get_multigraph();
+        with_excluded_contacts(&multigraph, excluded_contacts, || {
+            self.route(source, bundle, curr_time, excluded_nodes)
+        })
+    }
+
+    fn now(&self) -> Option<Date> {
+        Some(self.now)
+    }
 }
 
 impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>
@@ -61,12 +98,58 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         Ok(Self {
             pathfinding: P::new(Rc::new(RefCell::new(Multigraph::new(contact_plan)?))),
             route_storage: route_storage.clone(),
+            stats: PathfindingStats::default(),
+            now: 0.0,
             // for compilation
             _phantom_nm: PhantomData,
             _phantom_cm: PhantomData,
         })
     }
 
+    /// Retrieves the pathfinding statistics of the last call to `route`.
+    ///
+    /// The statistics of the searches run for the bundle are summed. If the route was taken from
+    /// the route storage, only `from_cache` is set.
+    ///
+    /// # Returns
+    ///
+    /// * The statistics of the searches run by the last call to `route`.
+    pub fn last_stats(&self) -> PathfindingStats {
+        self.stats
+    }
+
+    /// Sets the current time of the router, used by `route_now`.
+    ///
+    /// The routes are computed from this time on: like the `curr_time` of `route`, it should not
+    /// go backwards, the contacts ended before it being pruned.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The new current time.
+    pub fn set_now(&mut self, now: Date) {
+        self.now = now;
+    }
+
+    /// Releases the resources booked on every contact and clears the route storage, to run a new
+    /// scenario as if the router was just created. The node managers are left as is, see
+    /// `Multigraph::reset_resources`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the router was reset.
+    /// * `Err(ASABRError)` - If the multigraph or the storage cannot be borrowed, or a contact
+    ///   cannot be initialized again (`ASABRError::ResourceError`).
+    pub fn reset(&mut self) -> Result<(), ASABRError> {
+        self.pathfinding
+            .get_multigraph()
+            .try_borrow_mut()?
+            .reset_resources()?;
+        self.route_storage.try_borrow_mut()?.clear();
+        self.pathfinding.clear_suppressions()?;
+        self.now = 0.0;
+        Ok(())
+    }
+
     fn route_unicast(
         &mut self,
         source: NodeID,
@@ -89,6 +172,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         )?;
 
         if let Some(route) = route_option {
+            self.stats.from_cache = true;
             return Ok(Some(schedule_unicast_path(
                 bundle,
                 curr_time,
@@ -103,6 +187,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
                 &bundle_to_consider,
                 excluded_nodes,
             )?;
+            self.stats += self.pathfinding.last_stats();
             let tree = Rc::new(RefCell::new(new_tree));
 
             let Some(route) = Route::from_tree(tree, dest) else {
@@ -125,4 +210,270 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         }
         Ok(None)
     }
+
+    /// Enumerates up to `k` loopless routes to the bundle's destination, in increasing `D` order.
+    ///
+    /// This is a Yen-style deviation loop restricted to node exclusions, the only constraint
+    /// `Pathfinding::get_next` supports: once a route is selected, a candidate is computed for
+    /// each of its intermediate nodes by excluding that node on top of the exclusions the route
+    /// was found with. The best candidate that was not selected yet becomes the next route.
+    ///
+    /// As for routing, the volume constraints are dropped during pathfinding. The routes are
+    /// initialized but neither dry run nor scheduled, and the route storage is not updated.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID.
+    /// - `bundle`: The bundle to route, only its first destination is considered.
+    /// - `curr_time`: The current time.
+    /// - `k`: The maximum number of routes to return.
+    ///
+    /// # Returns
+    /// The routes found, the shortest first, or an error if the pathfinding fails.
+    pub fn route_k_unicast<D: Distance<NM, CM>>(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        k: usize,
+    ) -> Result<Vec<Route<NM, CM>>, ASABRError> {
+        let dest = bundle.destinations[0];
+
+        let mut bundle_to_consider = bundle.clone();
+        bundle_to_consider.priority = 1;
+        bundle_to_consider.size = 0.0;
+
+        let mut routes: Vec<Route<NM, CM>> = Vec::new();
+        let mut selected_contacts: Vec<Vec<RcKey>> = Vec::new();
+        let mut candidates: Vec<(Route<NM, CM>, Vec<NodeID>)> = Vec::new();
+
+        let first = self
+            .pathfinding
+            .get_next(curr_time, source, &bundle_to_consider, &[])?;
+        if let Some(route) = Route::from_tree(Rc::new(RefCell::new(first)), dest) {
+            candidates.push((route, Vec::new()));
+        }
+
+        while routes.len() < k {
+            let Some(best) = (0..candidates.len()).min_by(|a, b| {
+                D::cmp(
+                    &candidates[*a].0.destination_stage.borrow(),
+                    &candidates[*b].0.destination_stage.borrow(),
+                )
+            }) else {
+                break;
+            };
+            let (route, excluded_nodes) = candidates.swap_remove(best);
+
+            let (contacts, nodes) = route_hops(&route);
+            if selected_contacts.contains(&contacts) {
+                continue;
+            }
+
+            for node in nodes {
+                if node == source || node == dest {
+                    continue;
+                }
+                let mut excluded = excluded_nodes.clone();
+                if let Err(pos) = excluded.binary_search(&node) {
+                    excluded.insert(pos, node);
+                } else {
+                    continue;
+                }
+                let tree =
+                    self.pathfinding
+                        .get_next(curr_time, source, &bundle_to_consider, &excluded)?;
+                if let Some(candidate) = Route::from_tree(Rc::new(RefCell::new(tree)), dest) {
+                    candidates.push((candidate, excluded));
+                }
+            }
+
+            RouteStage::init_route(route.destination_stage.clone())?;
+            selected_contacts.push(contacts);
+            routes.push(route);
+        }
+
+        Ok(routes)
+    }
+}
+
+/// Returns the contacts of a route and the nodes they reach, from the destination to the source.
+fn route_hops<NM: NodeManager, CM: ContactManager>(
+    route: &Route<NM, CM>,
+) -> (Vec<RcKey>, Vec<NodeID>) {
+    let mut contacts = Vec::new();
+    let mut nodes = Vec::new();
+    let mut curr = route.destination_stage.clone();
+    loop {
+        let parent = {
+            let stage = curr.borrow();
+            nodes.push(stage.to_node);
+            match &stage.via {
+                Some(via) => {
+                    contacts.push(RcKey::of(&via.contact));
+                    via.parent_route.clone()
+                }
+                None => break,
+            }
+        };
+        curr = parent;
+    }
+    (contacts, nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::route_storage::table::RoutingTable;
+    use crate::test_support::GraphBuilder;
+
+    type TestCgr = Cgr<
+        NoManagement,
+        EVLManager,
+        NodeParentingTreeExcl<NoManagement, EVLManager, SABR>,
+        RoutingTable<NoManagement, EVLManager, SABR>,
+    >;
+
+    #[cfg(feature = "contact_suppression")]
+    #[test]
+    fn suppression_flags_do_not_leak_to_the_next_route() {
+        use crate::routing::aliases::CgrFirstEndingNodeParenting;
+
+        // 0 -> 1 -> 3 arrives at 4.0, 0 -> 2 -> 3 arrives at 8.0
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .delay(1.0)
+            .contact(1, 3, 0.0, 100.0)
+            .delay(1.0)
+            .contact(0, 2, 0.0, 100.0)
+            .delay(3.0)
+            .contact(2, 3, 0.0, 100.0)
+            .delay(3.0)
+            .plan();
+        let table = Rc::new(RefCell::new(RoutingTable::new()));
+        let mut router =
+            CgrFirstEndingNodeParenting::<NoManagement, EVLManager>::new(plan, table.clone())
+                .unwrap();
+        let bundle = make_bundle(3, 0, 1.0, 1000.0);
+
+        let first = router
+            .route(0, &bundle, 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Node 3 should be reached.");
+        assert!(
+            router
+                .pathfinding
+                .get_multigraph()
+                .borrow()
+                .suppressed_contacts()
+                .unwrap()
+                .is_empty(),
+            "TEST FAILED: No contact should stay suppressed after routing."
+        );
+
+        // without the stored route, the next search goes on with the alternative route: only
+        // the flags are reset, not the contacts planned for suppression
+        table.borrow_mut().clear();
+        let second = router
+            .route(0, &bundle, 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Node 3 should be reached again.");
+        assert!(
+            second.route_metrics[&3].arrival_time > first.route_metrics[&3].arrival_time,
+            "TEST FAILED: The next search should give the alternative route through node 2."
+        );
+        assert!(
+            router
+                .pathfinding
+                .get_multigraph()
+                .borrow()
+                .suppressed_contacts()
+                .unwrap()
+                .is_empty(),
+            "TEST FAILED: No contact should stay suppressed after the second route."
+        );
+    }
+
+    #[test]
+    fn diamond_gives_two_routes_shortest_first() {
+        // 0 -> 1 -> 3 arrives at 2.0, 0 -> 2 -> 3 arrives at 6.0
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .delay(1.0)
+            .contact(1, 3, 0.0, 100.0)
+            .delay(1.0)
+            .contact(0, 2, 0.0, 100.0)
+            .delay(3.0)
+            .contact(2, 3, 0.0, 100.0)
+            .delay(3.0)
+            .plan();
+        let table = Rc::new(RefCell::new(RoutingTable::new()));
+        let mut router = TestCgr::new(plan, table).unwrap();
+
+        let routes = router
+            .route_k_unicast::<SABR>(0, &make_bundle(3, 0, 1.0, 1000.0), 0.0, 5)
+            .unwrap();
+
+        assert_eq!(
+            routes.len(),
+            2,
+            "TEST FAILED: Expected two distinct routes."
+        );
+        let relays: Vec<NodeID> = routes.iter().map(|route| route_hops(route).1[1]).collect();
+        assert_eq!(
+            relays,
+            [1, 2],
+            "TEST FAILED: The route through node 1 should come first."
+        );
+        assert_eq!(
+            routes[0].destination_stage.borrow().at_time,
+            2.0,
+            "TEST FAILED: Expected an arrival at 2.0 for the shortest route."
+        );
+        assert_eq!(
+            routes[1].destination_stage.borrow().at_time,
+            6.0,
+            "TEST FAILED: Expected an arrival at 6.0 for the second route."
+        );
+    }
+
+    #[test]
+    fn route_now_starts_from_the_clock() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let table = Rc::new(RefCell::new(RoutingTable::new()));
+        let mut router = TestCgr::new(plan, table).unwrap();
+        let bundle = make_bundle(1, 0, 1.0, 1000.0);
+
+        let first = router.route_now(0, &bundle).unwrap().unwrap();
+        assert_eq!(
+            first.path_to(1).unwrap()[0].2,
+            0.0,
+            "TEST FAILED: The first bundle should be sent from the initial now."
+        );
+
+        router.set_now(20.0);
+        let second = router.route_now(0, &bundle).unwrap().unwrap();
+        let tx_start = second.path_to(1).unwrap()[0].2;
+        assert!(
+            router.now() == Some(20.0) && tx_start >= 20.0,
+            "TEST FAILED: The second bundle should not be sent before the new now, got {}.",
+            tx_start
+        );
+    }
 }
@@ -0,0 +1,123 @@
+extern crate alloc;
+use alloc::{collections::BTreeMap as HashMap, rc::Rc};
+use core::cell::RefCell;
+
+use crate::{contact_manager::TxReject, vertex::VertexID};
+
+/// The number of `TxReject` variants.
+const REJECT_KINDS: usize = 3;
+
+/// Collects the dry run rejections met during pathfinding, per receiving vertex.
+///
+/// A collector is attached to a multigraph with `Multigraph::diagnostics`, the contacts rejected
+/// while searching routes are then recorded until `clear` is called. When a destination cannot
+/// be reached, `dominant_reason` tells why the contacts towards it were rejected most often.
+#[derive(Debug, Default)]
+pub struct TxDiagnostics {
+    /// The number of rejections of each kind, by receiving vertex.
+    rejections: HashMap<VertexID, [usize; REJECT_KINDS]>,
+}
+
+/// A collector shared between a multigraph and the caller.
+pub type SharedTxDiagnostics = Rc<RefCell<TxDiagnostics>>;
+
+impl TxDiagnostics {
+    /// Creates a new, empty `TxDiagnostics`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the rejection of a contact towards `receiver`.
+    pub fn record(&mut self, receiver: VertexID, reason: TxReject) {
+        self.rejections.entry(receiver).or_default()[reason as usize] += 1;
+    }
+
+    /// Returns the number of rejections of a kind recorded for `receiver`.
+    pub fn count(&self, receiver: VertexID, reason: TxReject) -> usize {
+        self.rejections
+            .get(&receiver)
+            .map_or(0, |counts| counts[reason as usize])
+    }
+
+    /// Returns the most frequent rejection reason recorded for `receiver`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<TxReject>` - The most frequent reason, the first in declaration order on ties, or
+    ///   `None` if no rejection was recorded.
+    pub fn dominant_reason(&self, receiver: VertexID) -> Option<TxReject> {
+        let counts = self.rejections.get(&receiver)?;
+        [
+            TxReject::VolumeExceeded,
+            TxReject::DeadlineExceeded,
+            TxReject::Expired,
+        ]
+        .into_iter()
+        .filter(|reason| counts[*reason as usize] > 0)
+        .rev()
+        .max_by_key(|reason| counts[*reason as usize])
+    }
+
+    /// Forgets the recorded rejections.
+    pub fn clear(&mut self) {
+        self.rejections.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::errors::ASABRError;
+    use crate::multigraph::Multigraph;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::test_support::GraphBuilder;
+
+    #[test]
+    fn unreached_destinations_get_their_dominant_reason() -> Result<(), ASABRError> {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 10.0)
+            // opens after the bundle expiration
+            .contact(1, 2, 20.0, 30.0)
+            // too short for the bundle
+            .contact(0, 3, 0.0, 0.5)
+            .plan();
+        let multigraph = Rc::new(RefCell::new(Multigraph::new(plan)?));
+        let diagnostics = Rc::new(RefCell::new(TxDiagnostics::new()));
+        multigraph.borrow_mut().diagnostics = Some(diagnostics.clone());
+
+        let mut pathfinding =
+            NodeParentingTreeExcl::<NoManagement, EVLManager, SABR>::new(multigraph.clone());
+        let tree = pathfinding.get_next(0.0, 0, &make_bundle(2, 0, 1.0, 5.0), &[])?;
+        assert!(
+            tree.by_destination[2].is_none() && tree.by_destination[3].is_none(),
+            "TEST FAILED: Nodes 2 and 3 should not be reached."
+        );
+
+        let diagnostics = diagnostics.borrow();
+        assert_eq!(
+            diagnostics.dominant_reason(2),
+            Some(TxReject::Expired),
+            "TEST FAILED: The contact to node 2 opens after the bundle expiration."
+        );
+        assert_eq!(
+            diagnostics.dominant_reason(3),
+            Some(TxReject::VolumeExceeded),
+            "TEST FAILED: The contact to node 3 cannot carry the bundle."
+        );
+        assert_eq!(
+            diagnostics.dominant_reason(1),
+            None,
+            "TEST FAILED: No contact to node 1 was rejected."
+        );
+        Ok(())
+    }
+}
@@ -2,26 +2,38 @@
 #![allow(clippy::needless_borrow)]
 
 extern crate alloc;
-use alloc::{collections::BTreeMap as HashMap, rc::Rc, vec, vec::Vec};
+use alloc::{
+    collections::{BTreeMap as HashMap, BTreeSet},
+    rc::Rc,
+    vec,
+    vec::Vec,
+};
 use core::cell::RefCell;
 
 use crate::{
     bundle::Bundle,
-    contact::Contact,
-    contact_manager::ContactManager,
-    errors::ASABRError,
+    contact::{Contact, SharedContact},
+    contact_manager::{ContactManager, ContactManagerTxData, ManagerState},
+    errors::{ASABRError, InconsistencyError, RoutingError},
+    multigraph::Multigraph,
     node_manager::NodeManager,
     pathfinding::PathFindingOutput,
-    route_stage::{RouteStage, SharedRouteStage},
-    types::{Date, NodeID},
+    route_stage::{RouteStage, SharedRouteStage, StageKey},
+    types::{Date, HopCount, NodeID},
 };
+use observer::RoutingObserver;
 
 pub mod aliases;
 pub mod cgr;
+pub mod diagnostics;
+pub mod observer;
+#[cfg(feature = "serde")]
+pub mod serialization;
 pub mod spsn;
+pub mod trace;
 pub mod volcgr;
 
-type FirstHopsVec<NM, CM> = (
+pub type FirstHopsVec<NM, CM> = (
     Rc<RefCell<Contact<NM, CM>>>,
     Vec<Rc<RefCell<RouteStage<NM, CM>>>>,
 );
@@ -29,8 +41,93 @@ type FirstHop<NM, CM> = (
     Rc<RefCell<Contact<NM, CM>>>,
     Rc<RefCell<RouteStage<NM, CM>>>,
 );
+type FirstHopEntry<'a, NM, CM> = (&'a SharedContact<NM, CM>, &'a SharedRouteStage<NM, CM>);
+
+/// The identity of a shared (`Rc`) value, used to group items attached to the same value.
+///
+/// The key is the address of the allocation. It is only used by groupings that keep a clone of
+/// the `Rc` along with the key (see `group_entry`), so the address cannot be reused while the key
+/// is in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RcKey(usize);
+
+impl RcKey {
+    /// Returns the key of the value pointed to by `rc`.
+    pub fn of<T>(rc: &Rc<RefCell<T>>) -> Self {
+        RcKey(Rc::as_ptr(rc) as usize)
+    }
+}
+
+/// The groups built by `group_entry`: each shared value with the items attached to it.
+type Groups<T, V> = HashMap<RcKey, (Rc<RefCell<T>>, Vec<V>)>;
+
+/// Returns the items attached to `shared` in `groups`, creating an empty group if needed.
+fn group_entry<'a, T, V>(groups: &'a mut Groups<T, V>, shared: &Rc<RefCell<T>>) -> &'a mut Vec<V> {
+    &mut groups
+        .entry(RcKey::of(shared))
+        .or_insert_with(|| (shared.clone(), Vec::new()))
+        .1
+}
+
+/// The stages to follow next, each with the destinations reached through it.
+type StageGroups<NM, CM> = Vec<(SharedRouteStage<NM, CM>, Vec<NodeID>)>;
+
+/// Merges the groups of the distinct stages sharing a `StageKey`, i.e. the identical sub-routes
+/// reached through different parents, so that they are followed once.
+///
+/// The destinations of a stage are moved to the first stage with the same key that also leads
+/// to all of them (the stage is a destination, or the tree continues from it towards them). The
+/// other groups are kept as is, in their order.
+fn merge_identical_stages<NM: NodeManager, CM: ContactManager>(
+    groups: Groups<RouteStage<NM, CM>, NodeID>,
+) -> Result<StageGroups<NM, CM>, ASABRError> {
+    let mut merged: StageGroups<NM, CM> = Vec::new();
+    let mut by_key: HashMap<StageKey, Vec<usize>> = HashMap::new();
+    for (stage, dests) in groups.into_values() {
+        let candidates = by_key
+            .entry(StageKey::of(&*stage.try_borrow()?))
+            .or_default();
+        let mut target = None;
+        for &idx in candidates.iter() {
+            let kept = merged[idx].0.try_borrow()?;
+            if dests
+                .iter()
+                .all(|dest| kept.to_node == *dest || kept.next_for_destination.contains_key(dest))
+            {
+                target = Some(idx);
+                break;
+            }
+        }
+        match target {
+            Some(idx) => merged[idx].1.extend(dests),
+            None => {
+                candidates.push(merged.len());
+                merged.push((stage, dests));
+            }
+        }
+    }
+    Ok(merged)
+}
+
+/// Records the visit of a stage while following a tree, failing if it was already visited.
+///
+/// A tree built by a faulty pathfinding could link its stages in a cycle, that would be
+/// followed forever otherwise.
+fn visit(
+    visited: &mut BTreeSet<RcKey>,
+    stage: &SharedRouteStage<impl NodeManager, impl ContactManager>,
+) -> Result<(), ASABRError> {
+    if !visited.insert(RcKey::of(stage)) {
+        let to_node = stage.try_borrow()?.to_node;
+        return Err(RoutingError::CyclicTree { to_node }.into());
+    }
+    Ok(())
+}
 
 /// A trait to allow generic initialization of routers.
+///
+/// `Spsn`, `Cgr` and `VolCgr` implement it, they can be used as `Box<dyn Router<NM, CM>>`, see
+/// `aliases::build_generic_router`.
 pub trait Router<NM: NodeManager, CM: ContactManager> {
     /// Routes a bundle to its destination(s) using either unicast or multicast routing,
     /// depending on the number of destinations.
@@ -54,6 +151,286 @@ pub trait Router<NM: NodeManager, CM: ContactManager> {
         curr_time: Date,
         excluded_nodes: &[NodeID],
     ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError>;
+
+    /// Routes a bundle like `route`, without using the excluded contacts.
+    ///
+    /// A contact is identified by its transmitting node, receiving node and start time. The
+    /// exclusions only apply to this call, but the trees or routes computed are stored as for
+    /// `route` (they remain valid, if possibly not optimal, once the contacts are available again).
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing operation.
+    /// - `bundle`: The `Bundle` containing destination information and other relevant routing data.
+    /// - `curr_time`: The current time, which affects scheduling and time-sensitive routing calculations.
+    /// - `excluded_nodes`: A list of nodes to exclude from the routing paths.
+    /// - `excluded_contacts`: The (tx, rx, start) of the contacts to exclude from the routing paths.
+    ///
+    /// The default implementation can only route without excluded contacts, it has no access to
+    /// the multigraph to flag them.
+    ///
+    /// # Returns
+    /// The same as `route`, or `RoutingError::ContactExclusionUnsupported` with the default
+    /// implementation if `excluded_contacts` is not empty.
+    fn route_with_constraints(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+        excluded_contacts: &[(NodeID, NodeID, Date)],
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        if !excluded_contacts.is_empty() {
+            return Err(RoutingError::ContactExclusionUnsupported.into());
+        }
+        self.route(source, bundle, curr_time, excluded_nodes)
+    }
+
+    /// Returns the current time of the router, used by `route_now`, e.g. set with
+    /// `Spsn::set_now`.
+    ///
+    /// The default implementation has no current time and returns `None`.
+    fn now(&self) -> Option<Date> {
+        None
+    }
+
+    /// Routes a bundle like `route`, from the current time of the router and excluding no node.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing operation.
+    /// - `bundle`: The `Bundle` to route.
+    ///
+    /// # Returns
+    /// The output of `route` at `now()`, or `RoutingError::NoCurrentTime` if the router has no
+    /// current time.
+    fn route_now(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        let Some(now) = self.now() else {
+            return Err(RoutingError::NoCurrentTime.into());
+        };
+        self.route(source, bundle, now, &[])
+    }
+}
+
+/// Checks that the destinations of `bundle` are vertices of `multigraph`.
+///
+/// The bundles may come from untrusted sources, an unknown destination must not be used as an
+/// index in the trees.
+fn has_known_destinations<NM: NodeManager, CM: ContactManager>(
+    bundle: &Bundle,
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+) -> Result<bool, ASABRError> {
+    let vertex_count = multigraph.try_borrow()?.get_vertex_count();
+    Ok(bundle
+        .destinations
+        .iter()
+        .all(|dest| (*dest as usize) < vertex_count))
+}
+
+/// Checks that the priority of `bundle` is a level distinguished by the contact managers of
+/// `multigraph`, see `Multigraph::priority_levels`.
+///
+/// A priority out of range would be clamped by the managers, silently changing the volume
+/// available to the bundle.
+fn check_priority<NM: NodeManager, CM: ContactManager>(
+    bundle: &Bundle,
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+) -> Result<(), ASABRError> {
+    if let Some(levels) = multigraph.try_borrow()?.priority_levels()
+        && (bundle.priority < 0 || bundle.priority as usize >= levels)
+    {
+        return Err(RoutingError::InvalidPriority {
+            priority: bundle.priority,
+            levels,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Runs `routing` with the `excluded_contacts` flagged in `multigraph`, clearing the flags after.
+fn with_excluded_contacts<NM: NodeManager, CM: ContactManager, T>(
+    multigraph: &Rc<RefCell<Multigraph<NM, CM>>>,
+    excluded_contacts: &[(NodeID, NodeID, Date)],
+    routing: impl FnOnce() -> Result<T, ASABRError>,
+) -> Result<T, ASABRError> {
+    if excluded_contacts.is_empty() {
+        return routing();
+    }
+    multigraph
+        .try_borrow_mut()?
+        .prepare_for_contact_exclusions(excluded_contacts)?;
+    let result = routing();
+    multigraph
+        .try_borrow_mut()?
+        .prepare_for_contact_exclusions(&[])?;
+    result
+}
+
+/// The resources of the contacts touched by a routing operation, captured before their first
+/// booking (see `ContactManager::snapshot`) so that the operation can be undone whether or not
+/// the managers support cancellation. The node managers are not captured.
+pub(crate) struct Rollback<NM: NodeManager, CM: ContactManager> {
+    /// The contacts already captured.
+    seen: BTreeSet<RcKey>,
+    /// The contacts with the state of their manager before the operation.
+    states: Vec<(SharedContact<NM, CM>, ManagerState)>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> Rollback<NM, CM> {
+    pub(crate) fn new() -> Self {
+        Rollback {
+            seen: BTreeSet::new(),
+            states: Vec::new(),
+        }
+    }
+
+    /// Captures the state of `contact`, unless it was already captured.
+    pub(crate) fn capture(&mut self, contact: &SharedContact<NM, CM>) -> Result<(), ASABRError> {
+        if self.seen.insert(RcKey::of(contact)) {
+            let state = contact.try_borrow()?.manager.snapshot();
+            self.states.push((contact.clone(), state));
+        }
+        Ok(())
+    }
+
+    /// Captures the contacts of the route leading to `stage`.
+    pub(crate) fn capture_route(
+        &mut self,
+        stage: &SharedRouteStage<NM, CM>,
+    ) -> Result<(), ASABRError> {
+        let mut curr = stage.clone();
+        loop {
+            let parent = {
+                let stage = curr.try_borrow()?;
+                let Some(via) = &stage.via else {
+                    return Ok(());
+                };
+                self.capture(&via.contact)?;
+                via.parent_route.clone()
+            };
+            curr = parent;
+        }
+    }
+
+    /// Restores every captured contact to its state before the operation. The restoration goes
+    /// on after a failure, the first failure being returned.
+    pub(crate) fn restore(&self) -> Result<(), ASABRError> {
+        let mut result = Ok(());
+        for (contact, state) in &self.states {
+            let restored = match contact.try_borrow_mut() {
+                Ok(mut contact) => {
+                    contact.generation += 1;
+                    if contact.manager.restore(state.as_ref()) {
+                        Ok(())
+                    } else {
+                        Err(RoutingError::RollbackFailed.into())
+                    }
+                }
+                Err(err) => Err(err.into()),
+            };
+            if result.is_ok() {
+                result = restored;
+            }
+        }
+        result
+    }
+}
+
+/// A summary of the quality of the route scheduled to a destination.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteMetrics {
+    /// The arrival time at the destination.
+    pub arrival_time: Date,
+    /// The number of hops to the destination.
+    pub hop_count: HopCount,
+    /// The time at which the route expires.
+    pub expiration: Date,
+}
+
+impl RouteMetrics {
+    /// Reads the metrics of the stage reaching the destination.
+    pub fn of<NM: NodeManager, CM: ContactManager>(stage: &RouteStage<NM, CM>) -> Self {
+        Self {
+            arrival_time: stage.at_time,
+            hop_count: stage.hop_count,
+            expiration: stage.expiration,
+        }
+    }
+}
+
+/// The first hop contacts of a routing output, each with the route stages reached through it.
+///
+/// An output has a handful of first hops (a single one for unicast), they are kept in a vector
+/// in insertion order and found by identity with a linear search, cheaper than a map here.
+#[derive(Debug)]
+pub struct FirstHops<NM: NodeManager, CM: ContactManager> {
+    groups: Vec<FirstHopsVec<NM, CM>>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> FirstHops<NM, CM> {
+    /// Creates an empty set of first hops.
+    pub fn new() -> Self {
+        Self { groups: Vec::new() }
+    }
+
+    /// Creates the first hops of a unicast route, reaching `stage` through `contact`.
+    pub fn single(contact: Rc<RefCell<Contact<NM, CM>>>, stage: SharedRouteStage<NM, CM>) -> Self {
+        Self {
+            groups: vec![(contact, vec![stage])],
+        }
+    }
+
+    /// Returns the stages reached through `contact`, adding `contact` as a first hop if needed.
+    pub fn group(
+        &mut self,
+        contact: &Rc<RefCell<Contact<NM, CM>>>,
+    ) -> &mut Vec<SharedRouteStage<NM, CM>> {
+        let idx = match self.position(RcKey::of(contact)) {
+            Some(idx) => idx,
+            None => {
+                self.groups.push((contact.clone(), Vec::new()));
+                self.groups.len() - 1
+            }
+        };
+        &mut self.groups[idx].1
+    }
+
+    /// Returns the stages reached through the first hop contact identified by `key`, if any.
+    pub fn get_mut(&mut self, key: RcKey) -> Option<&mut Vec<SharedRouteStage<NM, CM>>> {
+        let idx = self.position(key)?;
+        Some(&mut self.groups[idx].1)
+    }
+
+    /// Returns the index of the first hop contact identified by `key`.
+    fn position(&self, key: RcKey) -> Option<usize> {
+        self.groups
+            .iter()
+            .position(|(contact, _)| RcKey::of(contact) == key)
+    }
+
+    /// Iterates over the first hop contacts, each with the stages reached through it.
+    pub fn values(&self) -> core::slice::Iter<'_, FirstHopsVec<NM, CM>> {
+        self.groups.iter()
+    }
+
+    /// Returns the number of first hop contacts.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Checks whether there is no first hop.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+impl<NM: NodeManager, CM: ContactManager> Default for FirstHops<NM, CM> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// A struct that represents the output of a routing operation.
@@ -63,27 +440,185 @@ pub trait Router<NM: NodeManager, CM: ContactManager> {
 ///
 /// # Fields
 ///
-/// * `first_hops` - A hashmap mapping from a unique identifier (e.g., an index or destination ID)
-///   to a `FirstHopsVec` tuple containing:
+/// * `first_hops` - The `FirstHops`, a `FirstHopsVec` tuple per first hop contact containing:
 ///     - `Rc<RefCell<Contact<NM, CM>>>`: A reference-counted, mutable reference to the `Contact`
 ///       that represents the first hop for the respective route.
 ///     - `Vec<Rc<RefCell<RouteStage<NM, CM>>>>`: A vector of reference-counted, mutable
 ///       references to `RouteStage`s to the nodes that can be reached from the first hop.
+/// * `route_metrics` - The `RouteMetrics` of the route scheduled to each reached destination.
 #[derive(Debug)]
 pub struct RoutingOutput<NM: NodeManager, CM: ContactManager> {
-    pub first_hops: HashMap<usize, FirstHopsVec<NM, CM>>,
+    pub first_hops: FirstHops<NM, CM>,
+    pub route_metrics: HashMap<NodeID, RouteMetrics>,
 }
 
 impl<NM: NodeManager, CM: ContactManager> RoutingOutput<NM, CM> {
     pub fn lazy_get_for_unicast(&self, dest: NodeID) -> Option<FirstHop<NM, CM>> {
-        for (contact, dest_routes) in self.first_hops.values() {
-            for route_rc in dest_routes {
-                if route_rc.borrow().to_node == dest {
-                    return Some((contact.clone(), route_rc.clone()));
-                }
+        self.first_hop_entries()
+            .find(|(_, route_rc)| route_rc.borrow().to_node == dest)
+            .map(|(contact, route_rc)| (contact.clone(), route_rc.clone()))
+    }
+
+    /// Iterates over the routes of the output, each with the first hop contact it starts with.
+    ///
+    /// # Returns
+    ///
+    /// * The (first hop contact, destination stage) pairs, each route being yielded once.
+    pub fn first_hop_entries(
+        &self,
+    ) -> impl Iterator<Item = (&SharedContact<NM, CM>, &SharedRouteStage<NM, CM>)> {
+        self.first_hops
+            .values()
+            .flat_map(|(contact, routes)| routes.iter().map(move |route| (contact, route)))
+    }
+
+    /// Lists the routes of the output like `first_hop_entries`, in a stable order.
+    ///
+    /// The entries follow the order of the search, which depends on how ties between equal-cost
+    /// first hops were broken. They are sorted here by first hop contact (transmitting node,
+    /// receiving node, start) then destination, e.g. for tests and reproducible simulations.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<...>)` - The (first hop contact, destination stage) pairs in the stable order.
+    /// * `Err(ASABRError)` - If a contact or a stage cannot be borrowed.
+    pub fn sorted_first_hop_entries(&self) -> Result<Vec<FirstHopEntry<'_, NM, CM>>, ASABRError> {
+        let mut entries = Vec::new();
+        for (contact, route) in self.first_hop_entries() {
+            let info = contact.try_borrow()?.info;
+            let to_node = route.try_borrow()?.to_node;
+            entries.push((
+                (info.tx_node_id, info.rx_node_id, info.start, to_node),
+                (contact, route),
+            ));
+        }
+        entries.sort_by(|(a, _), (b, _)| {
+            (a.0, a.1)
+                .cmp(&(b.0, b.1))
+                .then(a.2.total_cmp(&b.2))
+                .then(a.3.cmp(&b.3))
+        });
+        Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+    }
+
+    /// Returns the hops of the route to `dest`, see `RouteStage::path`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(NodeID, NodeID, Date)>)` - The transmitting node, receiving node and
+    ///   transmission start of each hop from the source, empty if `dest` is not reached.
+    /// * `Err(ASABRError)` - If a stage or a contact of the route cannot be borrowed.
+    pub fn path_to(&self, dest: NodeID) -> Result<Vec<(NodeID, NodeID, Date)>, ASABRError> {
+        match self.lazy_get_for_unicast(dest) {
+            Some((_, route)) => route.try_borrow()?.path(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the destinations a route was scheduled to, in increasing ID order.
+    pub fn reached(&self) -> Vec<NodeID> {
+        self.route_metrics.keys().copied().collect()
+    }
+
+    /// Returns the destinations of `bundle` no route was scheduled to, e.g. to retry them
+    /// through other paths.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle` - The bundle the output was computed for.
+    ///
+    /// # Returns
+    ///
+    /// * The destinations not reached, in the order of `bundle.destinations`.
+    pub fn unreached(&self, bundle: &Bundle) -> Vec<NodeID> {
+        bundle
+            .destinations
+            .iter()
+            .copied()
+            .filter(|dest| !self.route_metrics.contains_key(dest))
+            .collect()
+    }
+
+    /// Checks that every route of the output forms a connected chain of hops.
+    ///
+    /// Each route is walked from its destination stage back to the source stage. Along the way,
+    /// the receiving node of a hop must be the transmitting node of the next hop, the arrival
+    /// times must not decrease, and the first contact must be the first hop the route is
+    /// registered under.
+    ///
+    /// # Returns
+    /// * `Ok(())` if all the routes are consistent, the first inconsistency found otherwise.
+    pub fn verify_consistency(&self) -> Result<(), InconsistencyError> {
+        for (first_hop, route_rc) in self.first_hop_entries() {
+            let to_node = route_rc.borrow().to_node;
+            let mut curr = route_rc.clone();
+            loop {
+                let next = {
+                    let stage = curr.borrow();
+                    let Some(via) = &stage.via else {
+                        // a route without any hop cannot start with its first hop
+                        return Err(InconsistencyError::FirstHopMismatch { to_node });
+                    };
+                    let parent = via.parent_route.borrow();
+                    if parent.at_time > stage.at_time {
+                        return Err(InconsistencyError::TimeRegression {
+                            from: parent.at_time,
+                            to: stage.at_time,
+                        });
+                    }
+                    let tx_node = via.tx_node.borrow().info.id;
+                    let parent_node = match &parent.via {
+                        Some(parent_via) => parent_via.rx_node.borrow().info.id,
+                        None => parent.to_node,
+                    };
+                    if parent_node != tx_node {
+                        return Err(InconsistencyError::NodeDiscontinuity {
+                            rx_node: parent_node,
+                            next_tx_node: tx_node,
+                        });
+                    }
+                    if parent.via.is_none() {
+                        if !Rc::ptr_eq(&via.contact, first_hop) {
+                            return Err(InconsistencyError::FirstHopMismatch { to_node });
+                        }
+                        break;
+                    }
+                    via.parent_route.clone()
+                };
+                curr = next;
             }
         }
-        None
+        Ok(())
+    }
+}
+
+/// The transmitting node, receiving node and transmission start of each hop of a route.
+pub type HopList = Vec<(NodeID, NodeID, Date)>;
+
+/// The routes of a `RoutingOutput` detached from the multigraph, holding no shared pointer (e.g.
+/// to be sent to another thread).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteSummary {
+    /// The metrics and the hops (see `RoutingOutput::path_to`) of the route to each reached
+    /// destination.
+    pub routes: HashMap<NodeID, (RouteMetrics, HopList)>,
+}
+
+impl RouteSummary {
+    /// Reads the routes of `output`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RouteSummary)` - The routes to the destinations reached by `output`.
+    /// * `Err(ASABRError)` - If a stage or a contact of a route cannot be borrowed.
+    pub fn of<NM: NodeManager, CM: ContactManager>(
+        output: &RoutingOutput<NM, CM>,
+    ) -> Result<Self, ASABRError> {
+        let mut routes = HashMap::new();
+        for (&dest, &metrics) in &output.route_metrics {
+            routes.insert(dest, (metrics, output.path_to(dest)?));
+        }
+        Ok(Self { routes })
     }
 }
 
@@ -104,10 +639,12 @@ pub fn dry_run_multicast<NM: NodeManager, CM: ContactManager>(
 
     let source_route = tree_ref.get_source_route();
     let mut accumulator = vec![(source_route, true, at_time, dests_in_tree)];
+    let mut visited = BTreeSet::new();
     #[cfg(not(feature = "node_proc"))]
     let bundle_to_consider = bundle;
 
     while let Some((current_route, is_source, mut time, downstream_dests)) = accumulator.pop() {
+        visit(&mut visited, &current_route)?;
         let mut route_borrowed = current_route.try_borrow_mut()?;
 
         #[cfg(feature = "node_proc")]
@@ -122,21 +659,15 @@ pub fn dry_run_multicast<NM: NodeManager, CM: ContactManager>(
 
         let reached_node = route_borrowed.to_node;
 
-        let mut next_routes: HashMap<usize, (SharedRouteStage<NM, CM>, Vec<NodeID>)> =
-            HashMap::new();
+        let mut next_routes: Groups<RouteStage<NM, CM>, NodeID> = HashMap::new();
         for dest in downstream_dests {
             if reached_node == dest {
                 reached_destinations.push(dest);
             } else if let Some(next_route) = route_borrowed.next_for_destination.get(&dest) {
-                let ptr = Rc::as_ptr(next_route) as usize;
-                if let Some((_, entry)) = next_routes.get_mut(&ptr) {
-                    entry.push(dest);
-                } else {
-                    next_routes.insert(ptr, (next_route.clone(), vec![dest]));
-                }
+                group_entry(&mut next_routes, next_route).push(dest);
             }
         }
-        for (_ptr, (next_route, next_downstream_dests)) in next_routes {
+        for (next_route, next_downstream_dests) in merge_identical_stages(next_routes)? {
             accumulator.push((next_route, false, time, next_downstream_dests));
         }
     }
@@ -144,71 +675,133 @@ pub fn dry_run_multicast<NM: NodeManager, CM: ContactManager>(
     Ok(reached_destinations)
 }
 
-type FirstHopPtr = Option<usize>;
+type FirstHopKey = Option<RcKey>;
 type Destinations = Vec<NodeID>;
 
+/// The stages scheduled by a routing operation, with the transmission booked to reach each.
+type ScheduledHops<NM, CM> = Vec<(SharedRouteStage<NM, CM>, ContactManagerTxData)>;
+
+/// Tells `observer` about the transmission booked to reach `stage`, for `bundle` (the bundle of
+/// the stage with `node_proc`).
+fn notify_scheduled<NM: NodeManager, CM: ContactManager>(
+    observer: Option<&dyn RoutingObserver>,
+    stage: &RouteStage<NM, CM>,
+    _bundle: &Bundle,
+    tx_data: &ContactManagerTxData,
+) -> Result<(), ASABRError> {
+    let (Some(observer), Some(via)) = (observer, &stage.via) else {
+        return Ok(());
+    };
+    #[cfg(feature = "node_proc")]
+    let bundle_to_consider = &stage.bundle;
+    #[cfg(not(feature = "node_proc"))]
+    let bundle_to_consider = _bundle;
+    observer.on_scheduled(bundle_to_consider, &via.contact.try_borrow()?.info, tx_data);
+    Ok(())
+}
+
+/// Schedules the branches of a multicast tree to the destinations that passed the dry run, all
+/// or none of them.
+///
+/// The branches were dry run one by one, scheduling them together can still fail (e.g. a contact
+/// shared by two branches). If a stage fails to be scheduled, the contacts booked by the other
+/// stages are restored to their state before the schedule (see `ContactManager::snapshot`, the
+/// node managers keep their bookings) and the scheduling error is returned. Otherwise, the hops
+/// are reported to `observer` in the order they were scheduled.
 fn update_multicast<NM: NodeManager, CM: ContactManager>(
+    bundle: &Bundle,
+    at_time: Date,
+    reachable_after_dry_run: Vec<NodeID>,
+    source_route: SharedRouteStage<NM, CM>,
+    observer: Option<&dyn RoutingObserver>,
+) -> Result<RoutingOutput<NM, CM>, ASABRError> {
+    let mut scheduled = Vec::new();
+    let mut rollback = Rollback::new();
+    let output = match update_multicast_branches(
+        bundle,
+        at_time,
+        reachable_after_dry_run,
+        source_route,
+        &mut scheduled,
+        &mut rollback,
+    ) {
+        Ok(output) => output,
+        Err(err) => {
+            // the scheduling error is the cause, a failed restoration is not reported over it
+            let _ = rollback.restore();
+            return Err(err);
+        }
+    };
+    for (stage, tx_data) in &scheduled {
+        notify_scheduled(observer, &*stage.try_borrow()?, bundle, tx_data)?;
+    }
+    Ok(output)
+}
+
+/// Schedules the branches for `update_multicast`, recording the scheduled stages in `scheduled`
+/// and capturing each contact in `rollback` before it is booked.
+fn update_multicast_branches<NM: NodeManager, CM: ContactManager>(
     _bundle: &Bundle,
     at_time: Date,
     reachable_after_dry_run: Vec<NodeID>,
     source_route: SharedRouteStage<NM, CM>,
+    scheduled: &mut ScheduledHops<NM, CM>,
+    rollback: &mut Rollback<NM, CM>,
 ) -> Result<RoutingOutput<NM, CM>, ASABRError> {
-    let mut first_hops_map: HashMap<usize, FirstHopsVec<NM, CM>> = HashMap::new();
-    let mut accumulator: Vec<(SharedRouteStage<NM, CM>, FirstHopPtr, Date, Destinations)> =
+    let mut first_hops_map = FirstHops::new();
+    let mut route_metrics = HashMap::new();
+    let mut accumulator: Vec<(SharedRouteStage<NM, CM>, FirstHopKey, Date, Destinations)> =
         vec![(source_route, None, at_time, reachable_after_dry_run)];
+    let mut visited = BTreeSet::new();
     #[cfg(not(feature = "node_proc"))]
     let bundle_to_consider = _bundle;
 
-    while let Some((current_route, mut first_hop_ptr, mut time, downstream_dests)) =
-        accumulator.pop()
-    {
+    while let Some((current_route, first_hop_key, mut time, downstream_dests)) = accumulator.pop() {
+        visit(&mut visited, &current_route)?;
         let mut route_borrowed = current_route.try_borrow_mut()?;
 
         #[cfg(feature = "node_proc")]
         let bundle_to_consider = route_borrowed.bundle.clone();
 
-        if first_hop_ptr.is_some() {
-            if route_borrowed.schedule(time, &bundle_to_consider).is_err() {
-                continue;
+        if first_hop_key.is_some() {
+            if let Some(via) = &route_borrowed.via {
+                rollback.capture(&via.contact)?;
             }
+            let tx_data = route_borrowed.schedule(time, &bundle_to_consider)?;
+            scheduled.push((current_route.clone(), tx_data));
             time = route_borrowed.at_time;
         }
         let reached_node = route_borrowed.to_node;
 
-        let mut next_routes: HashMap<usize, (SharedRouteStage<NM, CM>, Vec<NodeID>)> =
-            HashMap::new();
+        let mut next_routes: Groups<RouteStage<NM, CM>, NodeID> = HashMap::new();
         for dest in downstream_dests {
             if reached_node == dest {
-                if let Some(ptr) = first_hop_ptr
-                    && let Some((_, rts)) = first_hops_map.get_mut(&ptr)
+                if let Some(key) = first_hop_key
+                    && let Some(rts) = first_hops_map.get_mut(key)
                 {
                     rts.push(current_route.clone());
+                    route_metrics.insert(dest, RouteMetrics::of(&route_borrowed));
                 }
             } else if let Some(next_route) = route_borrowed.next_for_destination.get(&dest) {
-                let ptr = Rc::as_ptr(next_route) as usize;
-                if let Some((_, entry)) = next_routes.get_mut(&ptr) {
-                    entry.push(dest);
-                } else {
-                    next_routes.insert(ptr, (next_route.clone(), vec![dest]));
-                }
+                group_entry(&mut next_routes, next_route).push(dest);
             }
         }
-        for (_ptr, (next_route, next_downstream_dests)) in next_routes {
-            if first_hop_ptr.is_none() {
+        for (next_route, next_downstream_dests) in merge_identical_stages(next_routes)? {
+            // from the source, each branch starts with its own first hop
+            let mut next_first_hop_key = first_hop_key;
+            if next_first_hop_key.is_none() {
                 let first_hop_contact = next_route.borrow().get_via_contact();
                 if let Some(first_hop_contact) = first_hop_contact {
-                    let ptr = first_hop_contact.as_ptr() as usize;
-                    first_hop_ptr = Some(ptr);
-                    first_hops_map
-                        .entry(ptr)
-                        .or_insert_with(|| (first_hop_contact, Vec::new()));
+                    first_hops_map.group(&first_hop_contact);
+                    next_first_hop_key = Some(RcKey::of(&first_hop_contact));
                 }
             }
-            accumulator.push((next_route, first_hop_ptr, time, next_downstream_dests));
+            accumulator.push((next_route, next_first_hop_key, time, next_downstream_dests));
         }
     }
     Ok(RoutingOutput {
         first_hops: first_hops_map,
+        route_metrics,
     })
 }
 
@@ -224,6 +817,7 @@ fn update_multicast<NM: NodeManager, CM: ContactManager>(
 /// * `tree` - A reference to the pathfinding output.
 /// * `targets_opt` - An optional list of target node IDs. If `None`,
 ///   the function will perform a dry run to determine reachable targets.
+/// * `observer` - The observer told about the scheduled hops, if any.
 /// # Returns
 ///
 /// * `Result<RoutingOutput<NM, CM>, ASABRError>` - The routing output, or an error if the operation fails.
@@ -232,13 +826,14 @@ fn schedule_multicast<NM: NodeManager, CM: ContactManager>(
     curr_time: Date,
     tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
     targets_opt: Option<Vec<NodeID>>,
+    observer: Option<&dyn RoutingObserver>,
 ) -> Result<RoutingOutput<NM, CM>, ASABRError> {
     let targets = match targets_opt {
         Some(targets) => targets,
         None => dry_run_multicast(bundle, curr_time, tree.clone())?,
     };
     let source_route = tree.borrow().get_source_route();
-    update_multicast(bundle, curr_time, targets, source_route.clone())
+    update_multicast(bundle, curr_time, targets, source_route.clone(), observer)
 }
 
 pub fn dry_run_unicast_path<NM: NodeManager, CM: ContactManager>(
@@ -331,11 +926,21 @@ pub fn dry_run_unicast_tree<NM: NodeManager, CM: ContactManager>(
 /// * `dest` - The destination for the bundle.
 /// * `at_time` - The current date/time for the routing operation.
 /// * `source_route` - The source route.
+/// * `observer` - The observer told about the hops once the whole route is scheduled, if any.
+///
+/// If a hop fails to be scheduled, the contacts booked by the previous hops are restored (see
+/// `ContactManager::snapshot`, the node managers keep their bookings) and nothing is reported.
+///
+/// # Returns
+///
+/// * `Result<RoutingOutput<NM, CM>, ASABRError>` - The routing output, or a `RoutingError` if the
+///   route cannot be scheduled as the dry run predicted (no panic is raised in that case).
 fn update_unicast<NM: NodeManager, CM: ContactManager>(
     _bundle: &Bundle,
     dest: NodeID,
-    mut at_time: Date,
+    at_time: Date,
     source_route: SharedRouteStage<NM, CM>,
+    observer: Option<&dyn RoutingObserver>,
 ) -> Result<RoutingOutput<NM, CM>, ASABRError> {
     if source_route.borrow().to_node == dest {
         return Err(ASABRError::ScheduleError(
@@ -343,12 +948,46 @@ fn update_unicast<NM: NodeManager, CM: ContactManager>(
         ));
     }
 
-    let mut curr_opt = source_route
+    let curr_opt = source_route
         .borrow()
         .next_for_destination
         .get(&dest)
         .cloned();
 
+    let mut scheduled = Vec::new();
+    let mut rollback = Rollback::new();
+    match update_unicast_hops(
+        _bundle,
+        dest,
+        at_time,
+        curr_opt,
+        &mut scheduled,
+        &mut rollback,
+    ) {
+        Ok(output) => {
+            for (stage, tx_data) in &scheduled {
+                notify_scheduled(observer, &*stage.try_borrow()?, _bundle, tx_data)?;
+            }
+            Ok(output)
+        }
+        Err(err) => {
+            // the scheduling error is the cause, a failed restoration is not reported over it
+            let _ = rollback.restore();
+            Err(err)
+        }
+    }
+}
+
+/// Schedules the hops for `update_unicast` from `curr_opt`, recording the scheduled stages in
+/// `scheduled` and capturing each contact in `rollback` before it is booked.
+fn update_unicast_hops<NM: NodeManager, CM: ContactManager>(
+    _bundle: &Bundle,
+    dest: NodeID,
+    mut at_time: Date,
+    mut curr_opt: Option<SharedRouteStage<NM, CM>>,
+    scheduled: &mut ScheduledHops<NM, CM>,
+    rollback: &mut Rollback<NM, CM>,
+) -> Result<RoutingOutput<NM, CM>, ASABRError> {
     let mut first_hop: Option<Rc<RefCell<Contact<NM, CM>>>> = None;
     #[cfg(not(feature = "node_proc"))]
     let bundle_to_consider = _bundle;
@@ -362,23 +1001,31 @@ fn update_unicast<NM: NodeManager, CM: ContactManager>(
         #[cfg(feature = "node_proc")]
         let bundle_to_consider = curr_route_borrowed.bundle.clone();
 
-        curr_route_borrowed.schedule(at_time, &bundle_to_consider)?;
+        if let Some(via) = &curr_route_borrowed.via {
+            rollback.capture(&via.contact)?;
+        }
+        let tx_data = curr_route_borrowed.schedule(at_time, &bundle_to_consider)?;
+        scheduled.push((curr_route.clone(), tx_data));
 
         at_time = curr_route_borrowed.at_time;
 
         if curr_route_borrowed.to_node == dest {
             if let Some(first) = first_hop {
-                let mut first_hops: HashMap<usize, FirstHopsVec<NM, CM>> = HashMap::new();
-                first_hops.insert(first.as_ptr() as usize, (first, vec![curr_route.clone()]));
-                return Ok(RoutingOutput { first_hops });
+                let first_hops = FirstHops::single(first, curr_route.clone());
+                let mut route_metrics = HashMap::new();
+                route_metrics.insert(dest, RouteMetrics::of(&curr_route_borrowed));
+                return Ok(RoutingOutput {
+                    first_hops,
+                    route_metrics,
+                });
             }
-            return Err(ASABRError::ScheduleError("First hop tracking issue"));
+            return Err(RoutingError::MissingFirstHop.into());
         }
 
         curr_opt = curr_route_borrowed.next_for_destination.get(&dest).cloned();
     }
 
-    Err(ASABRError::ScheduleError("Faulty dry run"))
+    Err(RoutingError::DryRunScheduleMismatch.into())
 }
 
 /// Schedules a unicast routing operation, optionally initializing the multicast tree.
@@ -396,6 +1043,7 @@ fn update_unicast<NM: NodeManager, CM: ContactManager>(
 ///   which holds route stages by destination.
 /// - `init_tree`: A boolean flag indicating whether to initialize the tree for routing to the
 ///   destination node.
+/// - `observer`: The observer told about the scheduled hops, if any.
 ///
 /// # Returns
 /// Returns a `Result<RoutingOutput<NM, CM>, ASABRError> ` containing the scheduled routing details,
@@ -405,13 +1053,14 @@ fn schedule_unicast<NM: NodeManager, CM: ContactManager>(
     curr_time: Date,
     tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
     init_tree: bool,
+    observer: Option<&dyn RoutingObserver>,
 ) -> Result<RoutingOutput<NM, CM>, ASABRError> {
     if init_tree {
         tree.borrow().init_for_destination(bundle.destinations[0])?;
     }
     let dest = bundle.destinations[0];
     let source_route = tree.borrow().get_source_route();
-    update_unicast(bundle, dest, curr_time, source_route.clone())
+    update_unicast(bundle, dest, curr_time, source_route.clone(), observer)
 }
 
 /// Schedules a unicast pathfinding operation for a given source route without tree initialization.
@@ -435,5 +1084,908 @@ fn schedule_unicast_path<NM: NodeManager, CM: ContactManager>(
     source_route: SharedRouteStage<NM, CM>,
 ) -> Result<RoutingOutput<NM, CM>, ASABRError> {
     let dest = bundle.destinations[0];
-    update_unicast(bundle, dest, curr_time, source_route.clone())
+    update_unicast(bundle, dest, curr_time, source_route.clone(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact::ContactInfo;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingPath;
+    use crate::pathfinding::test_helpers::*;
+    use crate::route_stage::ViaHop;
+    use crate::types::{Priority, Volume};
+    use alloc::boxed::Box;
+    use core::any::Any;
+
+    fn make_stage(
+        at_time: Date,
+        parent: &SharedRouteStage<NoManagement, EVLManager>,
+        contact: &Rc<RefCell<Contact<NoManagement, EVLManager>>>,
+        bundle: &Bundle,
+    ) -> SharedRouteStage<NoManagement, EVLManager> {
+        let (tx, rx) = {
+            let info = contact.borrow().info;
+            (info.tx_node_id, info.rx_node_id)
+        };
+        let stage = make_source::<NoManagement>(at_time, rx, bundle);
+        stage.borrow_mut().via = Some(ViaHop {
+            contact: contact.clone(),
+            parent_route: parent.clone(),
+            tx_node: make_node_rc(tx, "tx", NoManagement {}),
+            rx_node: make_node_rc(rx, "rx", NoManagement {}),
+        });
+        stage
+    }
+
+    fn make_output(
+        first_hop: &Rc<RefCell<Contact<NoManagement, EVLManager>>>,
+        dest_route: SharedRouteStage<NoManagement, EVLManager>,
+    ) -> RoutingOutput<NoManagement, EVLManager> {
+        let mut route_metrics = HashMap::new();
+        route_metrics.insert(
+            dest_route.borrow().to_node,
+            RouteMetrics::of(&dest_route.borrow()),
+        );
+        let first_hops = FirstHops::single(first_hop.clone(), dest_route);
+        RoutingOutput {
+            first_hops,
+            route_metrics,
+        }
+    }
+
+    #[test]
+    fn first_hops_are_grouped_by_identity() {
+        let c0 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let c1 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let bundle = make_bundle(1, 0, 1.0, 100.0);
+        let stages: Vec<_> = (0..3).map(|_| make_source(0.0, 1, &bundle)).collect();
+
+        let mut first_hops = FirstHops::new();
+        first_hops.group(&c0).push(stages[0].clone());
+        first_hops.group(&c1).push(stages[1].clone());
+        first_hops.group(&c0.clone()).push(stages[2].clone());
+
+        assert_eq!(
+            first_hops.len(),
+            2,
+            "TEST FAILED: Equal but distinct contacts should not share a first hop."
+        );
+        let (contact, routes) = first_hops.values().next().unwrap();
+        assert!(
+            Rc::ptr_eq(contact, &c0) && routes.len() == 2 && Rc::ptr_eq(&routes[1], &stages[2]),
+            "TEST FAILED: Clones of the same Rc should share the first hop."
+        );
+        assert_eq!(
+            first_hops
+                .get_mut(RcKey::of(&c1))
+                .map(|routes| routes.len()),
+            Some(1),
+            "TEST FAILED: The second contact should have its own first hop."
+        );
+    }
+
+    #[test]
+    fn group_entry_groups_by_identity() {
+        let c0 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let c1 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let mut groups: Groups<Contact<NoManagement, EVLManager>, NodeID> = HashMap::new();
+
+        group_entry(&mut groups, &c0).push(1);
+        group_entry(&mut groups, &c1).push(2);
+        group_entry(&mut groups, &c0.clone()).push(3);
+
+        assert_eq!(
+            groups.len(),
+            2,
+            "TEST FAILED: Equal but distinct contacts should not share a group."
+        );
+        let (shared, items) = &groups[&RcKey::of(&c0)];
+        assert!(
+            Rc::ptr_eq(shared, &c0),
+            "TEST FAILED: The group should hold the contact it is keyed by."
+        );
+        assert_eq!(
+            items,
+            &vec![1, 3],
+            "TEST FAILED: Clones of the same Rc should share a group."
+        );
+        assert_eq!(
+            groups[&RcKey::of(&c1)].1,
+            vec![2],
+            "TEST FAILED: The second contact should have its own group."
+        );
+    }
+
+    #[test]
+    fn identical_sub_routes_are_grouped_together() -> Result<(), ASABRError> {
+        let bundle = make_bundle(2, 0, 1.0, 100.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+        let c0 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let c1 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        // two sub-routes reaching node 1 at t=5 through distinct contacts, both leading to node 2
+        let via_c0 = make_stage(5.0, &source, &c0, &bundle);
+        let via_c1 = make_stage(5.0, &source, &c1, &bundle);
+        for stage in [&via_c0, &via_c1] {
+            let next = make_stage(6.0, stage, &c0, &bundle);
+            stage.borrow_mut().next_for_destination.insert(2, next);
+        }
+        // the same node and time, one more hop
+        let longer = make_stage(5.0, &source, &c0, &bundle);
+        longer.borrow_mut().hop_count = 1;
+        assert_eq!(
+            StageKey::of(&*via_c0.borrow()),
+            StageKey::of(&*via_c1.borrow()),
+            "TEST FAILED: Identical sub-routes should share a key."
+        );
+        assert_eq!(
+            StageKey::from((1, -0.0, 0)),
+            StageKey::from((1, 0.0, 0)),
+            "TEST FAILED: -0 and +0 should share a key."
+        );
+
+        let mut groups: Groups<RouteStage<NoManagement, EVLManager>, NodeID> = HashMap::new();
+        group_entry(&mut groups, &via_c0).push(2);
+        group_entry(&mut groups, &via_c1).push(1);
+        group_entry(&mut groups, &longer).push(4);
+        let merged = merge_identical_stages(groups)?;
+
+        assert_eq!(
+            merged.len(),
+            2,
+            "TEST FAILED: The identical sub-routes should be merged, and only them."
+        );
+        let (stage, dests) = merged
+            .iter()
+            .find(|(stage, _)| !Rc::ptr_eq(stage, &longer))
+            .unwrap();
+        assert!(
+            Rc::ptr_eq(stage, &via_c0) || Rc::ptr_eq(stage, &via_c1),
+            "TEST FAILED: One of the identical sub-routes should be kept."
+        );
+        let mut dests = dests.clone();
+        dests.sort();
+        assert_eq!(
+            dests,
+            vec![1, 2],
+            "TEST FAILED: The kept sub-route should carry the destinations of both."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failed_multicast_branch_releases_the_others() -> Result<(), ASABRError> {
+        let bundle = Bundle {
+            source: 0,
+            destinations: vec![1, 2],
+            priority: 0,
+            size: 1.0,
+            expiration: 100.0,
+        };
+        // 0 -> 1 fits, the next hop 1 -> 2 cannot carry the bundle
+        let c01 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let c12 = make_contact_rc::<NoManagement>(1, 2, 0.0, 1.0, 0.1, 0.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+        let s1 = make_stage(0.0, &source, &c01, &bundle);
+        let s2 = make_stage(0.0, &s1, &c12, &bundle);
+        source
+            .borrow_mut()
+            .next_for_destination
+            .insert(1, s1.clone());
+        source
+            .borrow_mut()
+            .next_for_destination
+            .insert(2, s1.clone());
+        s1.borrow_mut().next_for_destination.insert(2, s2);
+
+        assert!(
+            matches!(
+                update_multicast(&bundle, 0.0, vec![1, 2], source, None),
+                Err(ASABRError::RoutingError(
+                    RoutingError::DryRunScheduleMismatch
+                ))
+            ),
+            "TEST FAILED: The scheduling error of the branch to node 2 should be returned."
+        );
+        assert_eq!(
+            c01.borrow().manager.remaining_volume(bundle.priority),
+            10.0,
+            "TEST FAILED: The first hop booked for node 1 should be released."
+        );
+        assert_eq!(
+            c12.borrow().manager.remaining_volume(bundle.priority),
+            0.1,
+            "TEST FAILED: The failed hop should book nothing."
+        );
+        Ok(())
+    }
+
+    /// An EVL manager that cannot cancel its bookings.
+    struct NoCancellation(EVLManager);
+
+    impl ContactManager for NoCancellation {
+        fn dry_run_tx(
+            &self,
+            contact_data: &ContactInfo,
+            at_time: Date,
+            bundle: &Bundle,
+        ) -> Option<ContactManagerTxData> {
+            self.0.dry_run_tx(contact_data, at_time, bundle)
+        }
+
+        fn schedule_tx(
+            &mut self,
+            contact_data: &ContactInfo,
+            at_time: Date,
+            bundle: &Bundle,
+        ) -> Option<ContactManagerTxData> {
+            self.0.schedule_tx(contact_data, at_time, bundle)
+        }
+
+        fn remaining_volume(&self, priority: Priority) -> Volume {
+            self.0.remaining_volume(priority)
+        }
+
+        fn get_original_volume(&self) -> Volume {
+            self.0.get_original_volume()
+        }
+
+        fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+            self.0.try_init(contact_data)
+        }
+
+        fn snapshot(&self) -> ManagerState {
+            self.0.snapshot()
+        }
+
+        fn restore(&mut self, state: &dyn Any) -> bool {
+            self.0.restore(state)
+        }
+    }
+
+    #[test]
+    fn failed_multicast_branch_is_undone_without_cancellation_support() {
+        let contact = |tx, rx, rate| {
+            let manager = NoCancellation(EVLManager::new(rate, 0.0));
+            Rc::new(RefCell::new(
+                Contact::try_new(ContactInfo::new(tx, rx, 0.0, 10.0), manager).unwrap(),
+            ))
+        };
+        let stage = |parent: &SharedRouteStage<NoManagement, NoCancellation>,
+                     contact: &SharedContact<NoManagement, NoCancellation>,
+                     _bundle: &Bundle| {
+            let (tx, rx) = {
+                let info = contact.borrow().info;
+                (info.tx_node_id, info.rx_node_id)
+            };
+            Rc::new(RefCell::new(RouteStage::new(
+                0.0,
+                rx,
+                Some(ViaHop {
+                    contact: contact.clone(),
+                    parent_route: parent.clone(),
+                    tx_node: make_node_rc(tx, "tx", NoManagement {}),
+                    rx_node: make_node_rc(rx, "rx", NoManagement {}),
+                }),
+                #[cfg(feature = "node_proc")]
+                _bundle.clone(),
+            )))
+        };
+        let bundle = Bundle {
+            source: 0,
+            destinations: vec![1, 2],
+            priority: 0,
+            size: 1.0,
+            expiration: 100.0,
+        };
+        // 0 -> 1 fits, the next hop 1 -> 2 cannot carry the bundle
+        let c01 = contact(0, 1, 1.0);
+        let c12 = contact(1, 2, 0.01);
+        let source = Rc::new(RefCell::new(RouteStage::new(
+            0.0,
+            0,
+            None,
+            #[cfg(feature = "node_proc")]
+            bundle.clone(),
+        )));
+        let s1 = stage(&source, &c01, &bundle);
+        let s2 = stage(&s1, &c12, &bundle);
+        source
+            .borrow_mut()
+            .next_for_destination
+            .insert(1, s1.clone());
+        source
+            .borrow_mut()
+            .next_for_destination
+            .insert(2, s1.clone());
+        s1.borrow_mut().next_for_destination.insert(2, s2);
+
+        assert!(
+            update_multicast(&bundle, 0.0, vec![1, 2], source, None).is_err(),
+            "TEST FAILED: The branch to node 2 should fail to be scheduled."
+        );
+        assert_eq!(
+            c01.borrow().manager.remaining_volume(bundle.priority),
+            10.0,
+            "TEST FAILED: The first hop booked for node 1 should be restored."
+        );
+    }
+
+    /// Counts the reported hops.
+    struct HopCounter(Rc<core::cell::Cell<usize>>);
+
+    impl RoutingObserver for HopCounter {
+        fn on_scheduled(
+            &self,
+            _bundle: &Bundle,
+            _contact_info: &ContactInfo,
+            _tx_data: &ContactManagerTxData,
+        ) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn failed_unicast_route_is_undone_and_not_reported() {
+        let bundle = make_bundle(2, 0, 1.0, 100.0);
+        // 0 -> 1 fits, the next hop 1 -> 2 cannot carry the bundle
+        let c01 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let c12 = make_contact_rc::<NoManagement>(1, 2, 0.0, 1.0, 0.1, 0.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+        let s1 = make_stage(0.0, &source, &c01, &bundle);
+        let s2 = make_stage(0.0, &s1, &c12, &bundle);
+        source
+            .borrow_mut()
+            .next_for_destination
+            .insert(2, s1.clone());
+        s1.borrow_mut().next_for_destination.insert(2, s2);
+        let hops = Rc::new(core::cell::Cell::new(0));
+        let observer = HopCounter(hops.clone());
+
+        assert!(
+            update_unicast(&bundle, 2, 0.0, source, Some(&observer)).is_err(),
+            "TEST FAILED: The second hop should fail to be scheduled."
+        );
+        assert_eq!(
+            c01.borrow().manager.remaining_volume(bundle.priority),
+            10.0,
+            "TEST FAILED: The first hop should be restored."
+        );
+        assert_eq!(
+            hops.get(),
+            0,
+            "TEST FAILED: The hops of the failed route should not be reported."
+        );
+    }
+
+    #[test]
+    fn update_unicast_without_path_reports_mismatch() {
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+
+        assert!(
+            matches!(
+                update_unicast(&bundle, 2, 0.0, source, None),
+                Err(ASABRError::RoutingError(
+                    RoutingError::DryRunScheduleMismatch
+                ))
+            ),
+            "TEST FAILED: A missing path should be reported as a dry run/schedule mismatch."
+        );
+    }
+
+    #[test]
+    fn update_unicast_on_depleted_contact_reports_mismatch() {
+        let bundle = make_bundle(1, 0, 5.0, 2000.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+        let c0 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let dest = make_stage(5.0, &source, &c0, &bundle);
+        source.borrow_mut().next_for_destination.insert(1, dest);
+
+        // resources consumed after the dry run would have allowed the route
+        {
+            let mut contact = c0.borrow_mut();
+            let info = contact.info;
+            contact
+                .manager
+                .schedule_tx(&info, 0.0, &make_bundle(1, 0, 8.0, 2000.0));
+        }
+
+        assert!(
+            matches!(
+                update_unicast(&bundle, 1, 0.0, source, None),
+                Err(ASABRError::RoutingError(
+                    RoutingError::DryRunScheduleMismatch
+                ))
+            ),
+            "TEST FAILED: A depleted contact should be reported instead of panicking."
+        );
+    }
+
+    #[test]
+    fn verify_consistency_accepts_scheduled_route() -> Result<(), ASABRError> {
+        let mg = unit_graph_test()?;
+        let mut pathfinding = NodeParentingPath::<NoManagement, EVLManager, SABR>::new(mg);
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let tree = pathfinding.get_next(0.0, 0, &bundle, &[])?;
+        let output = schedule_unicast(&bundle, 0.0, Rc::new(RefCell::new(tree)), true, None)?;
+
+        assert_eq!(
+            output.verify_consistency(),
+            Ok(()),
+            "TEST FAILED: A scheduled route should be consistent."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn verify_consistency_reports_node_discontinuity() {
+        let bundle = make_bundle(3, 0, 1.0, 2000.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+        let c0 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let c1 = make_contact_rc::<NoManagement>(2, 3, 0.0, 10.0, 1.0, 0.0);
+        let first = make_stage(1.0, &source, &c0, &bundle);
+        let second = make_stage(2.0, &first, &c1, &bundle);
+
+        assert_eq!(
+            make_output(&c0, second).verify_consistency(),
+            Err(InconsistencyError::NodeDiscontinuity {
+                rx_node: 1,
+                next_tx_node: 2
+            }),
+            "TEST FAILED: The gap between node 1 and node 2 should be reported."
+        );
+    }
+
+    #[test]
+    fn verify_consistency_reports_time_regression() {
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+        let c0 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let c1 = make_contact_rc::<NoManagement>(1, 2, 0.0, 10.0, 1.0, 0.0);
+        let first = make_stage(5.0, &source, &c0, &bundle);
+        let second = make_stage(3.0, &first, &c1, &bundle);
+
+        assert_eq!(
+            make_output(&c0, second).verify_consistency(),
+            Err(InconsistencyError::TimeRegression { from: 5.0, to: 3.0 }),
+            "TEST FAILED: The arrival time going back should be reported."
+        );
+    }
+
+    #[test]
+    fn verify_consistency_reports_first_hop_mismatch() {
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let source = make_source::<NoManagement>(0.0, 0, &bundle);
+        let c0 = make_contact_rc::<NoManagement>(0, 1, 0.0, 10.0, 1.0, 0.0);
+        let c1 = make_contact_rc::<NoManagement>(1, 2, 0.0, 10.0, 1.0, 0.0);
+        let first = make_stage(1.0, &source, &c0, &bundle);
+        let second = make_stage(2.0, &first, &c1, &bundle);
+
+        assert_eq!(
+            make_output(&c1, second).verify_consistency(),
+            Err(InconsistencyError::FirstHopMismatch { to_node: 2 }),
+            "TEST FAILED: A route registered under the wrong first hop should be reported."
+        );
+    }
+
+    fn metrics_plan() -> crate::contact_plan::ContactPlan<NoManagement, EVLManager> {
+        crate::test_support::GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .rate(10.0)
+            .delay(1.0)
+            .contact(1, 2, 5.0, 100.0)
+            .rate(10.0)
+            .delay(2.0)
+            .plan()
+    }
+
+    fn assert_metrics_match_stages(output: &RoutingOutput<NoManagement, EVLManager>) {
+        for (_, route) in output.first_hop_entries() {
+            let stage = route.borrow();
+            assert_eq!(
+                output.route_metrics.get(&stage.to_node),
+                Some(&RouteMetrics::of(&stage)),
+                "TEST FAILED: The metrics should match the destination stage."
+            );
+        }
+    }
+
+    #[test]
+    fn unicast_reports_route_metrics() {
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = aliases::SpsnNodeParenting::new(metrics_plan(), cache, false).unwrap();
+        let output = router
+            .route(0, &make_bundle(2, 0, 10.0, 1000.0), 0.0, &[])
+            .unwrap()
+            .unwrap();
+
+        let metrics = output.route_metrics[&2];
+        assert_eq!(
+            (metrics.arrival_time, metrics.hop_count),
+            (8.0, 2),
+            "TEST FAILED: Expected an arrival at 8.0 after 2 hops."
+        );
+        assert_metrics_match_stages(&output);
+    }
+
+    #[test]
+    fn multicast_reports_route_metrics() {
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = aliases::SpsnNodeParenting::new(metrics_plan(), cache, false).unwrap();
+        let mut bundle = make_bundle(1, 0, 10.0, 1000.0);
+        bundle.destinations = vec![1, 2];
+        let output = router.route(0, &bundle, 0.0, &[]).unwrap().unwrap();
+
+        assert_eq!(
+            output.route_metrics.len(),
+            2,
+            "TEST FAILED: Expected metrics for both destinations."
+        );
+        assert_eq!(
+            output.route_metrics[&1].arrival_time, 2.0,
+            "TEST FAILED: Expected an arrival at 2.0 at node 1."
+        );
+        assert_metrics_match_stages(&output);
+    }
+
+    #[test]
+    fn multicast_reports_unreached_destinations() {
+        let plan = crate::test_support::GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 2, 5.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = aliases::SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let mut bundle = make_bundle(1, 0, 10.0, 1000.0);
+        bundle.destinations = vec![3, 1, 2];
+        let output = router.route(0, &bundle, 0.0, &[]).unwrap().unwrap();
+
+        assert_eq!(
+            output.reached(),
+            vec![1, 2],
+            "TEST FAILED: Nodes 1 and 2 should be reached."
+        );
+        assert_eq!(
+            output.unreached(&bundle),
+            vec![3],
+            "TEST FAILED: Node 3 has no contact and should be unreached."
+        );
+    }
+
+    #[test]
+    fn first_hop_entries_yield_each_route_once() {
+        // 1 and 2 are reached through the contact to 1, 3 through its own contact
+        let plan = crate::test_support::GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 2, 5.0, 100.0)
+            .contact(0, 3, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = aliases::SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let mut bundle = make_bundle(1, 0, 10.0, 1000.0);
+        bundle.destinations = vec![1, 2, 3];
+        let output = router.route(0, &bundle, 0.0, &[]).unwrap().unwrap();
+
+        let route_count: usize = output
+            .first_hops
+            .values()
+            .map(|(_, routes)| routes.len())
+            .sum();
+        let mut reached: Vec<_> = output
+            .first_hop_entries()
+            .map(|(contact, route)| (contact.borrow().info.rx_node_id, route.borrow().to_node))
+            .collect();
+        reached.sort();
+        assert_eq!(
+            (output.first_hops.len(), output.first_hop_entries().count()),
+            (2, route_count),
+            "TEST FAILED: Expected one entry per destination route."
+        );
+        assert_eq!(
+            reached,
+            vec![(1, 1), (1, 2), (3, 3)],
+            "TEST FAILED: Each route should come with its first hop contact."
+        );
+        assert_eq!(
+            output.verify_consistency(),
+            Ok(()),
+            "TEST FAILED: Each branch from the source should have its own first hop."
+        );
+    }
+
+    /// A router implementing only `route`, relying on the default methods of the trait.
+    struct RouteOnly(aliases::SpsnNodeParenting<NoManagement, EVLManager>);
+
+    impl Router<NoManagement, EVLManager> for RouteOnly {
+        fn route(
+            &mut self,
+            source: NodeID,
+            bundle: &Bundle,
+            curr_time: Date,
+            excluded_nodes: &[NodeID],
+        ) -> Result<Option<RoutingOutput<NoManagement, EVLManager>>, ASABRError> {
+            self.0.route(source, bundle, curr_time, excluded_nodes)
+        }
+    }
+
+    #[test]
+    fn default_constrained_routing_refuses_contact_exclusions() {
+        let plan = crate::test_support::GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = RouteOnly(aliases::SpsnNodeParenting::new(plan, cache, false).unwrap());
+        let bundle = make_bundle(1, 0, 1.0, 1000.0);
+
+        assert!(
+            matches!(
+                router.route_with_constraints(0, &bundle, 0.0, &[], &[(0, 1, 0.0)]),
+                Err(ASABRError::RoutingError(
+                    RoutingError::ContactExclusionUnsupported
+                ))
+            ),
+            "TEST FAILED: The exclusions cannot be applied without the multigraph."
+        );
+        assert!(
+            router
+                .route_with_constraints(0, &bundle, 0.0, &[], &[])
+                .unwrap()
+                .is_some(),
+            "TEST FAILED: Without excluded contacts, the call should behave like route."
+        );
+    }
+
+    #[test]
+    fn default_clock_refuses_to_route_now() {
+        let plan = crate::test_support::GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = RouteOnly(aliases::SpsnNodeParenting::new(plan, cache, false).unwrap());
+
+        assert!(
+            router.now().is_none()
+                && matches!(
+                    router.route_now(0, &make_bundle(1, 0, 1.0, 1000.0)),
+                    Err(ASABRError::RoutingError(RoutingError::NoCurrentTime))
+                ),
+            "TEST FAILED: A router without a clock cannot route now."
+        );
+    }
+
+    #[test]
+    fn multicast_branches_keep_their_own_first_hop() {
+        // every destination is reached through a different neighbour of the source
+        let plan = crate::test_support::GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .node(4)
+            .node(5)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 4, 5.0, 100.0)
+            .contact(0, 2, 0.0, 100.0)
+            .contact(2, 5, 5.0, 100.0)
+            .contact(0, 3, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = aliases::SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let mut bundle = make_bundle(4, 0, 10.0, 1000.0);
+        bundle.destinations = vec![3, 4, 5];
+        let output = router.route(0, &bundle, 0.0, &[]).unwrap().unwrap();
+
+        let mut reached: Vec<_> = output
+            .first_hop_entries()
+            .map(|(contact, route)| (contact.borrow().info.rx_node_id, route.borrow().to_node))
+            .collect();
+        reached.sort();
+        assert_eq!(
+            output.first_hops.len(),
+            3,
+            "TEST FAILED: Each branch should get its own first hop."
+        );
+        assert_eq!(
+            reached,
+            vec![(1, 4), (2, 5), (3, 3)],
+            "TEST FAILED: A branch should not inherit the first hop of a sibling branch."
+        );
+    }
+
+    #[test]
+    fn sorted_first_hop_entries_are_stable() {
+        // 1, 2 and 3 are all one hop away, with the same cost
+        let plan = crate::test_support::GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 3, 0.0, 100.0)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(0, 2, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = aliases::SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let mut bundle = make_bundle(1, 0, 10.0, 1000.0);
+        bundle.destinations = vec![3, 2, 1];
+
+        let mut listings = Vec::new();
+        for _ in 0..2 {
+            let output = router.route(0, &bundle, 0.0, &[]).unwrap().unwrap();
+            let listing: Vec<_> = output
+                .sorted_first_hop_entries()
+                .unwrap()
+                .into_iter()
+                .map(|(contact, route)| {
+                    let info = contact.borrow().info;
+                    (info.tx_node_id, info.rx_node_id, route.borrow().to_node)
+                })
+                .collect();
+            listings.push(listing);
+        }
+        assert_eq!(
+            listings[0],
+            vec![(0, 1, 1), (0, 2, 2), (0, 3, 3)],
+            "TEST FAILED: The first hops should be sorted by contact."
+        );
+        assert_eq!(
+            listings[0], listings[1],
+            "TEST FAILED: Routing the same bundle again should list the same first hops."
+        );
+    }
+
+    #[test]
+    fn routers_are_usable_through_the_trait() {
+        type Nm = NoManagement;
+        type Table = crate::route_storage::table::RoutingTable<Nm, EVLManager, SABR>;
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut routers: Vec<Box<dyn Router<Nm, EVLManager>>> = vec![
+            Box::new(aliases::SpsnNodeParenting::new(metrics_plan(), cache, false).unwrap()),
+            Box::new(
+                cgr::Cgr::<Nm, EVLManager, NodeParentingPath<Nm, EVLManager, SABR>, Table>::new(
+                    metrics_plan(),
+                    Rc::new(RefCell::new(Table::new())),
+                )
+                .unwrap(),
+            ),
+            Box::new(
+                aliases::VolCgrNodeParenting::new(
+                    metrics_plan(),
+                    Rc::new(RefCell::new(Table::new())),
+                )
+                .unwrap(),
+            ),
+        ];
+
+        for router in &mut routers {
+            let output = router
+                .route(0, &make_bundle(2, 0, 10.0, 1000.0), 0.0, &[])
+                .unwrap()
+                .expect("TEST FAILED: Every router should reach node 2.");
+            assert_eq!(
+                output.route_metrics[&2].hop_count, 2,
+                "TEST FAILED: Expected a 2-hop route for every router."
+            );
+        }
+    }
+
+    #[test]
+    fn path_to_lists_the_hops_in_order() {
+        let cache = Rc::new(RefCell::new(crate::route_storage::cache::TreeCache::new(
+            false, false, 10,
+        )));
+        let mut router = aliases::SpsnNodeParenting::new(metrics_plan(), cache, false).unwrap();
+        let output = router
+            .route(0, &make_bundle(2, 0, 10.0, 1000.0), 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Node 2 should be reached.");
+
+        // sent at once to node 1 (arrival at 2.0), then when the contact to node 2 opens
+        assert_eq!(
+            output.path_to(2).unwrap(),
+            vec![(0, 1, 0.0), (1, 2, 5.0)],
+            "TEST FAILED: Expected the hops 0 -> 1 at 0.0 and 1 -> 2 at 5.0."
+        );
+        assert!(
+            output.path_to(1).unwrap().is_empty(),
+            "TEST FAILED: Node 1 is not a destination of the bundle."
+        );
+    }
+
+    /// Builds a tree whose stages loop between nodes 1 and 3 on the way to node 2.
+    #[allow(clippy::type_complexity)]
+    fn make_cyclic_tree(
+        bundle: &Bundle,
+    ) -> (
+        SharedRouteStage<NoManagement, EVLManager>,
+        Rc<RefCell<PathFindingOutput<NoManagement, EVLManager>>>,
+    ) {
+        let c01 = make_contact_rc::<NoManagement>(0, 1, 0.0, 100.0, 10.0, 0.0);
+        let c13 = make_contact_rc::<NoManagement>(1, 3, 0.0, 100.0, 10.0, 0.0);
+        let source = make_source::<NoManagement>(0.0, 0, bundle);
+        let s1 = make_stage(1.0, &source, &c01, bundle);
+        let s3 = make_stage(2.0, &s1, &c13, bundle);
+        source
+            .borrow_mut()
+            .next_for_destination
+            .insert(2, s1.clone());
+        s1.borrow_mut().next_for_destination.insert(2, s3.clone());
+        s3.borrow_mut().next_for_destination.insert(2, s1.clone());
+
+        let mut tree = PathFindingOutput::new(bundle, source.clone(), &[], 4);
+        let s2 = make_source::<NoManagement>(3.0, 2, bundle);
+        s2.borrow_mut().route_initialized = true;
+        tree.by_destination[2] = Some(s2);
+        (source, Rc::new(RefCell::new(tree)))
+    }
+
+    #[test]
+    fn cyclic_tree_stops_the_multicast_dry_run() {
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let (_, tree) = make_cyclic_tree(&bundle);
+
+        assert!(
+            matches!(
+                dry_run_multicast(&bundle, 0.0, tree),
+                Err(ASABRError::RoutingError(RoutingError::CyclicTree {
+                    to_node: 1
+                }))
+            ),
+            "TEST FAILED: The cycle should be reported on its way back to node 1."
+        );
+    }
+
+    #[test]
+    fn cyclic_tree_stops_the_multicast_update() {
+        let bundle = make_bundle(2, 0, 1.0, 2000.0);
+        let (source, _) = make_cyclic_tree(&bundle);
+
+        assert!(
+            matches!(
+                update_multicast(&bundle, 0.0, vec![2], source, None),
+                Err(ASABRError::RoutingError(RoutingError::CyclicTree {
+                    to_node: 1
+                }))
+            ),
+            "TEST FAILED: The cycle should be reported on its way back to node 1."
+        );
+    }
 }
@@ -0,0 +1,61 @@
+extern crate alloc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::{bundle::Bundle, contact::ContactInfo, contact_manager::ContactManagerTxData};
+
+/// An observer of the scheduling decisions of a router, e.g. to keep an audit trail of the
+/// contacts booked for each bundle, see `Spsn::set_observer`.
+///
+/// The observer is told about the hops of a route or tree once all of them are booked, in the
+/// order the hops were scheduled (from the source towards the destinations). A route or tree
+/// rolled back reports nothing, the fragments of `Spsn::route_fragmented` are reported once all
+/// of them are scheduled, and the dry runs of `Spsn::route_isolated` report nothing.
+pub trait RoutingObserver {
+    /// Called for each hop booked on a contact. Does nothing by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle` - The bundle transmitted on the contact (as processed by the transmitting node
+    ///   with `node_proc`).
+    /// * `contact_info` - The contact booked.
+    /// * `tx_data` - The transmission booked on the contact.
+    fn on_scheduled(
+        &self,
+        _bundle: &Bundle,
+        _contact_info: &ContactInfo,
+        _tx_data: &ContactManagerTxData,
+    ) {
+    }
+}
+
+/// An observer holding the reported hops back until the operation they belong to commits, see
+/// `Spsn::route_fragmented`.
+#[derive(Default)]
+pub(crate) struct DeferredObserver {
+    hops: RefCell<Vec<(Bundle, ContactInfo, ContactManagerTxData)>>,
+}
+
+impl DeferredObserver {
+    /// Reports the hops held back to `observer`, in the order they were received.
+    pub(crate) fn commit(self, observer: Option<&dyn RoutingObserver>) {
+        if let Some(observer) = observer {
+            for (bundle, contact_info, tx_data) in self.hops.into_inner() {
+                observer.on_scheduled(&bundle, &contact_info, &tx_data);
+            }
+        }
+    }
+}
+
+impl RoutingObserver for DeferredObserver {
+    fn on_scheduled(
+        &self,
+        bundle: &Bundle,
+        contact_info: &ContactInfo,
+        tx_data: &ContactManagerTxData,
+    ) {
+        self.hops
+            .borrow_mut()
+            .push((bundle.clone(), *contact_info, *tx_data));
+    }
+}
@@ -0,0 +1,157 @@
+extern crate alloc;
+use alloc::{vec, vec::Vec};
+
+use serde::{Serialize, Serializer};
+
+use crate::{
+    contact::Contact,
+    contact_manager::ContactManager,
+    node_manager::NodeManager,
+    route_stage::SharedRouteStage,
+    types::{Date, NodeID},
+};
+
+use super::RoutingOutput;
+
+/// The endpoints and time window of a contact.
+#[derive(Debug, Serialize)]
+pub struct SerializedHop {
+    pub tx_node: NodeID,
+    pub rx_node: NodeID,
+    pub start: Date,
+    pub end: Date,
+}
+
+impl SerializedHop {
+    fn new<NM: NodeManager, CM: ContactManager>(contact: &Contact<NM, CM>) -> Self {
+        Self {
+            tx_node: contact.info.tx_node_id,
+            rx_node: contact.info.rx_node_id,
+            start: contact.info.start,
+            end: contact.info.end,
+        }
+    }
+}
+
+/// A destination reached through a first hop, with the hops of its route in transmission order.
+#[derive(Debug, Serialize)]
+pub struct SerializedDestination {
+    pub node: NodeID,
+    pub arrival: Date,
+    pub hops: Vec<SerializedHop>,
+}
+
+impl SerializedDestination {
+    fn new<NM: NodeManager, CM: ContactManager>(route: &SharedRouteStage<NM, CM>) -> Self {
+        let (node, arrival) = {
+            let stage = route.borrow();
+            (stage.to_node, stage.at_time)
+        };
+        let mut hops = vec![];
+        let mut curr = route.clone();
+        loop {
+            let parent = match &curr.borrow().via {
+                Some(via) => {
+                    hops.push(SerializedHop::new(&via.contact.borrow()));
+                    via.parent_route.clone()
+                }
+                None => break,
+            };
+            curr = parent;
+        }
+        hops.reverse();
+        Self {
+            node,
+            arrival,
+            hops,
+        }
+    }
+}
+
+/// A first hop contact and the destinations reached through it.
+#[derive(Debug, Serialize)]
+pub struct SerializedFirstHop {
+    #[serde(flatten)]
+    pub contact: SerializedHop,
+    pub destinations: Vec<SerializedDestination>,
+}
+
+/// The flat, serializable view of a `RoutingOutput`.
+#[derive(Debug, Serialize)]
+pub struct SerializedRoutingOutput {
+    pub first_hops: Vec<SerializedFirstHop>,
+}
+
+impl<NM: NodeManager, CM: ContactManager> From<&RoutingOutput<NM, CM>> for SerializedRoutingOutput {
+    fn from(output: &RoutingOutput<NM, CM>) -> Self {
+        Self {
+            first_hops: output
+                .first_hops
+                .values()
+                .map(|(contact, routes)| SerializedFirstHop {
+                    contact: SerializedHop::new(&contact.borrow()),
+                    destinations: routes.iter().map(SerializedDestination::new).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Serializes the output as a `SerializedRoutingOutput`, the shared graph is not serialized.
+impl<NM: NodeManager, CM: ContactManager> Serialize for RoutingOutput<NM, CM> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedRoutingOutput::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::route_storage::cache::TreeCache;
+    use crate::routing::Router;
+    use crate::routing::aliases::SpsnNodeParenting;
+    use crate::test_support::GraphBuilder;
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    #[test]
+    fn two_hop_route_is_serialized_in_transmission_order() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .rate(10.0)
+            .contact(1, 2, 5.0, 100.0)
+            .rate(10.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let output = router
+            .route(0, &make_bundle(2, 0, 10.0, 1000.0), 0.0, &[])
+            .unwrap()
+            .unwrap();
+
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "first_hops": [{
+                    "tx_node": 0, "rx_node": 1, "start": 0.0, "end": 100.0,
+                    "destinations": [{
+                        "node": 2,
+                        "arrival": 6.0,
+                        "hops": [
+                            {"tx_node": 0, "rx_node": 1, "start": 0.0, "end": 100.0},
+                            {"tx_node": 1, "rx_node": 2, "start": 5.0, "end": 100.0},
+                        ],
+                    }],
+                }],
+            }),
+            "TEST FAILED: The JSON should list both hops in transmission order."
+        );
+    }
+}
@@ -2,19 +2,39 @@ use crate::{
     bundle::Bundle,
     contact_manager::ContactManager,
     contact_plan::ContactPlan,
+    distance::Distance,
     errors::ASABRError,
     multigraph::Multigraph,
     node_manager::NodeManager,
-    pathfinding::Pathfinding,
+    pathfinding::{PathFindingOutput, Pathfinding, PathfindingStats},
+    route_stage::SharedRouteStage,
     route_storage::{Guard, TreeStorage},
-    types::{Date, NodeID},
+    types::{Date, HopCount, NodeID, Volume},
 };
 
 extern crate alloc;
-use alloc::rc::Rc;
+use alloc::{boxed::Box, rc::Rc, vec, vec::Vec};
 use core::{cell::RefCell, marker::PhantomData};
 
-use super::{Router, RoutingOutput, schedule_multicast, schedule_unicast};
+#[cfg(feature = "rayon")]
+use super::RouteSummary;
+use super::{
+    Rollback, Router, RoutingOutput, check_priority, has_known_destinations,
+    observer::{DeferredObserver, RoutingObserver},
+    schedule_multicast, schedule_unicast,
+    trace::{RoutingTrace, TraceEvent},
+    with_excluded_contacts,
+};
+#[cfg(feature = "rayon")]
+use crate::{
+    contact_plan::{asabr_file_lexer::parse_from_str, asabr_file_writer::write_multigraph},
+    parsing::{LexFrom, Unparse},
+};
+#[cfg(feature = "rayon")]
+use alloc::string::String;
+
+/// A routing output with the source it was routed from, see `Spsn::route_from_sources`.
+pub type SourcedOutput<NM, CM> = (NodeID, RoutingOutput<NM, CM>);
 
 /// A structure representing the Shortest Path with Safety Nodes (SPSN) algorithm.
 ///
@@ -38,6 +58,14 @@ pub struct Spsn<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S:
     /// The guard structure that enforces safety and priority constraints, checking if the routing
     /// can proceed based on the current bundle and its constraints.
     unicast_guard: Guard,
+    /// The pathfinding statistics of the last routing operation.
+    stats: PathfindingStats,
+    /// The current time used by `route_now`.
+    now: Date,
+    /// The trace of the current routing operation, only recorded by `route_explained`.
+    trace: Option<RoutingTrace>,
+    /// The observer told about the scheduled hops, see `set_observer`.
+    observer: Option<Box<dyn RoutingObserver>>,
 
     // for compilation
     #[doc(hidden)]
@@ -56,9 +84,39 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: TreeStorage
         curr_time: Date,
         excluded_nodes: &[NodeID],
     ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        self.stats = PathfindingStats::default();
+        check_priority(bundle, &self.pathfinding.get_multigraph())?;
         if bundle.expiration < curr_time {
+            self.trace(TraceEvent::BundleExpired {
+                expiration: bundle.expiration,
+                curr_time,
+            });
             return Ok(None);
         }
+        if !has_known_destinations(bundle, &self.pathfinding.get_multigraph())? {
+            if self.trace.is_some() {
+                let vertex_count = self
+                    .pathfinding
+                    .get_multigraph()
+                    .try_borrow()?
+                    .get_vertex_count();
+                for &destination in &bundle.destinations {
+                    if destination as usize >= vertex_count {
+                        self.trace(TraceEvent::UnknownDestination { destination });
+                    }
+                }
+            }
+            return Ok(None);
+        }
+        // the pruned trees are only valid for the expiration they were built for
+        if self
+            .pathfinding
+            .get_multigraph()
+            .try_borrow()?
+            .prune_expired
+        {
+            return self.route_with_settings(source, bundle, curr_time, excluded_nodes, |_| {});
+        }
 
         if bundle.destinations.len() == 1 {
             return self.route_unicast(source, bundle, curr_time, excluded_nodes);
@@ -66,6 +124,24 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: TreeStorage
 
         self.route_multicast(source, bundle, curr_time, excluded_nodes)
     }
+
+    fn route_with_constraints(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+        excluded_contacts: &[(NodeID, NodeID, Date)],
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        let multigraph = self.pathfinding.get_multigraph();
+        with_excluded_contacts(&multigraph, excluded_contacts, || {
+            self.route(source, bundle, curr_time, excluded_nodes)
+        })
+    }
+
+    fn now(&self) -> Option<Date> {
+        Some(self.now)
+    }
 }
 
 impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>
@@ -92,12 +168,257 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
             pathfinding: P::new(Rc::new(RefCell::new(Multigraph::new(contact_plan)?))),
             route_storage: route_storage.clone(),
             unicast_guard: Guard::new(with_priorities),
+            stats: PathfindingStats::default(),
+            now: 0.0,
+            trace: None,
+            observer: None,
             // for compilation
             _phantom_nm: PhantomData,
             _phantom_cm: PhantomData,
         })
     }
 
+    /// Retrieves the multigraph the routes are computed on.
+    ///
+    /// # Returns
+    ///
+    /// * A shared pointer to the multigraph.
+    pub fn get_multigraph(&self) -> Rc<RefCell<Multigraph<NM, CM>>> {
+        self.pathfinding.get_multigraph()
+    }
+
+    /// Retrieves the pathfinding statistics of the last routing operation.
+    ///
+    /// If the tree was taken from the route storage, only `from_cache` is set. If no tree was
+    /// needed (e.g. the bundle expired), the statistics are zeroed.
+    ///
+    /// # Returns
+    ///
+    /// * The statistics of the searches run by the last call to a routing method.
+    pub fn last_stats(&self) -> PathfindingStats {
+        self.stats
+    }
+
+    /// Sets the current time of the router, used by `route_now`.
+    ///
+    /// The routes are computed from this time on: like the `curr_time` of `route`, it should not
+    /// go backwards, the contacts ended before it being pruned.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The new current time.
+    pub fn set_now(&mut self, now: Date) {
+        self.now = now;
+    }
+
+    /// Sets the observer told about each hop booked by the router, e.g. to keep an audit trail
+    /// of the scheduling decisions (see `RoutingObserver`). No observer is set by default.
+    ///
+    /// # Parameters
+    ///
+    /// * `observer` - The observer, replacing the previous one.
+    pub fn set_observer(&mut self, observer: Box<dyn RoutingObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// Releases the resources booked on every contact and clears the route storage and the guard limits, to run a new
+    /// scenario as if the router was just created. The node managers are left as is, see
+    /// `Multigraph::reset_resources`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the router was reset.
+    /// * `Err(ASABRError)` - If the multigraph or the storage cannot be borrowed, or a contact
+    ///   cannot be initialized again (`ASABRError::ResourceError`).
+    pub fn reset(&mut self) -> Result<(), ASABRError> {
+        self.pathfinding
+            .get_multigraph()
+            .try_borrow_mut()?
+            .reset_resources()?;
+        self.route_storage.try_borrow_mut()?.clear();
+        self.clear_guard();
+        self.now = 0.0;
+        Ok(())
+    }
+
+    /// Forgets the bundle sizes the guard learned to abort, e.g. between routing epochs.
+    ///
+    /// The guard aborts the bundles to a destination it failed to reach with the resources at
+    /// the time. Once the resources are released or the contact plan changes, the limits are
+    /// stale and the aborted bundles should get a fresh chance. `reset` also clears them.
+    pub fn clear_guard(&mut self) {
+        self.unicast_guard.clear();
+    }
+
+    /// Routes a bundle like `route`, recording the decisions taken on the way.
+    ///
+    /// The trace tells whether the guard aborted the routing (with the limiting size), whether
+    /// the tree was taken from the route storage, which destinations the tree reaches and how
+    /// the arrival time compares to the bundle expiration. `route` records nothing.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing.
+    /// - `bundle`: The `Bundle` to route.
+    /// - `curr_time`: The current time for scheduling calculations.
+    /// - `excluded_nodes`: A list of nodes to exclude from the paths.
+    ///
+    /// # Returns
+    /// The output of `route` and the trace of the operation, or an error if the operation fails.
+    pub fn route_explained(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+    ) -> Result<(Option<RoutingOutput<NM, CM>>, RoutingTrace), ASABRError> {
+        self.trace = Some(RoutingTrace::new());
+        let output = self.route(source, bundle, curr_time, excluded_nodes);
+        let trace = self.trace.take().unwrap_or_default();
+        Ok((output?, trace))
+    }
+
+    /// Records an event if a trace is requested.
+    fn trace(&mut self, event: TraceEvent) {
+        if let Some(trace) = &mut self.trace {
+            trace.record(event);
+        }
+    }
+
+    /// Records whether each destination of `bundle` is reached by `tree`, if a trace is requested.
+    fn trace_reachability(&mut self, bundle: &Bundle, tree: &PathFindingOutput<NM, CM>) {
+        if self.trace.is_none() {
+            return;
+        }
+        for &destination in &bundle.destinations {
+            let reachable = tree.by_destination[destination as usize].is_some();
+            self.trace(TraceEvent::Reachability {
+                destination,
+                reachable,
+            });
+        }
+    }
+
+    /// Records the arrival time of each destination of `bundle` reached by `tree`, compared to
+    /// the bundle expiration, if a trace is requested. The destinations arriving too late are
+    /// dropped by the multicast dry run.
+    fn trace_arrivals(&mut self, bundle: &Bundle, tree: &PathFindingOutput<NM, CM>) {
+        if self.trace.is_none() {
+            return;
+        }
+        for &destination in &bundle.destinations {
+            if let Some(route) = &tree.by_destination[destination as usize] {
+                let arrival = route.borrow().at_time;
+                self.trace(TraceEvent::Arrival {
+                    destination,
+                    arrival,
+                    expiration: bundle.expiration,
+                });
+            }
+        }
+    }
+
+    /// Routes a batch of bundles, one after the other in submission order.
+    ///
+    /// The resources booked for a bundle are accounted for when routing the following ones, the
+    /// outcome is the same as calling `route` for each request in turn. No node is excluded.
+    ///
+    /// # Parameters
+    /// - `requests`: The source node, the bundle and the current time of each routing request.
+    ///
+    /// # Returns
+    /// The routing outputs in the order of `requests`, or the first error encountered.
+    pub fn route_batch(
+        &mut self,
+        requests: &[(NodeID, Bundle, Date)],
+    ) -> Result<Vec<Option<RoutingOutput<NM, CM>>>, ASABRError> {
+        requests
+            .iter()
+            .map(|(source, bundle, curr_time)| self.route(*source, bundle, *curr_time, &[]))
+            .collect()
+    }
+
+    /// Routes a bundle like `route`, leaving the resources of the contacts as they were.
+    ///
+    /// This is a dry run: the resources booked on the contacts for the bundle are restored once
+    /// the output is computed (see `Multigraph::snapshot`). The route storage and the guard are
+    /// bypassed, the output then only depends on the contacts and on the request, not on the
+    /// previous requests. The node managers are not restored, they keep the bookings made for
+    /// the bundle. The observer is not told about the hops.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing.
+    /// - `bundle`: The `Bundle` to route.
+    /// - `curr_time`: The current time for scheduling calculations.
+    /// - `excluded_nodes`: A list of nodes to exclude from the paths.
+    ///
+    /// # Returns
+    /// The output of `route`, or an error if the operation fails.
+    pub fn route_isolated(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        let multigraph = self.pathfinding.get_multigraph();
+        let snapshot = multigraph.try_borrow()?.snapshot()?;
+        // the bookings are undone, the observer is not told about them
+        let observer = self.observer.take();
+        let output = self.route_with_settings(source, bundle, curr_time, excluded_nodes, |_| {});
+        self.observer = observer;
+        multigraph.try_borrow_mut()?.restore(&snapshot)?;
+        output
+    }
+
+    /// Routes independent requests on all the cores, like `route_isolated` for each of them.
+    ///
+    /// This is a dry-run-only mode: no resource is booked, a request is not affected by the
+    /// others. The multigraph shares its nodes and contacts through `Rc` pointers, which cannot
+    /// be sent to other threads: the plan of this router is written in the A-SABR format (see
+    /// `write_multigraph`) and each worker parses its own copy. The managers are written with
+    /// their initial parameters, the requests are thus routed as if no resource was booked. No
+    /// node is excluded.
+    ///
+    /// # Parameters
+    /// - `requests`: The source node, the bundle and the current time of each routing request.
+    ///
+    /// # Returns
+    /// The routes found in the order of `requests`, or the first error encountered.
+    #[cfg(feature = "rayon")]
+    pub fn route_many_parallel(
+        &self,
+        requests: &[(NodeID, Bundle, Date)],
+    ) -> Result<Vec<Option<RouteSummary>>, ASABRError>
+    where
+        NM: Unparse + LexFrom<str>,
+        CM: Unparse + LexFrom<str>,
+    {
+        use rayon::prelude::*;
+
+        let mut plan = String::new();
+        write_multigraph(&*self.get_multigraph().try_borrow()?, &mut plan)
+            .map_err(|_| ASABRError::ContactPlanError("The contact plan cannot be written"))?;
+
+        requests
+            .par_iter()
+            .map_init(
+                || None,
+                |worker: &mut Option<P>, (source, bundle, curr_time)| {
+                    // the pathfinding of a worker is built on its first request
+                    let pathfinding = match worker {
+                        Some(pathfinding) => pathfinding,
+                        None => worker.insert(P::new(Rc::new(RefCell::new(Multigraph::new(
+                            parse_from_str(&plan)?,
+                        )?)))),
+                    };
+                    route_fresh_tree(pathfinding, *source, bundle, *curr_time)?
+                        .map(|output| RouteSummary::of(&output))
+                        .transpose()
+                },
+            )
+            .collect()
+    }
+
     /// Routes a bundle to a single destination node using unicast routing.
     ///
     /// The `route_unicast` function performs a unicast routing operation for bundles with only
@@ -121,24 +442,41 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
         curr_time: Date,
         excluded_nodes: &[NodeID],
     ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        let dest = bundle.destinations[0];
+
         if self.unicast_guard.must_abort(bundle) {
+            if let Some(limit) = self.unicast_guard.known_limit(bundle, dest) {
+                self.trace(TraceEvent::GuardAbort {
+                    destination: dest,
+                    limit,
+                });
+            }
             return Ok(None);
         }
 
-        let dest = bundle.destinations[0];
-
         let (tree_option, _reachable_nodes) =
             self.route_storage
                 .borrow()
                 .select(bundle, curr_time, excluded_nodes)?;
 
         if let Some(tree) = tree_option {
-            return Ok(Some(schedule_unicast(bundle, curr_time, tree, false)?));
+            self.stats.from_cache = true;
+            self.trace(TraceEvent::TreeCacheHit);
+            return Ok(Some(schedule_unicast(
+                bundle,
+                curr_time,
+                tree,
+                false,
+                self.observer.as_deref(),
+            )?));
         }
 
+        self.trace(TraceEvent::TreeCacheMiss);
         let new_tree = self
             .pathfinding
             .get_next(curr_time, source, bundle, excluded_nodes)?;
+        self.stats = self.pathfinding.last_stats();
+        self.trace_reachability(bundle, &new_tree);
         let tree_ref = Rc::new(RefCell::new(new_tree));
 
         self.route_storage
@@ -150,7 +488,13 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
             // Trees are not built while considering expirations for flexibility
             // /!\ But maybe it should, issues expected with non-SABR distances
             Some(route) => {
-                if route.borrow().at_time > bundle.expiration {
+                let arrival = route.borrow().at_time;
+                self.trace(TraceEvent::Arrival {
+                    destination: dest,
+                    arrival,
+                    expiration: bundle.expiration,
+                });
+                if arrival > bundle.expiration {
                     return Ok(None);
                 }
             }
@@ -160,7 +504,13 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
             }
         }
 
-        Ok(Some(schedule_unicast(bundle, curr_time, tree_ref, true)?))
+        Ok(Some(schedule_unicast(
+            bundle,
+            curr_time,
+            tree_ref,
+            true,
+            self.observer.as_deref(),
+        )?))
     }
 
     /// Routes a bundle to multiple destination nodes using multicast routing.
@@ -186,28 +536,1527 @@ impl<S: TreeStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding
         curr_time: Date,
         excluded_nodes: &[NodeID],
     ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
-        if let (Some(tree), Some(reachable_nodes)) =
-            self.route_storage
-                .borrow()
-                .select(bundle, curr_time, excluded_nodes)?
+        let selected = self
+            .route_storage
+            .borrow()
+            .select(bundle, curr_time, excluded_nodes)?;
+        if let (Some(tree), Some(reachable_nodes)) = selected
             && bundle.destinations.len() == reachable_nodes.len()
         {
+            self.stats.from_cache = true;
+            self.trace(TraceEvent::TreeCacheHit);
             return Ok(Some(schedule_multicast(
                 bundle,
                 curr_time,
                 tree,
                 Some(reachable_nodes),
+                self.observer.as_deref(),
             )?));
         }
 
+        self.trace(TraceEvent::TreeCacheMiss);
         let new_tree = self
             .pathfinding
             .get_next(curr_time, source, bundle, excluded_nodes)?;
+        self.stats = self.pathfinding.last_stats();
+        self.trace_reachability(bundle, &new_tree);
+        self.trace_arrivals(bundle, &new_tree);
         let tree = Rc::new(RefCell::new(new_tree));
         self.route_storage
             .try_borrow_mut()?
             .store(bundle, tree.clone());
 
-        Ok(Some(schedule_multicast(bundle, curr_time, tree, None)?))
+        Ok(Some(schedule_multicast(
+            bundle,
+            curr_time,
+            tree,
+            None,
+            self.observer.as_deref(),
+        )?))
+    }
+
+    /// Routes a bundle to the single destination of `bundle.destinations` that is the cheapest
+    /// to reach (anycast).
+    ///
+    /// A tree is built for all the destinations, the reached destination with the best `D`
+    /// ordered route stage is selected and only its branch is scheduled. The tree is not kept in
+    /// the route storage, as it was not scheduled for all the destinations of the bundle.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing.
+    /// - `bundle`: The `Bundle` containing the candidate destinations.
+    /// - `curr_time`: The current time for scheduling calculations.
+    /// - `excluded_nodes`: A list of nodes to exclude from the paths.
+    ///
+    /// # Returns
+    /// An `Result<Option<RoutingOutput<NM, CM>>, ASABRError>` containing the routing result for the
+    /// selected destination, or `None` if no destination can be reached before the bundle expires
+    /// or if a destination is unknown, or an error if the priority of the bundle is invalid.
+    pub fn route_anycast<D: Distance<NM, CM>>(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        let multigraph = self.pathfinding.get_multigraph();
+        self.stats = PathfindingStats::default();
+        check_priority(bundle, &multigraph)?;
+        if bundle.expiration < curr_time || !has_known_destinations(bundle, &multigraph)? {
+            return Ok(None);
+        }
+
+        let new_tree = self
+            .pathfinding
+            .get_next(curr_time, source, bundle, excluded_nodes)?;
+        self.stats = self.pathfinding.last_stats();
+
+        let mut selected: Option<(NodeID, SharedRouteStage<NM, CM>)> = None;
+        for dest in &bundle.destinations {
+            let Some(Some(route)) = new_tree.by_destination.get(*dest as usize) else {
+                continue;
+            };
+            if route.borrow().at_time > bundle.expiration {
+                continue;
+            }
+            let is_better = match &selected {
+                Some((_, best)) => D::cmp(&route.borrow(), &best.borrow()).is_lt(),
+                None => true,
+            };
+            if is_better {
+                selected = Some((*dest, route.clone()));
+            }
+        }
+        let Some((dest, _)) = selected else {
+            return Ok(None);
+        };
+
+        let mut unicast_bundle = bundle.clone();
+        unicast_bundle.destinations = vec![dest];
+        Ok(Some(schedule_unicast(
+            &unicast_bundle,
+            curr_time,
+            Rc::new(RefCell::new(new_tree)),
+            true,
+            self.observer.as_deref(),
+        )?))
+    }
+
+    /// Routes a bundle using only the transmissions that end by `horizon`.
+    ///
+    /// A contact spanning the horizon remains usable if the bundle is fully transmitted by then.
+    /// The route storage is bypassed, the trees it holds were computed without the horizon, and
+    /// the tree computed is not stored.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing.
+    /// - `bundle`: The `Bundle` containing destination information and other relevant routing data.
+    /// - `curr_time`: The current time for scheduling calculations.
+    /// - `excluded_nodes`: A list of nodes to exclude from the paths.
+    /// - `horizon`: The date by which each transmission must end.
+    ///
+    /// # Returns
+    /// An `Result<Option<RoutingOutput<NM, CM>>, ASABRError>` containing the routing result, or
+    /// `None` if no route respects the horizon, or an error if the operation fails.
+    pub fn route_with_horizon(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+        horizon: Date,
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        self.route_with_settings(source, bundle, curr_time, excluded_nodes, |multigraph| {
+            multigraph.horizon = Some(horizon)
+        })
+    }
+
+    /// Routes a bundle using only the routes of at most `max_hops` hops.
+    ///
+    /// A destination that can only be reached through more hops is unreachable. If `max_hops`
+    /// is set, the route storage is bypassed as for `route_with_horizon`, otherwise this is the
+    /// same as `route`.
+    /// Depending on the pathfinding, a route within the limit can be missed, see
+    /// `Multigraph::max_hops`.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing.
+    /// - `bundle`: The `Bundle` containing destination information and other relevant routing data.
+    /// - `curr_time`: The current time for scheduling calculations.
+    /// - `excluded_nodes`: A list of nodes to exclude from the paths.
+    /// - `max_hops`: The maximum number of hops of a route, if any.
+    ///
+    /// # Returns
+    /// An `Result<Option<RoutingOutput<NM, CM>>, ASABRError>` containing the routing result, or
+    /// `None` if no route respects the hop limit, or an error if the operation fails.
+    pub fn route_with_max_hops(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+        max_hops: Option<HopCount>,
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        if max_hops.is_none() {
+            return self.route(source, bundle, curr_time, excluded_nodes);
+        }
+        self.route_with_settings(source, bundle, curr_time, excluded_nodes, |multigraph| {
+            multigraph.max_hops = max_hops
+        })
+    }
+
+    /// Routes a bundle over a tree computed with the multigraph settings applied by `configure`,
+    /// the previous horizon and hop limit are restored once the tree is computed, even if the
+    /// computation fails. The route storage is bypassed.
+    fn route_with_settings(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+        configure: impl FnOnce(&mut Multigraph<NM, CM>),
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        let multigraph = self.pathfinding.get_multigraph();
+        self.stats = PathfindingStats::default();
+        check_priority(bundle, &multigraph)?;
+        if bundle.expiration < curr_time || !has_known_destinations(bundle, &multigraph)? {
+            return Ok(None);
+        }
+
+        let (horizon, max_hops) = {
+            let mut multigraph = multigraph.try_borrow_mut()?;
+            let previous = (multigraph.horizon, multigraph.max_hops);
+            configure(&mut multigraph);
+            previous
+        };
+        let new_tree = self
+            .pathfinding
+            .get_next(curr_time, source, bundle, excluded_nodes);
+        self.stats = self.pathfinding.last_stats();
+        {
+            let mut multigraph = multigraph.try_borrow_mut()?;
+            multigraph.horizon = horizon;
+            multigraph.max_hops = max_hops;
+        }
+        schedule_tree(
+            bundle,
+            curr_time,
+            Rc::new(RefCell::new(new_tree?)),
+            self.observer.as_deref(),
+        )
+    }
+
+    /// Routes a bundle from the source offering the best routes among `sources`, e.g. one of
+    /// the radios of a gateway.
+    ///
+    /// A tree is computed from each source, bypassing the route storage. The best tree reaches
+    /// the most destinations before the bundle expiration, and the latest of them the earliest.
+    /// Only the routes of the best tree are scheduled.
+    ///
+    /// # Parameters
+    /// - `sources`: The candidate source node IDs.
+    /// - `bundle`: The `Bundle` containing destination information and other relevant routing data.
+    /// - `curr_time`: The current time for scheduling calculations.
+    /// - `excluded_nodes`: A list of nodes to exclude from the paths.
+    ///
+    /// # Returns
+    /// An `Result<Option<SourcedOutput<NM, CM>>, ASABRError>` containing the chosen
+    /// source with the routing result, or `None` if no source reaches a destination, or an error
+    /// if the operation fails.
+    pub fn route_from_sources(
+        &mut self,
+        sources: &[NodeID],
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+    ) -> Result<Option<SourcedOutput<NM, CM>>, ASABRError> {
+        let multigraph = self.pathfinding.get_multigraph();
+        self.stats = PathfindingStats::default();
+        check_priority(bundle, &multigraph)?;
+        if bundle.expiration < curr_time || !has_known_destinations(bundle, &multigraph)? {
+            return Ok(None);
+        }
+        let vertex_count = multigraph.try_borrow()?.get_vertex_count();
+
+        // the reached destinations count, the latest arrival among them, the source and its tree
+        let mut best: Option<(usize, Date, NodeID, PathFindingOutput<NM, CM>)> = None;
+        for &source in sources {
+            if source as usize >= vertex_count {
+                continue;
+            }
+            let tree = self
+                .pathfinding
+                .get_next(curr_time, source, bundle, excluded_nodes)?;
+            self.stats += self.pathfinding.last_stats();
+
+            let mut reached = 0;
+            let mut latest = Date::MIN;
+            for dest in &bundle.destinations {
+                if let Some(route) = &tree.by_destination[*dest as usize] {
+                    let arrival = route.borrow().at_time;
+                    if arrival <= bundle.expiration {
+                        reached += 1;
+                        latest = latest.max(arrival);
+                    }
+                }
+            }
+            let is_better = reached > 0
+                && match &best {
+                    None => true,
+                    Some((best_reached, best_latest, _, _)) => {
+                        reached > *best_reached
+                            || (reached == *best_reached && latest < *best_latest)
+                    }
+                };
+            if is_better {
+                best = Some((reached, latest, source, tree));
+            }
+        }
+
+        let Some((_, _, source, tree)) = best else {
+            return Ok(None);
+        };
+        let output = schedule_tree(
+            bundle,
+            curr_time,
+            Rc::new(RefCell::new(tree)),
+            self.observer.as_deref(),
+        )?;
+        Ok(output.map(|output| (source, output)))
+    }
+
+    /// Routes a bundle, split into up to `max_fragments` fragments of equal size if it cannot be
+    /// routed whole (proactive fragmentation).
+    ///
+    /// The bundle is first routed whole with `route`. Otherwise, it is split into 2, 3, ...
+    /// `max_fragments` fragments, each fragment being routed and scheduled in turn so that the
+    /// following ones account for its bookings. If a fragment cannot reach the destination
+    /// before the expiration of the bundle, the contacts booked by the previous fragments are
+    /// restored to their state before the first fragment (see `ContactManager::snapshot`) and
+    /// the next fragment count is tried. The contacts are restored the same way before an error
+    /// is returned. The node managers keep their bookings. The fragments bypass the route storage.
+    ///
+    /// # Parameters
+    /// - `source`: The source node ID initiating the routing.
+    /// - `bundle`: The `Bundle` to route, with a single destination.
+    /// - `curr_time`: The current time for scheduling calculations.
+    /// - `max_fragments`: The maximum number of fragments the bundle can be split into.
+    ///
+    /// # Returns
+    /// A `Result<Vec<RoutingOutput<NM, CM>>, ASABRError>` with the output of each fragment, a
+    /// single one if the bundle was routed whole, or empty if the bundle cannot be delivered.
+    pub fn route_fragmented(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        max_fragments: usize,
+    ) -> Result<Vec<RoutingOutput<NM, CM>>, ASABRError> {
+        if let Some(output) = self.route(source, bundle, curr_time, &[])? {
+            return Ok(vec![output]);
+        }
+        if bundle.destinations.len() != 1
+            || bundle.expiration < curr_time
+            || !has_known_destinations(bundle, &self.pathfinding.get_multigraph())?
+        {
+            return Ok(Vec::new());
+        }
+
+        for fragment_count in 2..=max_fragments {
+            let mut fragment = bundle.clone();
+            fragment.size = bundle.size / fragment_count as Volume;
+
+            let mut rollback = Rollback::new();
+            let deferred = DeferredObserver::default();
+            let outputs = match self.schedule_fragments(
+                source,
+                &fragment,
+                curr_time,
+                fragment_count,
+                &mut rollback,
+                &deferred,
+            ) {
+                Ok(outputs) => outputs,
+                Err(err) => {
+                    // the scheduling error is the cause, a failed restoration is not reported over it
+                    let _ = rollback.restore();
+                    return Err(err);
+                }
+            };
+            if outputs.len() == fragment_count {
+                deferred.commit(self.observer.as_deref());
+                return Ok(outputs);
+            }
+            rollback.restore()?;
+        }
+        Ok(Vec::new())
+    }
+
+    /// Routes and schedules up to `fragment_count` copies of `fragment` one after the other, see
+    /// `route_fragmented`. Stops at the first fragment that cannot reach the destination before
+    /// the expiration, the contacts booked are captured in `rollback`.
+    fn schedule_fragments(
+        &mut self,
+        source: NodeID,
+        fragment: &Bundle,
+        curr_time: Date,
+        fragment_count: usize,
+        rollback: &mut Rollback<NM, CM>,
+        deferred: &DeferredObserver,
+    ) -> Result<Vec<RoutingOutput<NM, CM>>, ASABRError> {
+        let dest = fragment.destinations[0] as usize;
+        let mut outputs = Vec::with_capacity(fragment_count);
+        while outputs.len() < fragment_count {
+            let tree = self
+                .pathfinding
+                .get_next(curr_time, source, fragment, &[])?;
+            self.stats += self.pathfinding.last_stats();
+            match &tree.by_destination[dest] {
+                Some(route) if route.borrow().at_time <= fragment.expiration => {
+                    rollback.capture_route(route)?;
+                }
+                _ => break,
+            }
+            let tree = Rc::new(RefCell::new(tree));
+            outputs.push(schedule_unicast(
+                fragment,
+                curr_time,
+                tree,
+                true,
+                Some(deferred),
+            )?);
+        }
+        Ok(outputs)
+    }
+}
+
+/// Routes a bundle over a fresh tree of `pathfinding`, restoring the contacts once the output is
+/// computed, see `Spsn::route_many_parallel`.
+#[cfg(feature = "rayon")]
+fn route_fresh_tree<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>(
+    pathfinding: &mut P,
+    source: NodeID,
+    bundle: &Bundle,
+    curr_time: Date,
+) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+    let multigraph = pathfinding.get_multigraph();
+    check_priority(bundle, &multigraph)?;
+    if bundle.expiration < curr_time || !has_known_destinations(bundle, &multigraph)? {
+        return Ok(None);
+    }
+    let snapshot = multigraph.try_borrow()?.snapshot()?;
+    let output = pathfinding
+        .get_next(curr_time, source, bundle, &[])
+        .and_then(|tree| schedule_tree(bundle, curr_time, Rc::new(RefCell::new(tree)), None));
+    multigraph.try_borrow_mut()?.restore(&snapshot)?;
+    output
+}
+
+/// Schedules the routes of a fresh tree, not taken from a route storage, telling `observer`
+/// about the scheduled hops.
+///
+/// # Returns
+/// The routing output, or `None` if the unicast destination is not reached before the bundle
+/// expiration.
+fn schedule_tree<NM: NodeManager, CM: ContactManager>(
+    bundle: &Bundle,
+    curr_time: Date,
+    tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
+    observer: Option<&dyn RoutingObserver>,
+) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+    if bundle.destinations.len() == 1 {
+        match &tree.borrow().by_destination[bundle.destinations[0] as usize] {
+            Some(route) if route.borrow().at_time <= bundle.expiration => {}
+            _ => return Ok(None),
+        }
+        return Ok(Some(schedule_unicast(
+            bundle, curr_time, tree, true, observer,
+        )?));
+    }
+
+    Ok(Some(schedule_multicast(
+        bundle, curr_time, tree, None, observer,
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact::ContactInfo;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_manager::{ContactManagerTxData, ManagerState};
+    use crate::distance::sabr::SABR;
+    use crate::errors::RoutingError;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::{make_bundle, make_contact};
+    use crate::route_storage::cache::TreeCache;
+    use crate::routing::aliases::{SpsnHybridParenting, SpsnNodeParenting};
+    use crate::test_support::GraphBuilder;
+    use crate::types::{DataRate, Duration, Priority};
+    use core::any::Any;
+
+    #[test]
+    fn isolated_routing_books_nothing() {
+        // a single contact able to carry 10 units of volume
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 10.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(true, true, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let bundle = make_bundle(1, 0, 8.0, 100.0);
+
+        for _ in 0..2 {
+            assert!(
+                router
+                    .route_isolated(0, &bundle, 0.0, &[])
+                    .unwrap()
+                    .is_some(),
+                "TEST FAILED: The bundle should fit in the untouched contact."
+            );
+        }
+        assert!(
+            router.route(0, &bundle, 0.0, &[]).unwrap().is_some(),
+            "TEST FAILED: The isolated routing should not have booked the contact."
+        );
+    }
+
+    #[test]
+    fn isolated_routing_keeps_the_storage_and_the_guard() {
+        let make_router = || {
+            let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+                .node(0)
+                .node(1)
+                .contact(0, 1, 0.0, 10.0)
+                .plan();
+            let cache = Rc::new(RefCell::new(TreeCache::new(true, true, 10)));
+            SpsnNodeParenting::new(plan, cache, false).unwrap()
+        };
+        let small = make_bundle(1, 0, 1.0, 100.0);
+
+        let mut router = make_router();
+        router.route(0, &small, 0.0, &[]).unwrap();
+        router.route_isolated(0, &small, 0.0, &[]).unwrap();
+        router.route(0, &small, 0.0, &[]).unwrap();
+        assert!(
+            router.last_stats().from_cache,
+            "TEST FAILED: The stored tree should still be reused."
+        );
+
+        // the guard learns that 20 units cannot reach node 1
+        let mut router = make_router();
+        assert!(
+            router
+                .route(0, &make_bundle(1, 0, 20.0, 100.0), 0.0, &[])
+                .unwrap()
+                .is_none(),
+            "TEST FAILED: The contact cannot carry the bundle."
+        );
+        router.route_isolated(0, &small, 0.0, &[]).unwrap();
+        assert!(
+            router.route(0, &small, 0.0, &[]).unwrap().is_none()
+                && router.last_stats() == PathfindingStats::default(),
+            "TEST FAILED: The guard should still abort the smaller bundle."
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_routing_matches_the_isolated_one() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 10.0)
+            .contact(0, 2, 5.0, 20.0)
+            .contact(1, 3, 5.0, 20.0)
+            .contact(2, 3, 10.0, 30.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(true, true, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let requests: Vec<_> = (0..60)
+            .map(|i| {
+                let bundle = make_bundle(1 + i % 3, 0, (i % 20) as Volume, 100.0);
+                (0, bundle, (i % 7) as Date)
+            })
+            .collect();
+
+        let sequential: Vec<_> = requests
+            .iter()
+            .map(|(source, bundle, curr_time)| {
+                router
+                    .route_isolated(*source, bundle, *curr_time, &[])
+                    .unwrap()
+                    .map(|output| RouteSummary::of(&output).unwrap())
+            })
+            .collect();
+        let parallel = router.route_many_parallel(&requests).unwrap();
+
+        assert!(
+            sequential.iter().any(Option::is_some) && sequential.iter().any(Option::is_none),
+            "TEST FAILED: The requests should not all have the same outcome."
+        );
+        assert_eq!(
+            parallel, sequential,
+            "TEST FAILED: The parallel routes should be the isolated ones, in order."
+        );
+    }
+
+    #[test]
+    fn earlier_bundles_consume_resources_of_later_ones() {
+        // a single contact able to carry 10 units of volume
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 10.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(true, true, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        let outputs = router
+            .route_batch(&[
+                (0, make_bundle(1, 0, 8.0, 100.0), 0.0),
+                (0, make_bundle(1, 0, 5.0, 100.0), 0.0),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            outputs.len(),
+            2,
+            "TEST FAILED: Expected one output per request."
+        );
+        assert!(
+            outputs[0].is_some(),
+            "TEST FAILED: The first bundle fits in the contact."
+        );
+        assert!(
+            outputs[1].is_none(),
+            "TEST FAILED: The second bundle exceeds the remaining volume."
+        );
+    }
+
+    #[test]
+    fn reset_routes_as_if_fresh() {
+        // a single contact able to carry 10 units of volume
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 10.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(true, true, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let bundle = make_bundle(1, 0, 8.0, 100.0);
+
+        let fresh = router
+            .route(0, &bundle, 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: The first bundle fits in the contact.");
+        assert!(
+            router.route(0, &bundle, 0.0, &[]).unwrap().is_none(),
+            "TEST FAILED: The contact has no room left for a second bundle."
+        );
+
+        router.reset().unwrap();
+        let output = router
+            .route(0, &bundle, 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: The bundle should fit again after a reset.");
+        assert_eq!(
+            output.route_metrics, fresh.route_metrics,
+            "TEST FAILED: The route should match the one found on the fresh router."
+        );
+    }
+
+    #[test]
+    fn restore_reproduces_the_routing() {
+        // a single contact able to carry 10 units of volume, 8 of them booked
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 10.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(true, true, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        router
+            .route(0, &make_bundle(1, 0, 8.0, 100.0), 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: The first bundle fits in the contact.");
+
+        let multigraph = router.get_multigraph();
+        let snapshot = multigraph.borrow().snapshot().unwrap();
+        let route_all = |router: &mut SpsnNodeParenting<NoManagement, EVLManager>| {
+            [2.0, 1.0].map(|size| {
+                router
+                    .route(0, &make_bundle(1, 0, size, 100.0), 0.0, &[])
+                    .unwrap()
+                    .map(|output| output.route_metrics)
+            })
+        };
+        let before = route_all(&mut router);
+        assert!(
+            before[0].is_some() && before[1].is_none(),
+            "TEST FAILED: Only the first bundle fits in the remaining volume."
+        );
+
+        multigraph.borrow_mut().restore(&snapshot).unwrap();
+        assert_eq!(
+            route_all(&mut router),
+            before,
+            "TEST FAILED: The routing should be the same after a restore."
+        );
+    }
+
+    #[test]
+    fn out_of_range_priority_is_rejected() {
+        use crate::contact_manager::legacy::evl::PEVLManager;
+        use crate::errors::RoutingError;
+
+        let plan = GraphBuilder::<NoManagement, _>::new(PEVLManager::<3>::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        assert!(
+            router
+                .route(0, &make_bundle(1, 2, 1.0, 100.0), 0.0, &[])
+                .unwrap()
+                .is_some(),
+            "TEST FAILED: Priority 2 is the highest of the 3 levels."
+        );
+        assert!(
+            matches!(
+                router.route(0, &make_bundle(1, 3, 1.0, 100.0), 0.0, &[]),
+                Err(ASABRError::RoutingError(RoutingError::InvalidPriority {
+                    priority: 3,
+                    levels: 3
+                }))
+            ),
+            "TEST FAILED: Priority 3 is beyond the 3 levels of the managers."
+        );
+    }
+
+    #[test]
+    fn anycast_checks_the_priority_and_the_destinations() {
+        use crate::contact_manager::legacy::evl::PEVLManager;
+
+        let plan = GraphBuilder::<NoManagement, _>::new(PEVLManager::<3>::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        assert!(
+            matches!(
+                router.route_anycast::<SABR>(0, &make_bundle(1, 3, 1.0, 100.0), 0.0, &[]),
+                Err(ASABRError::RoutingError(RoutingError::InvalidPriority {
+                    priority: 3,
+                    levels: 3
+                }))
+            ),
+            "TEST FAILED: Priority 3 is beyond the 3 levels of the managers."
+        );
+        let mut bundle = make_bundle(1, 0, 1.0, 100.0);
+        bundle.destinations = vec![1, 5];
+        assert!(
+            router
+                .route_anycast::<SABR>(0, &bundle, 0.0, &[])
+                .unwrap()
+                .is_none(),
+            "TEST FAILED: Node 5 is not in the contact plan."
+        );
+    }
+
+    #[test]
+    fn route_from_sources_skips_the_unreachable_sources() {
+        // only node 1 has a contact to node 3, node 0 only reaches node 2
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 2, 0.0, 100.0)
+            .contact(1, 3, 10.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let bundle = make_bundle(3, 0, 1.0, 100.0);
+
+        let (source, output) = router
+            .route_from_sources(&[0, 1], &bundle, 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Node 3 is reached from node 1.");
+        assert_eq!(source, 1, "TEST FAILED: Only node 1 reaches node 3.");
+        assert_eq!(
+            output.route_metrics[&3].arrival_time, 11.0,
+            "TEST FAILED: Expected an arrival when the contact opens plus the transmission."
+        );
+        assert!(
+            router
+                .route_from_sources(&[0, 2], &bundle, 0.0, &[])
+                .unwrap()
+                .is_none(),
+            "TEST FAILED: Neither node 0 nor node 2 reaches node 3."
+        );
+    }
+
+    #[test]
+    fn anycast_selects_the_cheapest_destination() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            .contact(0, 1, 0.0, 100.0)
+            .delay(5.0)
+            .contact(0, 2, 0.0, 100.0)
+            .delay(1.0)
+            .contact(0, 3, 0.0, 100.0)
+            .delay(3.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        let mut bundle = make_bundle(1, 0, 1.0, 1000.0);
+        bundle.destinations = vec![1, 2, 3];
+        let output = router
+            .route_anycast::<SABR>(0, &bundle, 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Expected a route to one of the destinations.");
+
+        let routes: Vec<_> = output.first_hop_entries().map(|(_, route)| route).collect();
+        assert_eq!(
+            routes.len(),
+            1,
+            "TEST FAILED: Expected a single destination."
+        );
+        assert_eq!(
+            routes[0].borrow().to_node,
+            2,
+            "TEST FAILED: Node 2 is the cheapest destination."
+        );
+    }
+
+    #[test]
+    fn excluded_contact_falls_back_to_the_parallel_one() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(0, 1, 10.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let bundle = make_bundle(1, 0, 1.0, 1000.0);
+
+        let output = router
+            .route_with_constraints(0, &bundle, 0.0, &[], &[(0, 1, 0.0)])
+            .unwrap()
+            .expect("TEST FAILED: Expected a route through the second contact.");
+        let (first_hop, _) = output.lazy_get_for_unicast(1).unwrap();
+        assert_eq!(
+            first_hop.borrow().info.start,
+            10.0,
+            "TEST FAILED: The excluded contact should not be used."
+        );
+
+        let output = router
+            .route(0, &bundle, 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Expected a route once the exclusion is lifted.");
+        let (first_hop, _) = output.lazy_get_for_unicast(1).unwrap();
+        assert!(
+            !first_hop.borrow().excluded,
+            "TEST FAILED: The exclusion should only apply to the constrained call."
+        );
+    }
+
+    #[test]
+    fn constrained_routing_keeps_the_settings_of_the_caller() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(0, 2, 50.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        router.get_multigraph().borrow_mut().horizon = Some(40.0);
+
+        assert!(
+            router
+                .route_with_max_hops(0, &make_bundle(2, 0, 1.0, 1000.0), 0.0, &[], Some(3))
+                .unwrap()
+                .is_none(),
+            "TEST FAILED: The horizon of the caller should apply to the constrained call."
+        );
+        router
+            .route_with_horizon(0, &make_bundle(1, 0, 1.0, 1000.0), 0.0, &[], 60.0)
+            .unwrap();
+        let multigraph = router.get_multigraph();
+        let multigraph = multigraph.borrow();
+        assert_eq!(
+            (multigraph.horizon, multigraph.max_hops),
+            (Some(40.0), None),
+            "TEST FAILED: The settings of the caller should be restored."
+        );
+    }
+
+    #[test]
+    fn horizon_rejects_the_contacts_ending_after_it() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .node(3)
+            // spans the horizon, but the transmission ends at 1.0
+            .contact(0, 1, 0.0, 100.0)
+            // the transmission would end at 40.5
+            .contact(0, 2, 39.5, 100.0)
+            .contact(0, 3, 50.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        let output = router
+            .route_with_horizon(0, &make_bundle(1, 0, 1.0, 1000.0), 0.0, &[], 40.0)
+            .unwrap();
+        assert!(
+            output.is_some(),
+            "TEST FAILED: The transmission to node 1 ends before the horizon."
+        );
+        for dest in [2, 3] {
+            let output = router
+                .route_with_horizon(0, &make_bundle(dest, 0, 1.0, 1000.0), 0.0, &[], 40.0)
+                .unwrap();
+            assert!(
+                output.is_none(),
+                "TEST FAILED: The transmission to node {dest} ends after the horizon."
+            );
+        }
+        assert!(
+            router
+                .route(0, &make_bundle(3, 0, 1.0, 1000.0), 0.0, &[])
+                .unwrap()
+                .is_some(),
+            "TEST FAILED: The horizon should only apply to the constrained call."
+        );
+    }
+
+    #[test]
+    fn max_hops_bounds_the_route_length() {
+        // the only path to node 5 takes 5 hops
+        let mut builder = GraphBuilder::<NoManagement, _>::new(EVLManager::new);
+        for node in 0..6 {
+            builder = builder.node(node);
+        }
+        for node in 0..5 {
+            builder = builder.contact(node, node + 1, 0.0, 100.0);
+        }
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(builder.plan(), cache, false).unwrap();
+        let bundle = make_bundle(5, 0, 1.0, 1000.0);
+
+        assert!(
+            router
+                .route_with_max_hops(0, &bundle, 0.0, &[], Some(4))
+                .unwrap()
+                .is_none(),
+            "TEST FAILED: Node 5 is out of reach within 4 hops."
+        );
+        let output = router
+            .route_with_max_hops(0, &bundle, 0.0, &[], Some(5))
+            .unwrap()
+            .expect("TEST FAILED: Node 5 should be reached within 5 hops.");
+        assert_eq!(
+            output.route_metrics[&5].hop_count, 5,
+            "TEST FAILED: Expected a 5-hop route."
+        );
+    }
+
+    #[test]
+    fn max_hops_needs_hop_aware_labels_for_later_routes() {
+        // node 3 is reached first in 3 hops, then directly at 50, node 4 only from node 3
+        let plan = || {
+            GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+                .node(0)
+                .node(1)
+                .node(2)
+                .node(3)
+                .node(4)
+                .contact(0, 1, 0.0, 100.0)
+                .contact(1, 2, 0.0, 100.0)
+                .contact(2, 3, 0.0, 100.0)
+                .contact(0, 3, 50.0, 100.0)
+                .contact(3, 4, 0.0, 100.0)
+                .plan()
+        };
+        let cache = || Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let bundle = make_bundle(4, 0, 1.0, 1000.0);
+
+        // the single label of node 3 is the earliest route, which has no hop left
+        let mut router = SpsnNodeParenting::new(plan(), cache(), false).unwrap();
+        assert!(
+            router
+                .route_with_max_hops(0, &bundle, 0.0, &[], Some(3))
+                .unwrap()
+                .is_none(),
+            "TEST FAILED: The node parenting keeps only the earliest route to node 3."
+        );
+
+        let mut router = SpsnHybridParenting::new(plan(), cache(), false).unwrap();
+        let output = router
+            .route_with_max_hops(0, &bundle, 0.0, &[], Some(3))
+            .unwrap()
+            .expect("TEST FAILED: The later direct route to node 3 should be kept.");
+        assert_eq!(
+            output.route_metrics[&4].hop_count, 2,
+            "TEST FAILED: Expected the 2-hop route through the direct contact."
+        );
+    }
+
+    /// An EVL manager carrying bundles of at most 10 units, and refusing to schedule a second
+    /// bundle once its dry run accepted it.
+    struct SingleBooking {
+        inner: EVLManager,
+        booked: bool,
+    }
+
+    impl SingleBooking {
+        fn new(rate: DataRate, delay: Duration) -> Self {
+            SingleBooking {
+                inner: EVLManager::new(rate, delay),
+                booked: false,
+            }
+        }
+    }
+
+    impl ContactManager for SingleBooking {
+        fn dry_run_tx(
+            &self,
+            contact_data: &ContactInfo,
+            at_time: Date,
+            bundle: &Bundle,
+        ) -> Option<ContactManagerTxData> {
+            if bundle.size > 10.0 {
+                return None;
+            }
+            self.inner.dry_run_tx(contact_data, at_time, bundle)
+        }
+
+        fn schedule_tx(
+            &mut self,
+            contact_data: &ContactInfo,
+            at_time: Date,
+            bundle: &Bundle,
+        ) -> Option<ContactManagerTxData> {
+            if self.booked {
+                return None;
+            }
+            let data = self.inner.schedule_tx(contact_data, at_time, bundle)?;
+            self.booked = true;
+            Some(data)
+        }
+
+        fn remaining_volume(&self, priority: Priority) -> Volume {
+            self.inner.remaining_volume(priority)
+        }
+
+        fn get_original_volume(&self) -> Volume {
+            self.inner.get_original_volume()
+        }
+
+        fn try_init(&mut self, contact_data: &ContactInfo) -> bool {
+            self.inner.try_init(contact_data)
+        }
+
+        fn snapshot(&self) -> ManagerState {
+            Box::new((self.inner.snapshot(), self.booked))
+        }
+
+        fn restore(&mut self, state: &dyn Any) -> bool {
+            match state.downcast_ref::<(ManagerState, bool)>() {
+                Some((inner, booked)) if self.inner.restore(inner.as_ref()) => {
+                    self.booked = *booked;
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    #[test]
+    fn failed_fragment_scheduling_restores_the_previous_fragments() {
+        let plan = GraphBuilder::<NoManagement, _>::new(SingleBooking::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        // the second fragment passes the dry run but cannot be scheduled
+        let bundle = make_bundle(1, 0, 20.0, 1000.0);
+        assert!(
+            matches!(
+                router.route_fragmented(0, &bundle, 0.0, 2),
+                Err(ASABRError::RoutingError(
+                    RoutingError::DryRunScheduleMismatch
+                ))
+            ),
+            "TEST FAILED: The scheduling error of the second fragment should be returned."
+        );
+        let multigraph = router.get_multigraph();
+        let multigraph = multigraph.borrow();
+        let contact = multigraph.senders[0].receivers[0].contacts_to_receiver[0].borrow();
+        assert!(
+            contact.manager.remaining_volume(bundle.priority) == 100.0 && !contact.manager.booked,
+            "TEST FAILED: The booking of the first fragment should be restored."
+        );
+    }
+
+    #[test]
+    fn oversized_bundle_is_delivered_in_two_fragments() {
+        // each contact carries 10, the bundle is twice as large
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 10.0)
+            .contact(0, 1, 20.0, 30.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let bundle = make_bundle(1, 0, 20.0, 1000.0);
+
+        assert!(
+            router
+                .route_fragmented(0, &bundle, 0.0, 1)
+                .unwrap()
+                .is_empty(),
+            "TEST FAILED: The bundle does not fit in a single contact."
+        );
+        let outputs = router.route_fragmented(0, &bundle, 0.0, 3).unwrap();
+        let starts: Vec<Date> = outputs
+            .iter()
+            .map(|output| {
+                output
+                    .lazy_get_for_unicast(1)
+                    .unwrap()
+                    .0
+                    .borrow()
+                    .info
+                    .start
+            })
+            .collect();
+        assert_eq!(
+            starts,
+            vec![0.0, 20.0],
+            "TEST FAILED: Expected one fragment through each contact."
+        );
+    }
+
+    #[test]
+    fn failed_fragments_release_their_bookings() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 10.0)
+            .contact(0, 1, 20.0, 30.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        // the second half would arrive after the expiration
+        let bundle = make_bundle(1, 0, 20.0, 15.0);
+        let outputs = router.route_fragmented(0, &bundle, 0.0, 2).unwrap();
+        assert!(
+            outputs.is_empty(),
+            "TEST FAILED: Every fragment must arrive before the expiration."
+        );
+        let multigraph = router.get_multigraph();
+        let multigraph = multigraph.borrow();
+        let first_contact = multigraph.senders[0].receivers[0].contacts_to_receiver[0].borrow();
+        assert_eq!(
+            first_contact.manager.remaining_volume(bundle.priority),
+            10.0,
+            "TEST FAILED: The first fragment should have released the first contact."
+        );
+    }
+
+    #[test]
+    fn failed_fragments_are_undone_without_cancellation_support() {
+        use crate::contact_manager::segmentation::{Segment, seg::SegmentationManager};
+
+        // the segmentation manager cannot cancel a booking
+        let plan = GraphBuilder::<NoManagement, _>::new(|rate, delay| {
+            SegmentationManager::new(
+                vec![Segment {
+                    start: 0.0,
+                    end: 10.0,
+                    val: rate,
+                }],
+                vec![Segment {
+                    start: 0.0,
+                    end: 10.0,
+                    val: delay,
+                }],
+            )
+        })
+        .node(0)
+        .node(1)
+        .contact(0, 1, 0.0, 10.0)
+        .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        // the first half fills the contact, the second one cannot be sent
+        let bundle = make_bundle(1, 0, 20.0, 1000.0);
+        let outputs = router.route_fragmented(0, &bundle, 0.0, 2).unwrap();
+        assert!(
+            outputs.is_empty(),
+            "TEST FAILED: The contact cannot carry both fragments."
+        );
+        let multigraph = router.get_multigraph();
+        let multigraph = multigraph.borrow();
+        let contact = multigraph.senders[0].receivers[0].contacts_to_receiver[0].borrow();
+        assert_eq!(
+            contact.manager.remaining_volume(bundle.priority),
+            10.0,
+            "TEST FAILED: The first fragment should have been undone."
+        );
+    }
+
+    #[test]
+    fn pruned_trees_are_not_reused() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 20.0, 30.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        router.get_multigraph().borrow_mut().prune_expired = true;
+
+        assert!(
+            router
+                .route(0, &make_bundle(1, 0, 1.0, 15.0), 0.0, &[])
+                .unwrap()
+                .is_none(),
+            "TEST FAILED: The bundle expires before the contact starts."
+        );
+        assert!(
+            router
+                .route(0, &make_bundle(1, 0, 0.5, 1000.0), 0.0, &[])
+                .unwrap()
+                .is_some(),
+            "TEST FAILED: The pruned tree should not make the destination unreachable."
+        );
+    }
+
+    #[test]
+    fn unknown_destination_is_not_routed() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        let output = router
+            .route(0, &make_bundle(42, 0, 1.0, 1000.0), 0.0, &[])
+            .unwrap();
+        assert!(
+            output.is_none(),
+            "TEST FAILED: A destination beyond the node count is unroutable."
+        );
+
+        let mut bundle = make_bundle(1, 0, 1.0, 1000.0);
+        bundle.destinations = vec![1, 42];
+        let output = router.route(0, &bundle, 0.0, &[]).unwrap();
+        assert!(
+            output.is_none(),
+            "TEST FAILED: A multicast bundle with an unknown destination is unroutable."
+        );
+    }
+
+    #[test]
+    fn stats_tell_cached_trees_apart() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 2, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let bundle = make_bundle(2, 0, 1.0, 1000.0);
+
+        router.route(0, &bundle, 0.0, &[]).unwrap();
+        let stats = router.last_stats();
+        assert!(
+            !stats.from_cache && stats.nodes_expanded == 3,
+            "TEST FAILED: The first tree should be computed, got {:?}.",
+            stats
+        );
+
+        router.route(0, &bundle, 0.0, &[]).unwrap();
+        assert_eq!(
+            router.last_stats(),
+            PathfindingStats {
+                from_cache: true,
+                ..Default::default()
+            },
+            "TEST FAILED: The second tree should be taken from the cache."
+        );
+    }
+
+    /// Records the contact and the transmission start of each scheduled hop.
+    struct Recorder(Rc<RefCell<crate::routing::HopList>>);
+
+    impl RoutingObserver for Recorder {
+        fn on_scheduled(
+            &self,
+            _bundle: &Bundle,
+            contact_info: &crate::contact::ContactInfo,
+            tx_data: &crate::contact_manager::ContactManagerTxData,
+        ) {
+            self.0.borrow_mut().push((
+                contact_info.tx_node_id,
+                contact_info.rx_node_id,
+                tx_data.tx_start,
+            ));
+        }
+    }
+
+    #[test]
+    fn observer_sees_each_scheduled_hop_in_order() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .rate(10.0)
+            .delay(1.0)
+            .contact(1, 2, 0.0, 100.0)
+            .rate(10.0)
+            .delay(1.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        router.set_observer(Box::new(Recorder(events.clone())));
+
+        assert!(
+            router
+                .route(0, &make_bundle(2, 0, 10.0, 1000.0), 0.0, &[])
+                .unwrap()
+                .is_some(),
+            "TEST FAILED: Node 2 should be reached."
+        );
+        assert_eq!(
+            *events.borrow(),
+            vec![(0, 1, 0.0), (1, 2, 2.0)],
+            "TEST FAILED: Each hop should be reported once, from the source on."
+        );
+    }
+
+    #[test]
+    fn observer_does_not_see_the_undone_bookings() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 10.0)
+            .contact(0, 1, 20.0, 30.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        router.set_observer(Box::new(Recorder(events.clone())));
+
+        router
+            .route_isolated(0, &make_bundle(1, 0, 5.0, 1000.0), 0.0, &[])
+            .unwrap();
+        // the second half would arrive after the expiration
+        router
+            .route_fragmented(0, &make_bundle(1, 0, 20.0, 15.0), 0.0, 2)
+            .unwrap();
+        assert!(
+            events.borrow().is_empty(),
+            "TEST FAILED: The dry runs and the failed fragments should not be reported."
+        );
+
+        let outputs = router
+            .route_fragmented(0, &make_bundle(1, 0, 20.0, 1000.0), 0.0, 2)
+            .unwrap();
+        assert_eq!(outputs.len(), 2, "TEST FAILED: Expected two fragments.");
+        assert_eq!(
+            *events.borrow(),
+            vec![(0, 1, 0.0), (0, 1, 20.0)],
+            "TEST FAILED: Each fragment should be reported once all are scheduled."
+        );
+    }
+
+    #[test]
+    fn trace_records_the_guard_abort() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        // node 2 cannot be reached, the guard learns the size of the failing bundle
+        let (output, trace) = router
+            .route_explained(0, &make_bundle(2, 0, 10.0, 1000.0), 0.0, &[])
+            .unwrap();
+        assert!(output.is_none(), "TEST FAILED: Node 2 is unreachable.");
+        assert_eq!(
+            trace.events(),
+            &[
+                TraceEvent::TreeCacheMiss,
+                TraceEvent::Reachability {
+                    destination: 2,
+                    reachable: false
+                }
+            ],
+            "TEST FAILED: The tree should be computed and miss node 2."
+        );
+
+        let (output, trace) = router
+            .route_explained(0, &make_bundle(2, 0, 5.0, 1000.0), 0.0, &[])
+            .unwrap();
+        assert!(output.is_none(), "TEST FAILED: Node 2 is unreachable.");
+        assert_eq!(
+            trace.events(),
+            &[TraceEvent::GuardAbort {
+                destination: 2,
+                limit: 10.0
+            }],
+            "TEST FAILED: The guard should abort with the stored limit."
+        );
+        assert_eq!(
+            trace.guard_limit(),
+            Some(10.0),
+            "TEST FAILED: The trace should expose the limiting size."
+        );
+    }
+
+    #[test]
+    fn trace_records_the_multicast_arrivals() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .contact(1, 2, 20.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let mut bundle = make_bundle(1, 0, 1.0, 30.0);
+        bundle.destinations = vec![1, 2];
+
+        let (output, trace) = router.route_explained(0, &bundle, 0.0, &[]).unwrap();
+        assert!(output.is_some(), "TEST FAILED: Both nodes are reachable.");
+        let arrivals: Vec<_> = trace
+            .events()
+            .iter()
+            .filter(|event| matches!(event, TraceEvent::Arrival { .. }))
+            .collect();
+        assert_eq!(
+            arrivals,
+            vec![
+                &TraceEvent::Arrival {
+                    destination: 1,
+                    arrival: 1.0,
+                    expiration: 30.0
+                },
+                &TraceEvent::Arrival {
+                    destination: 2,
+                    arrival: 21.0,
+                    expiration: 30.0
+                }
+            ],
+            "TEST FAILED: The arrival of each destination should be traced, got {:?}.",
+            trace.events()
+        );
+    }
+
+    #[test]
+    fn cleared_guard_gives_a_fresh_chance() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let bundle = make_bundle(2, 0, 5.0, 1000.0);
+
+        // node 2 cannot be reached yet, the guard learns the failing size
+        router
+            .route(0, &make_bundle(2, 0, 10.0, 1000.0), 0.0, &[])
+            .unwrap();
+        router
+            .get_multigraph()
+            .borrow_mut()
+            .add_contact(make_contact(1, 2, 0.0, 100.0, 1.0, 0.0))
+            .unwrap();
+        let (output, trace) = router.route_explained(0, &bundle, 0.0, &[]).unwrap();
+        assert!(
+            output.is_none() && trace.guard_limit() == Some(10.0),
+            "TEST FAILED: The stale limit should abort the bundle."
+        );
+
+        router.clear_guard();
+        let (output, trace) = router.route_explained(0, &bundle, 0.0, &[]).unwrap();
+        assert!(
+            trace.guard_limit().is_none(),
+            "TEST FAILED: The bundle should be evaluated again, got {:?}.",
+            trace.events()
+        );
+        assert!(
+            output.is_some(),
+            "TEST FAILED: Node 2 is now reachable through node 1."
+        );
+    }
+
+    #[test]
+    fn route_now_starts_from_the_clock() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .contact(0, 1, 0.0, 100.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+        let bundle = make_bundle(1, 0, 1.0, 1000.0);
+
+        let first = router.route_now(0, &bundle).unwrap().unwrap();
+        assert_eq!(
+            first.path_to(1).unwrap()[0].2,
+            0.0,
+            "TEST FAILED: The first bundle should be sent at the start of the clock."
+        );
+
+        router.set_now(20.0);
+        let second = router.route_now(0, &bundle).unwrap().unwrap();
+        let tx_start = second.path_to(1).unwrap()[0].2;
+        assert!(
+            router.now() == Some(20.0) && tx_start >= 20.0,
+            "TEST FAILED: The second bundle should not be sent before the new now, got {}.",
+            tx_start
+        );
     }
 }
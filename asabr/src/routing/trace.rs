@@ -0,0 +1,65 @@
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::types::{Date, NodeID, Volume};
+
+/// A high-level decision taken while routing a bundle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceEvent {
+    /// The bundle was already expired at the current time.
+    BundleExpired { expiration: Date, curr_time: Date },
+    /// A destination of the bundle is not a node of the multigraph.
+    UnknownDestination { destination: NodeID },
+    /// The guard aborted the routing, a bundle of at least `limit` already failed to reach
+    /// `destination`.
+    GuardAbort { destination: NodeID, limit: Volume },
+    /// A tree was taken from the route storage.
+    TreeCacheHit,
+    /// No suitable tree was stored, a new one was computed.
+    TreeCacheMiss,
+    /// Whether `destination` is reached by the computed tree.
+    Reachability {
+        destination: NodeID,
+        reachable: bool,
+    },
+    /// The arrival time of the route to `destination`, compared to the bundle expiration.
+    Arrival {
+        destination: NodeID,
+        arrival: Date,
+        expiration: Date,
+    },
+}
+
+/// The decisions taken by a router for a single routing operation, see `Spsn::route_explained`.
+///
+/// Tracing is meant for debugging, the routers only record events when a trace is requested.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RoutingTrace {
+    /// The events, in the order they occurred.
+    events: Vec<TraceEvent>,
+}
+
+impl RoutingTrace {
+    /// Creates a new, empty `RoutingTrace`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an event to the trace.
+    pub fn record(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+
+    /// Returns the recorded events, in the order they occurred.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Returns the limit of the guard abort, if the guard aborted the routing.
+    pub fn guard_limit(&self) -> Option<Volume> {
+        self.events.iter().find_map(|event| match event {
+            TraceEvent::GuardAbort { limit, .. } => Some(*limit),
+            _ => None,
+        })
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     errors::ASABRError,
     multigraph::Multigraph,
     node_manager::NodeManager,
-    pathfinding::Pathfinding,
+    pathfinding::{Pathfinding, PathfindingStats},
     route_stage::RouteStage,
     route_storage::{Route, RouteStorage},
     types::{Date, NodeID},
@@ -14,7 +14,10 @@ extern crate alloc;
 use alloc::rc::Rc;
 use core::{cell::RefCell, marker::PhantomData};
 
-use super::{Router, RoutingOutput, dry_run_unicast_path, schedule_unicast_path};
+use super::{
+    Router, RoutingOutput, check_priority, dry_run_unicast_path, has_known_destinations,
+    schedule_unicast_path, with_excluded_contacts,
+};
 
 pub struct VolCgr<
     NM: NodeManager,
@@ -24,6 +27,10 @@ pub struct VolCgr<
 > {
     route_storage: Rc<RefCell<S>>,
     pathfinding: P,
+    /// The pathfinding statistics of the last routing operation.
+    stats: PathfindingStats,
+    /// The current time used by `route_now`.
+    now: Date,
 
     // for compilation
     #[doc(hidden)]
@@ -42,7 +49,11 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: RouteStorag
         curr_time: Date,
         excluded_nodes: &[NodeID],
     ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
-        if bundle.expiration < curr_time {
+        self.stats = PathfindingStats::default();
+        check_priority(bundle, &self.pathfinding.get_multigraph())?;
+        if bundle.expiration < curr_time
+            || !has_known_destinations(bundle, &self.pathfinding.get_multigraph())?
+        {
             return Ok(None);
         }
 
@@ -52,6 +63,24 @@ impl<NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>, S: RouteStorag
 
         Err(ASABRError::MulticastUnsupportedError)
     }
+
+    fn route_with_constraints(
+        &mut self,
+        source: NodeID,
+        bundle: &Bundle,
+        curr_time: Date,
+        excluded_nodes: &[NodeID],
+        excluded_contacts: &[(NodeID, NodeID, Date)],
+    ) -> Result<Option<RoutingOutput<NM, CM>>, ASABRError> {
+        let multigraph = self.pathfinding.get_multigraph();
+        with_excluded_contacts(&multigraph, excluded_contacts, || {
+            self.route(source, bundle, curr_time, excluded_nodes)
+        })
+    }
+
+    fn now(&self) -> Option<Date> {
+        Some(self.now)
+    }
 }
 
 impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfinding<NM, CM>>
@@ -64,12 +93,57 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         Ok(Self {
             pathfinding: P::new(Rc::new(RefCell::new(Multigraph::new(contact_plan)?))),
             route_storage: route_storage.clone(),
+            stats: PathfindingStats::default(),
+            now: 0.0,
             // for compilation
             _phantom_nm: PhantomData,
             _phantom_cm: PhantomData,
         })
     }
 
+    /// Retrieves the pathfinding statistics of the last call to `route`.
+    ///
+    /// The statistics of the searches run for the bundle are summed. If the route was taken from
+    /// the route storage, only `from_cache` is set.
+    ///
+    /// # Returns
+    ///
+    /// * The statistics of the searches run by the last call to `route`.
+    pub fn last_stats(&self) -> PathfindingStats {
+        self.stats
+    }
+
+    /// Sets the current time of the router, used by `route_now`.
+    ///
+    /// The routes are computed from this time on: like the `curr_time` of `route`, it should not
+    /// go backwards, the contacts ended before it being pruned.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The new current time.
+    pub fn set_now(&mut self, now: Date) {
+        self.now = now;
+    }
+
+    /// Releases the resources booked on every contact and clears the route storage, to run a new
+    /// scenario as if the router was just created. The node managers are left as is, see
+    /// `Multigraph::reset_resources`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the router was reset.
+    /// * `Err(ASABRError)` - If the multigraph or the storage cannot be borrowed, or a contact
+    ///   cannot be initialized again (`ASABRError::ResourceError`).
+    pub fn reset(&mut self) -> Result<(), ASABRError> {
+        self.pathfinding
+            .get_multigraph()
+            .try_borrow_mut()?
+            .reset_resources()?;
+        self.route_storage.try_borrow_mut()?.clear();
+        self.now = 0.0;
+        Ok(())
+    }
+
     fn route_unicast(
         &mut self,
         source: NodeID,
@@ -87,6 +161,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         )?;
 
         if let Some(route) = route_option {
+            self.stats.from_cache = true;
             return Ok(Some(schedule_unicast_path(
                 bundle,
                 curr_time,
@@ -97,6 +172,7 @@ impl<S: RouteStorage<NM, CM>, NM: NodeManager, CM: ContactManager, P: Pathfindin
         let new_tree = self
             .pathfinding
             .get_next(curr_time, source, bundle, excluded_nodes)?;
+        self.stats = self.pathfinding.last_stats();
         let tree = Rc::new(RefCell::new(new_tree));
 
         let Some(route) = Route::from_tree(tree, dest) else {
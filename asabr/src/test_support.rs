@@ -0,0 +1,170 @@
+extern crate alloc;
+
+use alloc::{format, vec::Vec};
+
+use crate::{
+    contact::{Contact, ContactInfo},
+    contact_manager::ContactManager,
+    contact_plan::ContactPlan,
+    node::{Node, NodeInfo},
+    node_manager::NodeManager,
+    types::{DataRate, Date, Duration, NodeID},
+    vertex::Vertex,
+};
+
+/// Rate used for a contact if `rate` is not called.
+pub(crate) const DEFAULT_RATE: DataRate = 1.0;
+/// Delay used for a contact if `delay` is not called.
+pub(crate) const DEFAULT_DELAY: Duration = 0.0;
+
+/// A fluent builder for the small topologies used across the tests.
+///
+/// The contact managers are built from the rate and delay of each contact with the factory
+/// given to `new`, e.g. `EVLManager::new`. `rate` and `delay` apply to the last declared contact.
+///
+/// ```ignore
+/// let (vertices, contacts) = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+///     .node(0)
+///     .node(1)
+///     .contact(0, 1, 0.0, 100.0)
+///     .rate(10.0)
+///     .delay(1.0)
+///     .build();
+/// ```
+pub(crate) struct GraphBuilder<NM: NodeManager + Default, CM: ContactManager> {
+    make_manager: fn(DataRate, Duration) -> CM,
+    nodes: Vec<NodeID>,
+    contacts: Vec<(ContactInfo, DataRate, Duration)>,
+    _phantom_nm: core::marker::PhantomData<NM>,
+}
+
+impl<NM: NodeManager + Default, CM: ContactManager> GraphBuilder<NM, CM> {
+    pub(crate) fn new(make_manager: fn(DataRate, Duration) -> CM) -> Self {
+        Self {
+            make_manager,
+            nodes: Vec::new(),
+            contacts: Vec::new(),
+            _phantom_nm: core::marker::PhantomData,
+        }
+    }
+
+    /// Declares a node, named after its id.
+    pub(crate) fn node(mut self, id: NodeID) -> Self {
+        self.nodes.push(id);
+        self
+    }
+
+    /// Declares a contact with the default rate and delay.
+    pub(crate) fn contact(mut self, from: NodeID, to: NodeID, start: Date, end: Date) -> Self {
+        self.contacts.push((
+            ContactInfo::new(from, to, start, end),
+            DEFAULT_RATE,
+            DEFAULT_DELAY,
+        ));
+        self
+    }
+
+    /// Sets the rate of the last declared contact.
+    pub(crate) fn rate(mut self, rate: DataRate) -> Self {
+        self.contacts
+            .last_mut()
+            .expect("rate called before any contact")
+            .1 = rate;
+        self
+    }
+
+    /// Sets the delay of the last declared contact.
+    pub(crate) fn delay(mut self, delay: Duration) -> Self {
+        self.contacts
+            .last_mut()
+            .expect("delay called before any contact")
+            .2 = delay;
+        self
+    }
+
+    /// Builds the vertices and contacts, in declaration order.
+    pub(crate) fn build(self) -> (Vec<Vertex<NM>>, Vec<Contact<NM, CM>>) {
+        let vertices = self
+            .nodes
+            .into_iter()
+            .map(|id| {
+                Vertex::INode(
+                    Node::try_new(
+                        NodeInfo {
+                            id,
+                            name: format!("n{id}").as_str().into(),
+                            excluded: false,
+                        },
+                        NM::default(),
+                    )
+                    .expect("Node creation failed"),
+                )
+            })
+            .collect();
+        let contacts = self
+            .contacts
+            .into_iter()
+            .map(|(info, rate, delay)| {
+                Contact::try_new(info, (self.make_manager)(rate, delay))
+                    .expect("Contact creation failed")
+            })
+            .collect();
+        (vertices, contacts)
+    }
+
+    /// Builds a contact plan without virtual nodes.
+    pub(crate) fn plan(self) -> ContactPlan<NM, CM> {
+        let (vertices, contacts) = self.build();
+        ContactPlan::new(vertices, contacts, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::route_storage::cache::TreeCache;
+    use crate::routing::Router;
+    use crate::routing::aliases::SpsnNodeParenting;
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    #[test]
+    fn three_node_chain_routes_through_the_relay() {
+        let plan = GraphBuilder::<NoManagement, _>::new(EVLManager::new)
+            .node(0)
+            .node(1)
+            .node(2)
+            .contact(0, 1, 0.0, 100.0)
+            .rate(10.0)
+            .delay(1.0)
+            .contact(1, 2, 5.0, 100.0)
+            .rate(10.0)
+            .delay(2.0)
+            .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        let output = router
+            .route(0, &make_bundle(2, 0, 10.0, 1000.0), 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Expected a route through the chain.");
+        let (first_hop, route) = output
+            .lazy_get_for_unicast(2)
+            .expect("TEST FAILED: Expected a route to node 2.");
+        assert_eq!(
+            first_hop.borrow().info.rx_node_id,
+            1,
+            "TEST FAILED: The route should go through node 1."
+        );
+        let route = route.borrow();
+        assert_eq!(route.hop_count, 2, "TEST FAILED: Expected 2 hops.");
+        // the second contact opens at 5.0 and carries the bundle in 1.0, plus a delay of 2.0
+        assert_eq!(
+            route.at_time, 8.0,
+            "TEST FAILED: Expected an arrival at 8.0."
+        );
+    }
+}
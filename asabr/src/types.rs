@@ -11,8 +11,21 @@ pub type NodeIDMap = HashMap<NodeID, Vec<NodeID>>;
 pub type NodeID = u16;
 const_assert!(size_of::<NodeID>() <= size_of::<usize>());
 
+/// The floating point type of the dates, durations, volumes and data rates.
+///
+/// The `small_floats` feature makes it `f32`, halving the footprint of the contacts and route
+/// stages (e.g. for large plans on embedded nodes) at the cost of precision.
+#[cfg(not(feature = "small_floats"))]
+pub type Float = f64;
+/// The floating point type of the dates, durations, volumes and data rates.
+///
+/// The `small_floats` feature makes it `f32`, halving the footprint of the contacts and route
+/// stages (e.g. for large plans on embedded nodes) at the cost of precision.
+#[cfg(feature = "small_floats")]
+pub type Float = f32;
+
 /// Represents a duration in units (e.g., seconds).
-pub type Duration = f64;
+pub type Duration = Float;
 
 /// Represents a date (could represent days since a specific epoch).lo
 pub type Date = Duration;
@@ -21,10 +34,10 @@ pub type Date = Duration;
 pub type Priority = i8;
 
 /// Represents the volume of data (in bytes, for example).
-pub type Volume = f64;
+pub type Volume = Float;
 
 /// Represents a data transfer rate (in bits per second).
-pub type DataRate = f64;
+pub type DataRate = Float;
 
 /// Represents the count of hops in a routing path.
 pub type HopCount = u16;
@@ -42,6 +55,17 @@ assert_impl_all!(
     Into<HopCount>,
 );
 
+/// Represent a real value, typically for use in parser for the dates, durations, volumes and
+/// data rates. Unlike `AnyNumber`, decimal values are accepted.
+#[derive(Clone, Copy, Debug)]
+pub struct AnyFloat(f64);
+assert_impl_all!(
+    AnyFloat: TryFrom<&'static str>,
+    Into<Duration>,
+    Into<Volume>,
+    Into<DataRate>,
+);
+
 /// The name of a node. Use the "debug" feature to populate it with usefull data
 /// Can be created from a &str, and displayed
 #[derive(Clone, Debug)]
@@ -66,11 +90,40 @@ impl TryFrom<&str> for AnyNumber {
     }
 }
 
+impl TryFrom<&str> for AnyFloat {
+    type Error = ();
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Ok(Self(s.parse().map_err(|_| ())?))
+    }
+}
+
+impl From<AnyFloat> for f64 {
+    fn from(value: AnyFloat) -> Self {
+        value.0
+    }
+}
+
+#[cfg(feature = "small_floats")]
+impl From<AnyFloat> for f32 {
+    fn from(value: AnyFloat) -> Self {
+        value.0 as f32
+    }
+}
+
 impl From<AnyNumber> for f64 {
     fn from(value: AnyNumber) -> Self {
         value.0 as Self
     }
 }
+
+#[cfg(feature = "small_floats")]
+impl From<AnyNumber> for f32 {
+    fn from(value: AnyNumber) -> Self {
+        value.0 as f32
+    }
+}
+
 impl From<AnyNumber> for i8 {
     fn from(value: AnyNumber) -> Self {
         value.0 as Self
@@ -103,3 +156,46 @@ impl<T: AsRef<str>> From<T> for NodeName {
         }
     }
 }
+
+#[cfg(all(test, feature = "small_floats"))]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::contact_plan::asabr_file_lexer::parse_from_str;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::route_storage::cache::TreeCache;
+    use crate::routing::Router;
+    use crate::routing::aliases::SpsnNodeParenting;
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    #[test]
+    fn small_floats_parse_and_route() {
+        assert_eq!(
+            size_of::<Date>(),
+            4,
+            "TEST FAILED: The dates should be single precision."
+        );
+        let plan = parse_from_str::<NoManagement, EVLManager>(
+            "node 0 a
+            node 1 b
+            node 2 c
+            contact 0 1 0 100 10 0.5
+            contact 1 2 10 100 10 0.25",
+        )
+        .unwrap();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false).unwrap();
+
+        let output = router
+            .route(0, &make_bundle(2, 0, 5.0, 1000.0), 0.0, &[])
+            .unwrap()
+            .expect("TEST FAILED: Node 2 should be reached.");
+        // waits for the second contact at 10, then 0.5 to transmit and 0.25 of delay
+        assert_eq!(
+            output.route_metrics[&2].arrival_time, 10.75,
+            "TEST FAILED: The arrival time should be exact in single precision."
+        );
+    }
+}
@@ -0,0 +1,121 @@
+extern crate alloc;
+use alloc::{collections::BTreeSet, rc::Rc, string::String, vec, vec::Vec};
+use core::{cell::RefCell, fmt::Write};
+
+use crate::{
+    contact_manager::ContactManager, node_manager::NodeManager, pathfinding::PathFindingOutput,
+    route_stage::SharedRouteStage,
+};
+
+/// Exports a pathfinding tree as a GraphViz digraph.
+///
+/// The stages are collected from `by_destination`, their parents and their
+/// `next_for_destination` entries. Each stage reached through a contact gives an edge from the
+/// transmitting node to `to_node`, labeled with the arrival time and the hop count. The nodes
+/// reachable in the tree (other than the source) are filled.
+///
+/// # Parameters
+///
+/// * `tree` - The pathfinding output to export.
+///
+/// # Returns
+///
+/// The DOT representation of the tree.
+pub fn export_tree_dot<NM: NodeManager, CM: ContactManager>(
+    tree: Rc<RefCell<PathFindingOutput<NM, CM>>>,
+) -> String {
+    let tree = tree.borrow();
+    let source_node = tree.source.borrow().to_node;
+    let mut dot = String::from("digraph tree {\n");
+
+    let _ = writeln!(
+        dot,
+        "    n{source_node} [label=\"{source_node}\", shape=doublecircle];"
+    );
+    for (node, stage) in tree.by_destination.iter().enumerate() {
+        if stage.is_some() && node != source_node as usize {
+            let _ = writeln!(
+                dot,
+                "    n{node} [label=\"{node}\", style=filled, fillcolor=palegreen];"
+            );
+        }
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut to_visit: Vec<SharedRouteStage<NM, CM>> = vec![tree.source.clone()];
+    to_visit.extend(tree.by_destination.iter().flatten().cloned());
+
+    while let Some(stage) = to_visit.pop() {
+        if !visited.insert(Rc::as_ptr(&stage) as usize) {
+            continue;
+        }
+        let stage = stage.borrow();
+        to_visit.extend(stage.next_for_destination.values().cloned());
+        if let Some(via) = &stage.via {
+            let parent = via.parent_route.borrow().to_node;
+            let _ = writeln!(
+                dot,
+                "    n{parent} -> n{} [label=\"t={} hops={}\"];",
+                stage.to_node, stage.at_time, stage.hop_count
+            );
+            to_visit.push(via.parent_route.clone());
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::utils::init_pathfinding;
+
+    #[test]
+    fn every_scheduled_hop_is_exported() {
+        let plan = "node 0 a
+            node 1 b
+            node 2 c
+            node 3 d
+            contact 0 1 0 100 10 1
+            contact 1 2 10 100 10 1
+            contact 0 3 0 100 10 1";
+        let mut pathfinding = init_pathfinding::<
+            NoManagement,
+            EVLManager,
+            NodeParentingTreeExcl<NoManagement, EVLManager, SABR>,
+            _,
+            _,
+        >(plan.lines())
+        .unwrap();
+        let tree = pathfinding
+            .get_next(0.0, 0, &make_bundle(2, 0, 10.0, 1000.0), &[])
+            .unwrap();
+        let dot = export_tree_dot(Rc::new(RefCell::new(tree)));
+
+        assert!(
+            dot.starts_with("digraph tree {"),
+            "TEST FAILED: The export should be a digraph."
+        );
+        for edge in [
+            "n0 -> n1 [label=\"t=2 hops=1\"]",
+            "n1 -> n2 [label=\"t=12 hops=2\"]",
+            "n0 -> n3 [label=\"t=2 hops=1\"]",
+        ] {
+            assert!(
+                dot.contains(edge),
+                "TEST FAILED: Missing edge `{edge}` in:\n{dot}"
+            );
+        }
+        assert!(
+            dot.contains("n2 [label=\"2\", style=filled"),
+            "TEST FAILED: The reachable destination should be highlighted."
+        );
+    }
+}
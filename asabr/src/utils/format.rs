@@ -0,0 +1,92 @@
+extern crate alloc;
+use alloc::{rc::Rc, string::String, vec};
+use core::{cell::RefCell, fmt::Write};
+
+use crate::{contact_manager::ContactManager, node_manager::NodeManager, route_stage::RouteStage};
+
+/// Formats the backtrace of a route, one stage per line from the source to `route`.
+///
+/// Each line gives the node, the arrival time and the hop count of the stage, followed by the
+/// contact the stage was reached through, e.g. `node 2 at t=12 (2 hops) via 1->2 [10, 100]`.
+/// The crate being `no_std`, printing the result is left to the caller.
+///
+/// # Parameters
+///
+/// * `route` - The last stage of the route.
+///
+/// # Returns
+///
+/// The description of the route.
+pub fn format_route<NM: NodeManager, CM: ContactManager>(
+    route: Rc<RefCell<RouteStage<NM, CM>>>,
+) -> String {
+    let mut stages = vec![route];
+    loop {
+        let parent = match &stages.last().unwrap().borrow().via {
+            Some(via) => via.parent_route.clone(),
+            None => break,
+        };
+        stages.push(parent);
+    }
+
+    let mut text = String::new();
+    for stage in stages.iter().rev() {
+        let stage = stage.borrow();
+        let _ = write!(
+            text,
+            "node {} at t={} ({} hops)",
+            stage.to_node, stage.at_time, stage.hop_count
+        );
+        if let Some(via) = &stage.via {
+            let info = via.contact.borrow().info;
+            let _ = write!(
+                text,
+                " via {}->{} [{}, {}]",
+                info.tx_node_id, info.rx_node_id, info.start, info.end
+            );
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact_manager::legacy::evl::EVLManager;
+    use crate::distance::sabr::SABR;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::Pathfinding;
+    use crate::pathfinding::node_parenting::NodeParentingTreeExcl;
+    use crate::pathfinding::test_helpers::make_bundle;
+    use crate::utils::init_pathfinding;
+
+    #[test]
+    fn route_is_formatted_from_source_to_destination() {
+        let plan = "node 0 a
+            node 1 b
+            node 2 c
+            contact 0 1 0 100 10 1
+            contact 1 2 10 100 10 1";
+        let mut pathfinding = init_pathfinding::<
+            NoManagement,
+            EVLManager,
+            NodeParentingTreeExcl<NoManagement, EVLManager, SABR>,
+            _,
+            _,
+        >(plan.lines())
+        .unwrap();
+        let tree = pathfinding
+            .get_next(0.0, 0, &make_bundle(2, 0, 10.0, 1000.0), &[])
+            .unwrap();
+        let route = tree.by_destination[2].clone().unwrap();
+
+        assert_eq!(
+            format_route(route),
+            "node 0 at t=0 (0 hops)\n\
+             node 1 at t=2 (1 hops) via 0->1 [0, 100]\n\
+             node 2 at t=12 (2 hops) via 1->2 [10, 100]\n",
+            "TEST FAILED: The route should be listed from the source to the destination."
+        );
+    }
+}
@@ -8,6 +8,14 @@ use crate::{
     pathfinding::Pathfinding,
 };
 
+mod dot;
+mod format;
+pub mod simulation;
+
+pub use dot::export_tree_dot;
+pub use format::format_route;
+pub use simulation::{SimSummary, simulate};
+
 pub fn init_pathfinding<
     NM: NodeManager + LexFrom<str>,
     CM: ContactManager + LexFrom<str>,
@@ -0,0 +1,268 @@
+extern crate alloc;
+use alloc::{collections::BTreeMap as HashMap, vec::Vec};
+
+use crate::{
+    bundle::Bundle,
+    contact_manager::ContactManager,
+    errors::ASABRError,
+    node_manager::NodeManager,
+    routing::{Router, RoutingOutput},
+    types::{Date, Duration, Float, NodeID, Priority, Volume},
+};
+
+/// The delivery statistics of a single priority level over a simulation.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PriorityReport {
+    /// The number of (bundle, destination) pairs for which a route was scheduled.
+    pub delivered_count: usize,
+    /// The number of (bundle, destination) pairs for which no route was found.
+    pub dropped_count: usize,
+    /// The volume delivered, counted once per reached destination.
+    pub delivered_volume: Volume,
+    /// The volume dropped, counted once per unreached destination.
+    pub dropped_volume: Volume,
+    /// The sum of the latencies of the delivered bundles.
+    pub cumulative_latency: Duration,
+}
+
+impl PriorityReport {
+    /// Returns the ratio of delivered volume over the injected volume (0.0 if nothing was injected).
+    pub fn delivery_ratio(&self) -> Float {
+        let total = self.delivered_volume + self.dropped_volume;
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.delivered_volume / total
+    }
+
+    /// Returns the mean latency of the delivered bundles, or `None` if nothing was delivered.
+    pub fn mean_latency(&self) -> Option<Duration> {
+        if self.delivered_count == 0 {
+            return None;
+        }
+        Some(self.cumulative_latency / self.delivered_count as Duration)
+    }
+}
+
+/// Accumulates the results of a simulation, broken down by priority.
+///
+/// The report is fed with the output of each routing call, a bundle is considered delivered
+/// to a destination if the routing output holds a route stage for that destination. The
+/// latency is the arrival time of that stage minus the time the bundle was routed.
+#[derive(Debug, Default)]
+pub struct SimReport {
+    /// The statistics for each priority level encountered.
+    pub by_priority: HashMap<Priority, PriorityReport>,
+}
+
+impl SimReport {
+    /// Creates an empty `SimReport`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the result of a routing call.
+    ///
+    /// # Parameters
+    ///
+    /// * `bundle` - The bundle that was routed.
+    /// * `curr_time` - The time at which the bundle was routed.
+    /// * `output` - The output of the routing call, `None` if the bundle was not routed.
+    pub fn record<NM: NodeManager, CM: ContactManager>(
+        &mut self,
+        bundle: &Bundle,
+        curr_time: Date,
+        output: Option<&RoutingOutput<NM, CM>>,
+    ) {
+        let report = self.by_priority.entry(bundle.priority).or_default();
+        for dest in &bundle.destinations {
+            match output.and_then(|out| out.lazy_get_for_unicast(*dest)) {
+                Some((_, route)) => {
+                    report.delivered_count += 1;
+                    report.delivered_volume += bundle.size;
+                    report.cumulative_latency += route.borrow().at_time - curr_time;
+                }
+                None => {
+                    report.dropped_count += 1;
+                    report.dropped_volume += bundle.size;
+                }
+            }
+        }
+    }
+
+    /// Returns the statistics of a priority level, if any bundle of that priority was recorded.
+    pub fn get(&self, priority: Priority) -> Option<&PriorityReport> {
+        self.by_priority.get(&priority)
+    }
+}
+
+/// The delivery time distribution of a traffic set, see `simulate`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SimSummary {
+    /// The number of (bundle, destination) pairs for which a route was scheduled.
+    pub delivered_count: usize,
+    /// The number of (bundle, destination) pairs for which no route was found.
+    pub dropped_count: usize,
+    /// The median delivery time, `None` if nothing was delivered.
+    pub p50: Option<Duration>,
+    /// The 90th percentile of the delivery times, `None` if nothing was delivered.
+    pub p90: Option<Duration>,
+    /// The 99th percentile of the delivery times, `None` if nothing was delivered.
+    pub p99: Option<Duration>,
+}
+
+/// Returns the nearest-rank `percent`th percentile of `sorted`, `None` if it is empty.
+fn percentile(sorted: &[Duration], percent: usize) -> Option<Duration> {
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+/// Routes a traffic set in submission order and summarizes the delivery times.
+///
+/// The resources booked for a bundle are accounted for when routing the following ones, like
+/// `Spsn::route_batch`. The delivery time to a destination is the arrival time of its route
+/// (see `RoutingOutput::route_metrics`) minus the time the bundle was routed, a destination
+/// without route is dropped.
+///
+/// # Parameters
+///
+/// * `router` - The router to evaluate.
+/// * `bundles` - The source node, the bundle and the current time of each routing request.
+///
+/// # Returns
+///
+/// * `Ok(SimSummary)` - The delivered and dropped counts and the delivery time percentiles.
+/// * `Err(ASABRError)` - The first routing error encountered.
+pub fn simulate<NM: NodeManager, CM: ContactManager, R: Router<NM, CM> + ?Sized>(
+    router: &mut R,
+    bundles: &[(NodeID, Bundle, Date)],
+) -> Result<SimSummary, ASABRError> {
+    let mut summary = SimSummary::default();
+    let mut delivery_times = Vec::new();
+    for (source, bundle, curr_time) in bundles {
+        let output = router.route(*source, bundle, *curr_time, &[])?;
+        for dest in &bundle.destinations {
+            match output
+                .as_ref()
+                .and_then(|output| output.route_metrics.get(dest))
+            {
+                Some(metrics) => {
+                    summary.delivered_count += 1;
+                    delivery_times.push(metrics.arrival_time - curr_time);
+                }
+                None => summary.dropped_count += 1,
+            }
+        }
+    }
+    delivery_times.sort_by(Duration::total_cmp);
+    summary.p50 = percentile(&delivery_times, 50);
+    summary.p90 = percentile(&delivery_times, 90);
+    summary.p99 = percentile(&delivery_times, 99);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact::{Contact, ContactInfo};
+    use crate::contact_manager::legacy::qd::PQDManager;
+    use crate::contact_plan::ContactPlan;
+    use crate::errors::ASABRError;
+    use crate::node_manager::none::NoManagement;
+    use crate::pathfinding::test_helpers::*;
+    use crate::route_storage::cache::TreeCache;
+    use crate::routing::Router;
+    use crate::routing::aliases::SpsnNodeParenting;
+    use alloc::rc::Rc;
+    use alloc::vec;
+    use core::cell::RefCell;
+
+    #[test]
+    fn high_priority_gets_better_service_under_saturation() -> Result<(), ASABRError> {
+        // a single contact able to carry 10 units of volume
+        let contact_plan = ContactPlan::new(
+            vec![
+                make_vertex(0, "A", NoManagement {}),
+                make_vertex(1, "B", NoManagement {}),
+            ],
+            vec![
+                Contact::try_new(ContactInfo::new(0, 1, 0.0, 10.0), PQDManager::new(1.0, 0.0))
+                    .unwrap(),
+            ],
+            None,
+        );
+        let cache = Rc::new(RefCell::new(TreeCache::new(true, true, 10)));
+        let mut router =
+            SpsnNodeParenting::<NoManagement, PQDManager>::new(contact_plan, cache, true)?;
+        let mut report = SimReport::new();
+
+        for priority in [2, 2, 0, 0, 2, 2, 0] {
+            let bundle = make_bundle(1, priority, 2.0, 100.0);
+            let output = router.route(0, &bundle, 0.0, &[])?;
+            report.record(&bundle, 0.0, output.as_ref());
+        }
+
+        let low = report.get(0).unwrap();
+        let high = report.get(2).unwrap();
+        assert_eq!(
+            (high.delivered_count, high.dropped_count),
+            (4, 0),
+            "TEST FAILED: All the priority-2 bundles should be delivered."
+        );
+        assert_eq!(
+            (low.delivered_count, low.dropped_count),
+            (2, 1),
+            "TEST FAILED: The last priority-0 bundle should be dropped."
+        );
+        assert!(
+            high.delivery_ratio() > low.delivery_ratio(),
+            "TEST FAILED: Priority-2 should achieve a higher delivery ratio."
+        );
+        assert!(
+            high.mean_latency().unwrap() < low.mean_latency().unwrap(),
+            "TEST FAILED: Priority-2 should achieve a lower mean latency."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn simulation_summarizes_the_delivery_times() -> Result<(), ASABRError> {
+        // 0 -> 1 delivers in 2, 1 -> 2 adds 6 more, node 3 is out of reach
+        let plan = crate::test_support::GraphBuilder::<NoManagement, _>::new(
+            crate::contact_manager::legacy::evl::EVLManager::new,
+        )
+        .node(0)
+        .node(1)
+        .node(2)
+        .node(3)
+        .contact(0, 1, 0.0, 100.0)
+        .rate(10.0)
+        .delay(1.0)
+        .contact(1, 2, 0.0, 100.0)
+        .rate(10.0)
+        .delay(5.0)
+        .plan();
+        let cache = Rc::new(RefCell::new(TreeCache::new(false, false, 10)));
+        let mut router = SpsnNodeParenting::new(plan, cache, false)?;
+        let traffic = [
+            (0, make_bundle(1, 0, 10.0, 1000.0), 0.0),
+            (0, make_bundle(1, 0, 10.0, 1000.0), 10.0),
+            (0, make_bundle(2, 0, 10.0, 1000.0), 20.0),
+            (0, make_bundle(1, 0, 10.0, 1000.0), 30.0),
+            (0, make_bundle(3, 0, 10.0, 1000.0), 40.0),
+        ];
+
+        let summary = simulate(&mut router, &traffic)?;
+        assert_eq!(
+            (summary.delivered_count, summary.dropped_count),
+            (4, 1),
+            "TEST FAILED: Only the bundle to node 3 should be dropped."
+        );
+        assert_eq!(
+            (summary.p50, summary.p90, summary.p99),
+            (Some(2.0), Some(8.0), Some(8.0)),
+            "TEST FAILED: Unexpected delivery time percentiles."
+        );
+        Ok(())
+    }
+}